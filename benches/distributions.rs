@@ -10,7 +10,7 @@ const RAND_BENCH_N: u64 = 1000;
 use std::mem::size_of;
 use test::{black_box, Bencher};
 
-use rand::{Rng, FromEntropy, XorShiftRng};
+use rand::{Rng, RngCore, FromEntropy, XorShiftRng};
 use rand::distributions::*;
 
 macro_rules! distr_int {
@@ -75,6 +75,8 @@ macro_rules! distr {
 distr_int!(distr_uniform_i8, i8, Uniform::new(20i8, 100));
 distr_int!(distr_uniform_i16, i16, Uniform::new(-500i16, 2000));
 distr_int!(distr_uniform_i32, i32, Uniform::new(-200_000_000i32, 800_000_000));
+distr_int!(distr_uniform_u32, u32, Uniform::new(0u32, 1_000_000_007));
+distr_int!(distr_uniform_u32_masked, u32, Uniform::new_masked(0u32, 1_000_000_007));
 distr_int!(distr_uniform_i64, i64, Uniform::new(3i64, 123_456_789_123));
 #[cfg(feature = "i128_support")]
 distr_int!(distr_uniform_i128, i128, Uniform::new(-123_456_789_123i128, 123_456_789_123_456_789));
@@ -136,10 +138,36 @@ macro_rules! gen_range_int {
 gen_range_int!(gen_range_i8, i8, -20i8, 100);
 gen_range_int!(gen_range_i16, i16, -500i16, 2000);
 gen_range_int!(gen_range_i32, i32, -200_000_000i32, 800_000_000);
+gen_range_int!(gen_range_u32, u32, 0u32, 1_000_000_007);
 gen_range_int!(gen_range_i64, i64, 3i64, 123_456_789_123);
 #[cfg(feature = "i128_support")]
 gen_range_int!(gen_range_i128, i128, -12345678901234i128, 123_456_789_123_456_789);
 
+// Baseline for `gen_range_u32`: rejection sampling with a modulo reduction
+// instead of the widening multiply used by `Uniform`.
+#[bench]
+fn gen_range_u32_modulo(b: &mut Bencher) {
+    let mut rng = XorShiftRng::from_entropy();
+
+    b.iter(|| {
+        let mut high = 1_000_000_007u32;
+        let mut accum = 0u32;
+        for _ in 0..::RAND_BENCH_N {
+            let zone = ::std::u32::MAX - ::std::u32::MAX % high;
+            loop {
+                let v = rng.next_u32();
+                if v < zone {
+                    accum = accum.wrapping_add(v % high);
+                    break;
+                }
+            }
+            high = high.wrapping_add(1) | 1;
+        }
+        black_box(accum);
+    });
+    b.bytes = size_of::<u32>() as u64 * ::RAND_BENCH_N;
+}
+
 #[bench]
 fn dist_iter(b: &mut Bencher) {
     let mut rng = XorShiftRng::from_entropy();
@@ -155,3 +183,19 @@ fn dist_iter(b: &mut Bencher) {
     });
     b.bytes = size_of::<f64>() as u64 * ::RAND_BENCH_N;
 }
+
+#[bench]
+fn dist_iter_chunks(b: &mut Bencher) {
+    let mut rng = XorShiftRng::from_entropy();
+    let distr = Normal::new(-2.71828, 3.14159);
+    let mut iter = distr.sample_chunks(&mut rng, 256);
+
+    b.iter(|| {
+        let mut accum = 0.0;
+        for _ in 0..::RAND_BENCH_N {
+            accum += iter.next().unwrap();
+        }
+        black_box(accum);
+    });
+    b.bytes = size_of::<f64>() as u64 * ::RAND_BENCH_N;
+}
@@ -5,12 +5,14 @@ extern crate rand;
 
 const RAND_BENCH_N: u64 = 1000;
 const BYTES_LEN: usize = 1024;
+const BYTES_LEN_LARGE: usize = 1024 * 1024;
 
 use std::mem::size_of;
 use test::{black_box, Bencher};
 
 use rand::prelude::*;
-use rand::prng::{XorShiftRng, Hc128Rng, IsaacRng, Isaac64Rng, ChaChaRng};
+use rand::{Jumpable, SplitRng};
+use rand::prng::{XorShiftRng, Xoshiro256StarStar, Xoshiro256StarStarX4, Hc128Rng, IsaacRng, Isaac64Rng, ChaChaRng, SplitMix64};
 use rand::prng::hc128::Hc128Core;
 use rand::rngs::adapter::ReseedingRng;
 use rand::rngs::{OsRng, JitterRng, EntropyRng};
@@ -33,14 +35,45 @@ macro_rules! gen_bytes {
 }
 
 gen_bytes!(gen_bytes_xorshift, XorShiftRng::from_entropy());
+gen_bytes!(gen_bytes_xoshiro256starstar, Xoshiro256StarStar::from_entropy());
+gen_bytes!(gen_bytes_xoshiro256starstar_x4, Xoshiro256StarStarX4::from_entropy());
 gen_bytes!(gen_bytes_chacha20, ChaChaRng::from_entropy());
 gen_bytes!(gen_bytes_hc128, Hc128Rng::from_entropy());
 gen_bytes!(gen_bytes_isaac, IsaacRng::from_entropy());
 gen_bytes!(gen_bytes_isaac64, Isaac64Rng::from_entropy());
+gen_bytes!(gen_bytes_splitmix64, SplitMix64::from_entropy());
 gen_bytes!(gen_bytes_std, StdRng::from_entropy());
 gen_bytes!(gen_bytes_small, SmallRng::from_entropy());
 gen_bytes!(gen_bytes_os, OsRng::new().unwrap());
 
+// Fill a single large buffer per iteration, to measure the bulk path of
+// `fill_bytes` rather than the per-call overhead.
+macro_rules! gen_bytes_large {
+    ($fnn:ident, $gen:expr) => {
+        #[bench]
+        fn $fnn(b: &mut Bencher) {
+            let mut rng = $gen;
+            let mut buf = vec![0u8; BYTES_LEN_LARGE];
+            b.iter(|| {
+                rng.fill_bytes(&mut buf);
+                black_box(&buf);
+            });
+            b.bytes = BYTES_LEN_LARGE as u64;
+        }
+    }
+}
+
+gen_bytes_large!(gen_bytes_1m_xorshift, XorShiftRng::from_entropy());
+gen_bytes_large!(gen_bytes_1m_xoshiro256starstar, Xoshiro256StarStar::from_entropy());
+gen_bytes_large!(gen_bytes_1m_xoshiro256starstar_x4, Xoshiro256StarStarX4::from_entropy());
+gen_bytes_large!(gen_bytes_1m_chacha20, ChaChaRng::from_entropy());
+gen_bytes_large!(gen_bytes_1m_hc128, Hc128Rng::from_entropy());
+gen_bytes_large!(gen_bytes_1m_isaac, IsaacRng::from_entropy());
+gen_bytes_large!(gen_bytes_1m_isaac64, Isaac64Rng::from_entropy());
+gen_bytes_large!(gen_bytes_1m_splitmix64, SplitMix64::from_entropy());
+gen_bytes_large!(gen_bytes_1m_std, StdRng::from_entropy());
+gen_bytes_large!(gen_bytes_1m_small, SmallRng::from_entropy());
+
 macro_rules! gen_uint {
     ($fnn:ident, $ty:ty, $gen:expr) => {
         #[bench]
@@ -59,19 +92,25 @@ macro_rules! gen_uint {
 }
 
 gen_uint!(gen_u32_xorshift, u32, XorShiftRng::from_entropy());
+gen_uint!(gen_u32_xoshiro256starstar, u32, Xoshiro256StarStar::from_entropy());
+gen_uint!(gen_u32_xoshiro256starstar_x4, u32, Xoshiro256StarStarX4::from_entropy());
 gen_uint!(gen_u32_chacha20, u32, ChaChaRng::from_entropy());
 gen_uint!(gen_u32_hc128, u32, Hc128Rng::from_entropy());
 gen_uint!(gen_u32_isaac, u32, IsaacRng::from_entropy());
 gen_uint!(gen_u32_isaac64, u32, Isaac64Rng::from_entropy());
+gen_uint!(gen_u32_splitmix64, u32, SplitMix64::from_entropy());
 gen_uint!(gen_u32_std, u32, StdRng::from_entropy());
 gen_uint!(gen_u32_small, u32, SmallRng::from_entropy());
 gen_uint!(gen_u32_os, u32, OsRng::new().unwrap());
 
 gen_uint!(gen_u64_xorshift, u64, XorShiftRng::from_entropy());
+gen_uint!(gen_u64_xoshiro256starstar, u64, Xoshiro256StarStar::from_entropy());
+gen_uint!(gen_u64_xoshiro256starstar_x4, u64, Xoshiro256StarStarX4::from_entropy());
 gen_uint!(gen_u64_chacha20, u64, ChaChaRng::from_entropy());
 gen_uint!(gen_u64_hc128, u64, Hc128Rng::from_entropy());
 gen_uint!(gen_u64_isaac, u64, IsaacRng::from_entropy());
 gen_uint!(gen_u64_isaac64, u64, Isaac64Rng::from_entropy());
+gen_uint!(gen_u64_splitmix64, u64, SplitMix64::from_entropy());
 gen_uint!(gen_u64_std, u64, StdRng::from_entropy());
 gen_uint!(gen_u64_small, u64, SmallRng::from_entropy());
 gen_uint!(gen_u64_os, u64, OsRng::new().unwrap());
@@ -105,6 +144,44 @@ init_gen!(init_hc128, Hc128Rng);
 init_gen!(init_isaac, IsaacRng);
 init_gen!(init_isaac64, Isaac64Rng);
 init_gen!(init_chacha, ChaChaRng);
+init_gen!(init_xoshiro256starstar, Xoshiro256StarStar);
+init_gen!(init_xoshiro256starstar_x4, Xoshiro256StarStarX4);
+init_gen!(init_splitmix64, SplitMix64);
+
+macro_rules! jump_gen {
+    ($fnn:ident, $gen:ident) => {
+        #[bench]
+        fn $fnn(b: &mut Bencher) {
+            let mut rng = $gen::from_entropy();
+            b.iter(|| {
+                rng.jump();
+                black_box(&rng);
+            });
+        }
+    }
+}
+
+jump_gen!(jump_xoshiro256starstar, Xoshiro256StarStar);
+jump_gen!(jump_chacha, ChaChaRng);
+
+#[bench]
+fn split_splitmix64(b: &mut Bencher) {
+    let mut rng = SplitMix64::from_entropy();
+    b.iter(|| {
+        black_box(rng.split());
+    });
+}
+
+#[bench]
+fn set_stream_chacha(b: &mut Bencher) {
+    let mut rng = ChaChaRng::from_entropy();
+    let mut stream = 0u64;
+    b.iter(|| {
+        stream = stream.wrapping_add(1);
+        rng.set_stream(stream);
+        black_box(&rng);
+    });
+}
 
 #[bench]
 fn init_jitter(b: &mut Bencher) {
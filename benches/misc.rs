@@ -135,6 +135,26 @@ sample_indices!(misc_sample_indices_10_of_1k, 10, 1000);
 sample_indices!(misc_sample_indices_50_of_1k, 50, 1000);
 sample_indices!(misc_sample_indices_100_of_1k, 100, 1000);
 
+macro_rules! index_sample {
+    ($name:ident, $amount:expr, $length:expr) => {
+        #[bench]
+        fn $name(b: &mut Bencher) {
+            let mut rng = SmallRng::from_rng(thread_rng()).unwrap();
+            b.iter(|| {
+                black_box(index::sample(&mut rng, $length, $amount));
+            })
+        }
+    }
+}
+
+index_sample!(misc_index_sample_1_of_1k, 1, 1000);
+index_sample!(misc_index_sample_10_of_1k, 10, 1000);
+index_sample!(misc_index_sample_100_of_1k, 100, 1000);
+index_sample!(misc_index_sample_400_of_1k, 400, 1000);
+index_sample!(misc_index_sample_100_of_1M, 100, 1_000_000);
+index_sample!(misc_index_sample_1k_of_1M, 1000, 1_000_000);
+index_sample!(misc_index_sample_10k_of_1M, 10_000, 1_000_000);
+
 #[bench]
 fn gen_1k_iter_repeat(b: &mut Bencher) {
     use std::iter;
@@ -178,3 +198,27 @@ fn gen_1k_fill(b: &mut Bencher) {
     });
     b.bytes = 1024;
 }
+
+#[bench]
+fn gen_1k_f64_gen(b: &mut Bencher) {
+    let mut rng = SmallRng::from_rng(&mut thread_rng()).unwrap();
+    let mut buf = [0f64; 128];
+    b.iter(|| {
+        for x in buf.iter_mut() {
+            *x = rng.gen();
+        }
+        black_box(buf);
+    });
+    b.bytes = 1024;
+}
+
+#[bench]
+fn gen_1k_f64_fill_floats(b: &mut Bencher) {
+    let mut rng = SmallRng::from_rng(&mut thread_rng()).unwrap();
+    let mut buf = [0f64; 128];
+    b.iter(|| {
+        rng.fill_floats(&mut buf[..]);
+        black_box(buf);
+    });
+    b.bytes = 1024;
+}
@@ -0,0 +1,117 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! ## Statistical test harness
+//!
+//! Writes the raw output of a generator to stdout, to be piped into a test
+//! suite like [PractRand] or [TestU01]'s BigCrush (via a program reading
+//! stdin). Any generator of `rand::rngs::registry` can be selected:
+//!
+//! ```text
+//! cargo run --release --example practrand-harness -- \
+//!     --rng xoshiro256** --seed 0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20 \
+//!     | RNG_test stdin64
+//! ```
+//!
+//! Options:
+//!
+//! - `--rng <name>`: the generator; `--list` prints the available names and
+//!   their seed sizes
+//! - `--seed <hex>`: the seed, with the seed size of the generator
+//! - `--bytes <n>`: stop after `n` bytes, instead of writing until stdout is
+//!   closed
+//!
+//! [PractRand]: http://pracrand.sourceforge.net/
+//! [TestU01]: http://simul.iro.umontreal.ca/testu01/tu01.html
+
+#![cfg(feature="std")]
+
+extern crate rand;
+
+use std::env;
+use std::io::{self, Write};
+use std::process;
+
+use rand::rngs::registry;
+
+fn usage() -> ! {
+    eprintln!("usage: practrand-harness --rng <name> --seed <hex> [--bytes <n>]");
+    eprintln!("       practrand-harness --list");
+    process::exit(2)
+}
+
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    s.as_bytes().chunks(2).map(|pair| {
+        match ((pair[0] as char).to_digit(16), (pair[1] as char).to_digit(16)) {
+            (Some(hi), Some(lo)) => Some((hi * 16 + lo) as u8),
+            _ => None,
+        }
+    }).collect()
+}
+
+fn main() {
+    let mut name = None;
+    let mut seed = None;
+    let mut limit = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => {
+                for info in registry::algorithms() {
+                    println!("{} ({} byte seed)", info.name, info.seed_size);
+                }
+                return;
+            }
+            "--rng" => name = Some(args.next().unwrap_or_else(|| usage())),
+            "--seed" => {
+                let hex = args.next().unwrap_or_else(|| usage());
+                seed = Some(parse_hex(&hex).unwrap_or_else(|| {
+                    eprintln!("invalid seed: {}", hex);
+                    process::exit(2)
+                }));
+            }
+            "--bytes" => {
+                let n = args.next().unwrap_or_else(|| usage());
+                limit = Some(n.parse::<u64>().unwrap_or_else(|_| usage()));
+            }
+            _ => usage(),
+        }
+    }
+    let (name, seed) = match (name, seed) {
+        (Some(name), Some(seed)) => (name, seed),
+        _ => usage(),
+    };
+
+    let mut rng = registry::by_name(&name, &seed).unwrap_or_else(|e| {
+        eprintln!("{}: {}", name, e);
+        process::exit(2)
+    });
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut buf = [0u8; 64 * 1024];
+    let mut remaining = limit.unwrap_or(u64::max_value());
+    while remaining > 0 {
+        let n = if remaining < buf.len() as u64 { remaining as usize } else { buf.len() };
+        rng.fill_bytes(&mut buf[..n]);
+        if let Err(e) = out.write_all(&buf[..n]) {
+            // The test suite closes the pipe when it is done
+            if e.kind() == io::ErrorKind::BrokenPipe {
+                return;
+            }
+            eprintln!("error writing output: {}", e);
+            process::exit(1)
+        }
+        remaining -= n as u64;
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Check that `fill_bytes` produces the same stream as `next_u64`, in
+//! little-endian order, when filling whole words.
+//!
+//! After the seed, each byte of the input is a step: the low 7 bits are a
+//! number of words, and the high bit chooses between filling them with
+//! `fill_bytes` and calling `next_u64`. A second generator with the same seed
+//! only uses `next_u64`.
+
+#![no_main]
+#[macro_use] extern crate libfuzzer_sys;
+extern crate rand;
+extern crate rand_fuzz;
+
+use rand::{RngCore, SeedableRng};
+use rand::prng::{ChaChaRng, Hc128Rng, IsaacRng, Isaac64Rng, SplitMix64,
+                 XorShiftRng, Xoshiro256StarStar, Xoshiro256StarStarX4};
+use rand::rngs::{SmallRng, StdRng, XofRng};
+use rand_fuzz::{dispatch, split_seed};
+
+fn check<R: SeedableRng + RngCore>(data: &[u8]) {
+    let (seed, steps) = split_seed::<R::Seed>(data);
+    let mut rng = R::from_seed(seed);
+    let mut reference = R::from_seed(split_seed::<R::Seed>(data).0);
+
+    let mut buf = [0u8; 8 * 0x7f];
+    for &step in steps {
+        let words = (step & 0x7f) as usize;
+        if step & 0x80 == 0 {
+            rng.fill_bytes(&mut buf[..8 * words]);
+            for chunk in buf[..8 * words].chunks(8) {
+                let x = chunk.iter().rev().fold(0, |x, &b| (x << 8) | b as u64);
+                assert_eq!(x, reference.next_u64());
+            }
+        } else {
+            for _ in 0..words {
+                assert_eq!(rng.next_u64(), reference.next_u64());
+            }
+        }
+    }
+}
+
+static CHECKS: [fn(&[u8]); 11] = [
+    check::<ChaChaRng>,
+    check::<Hc128Rng>,
+    check::<IsaacRng>,
+    check::<Isaac64Rng>,
+    check::<SplitMix64>,
+    check::<XorShiftRng>,
+    check::<Xoshiro256StarStar>,
+    check::<Xoshiro256StarStarX4>,
+    check::<SmallRng>,
+    check::<StdRng>,
+    check::<XofRng>,
+];
+
+fuzz_target!(|data: &[u8]| {
+    dispatch(data, &CHECKS)
+});
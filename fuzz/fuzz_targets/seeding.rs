@@ -0,0 +1,65 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Check that `from_seed` and `from_rng` do not panic for any seed, and
+//! that the seeded generators do not panic either.
+
+#![no_main]
+#[macro_use] extern crate libfuzzer_sys;
+extern crate rand;
+extern crate rand_fuzz;
+
+use rand::{RngCore, SeedableRng};
+use rand::prng::{ChaChaRng, Hc128Rng, IsaacRng, Isaac64Rng, SplitMix64,
+                 XorShiftRng, Xoshiro256StarStar, Xoshiro256StarStarX4};
+use rand::rngs::{CtrDrbg, HmacDrbg, SmallRng, StdRng, XofRng};
+use rand_fuzz::{dispatch, split_seed, InputRng};
+
+fn exercise<R: RngCore>(rng: &mut R) {
+    rng.next_u32();
+    rng.next_u64();
+    let mut buf = [0u8; 301];
+    rng.fill_bytes(&mut buf);
+    let _ = rng.try_fill_bytes(&mut buf);
+    // At least one of these is not aligned for the words of block RNGs
+    rng.fill_bytes(&mut buf[1..]);
+    let _ = rng.try_fill_bytes(&mut buf[3..]);
+}
+
+fn check<R: SeedableRng + RngCore>(data: &[u8]) {
+    let (seed, rest) = split_seed::<R::Seed>(data);
+    exercise(&mut R::from_seed(seed));
+
+    // The source fails once the input is exhausted, which must be reported
+    // as an error.
+    if let Ok(mut rng) = R::from_rng(InputRng::new(rest)) {
+        exercise(&mut rng);
+    }
+}
+
+static CHECKS: [fn(&[u8]); 13] = [
+    check::<ChaChaRng>,
+    check::<Hc128Rng>,
+    check::<IsaacRng>,
+    check::<Isaac64Rng>,
+    check::<SplitMix64>,
+    check::<XorShiftRng>,
+    check::<Xoshiro256StarStar>,
+    check::<Xoshiro256StarStarX4>,
+    check::<CtrDrbg>,
+    check::<HmacDrbg>,
+    check::<SmallRng>,
+    check::<StdRng>,
+    check::<XofRng>,
+];
+
+fuzz_target!(|data: &[u8]| {
+    dispatch(data, &CHECKS)
+});
@@ -0,0 +1,68 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Check that a generator restored from its serialized state produces the
+//! same output as the original, with bincode and with JSON, and that
+//! deserializing arbitrary input does not panic.
+
+#![no_main]
+#[macro_use] extern crate libfuzzer_sys;
+extern crate bincode;
+extern crate rand;
+extern crate rand_fuzz;
+extern crate serde;
+extern crate serde_json;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use rand::{RngCore, SeedableRng};
+use rand::prng::{IsaacRng, Isaac64Rng, SplitMix64, XorShiftRng,
+                 Xoshiro256StarStar};
+use rand_fuzz::{dispatch, split_seed};
+
+fn check<R>(data: &[u8])
+    where R: SeedableRng + RngCore + Serialize + DeserializeOwned
+{
+    let _ = bincode::deserialize::<R>(data);
+    let _ = serde_json::from_slice::<R>(data);
+
+    let (seed, rest) = split_seed::<R::Seed>(data);
+    let mut rng = R::from_seed(seed);
+    // Serialize at an arbitrary position in the output, which matters for
+    // the generators buffering a block of results.
+    for _ in 0..rest.first().map_or(0, |&n| n) {
+        rng.next_u32();
+    }
+
+    let bin = bincode::serialize(&rng).unwrap();
+    let mut from_bin: R = bincode::deserialize(&bin).unwrap();
+    let json = serde_json::to_vec(&rng).unwrap();
+    let mut from_json: R = serde_json::from_slice(&json).unwrap();
+
+    let mut expected = [0u8; 100];
+    let mut buf = [0u8; 100];
+    rng.fill_bytes(&mut expected);
+    from_bin.fill_bytes(&mut buf);
+    assert_eq!(&buf[..], &expected[..]);
+    from_json.fill_bytes(&mut buf);
+    assert_eq!(&buf[..], &expected[..]);
+}
+
+static CHECKS: [fn(&[u8]); 5] = [
+    check::<IsaacRng>,
+    check::<Isaac64Rng>,
+    check::<SplitMix64>,
+    check::<XorShiftRng>,
+    check::<Xoshiro256StarStar>,
+];
+
+fuzz_target!(|data: &[u8]| {
+    dispatch(data, &CHECKS)
+});
@@ -0,0 +1,95 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code shared by the fuzz targets in `fuzz_targets/`.
+//!
+//! Run a target with [cargo-fuzz] (nightly only), from the top-level
+//! directory:
+//!
+//! ```sh
+//! cargo fuzz run seeding
+//! cargo fuzz run serde_roundtrip
+//! cargo fuzz run fill_bytes
+//! ```
+//!
+//! Each target checks a property for many generators; the first byte of the
+//! input selects the generator and the rest is passed to the check.
+//!
+//! [cargo-fuzz]: https://github.com/rust-fuzz/cargo-fuzz
+
+extern crate rand;
+
+use std::cmp::min;
+
+use rand::{Error, ErrorKind, RngCore};
+
+/// Run one of `checks` on `data`, selected by the first byte of `data`.
+pub fn dispatch(data: &[u8], checks: &[fn(&[u8])]) {
+    if let Some((&selector, rest)) = data.split_first() {
+        checks[selector as usize % checks.len()](rest)
+    }
+}
+
+/// Take a seed from the start of `data`, returning the seed and the rest of
+/// the data. The seed is padded with zeros if `data` is too short.
+pub fn split_seed<S: AsMut<[u8]> + Default>(data: &[u8]) -> (S, &[u8]) {
+    let mut seed = S::default();
+    let len = min(seed.as_mut().len(), data.len());
+    seed.as_mut()[..len].copy_from_slice(&data[..len]);
+    (seed, &data[len..])
+}
+
+/// An RNG which returns the bytes of the fuzzer input.
+///
+/// `try_fill_bytes` fails once the input is exhausted, the other methods
+/// return zeros.
+#[derive(Debug)]
+pub struct InputRng<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> InputRng<'a> {
+    /// Create an RNG returning the bytes of `data`.
+    pub fn new(data: &'a [u8]) -> InputRng<'a> {
+        InputRng { data }
+    }
+}
+
+impl<'a> RngCore for InputRng<'a> {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.fill_bytes(&mut buf);
+        buf.iter().rev().fold(0, |x, &b| (x << 8) | b as u32)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.fill_bytes(&mut buf);
+        buf.iter().rev().fold(0, |x, &b| (x << 8) | b as u64)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let len = min(dest.len(), self.data.len());
+        dest[..len].copy_from_slice(&self.data[..len]);
+        for b in dest[len..].iter_mut() {
+            *b = 0;
+        }
+        self.data = &self.data[len..];
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        if dest.len() > self.data.len() {
+            self.data = &[];
+            return Err(Error::new(ErrorKind::Unavailable, "fuzzer input exhausted"));
+        }
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
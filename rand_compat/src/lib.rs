@@ -0,0 +1,178 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Wrappers implementing the traits of other `rand_core` versions for the
+//! random number generators of `rand`.
+//!
+//! [`RngCore05Compat`] and [`RngCore06Compat`] implement `RngCore`,
+//! `CryptoRng` and `SeedableRng` of `rand_core` 0.5 and 0.6, so the
+//! generators of `rand` can be passed to libraries using those versions:
+//!
+//! ```
+//! extern crate rand;
+//! extern crate rand_compat;
+//!
+//! use rand::SeedableRng;
+//! use rand::prng::Xoshiro256StarStar;
+//! use rand_compat::RngCore06Compat;
+//!
+//! # fn main() {
+//! let rng = RngCore06Compat::new(Xoshiro256StarStar::from_seed([7; 32]));
+//! # let _ = rng;
+//! # }
+//! ```
+//!
+//! This crate requires Rustc 1.36 or greater.
+//!
+//! [`RngCore05Compat`]: struct.RngCore05Compat.html
+//! [`RngCore06Compat`]: struct.RngCore06Compat.html
+
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
+       html_favicon_url = "https://www.rust-lang.org/favicon.ico",
+       html_root_url = "https://docs.rs/rand_compat/0.1.0")]
+
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+
+#![cfg_attr(not(feature="std"), no_std)]
+
+extern crate rand;
+extern crate rand_core_05;
+extern crate rand_core_06;
+
+use rand::{RngCore, CryptoRng, SeedableRng, Error};
+
+macro_rules! compat {
+    ($name:ident, $krate:ident, $version:expr) => {
+        #[doc = "A wrapper implementing the traits of `rand_core` "]
+        #[doc = $version]
+        #[doc = " for any RNG of `rand`.
+
+This allows the generators of `rand` to be used with libraries depending on a
+different major version of `rand_core`. `RngCore`, `CryptoRng` and
+`SeedableRng` are forwarded to the wrapped RNG. Errors are converted with
+`Error::new` when the `std` feature is enabled, and to the code
+`Error::CUSTOM_START` otherwise."]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name<R>(pub R);
+
+        impl<R> $name<R> {
+            /// Wrap `rng`.
+            pub fn new(rng: R) -> Self {
+                $name(rng)
+            }
+
+            /// Consume the wrapper, returning the wrapped RNG.
+            pub fn into_inner(self) -> R {
+                self.0
+            }
+        }
+
+        impl<R: RngCore> ::$krate::RngCore for $name<R> {
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                self.0.next_u32()
+            }
+
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                self.0.next_u64()
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.0.fill_bytes(dest)
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8])
+                -> Result<(), ::$krate::Error>
+            {
+                self.0.try_fill_bytes(dest).map_err(convert_error!($krate))
+            }
+        }
+
+        impl<R: RngCore + CryptoRng> ::$krate::CryptoRng for $name<R> {}
+
+        impl<R: SeedableRng> ::$krate::SeedableRng for $name<R> {
+            type Seed = R::Seed;
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                $name(R::from_seed(seed))
+            }
+        }
+    }
+}
+
+#[cfg(feature="std")]
+macro_rules! convert_error {
+    ($krate:ident) => { |e: Error| ::$krate::Error::new(e) }
+}
+
+#[cfg(not(feature="std"))]
+macro_rules! convert_error {
+    ($krate:ident) => {
+        |_: Error| {
+            let code = ::core::num::NonZeroU32::new(::$krate::Error::CUSTOM_START)
+                .unwrap();
+            ::$krate::Error::from(code)
+        }
+    }
+}
+
+compat!(RngCore05Compat, rand_core_05, "0.5");
+compat!(RngCore06Compat, rand_core_06, "0.6");
+
+#[cfg(test)]
+mod test {
+    use rand::{RngCore, SeedableRng, Error, ErrorKind};
+    use rand::prng::Xoshiro256StarStar;
+
+    struct FailingRng;
+
+    impl RngCore for FailingRng {
+        fn next_u32(&mut self) -> u32 { 0 }
+        fn next_u64(&mut self) -> u64 { 0 }
+        fn fill_bytes(&mut self, _: &mut [u8]) {}
+        fn try_fill_bytes(&mut self, _: &mut [u8]) -> Result<(), Error> {
+            Err(Error::new(ErrorKind::Unavailable, "no randomness"))
+        }
+    }
+
+    macro_rules! test_compat {
+        ($test:ident, $name:ident, $krate:ident) => {
+            #[test]
+            fn $test() {
+                use super::$name;
+                use $krate::{RngCore as RngCoreCompat,
+                             SeedableRng as SeedableRngCompat};
+
+                let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+                let mut compat = $name::new(rng.clone());
+                assert_eq!(compat.next_u32(), rng.next_u32());
+                assert_eq!(compat.next_u64(), rng.next_u64());
+                let mut buf = [0u8; 13];
+                let mut buf2 = [0u8; 13];
+                compat.try_fill_bytes(&mut buf).unwrap();
+                rng.fill_bytes(&mut buf2);
+                assert_eq!(buf, buf2);
+                assert_eq!(compat.into_inner().next_u64(), rng.next_u64());
+
+                let mut compat = <$name<Xoshiro256StarStar>>::from_seed([7; 32]);
+                let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+                assert_eq!(compat.next_u64(), rng.next_u64());
+
+                let mut compat = $name(FailingRng);
+                assert!(compat.try_fill_bytes(&mut buf).is_err());
+            }
+        }
+    }
+
+    test_compat!(test_rand_core_05, RngCore05Compat, rand_core_05);
+    test_compat!(test_rand_core_06, RngCore06Compat, rand_core_06);
+}
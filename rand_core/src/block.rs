@@ -53,11 +53,28 @@
 //! [`fill_bytes`]: ../trait.RngCore.html#tymethod.fill_bytes
 //! [`ReseedingRng`]: ../../rand/rngs/adapter/struct.ReseedingRng.html
 
+use core::cmp::min;
 use core::convert::AsRef;
 use core::fmt;
+use core::mem;
+use core::ptr;
 use {RngCore, CryptoRng, SeedableRng, Error};
 use impls::{fill_via_u32_chunks, fill_via_u64_chunks};
 
+// Like `&slice[from..]` and `&mut slice[from..]`, but returning an empty slice
+// instead of panicking if `from` is out of bounds. The compiler can not always
+// prove that the indices used by `fill_bytes` are in bounds, and `fill_bytes`
+// must not panic.
+#[inline(always)]
+fn tail<T>(slice: &[T], from: usize) -> &[T] {
+    slice.get(from..).unwrap_or(&[])
+}
+
+#[inline(always)]
+fn tail_mut<T>(slice: &mut [T], from: usize) -> &mut [T] {
+    slice.get_mut(from..).unwrap_or(&mut [])
+}
+
 /// A trait for RNGs which do not generate random numbers individually, but in
 /// blocks (typically `[u32; N]`). This technique is commonly used by
 /// cryptographic RNGs to improve performance.
@@ -118,6 +135,8 @@ pub trait BlockRngCore {
 /// [`SeedableRng`]: ../SeedableRng.t.html
 #[derive(Clone)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+#[cfg_attr(feature="rkyv", rkyv(deserialize_bounds(R: Sized)))]
 pub struct BlockRng<R: BlockRngCore + ?Sized> {
     results: R::Results,
     index: usize,
@@ -172,6 +191,28 @@ impl<R: BlockRngCore> BlockRng<R> {
     }
 }
 
+impl<R: BlockRngCore<Item=u32>> BlockRng<R>
+where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
+{
+    /// Fill `dest` with the next `u32` values, copying them straight from the
+    /// results buffer. This gives the same values as calling `next_u32` for
+    /// every element of `dest`.
+    pub fn next_u32_into(&mut self, dest: &mut [u32]) {
+        let mut filled = 0;
+        while filled < dest.len() {
+            if self.index >= self.results.as_ref().len() {
+                self.generate_and_set(0);
+            }
+            let src = tail(self.results.as_ref(), self.index);
+            let rest = tail_mut(dest, filled);
+            let n = min(src.len(), rest.len());
+            rest[..n].copy_from_slice(&src[..n]);
+            self.index += n;
+            filled += n;
+        }
+    }
+}
+
 impl<R: BlockRngCore<Item=u32>> RngCore for BlockRng<R>
 where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
 {
@@ -191,7 +232,7 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
         let read_u64 = |results: &[u32], index| {
             if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
                 // requires little-endian CPU supporting unaligned reads:
-                unsafe { *(&results[index] as *const u32 as *const u64) }
+                unsafe { ptr::read_unaligned(&results[index] as *const u32 as *const u64) }
             } else {
                 let x = u64::from(results[index]);
                 let y = u64::from(results[index + 1]);
@@ -218,8 +259,7 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
     }
 
     // As an optimization we try to write directly into the output buffer.
-    // This is only enabled for little-endian platforms where unaligned writes
-    // are known to be safe and fast.
+    // This is only enabled for little-endian platforms.
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
         let mut filled = 0;
@@ -227,20 +267,26 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
         // Continue filling from the current set of results
         if self.index < self.results.as_ref().len() {
             let (consumed_u32, filled_u8) =
-                fill_via_u32_chunks(&self.results.as_ref()[self.index..],
+                fill_via_u32_chunks(tail(self.results.as_ref(), self.index),
                                     dest);
 
             self.index += consumed_u32;
             filled += filled_u8;
         }
 
-        let len_remainder =
-            (dest.len() - filled) % (self.results.as_ref().len() * 4);
-        let end_direct = dest.len() - len_remainder;
+        // Only write directly into `dest` if it is aligned for `R::Results`,
+        // otherwise copy from `self.results`.
+        let aligned = (dest.as_ptr() as usize).wrapping_add(filled) %
+                      mem::align_of::<R::Results>() == 0;
+        let end_direct = if aligned {
+            dest.len() - (dest.len() - filled) % (self.results.as_ref().len() * 4)
+        } else {
+            filled
+        };
 
         while filled < end_direct {
             let dest_u32: &mut R::Results = unsafe {
-                &mut *(dest[filled..].as_mut_ptr() as
+                &mut *(dest.as_mut_ptr().offset(filled as isize) as
                 *mut <R as BlockRngCore>::Results)
             };
             self.core.generate(dest_u32);
@@ -248,13 +294,14 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
             self.index = self.results.as_ref().len();
         }
 
-        if len_remainder > 0 {
+        while filled < dest.len() {
             self.core.generate(&mut self.results);
-            let (consumed_u32, _) =
+            let (consumed_u32, filled_u8) =
                 fill_via_u32_chunks(self.results.as_ref(),
-                                    &mut dest[filled..]);
+                                    tail_mut(dest, filled));
 
             self.index = consumed_u32;
+            filled += filled_u8;
         }
     }
 
@@ -266,8 +313,8 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
                 self.generate_and_set(0);
             }
             let (consumed_u32, filled_u8) =
-                fill_via_u32_chunks(&self.results.as_ref()[self.index..],
-                                    &mut dest[read_len..]);
+                fill_via_u32_chunks(tail(self.results.as_ref(), self.index),
+                                    tail_mut(dest, read_len));
 
             self.index += consumed_u32;
             read_len += filled_u8;
@@ -278,6 +325,16 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
         self.fill_bytes(dest);
         Ok(())
     }
+
+    #[inline(always)]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        Ok(self.next_u32())
+    }
+
+    #[inline(always)]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        Ok(self.next_u64())
+    }
 }
 
 impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng<R> {
@@ -321,6 +378,8 @@ impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng<R> {
 /// [`BlockRng`]: struct.BlockRng.html
 #[derive(Clone)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+#[cfg_attr(feature="rkyv", rkyv(deserialize_bounds(R: Sized)))]
 pub struct BlockRng64<R: BlockRngCore + ?Sized> {
     results: R::Results,
     index: usize,
@@ -380,6 +439,29 @@ impl<R: BlockRngCore> BlockRng64<R> {
     }
 }
 
+impl<R: BlockRngCore<Item=u64>> BlockRng64<R>
+where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
+{
+    /// Fill `dest` with the next `u64` values, copying them straight from the
+    /// results buffer. This gives the same values as calling `next_u64` for
+    /// every element of `dest`.
+    pub fn next_u64_into(&mut self, dest: &mut [u64]) {
+        let mut filled = 0;
+        self.half_used = false;
+        while filled < dest.len() {
+            if self.index >= self.results.as_ref().len() {
+                self.generate_and_set(0);
+            }
+            let src = tail(self.results.as_ref(), self.index);
+            let rest = tail_mut(dest, filled);
+            let n = min(src.len(), rest.len());
+            rest[..n].copy_from_slice(&src[..n]);
+            self.index += n;
+            filled += n;
+        }
+    }
+}
+
 impl<R: BlockRngCore<Item=u64>> RngCore for BlockRng64<R>
 where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
 {
@@ -399,12 +481,11 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
 
         // Index as if this is a u32 slice.
         unsafe {
-            let results =
-                &*(self.results.as_ref() as *const [u64] as *const [u32]);
+            let results = self.results.as_ref().as_ptr() as *const u32;
             if cfg!(target_endian = "little") {
-                *results.get_unchecked(index)
+                *results.offset(index as isize)
             } else {
-                *results.get_unchecked(index ^ 1)
+                *results.offset((index ^ 1) as isize)
             }
         }
     }
@@ -423,8 +504,7 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
     }
 
     // As an optimization we try to write directly into the output buffer.
-    // This is only enabled for little-endian platforms where unaligned writes
-    // are known to be safe and fast.
+    // This is only enabled for little-endian platforms.
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
         let mut filled = 0;
@@ -433,33 +513,41 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
         // Continue filling from the current set of results
         if self.index < self.results.as_ref().len() {
             let (consumed_u64, filled_u8) =
-                fill_via_u64_chunks(&self.results.as_ref()[self.index..],
+                fill_via_u64_chunks(tail(self.results.as_ref(), self.index),
                                     dest);
 
             self.index += consumed_u64;
             filled += filled_u8;
         }
 
-        let len_remainder =
-            (dest.len() - filled) % (self.results.as_ref().len() * 8);
-        let end_direct = dest.len() - len_remainder;
+        // Only write directly into `dest` if it is aligned for `R::Results`,
+        // otherwise copy from `self.results`.
+        let aligned = (dest.as_ptr() as usize).wrapping_add(filled) %
+                      mem::align_of::<R::Results>() == 0;
+        let end_direct = if aligned {
+            dest.len() - (dest.len() - filled) % (self.results.as_ref().len() * 8)
+        } else {
+            filled
+        };
 
         while filled < end_direct {
             let dest_u64: &mut R::Results = unsafe {
-                ::core::mem::transmute(dest[filled..].as_mut_ptr())
+                &mut *(dest.as_mut_ptr().offset(filled as isize) as
+                *mut <R as BlockRngCore>::Results)
             };
             self.core.generate(dest_u64);
             filled += self.results.as_ref().len() * 8;
             self.index = self.results.as_ref().len();
         }
 
-        if len_remainder > 0 {
+        while filled < dest.len() {
             self.core.generate(&mut self.results);
-            let (consumed_u64, _) =
-                fill_via_u64_chunks(&mut self.results.as_ref(),
-                                    &mut dest[filled..]);
+            let (consumed_u64, filled_u8) =
+                fill_via_u64_chunks(self.results.as_ref(),
+                                    tail_mut(dest, filled));
 
             self.index = consumed_u64;
+            filled += filled_u8;
         }
     }
 
@@ -474,8 +562,8 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
             }
 
             let (consumed_u64, filled_u8) =
-                fill_via_u64_chunks(&self.results.as_ref()[self.index as usize..],
-                                    &mut dest[read_len..]);
+                fill_via_u64_chunks(tail(self.results.as_ref(), self.index),
+                                    tail_mut(dest, read_len));
 
             self.index += consumed_u64;
             read_len += filled_u8;
@@ -485,6 +573,16 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         Ok(self.fill_bytes(dest))
     }
+
+    #[inline(always)]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        Ok(self.next_u32())
+    }
+
+    #[inline(always)]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        Ok(self.next_u64())
+    }
 }
 
 impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng64<R> {
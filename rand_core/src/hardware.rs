@@ -0,0 +1,186 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `HardwareRng` trait for hardware random number generators
+//!
+//! Microcontrollers often have a true random number generator peripheral
+//! (TRNG), which produces one word at a time and signals with a status flag
+//! when the next word is ready. The [`HardwareRng`] trait is a minimal
+//! interface to such a peripheral, meant to be implemented by HAL crates
+//! without depending on the rest of Rand. The [`HardwareAdapter`] wrapper
+//! implements [`RngCore`] on top of it, so that the peripheral can seed
+//! generators with [`SeedableRng::from_rng`], or act as the reseeding source
+//! of [`ReseedingRng`].
+//!
+//! # Example
+//!
+//! ```
+//! use rand_core::{RngCore, Error, ErrorKind};
+//! use rand_core::hardware::{HardwareRng, HardwareAdapter};
+//!
+//! # struct Registers { sr: u32, dr: u32 }
+//! # impl Registers { fn read_sr(&self) -> u32 { self.sr } fn read_dr(&self) -> u32 { self.dr } }
+//! // A TRNG peripheral with a status register (SR) and a data register (DR)
+//! struct Trng { regs: Registers }
+//!
+//! impl HardwareRng for Trng {
+//!     fn read_word(&mut self) -> Result<Option<u32>, Error> {
+//!         let status = self.regs.read_sr();
+//!         if status & 0b110 != 0 {
+//!             return Err(Error::new(ErrorKind::Unavailable, "TRNG: clock or seed error"));
+//!         }
+//!         if status & 0b001 == 0 {
+//!             // Data not ready yet
+//!             return Ok(None);
+//!         }
+//!         Ok(Some(self.regs.read_dr()))
+//!     }
+//! }
+//!
+//! let trng = Trng { regs: Registers { sr: 1, dr: 0x1234_5678 } };
+//! let mut rng = HardwareAdapter::new(trng);
+//! let x = rng.try_next_u32().unwrap();
+//! ```
+//!
+//! [`HardwareRng`]: trait.HardwareRng.html
+//! [`HardwareAdapter`]: struct.HardwareAdapter.html
+//! [`RngCore`]: ../trait.RngCore.html
+//! [`SeedableRng::from_rng`]: ../trait.SeedableRng.html#method.from_rng
+//! [`ReseedingRng`]: ../../rand/rngs/adapter/struct.ReseedingRng.html
+
+use {RngCore, CryptoRng, Error, ErrorKind};
+
+/// Default number of times [`HardwareAdapter`] polls the peripheral for a
+/// word, before giving up.
+///
+/// [`HardwareAdapter`]: struct.HardwareAdapter.html
+pub const DEFAULT_MAX_POLLS: u32 = 100_000;
+
+/// A hardware random number generator, which produces one `u32` at a time.
+///
+/// Implementations should also implement [`CryptoRng`] if the output of the
+/// peripheral is suitable for cryptography (for example after on-chip
+/// conditioning); [`HardwareAdapter`] then implements it too.
+///
+/// See the [module documentation](index.html) for details.
+///
+/// [`CryptoRng`]: ../trait.CryptoRng.html
+/// [`HardwareAdapter`]: struct.HardwareAdapter.html
+pub trait HardwareRng {
+    /// Read the next word from the peripheral, without blocking.
+    ///
+    /// Returns `Ok(None)` if the next word is not ready yet. Faults reported
+    /// by the peripheral, such as clock errors or failed health tests, should
+    /// be returned as errors: [`ErrorKind::Transient`] if reading again may
+    /// succeed, and [`ErrorKind::Unavailable`] if the peripheral has to be
+    /// reset first.
+    ///
+    /// [`ErrorKind::Transient`]: ../enum.ErrorKind.html#variant.Transient
+    /// [`ErrorKind::Unavailable`]: ../enum.ErrorKind.html#variant.Unavailable
+    fn read_word(&mut self) -> Result<Option<u32>, Error>;
+}
+
+impl<'a, H: HardwareRng + ?Sized> HardwareRng for &'a mut H {
+    fn read_word(&mut self) -> Result<Option<u32>, Error> {
+        (**self).read_word()
+    }
+}
+
+/// A wrapper type implementing [`RngCore`] for some type implementing
+/// [`HardwareRng`].
+///
+/// Each method polls the peripheral until the required words are ready.
+/// After [`DEFAULT_MAX_POLLS`] unsuccessful polls for a word (see
+/// [`set_max_polls`]), [`try_fill_bytes`] fails with
+/// [`ErrorKind::NotReady`]. Errors reported by the peripheral are passed on.
+/// The methods which cannot return an error panic in both cases.
+///
+/// [`fill_bytes`] and [`try_fill_bytes`] consume a whole number of words,
+/// converting each word to bytes in little-endian order.
+///
+/// [`RngCore`]: ../trait.RngCore.html
+/// [`HardwareRng`]: trait.HardwareRng.html
+/// [`DEFAULT_MAX_POLLS`]: constant.DEFAULT_MAX_POLLS.html
+/// [`set_max_polls`]: #method.set_max_polls
+/// [`fill_bytes`]: ../trait.RngCore.html#tymethod.fill_bytes
+/// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
+/// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+#[derive(Debug)]
+pub struct HardwareAdapter<H: HardwareRng> {
+    hw: H,
+    max_polls: u32,
+}
+
+impl<H: HardwareRng> HardwareAdapter<H> {
+    /// Create a new `HardwareAdapter` for the peripheral `hw`.
+    pub fn new(hw: H) -> HardwareAdapter<H> {
+        HardwareAdapter { hw, max_polls: DEFAULT_MAX_POLLS }
+    }
+
+    /// Set the number of times to poll the peripheral for a word, before
+    /// giving up. Must be at least 1.
+    pub fn set_max_polls(&mut self, max_polls: u32) {
+        assert!(max_polls > 0, "HardwareAdapter: max_polls must be positive");
+        self.max_polls = max_polls;
+    }
+
+    /// Unwrap the peripheral.
+    pub fn into_inner(self) -> H {
+        self.hw
+    }
+
+    fn try_next_word(&mut self) -> Result<u32, Error> {
+        for _ in 0..self.max_polls {
+            if let Some(x) = self.hw.read_word()? {
+                return Ok(x);
+            }
+        }
+        Err(Error::new(ErrorKind::NotReady, "hardware RNG timed out"))
+    }
+}
+
+impl<H: HardwareRng> RngCore for HardwareAdapter<H> {
+    fn next_u32(&mut self) -> u32 {
+        self.try_next_u32().unwrap_or_else(|err|
+            panic!("HardwareAdapter: {}", err))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.try_next_u64().unwrap_or_else(|err|
+            panic!("HardwareAdapter: {}", err))
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+            panic!("HardwareAdapter: {}", err))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        for chunk in dest.chunks_mut(4) {
+            let x = self.try_next_word()?;
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (x >> (8 * i)) as u8;
+            }
+        }
+        Ok(())
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        self.try_next_word()
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        let x = u64::from(self.try_next_word()?);
+        let y = u64::from(self.try_next_word()?);
+        Ok((y << 32) | x)
+    }
+}
+
+impl<H: HardwareRng + CryptoRng> CryptoRng for HardwareAdapter<H> {}
@@ -40,9 +40,24 @@ pub fn next_u64_via_u32<R: RngCore + ?Sized>(rng: &mut R) -> u64 {
 /// The fastest way to fill a slice is usually to work as long as possible with
 /// integers. That is why this method mostly uses `next_u64`, and only when
 /// there are 4 or less bytes remaining at the end of the slice it uses
-/// `next_u32` once.
+/// `next_u32` once. Values are collected in a small buffer, which is copied to
+/// `dest` as a whole.
 pub fn fill_bytes_via_next<R: RngCore + ?Sized>(rng: &mut R, dest: &mut [u8]) {
+    const BUF_LEN: usize = 16;
     let mut left = dest;
+    while left.len() >= 8 * BUF_LEN {
+        let (l, r) = {left}.split_at_mut(8 * BUF_LEN);
+        left = r;
+        let mut buf = [0u64; BUF_LEN];
+        for x in buf.iter_mut() {
+            *x = rng.next_u64().to_le();
+        }
+        unsafe {
+            copy_nonoverlapping(buf.as_ptr() as *const u8,
+                                l.as_mut_ptr(),
+                                8 * BUF_LEN);
+        }
+    }
     while left.len() >= 8 {
         let (l, r) = {left}.split_at_mut(8);
         left = r;
@@ -31,6 +31,22 @@ macro_rules! read_slice {
     }};
 }
 
+macro_rules! write_slice {
+    ($src:expr, $dst:expr, $size:expr) => {{
+        assert_eq!($size * $src.len(), $dst.len());
+
+        for (chunk, v) in $dst.chunks_mut($size).zip($src.iter()) {
+            let v = v.to_le();
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    &v as *const _ as *const u8,
+                    chunk.as_mut_ptr(),
+                    $size);
+            }
+        }
+    }};
+}
+
 /// Reads unsigned 32 bit integers from `src` into `dst`.
 /// Borrowed from the `byteorder` crate.
 #[inline]
@@ -45,6 +61,18 @@ pub fn read_u64_into(src: &[u8], dst: &mut [u64]) {
     read_slice!(src, dst, 8, to_le);
 }
 
+/// Writes unsigned 32 bit integers from `src` into `dst`.
+#[inline]
+pub fn write_u32_into(src: &[u32], dst: &mut [u8]) {
+    write_slice!(src, dst, 4);
+}
+
+/// Writes unsigned 64 bit integers from `src` into `dst`.
+#[inline]
+pub fn write_u64_into(src: &[u64], dst: &mut [u8]) {
+    write_slice!(src, dst, 8);
+}
+
 #[test]
 fn test_read() {
     let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
@@ -68,3 +96,17 @@ fn test_read() {
     read_u64_into(&bytes[7..15], &mut buf); // unaligned
     assert_eq!(buf[0], 0x0F0E0D0C0B0A0908);
 }
+
+#[test]
+fn test_write() {
+    let mut bytes = [0u8; 17];
+    write_u32_into(&[0x04030201, 0x08070605], &mut bytes[1..9]); // unaligned
+    assert_eq!(bytes[..9], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+    write_u64_into(&[0x100F0E0D0C0B0A09], &mut bytes[9..]);
+    assert_eq!(bytes[9..], [9, 10, 11, 12, 13, 14, 15, 16]);
+
+    let mut buf = [0u64; 1];
+    read_u64_into(&bytes[1..9], &mut buf);
+    assert_eq!(buf[0], 0x0807060504030201);
+}
@@ -25,6 +25,9 @@
 //! 
 //! The [`impls`] and [`le`] sub-modules include a few small functions to assist
 //! implementation of [`RngCore`].
+//!
+//! The [`hardware`] sub-module defines a minimal trait for hardware random
+//! number generators, such as the TRNG peripherals of microcontrollers.
 //! 
 //! [rand]: https://crates.io/crates/rand
 //! [`RngCore`]: trait.RngCore.html
@@ -32,6 +35,7 @@
 //! [`Error`]: struct.Error.html
 //! [`impls`]: impls/index.html
 //! [`le`]: le/index.html
+//! [`hardware`]: hardware/index.html
 
 #![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
        html_favicon_url = "https://www.rust-lang.org/favicon.ico",
@@ -43,11 +47,14 @@
 
 #![cfg_attr(not(feature="std"), no_std)]
 #![cfg_attr(all(feature="alloc", not(feature="std")), feature(alloc))]
+// The rkyv `Deserialize` impls of the block wrappers require `R: Sized`
+#![cfg_attr(feature="rkyv", allow(clippy::needless_maybe_sized))]
 
 #[cfg(feature="std")] extern crate core;
 #[cfg(all(feature = "alloc", not(feature="std")))] extern crate alloc;
 #[cfg(feature="serde1")] extern crate serde;
 #[cfg(feature="serde1")] #[macro_use] extern crate serde_derive;
+#[cfg(feature="rkyv")] extern crate rkyv;
 
 
 use core::default::Default;
@@ -60,6 +67,7 @@ pub use error::{ErrorKind, Error};
 
 mod error;
 pub mod block;
+pub mod hardware;
 pub mod impls;
 pub mod le;
 
@@ -190,6 +198,101 @@ pub trait RngCore {
     /// 
     /// [`fill_bytes`]: trait.RngCore.html#method.fill_bytes
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error>;
+
+    /// Return the next random `u32`, or an error if the RNG failed.
+    ///
+    /// Like [`try_fill_bytes`], this never panics, so it can be used where
+    /// errors have to be handled, for example with an external RNG like
+    /// `OsRng`. The default implementation reads four bytes with
+    /// [`try_fill_bytes`] in little-endian order. Infallible generators may
+    /// implement this as `Ok(self.next_u32())`.
+    ///
+    /// [`try_fill_bytes`]: trait.RngCore.html#tymethod.try_fill_bytes
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        self.try_fill_bytes(&mut bytes)?;
+        let mut result = [0u32; 1];
+        le::read_u32_into(&bytes, &mut result);
+        Ok(result[0])
+    }
+
+    /// Return the next random `u64`, or an error if the RNG failed.
+    ///
+    /// Like [`try_fill_bytes`], this never panics. The default implementation
+    /// reads eight bytes with [`try_fill_bytes`] in little-endian order.
+    /// Infallible generators may implement this as `Ok(self.next_u64())`.
+    ///
+    /// [`try_fill_bytes`]: trait.RngCore.html#tymethod.try_fill_bytes
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        self.try_fill_bytes(&mut bytes)?;
+        let mut result = [0u64; 1];
+        le::read_u64_into(&bytes, &mut result);
+        Ok(result[0])
+    }
+
+    /// Convert a mutable reference to this RNG into an adapter implementing
+    /// `std::io::Read`.
+    ///
+    /// This makes it possible to use an RNG wherever a reader is expected,
+    /// for example with `std::io::copy`. Errors from [`try_fill_bytes`] are
+    /// converted to `std::io::Error`.
+    ///
+    /// ```
+    /// use std::io::{self, Read};
+    /// use rand_core::RngCore;
+    /// # use rand_core::{Error, impls};
+    /// # struct CountingRng(u64);
+    /// # impl RngCore for CountingRng {
+    /// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+    /// #     fn next_u64(&mut self) -> u64 { self.0 += 1; self.0 }
+    /// #     fn fill_bytes(&mut self, dest: &mut [u8]) {
+    /// #         impls::fill_bytes_via_next(self, dest)
+    /// #     }
+    /// #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    /// #         Ok(self.fill_bytes(dest))
+    /// #     }
+    /// # }
+    ///
+    /// let mut rng = CountingRng(0);
+    /// let mut file = Vec::new();
+    /// // Write 1 MiB of random data
+    /// io::copy(&mut rng.read_adapter().take(1 << 20), &mut file).unwrap();
+    /// assert_eq!(file.len(), 1 << 20);
+    /// ```
+    ///
+    /// [`try_fill_bytes`]: trait.RngCore.html#tymethod.try_fill_bytes
+    #[cfg(feature="std")]
+    fn read_adapter<'a>(&'a mut self) -> RngReadAdapter<'a, Self> where Self: Sized {
+        RngReadAdapter { rng: self }
+    }
+}
+
+/// An adapter implementing `std::io::Read` for an [`RngCore`].
+///
+/// This struct is created by [`RngCore::read_adapter`].
+///
+/// [`RngCore`]: trait.RngCore.html
+/// [`RngCore::read_adapter`]: trait.RngCore.html#method.read_adapter
+#[cfg(feature="std")]
+pub struct RngReadAdapter<'a, R: RngCore + ?Sized + 'a> {
+    rng: &'a mut R,
+}
+
+#[cfg(feature="std")]
+impl<'a, R: RngCore + ?Sized> std::io::Read for RngReadAdapter<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        self.rng.try_fill_bytes(buf)?;
+        Ok(buf.len())
+    }
+}
+
+// Custom Debug implementation that does not expose the state of the RNG
+#[cfg(feature="std")]
+impl<'a, R: RngCore + ?Sized> core::fmt::Debug for RngReadAdapter<'a, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("RngReadAdapter").finish()
+    }
 }
 
 /// A marker trait used to indicate that an [`RngCore`] or [`BlockRngCore`]
@@ -212,6 +315,19 @@ pub trait RngCore {
 /// 
 /// Note also that use of a `CryptoRng` does not protect against other
 /// weaknesses such as seeding from a weak entropy source or leaking state.
+///
+/// APIs which need secure random data can require it at compile time:
+///
+/// ```
+/// # #![allow(dead_code)]
+/// use rand_core::{RngCore, CryptoRng};
+///
+/// fn generate_key<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; 16] {
+///     let mut key = [0u8; 16];
+///     rng.fill_bytes(&mut key);
+///     key
+/// }
+/// ```
 /// 
 /// [`RngCore`]: trait.RngCore.html
 /// [`BlockRngCore`]: ../rand_core/block/trait.BlockRngCore.html
@@ -247,6 +363,7 @@ pub trait SeedableRng: Sized {
     /// used:
     ///
     /// ```
+    /// # #![allow(dead_code)]
     /// use rand_core::SeedableRng;
     ///
     /// const N: usize = 64;
@@ -360,6 +477,16 @@ impl<'a, R: RngCore + ?Sized> RngCore for &'a mut R {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         (**self).try_fill_bytes(dest)
     }
+
+    #[inline(always)]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        (**self).try_next_u32()
+    }
+
+    #[inline(always)]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        (**self).try_next_u64()
+    }
 }
 
 // Implement `RngCore` for boxed references to an `RngCore`.
@@ -386,8 +513,98 @@ impl<R: RngCore + ?Sized> RngCore for Box<R> {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         (**self).try_fill_bytes(dest)
     }
+
+    #[inline(always)]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        (**self).try_next_u32()
+    }
+
+    #[inline(always)]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        (**self).try_next_u64()
+    }
+}
+
+// Implement `RngCore` for a borrow of an `RngCore` from a `RefCell`, so that
+// a generator shared within a thread can be passed where `RngCore` is
+// expected.
+impl<'a, R: RngCore + ?Sized> RngCore for core::cell::RefMut<'a, R> {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        (**self).next_u32()
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+
+    #[inline(always)]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest)
+    }
+
+    #[inline(always)]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        (**self).try_fill_bytes(dest)
+    }
+
+    #[inline(always)]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        (**self).try_next_u32()
+    }
+
+    #[inline(always)]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        (**self).try_next_u64()
+    }
+}
+
+// Implement `RngCore` for a locked `Mutex` holding an `RngCore`, so that a
+// generator shared between threads can be passed where `RngCore` is expected.
+#[cfg(feature="std")]
+impl<'a, R: RngCore + ?Sized> RngCore for std::sync::MutexGuard<'a, R> {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        (**self).next_u32()
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+
+    #[inline(always)]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest)
+    }
+
+    #[inline(always)]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        (**self).try_fill_bytes(dest)
+    }
+
+    #[inline(always)]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        (**self).try_next_u32()
+    }
+
+    #[inline(always)]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        (**self).try_next_u64()
+    }
 }
 
+impl<'a, R: CryptoRng + ?Sized> CryptoRng for &'a mut R {}
+
+#[cfg(feature="alloc")]
+impl<R: CryptoRng + ?Sized> CryptoRng for Box<R> {}
+
+impl<'a, R: CryptoRng + ?Sized> CryptoRng for core::cell::RefMut<'a, R> {}
+
+#[cfg(feature="std")]
+impl<'a, R: CryptoRng + ?Sized> CryptoRng for std::sync::MutexGuard<'a, R> {}
+
 #[cfg(feature="std")]
 impl std::io::Read for RngCore {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
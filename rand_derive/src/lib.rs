@@ -0,0 +1,137 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Custom derives for the [`rand`] crate.
+//!
+//! This crate is not intended to be used directly; enable the `derive`
+//! feature of `rand` instead, which re-exports the derives.
+//!
+//! [`rand`]: https://docs.rs/rand
+
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
+       html_favicon_url = "https://www.rust-lang.org/favicon.ico",
+       html_root_url = "https://docs.rs/rand_derive/0.5.0-pre.2")]
+
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Derive `Distribution<T> for Standard` for a fieldless enum `T`, picking
+/// one of the variants uniformly at random.
+///
+/// Enums with fields, generic enums and enums without variants are rejected
+/// with a compile error.
+#[proc_macro_derive(UniformEnum)]
+pub fn derive_uniform_enum(input: TokenStream) -> TokenStream {
+    let code = match parse_enum(input) {
+        Ok((name, variants)) => impl_uniform_enum(&name, &variants),
+        Err(msg) => format!("compile_error!({:?});", msg),
+    };
+    code.parse().expect("UniformEnum: generated invalid code")
+}
+
+/// Generate the `Distribution` implementation for the enum `name`.
+fn impl_uniform_enum(name: &str, variants: &[String]) -> String {
+    let mut arms = String::new();
+    for (i, variant) in variants.iter().enumerate() {
+        arms.push_str(&format!("{}u32 => {}::{},\n", i, name, variant));
+    }
+    format!("
+        impl ::rand::distributions::Distribution<{name}>
+            for ::rand::distributions::Standard
+        {{
+            #[inline]
+            fn sample<R: ::rand::Rng + ?Sized>(&self, rng: &mut R) -> {name} {{
+                match ::rand::Rng::gen_range(rng, 0u32, {n}u32) {{
+                    {arms}
+                    _ => unreachable!(),
+                }}
+            }}
+        }}",
+        name = name, n = variants.len(), arms = arms)
+}
+
+/// Parse the name and variant names of a fieldless enum.
+fn parse_enum(input: TokenStream) -> Result<(String, Vec<String>), String> {
+    let mut tokens = input.into_iter();
+
+    // Skip attributes and visibility, up to the `enum` keyword.
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Ident(ref ident)) if ident.to_string() == "enum" => break,
+            Some(TokenTree::Ident(ref ident))
+                if ident.to_string() == "struct" || ident.to_string() == "union" =>
+            {
+                return Err("UniformEnum can only be derived for enums".to_string());
+            }
+            Some(_) => {}
+            None => return Err("UniformEnum: expected an enum".to_string()),
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("UniformEnum: expected the name of the enum".to_string()),
+    };
+
+    let body = match tokens.next() {
+        Some(TokenTree::Group(ref group)) if group.delimiter() == Delimiter::Brace => {
+            group.stream()
+        }
+        Some(TokenTree::Punct(ref punct)) if punct.as_char() == '<' => {
+            return Err("UniformEnum cannot be derived for generic enums".to_string());
+        }
+        _ => return Err("UniformEnum: expected the body of the enum".to_string()),
+    };
+
+    let mut variants = Vec::new();
+    // Whether we are at the start of a variant, i.e. before its name
+    let mut expect_name = true;
+    // Whether we are inside an explicit discriminant, which is skipped
+    let mut in_discriminant = false;
+    let mut body = body.into_iter();
+    while let Some(token) = body.next() {
+        match token {
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => {
+                expect_name = true;
+                in_discriminant = false;
+            }
+            _ if in_discriminant => {}
+            TokenTree::Punct(ref punct) if punct.as_char() == '#' && expect_name => {
+                // Skip the attribute `#[...]` of the variant.
+                body.next();
+            }
+            TokenTree::Ident(ident) if expect_name => {
+                variants.push(ident.to_string());
+                expect_name = false;
+            }
+            TokenTree::Punct(ref punct) if punct.as_char() == '=' => {
+                in_discriminant = true;
+            }
+            TokenTree::Group(_) => {
+                return Err(format!("UniformEnum: variant `{}::{}` has fields, which \
+                                    is not supported", name,
+                                   variants.last().map(|s| s.as_str()).unwrap_or("")));
+            }
+            token => {
+                return Err(format!("UniformEnum: unexpected token `{}`", token));
+            }
+        }
+    }
+
+    if variants.is_empty() {
+        return Err(format!("UniformEnum cannot be derived for `{}`, which has no \
+                            variants", name));
+    }
+    Ok((name, variants))
+}
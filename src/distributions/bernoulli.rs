@@ -67,6 +67,32 @@ impl Bernoulli {
         };
         Bernoulli { p_int }
     }
+
+    /// Construct a new `Bernoulli` with the probability of success of
+    /// `numerator`-in-`denominator`. I.e. `from_ratio(2, 3)` will return
+    /// a `Bernoulli` with a 2-in-3 chance, or about 67%, of returning `true`.
+    ///
+    /// If `numerator == denominator` then the returned `Bernoulli` will always
+    /// return `true`. If `numerator == 0` it will always return `false`.
+    ///
+    /// # Panics
+    ///
+    /// If `denominator == 0` or `numerator > denominator`.
+    #[inline]
+    pub fn from_ratio(numerator: u32, denominator: u32) -> Bernoulli {
+        assert!(denominator != 0, "Bernoulli::from_ratio called with `denominator == 0`");
+        assert!(numerator <= denominator,
+                "Bernoulli::from_ratio called with `numerator > denominator`");
+        if numerator == denominator {
+            return Bernoulli { p_int: ::core::u64::MAX };
+        }
+        // Compute `numerator * 2^64 / denominator` with two 64-bit divisions
+        // of 32 bits each; this is exact since `numerator < denominator`.
+        let (num, den) = (numerator as u64, denominator as u64);
+        let high = (num << 32) / den;
+        let low = (((num << 32) % den) << 32) / den;
+        Bernoulli { p_int: (high << 32) | low }
+    }
 }
 
 impl Distribution<bool> for Bernoulli {
@@ -100,6 +126,26 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_from_ratio() {
+        let mut r = ::test::rng(3);
+        let always_false = Bernoulli::from_ratio(0, 7);
+        let always_true = Bernoulli::from_ratio(7, 7);
+        for _ in 0..5 {
+            assert_eq!(r.sample::<bool, _>(&always_false), false);
+            assert_eq!(r.sample::<bool, _>(&always_true), true);
+        }
+
+        assert_eq!(Bernoulli::from_ratio(1, 2).p_int, Bernoulli::new(0.5).p_int);
+        assert_eq!(Bernoulli::from_ratio(3, 4).p_int, Bernoulli::new(0.75).p_int);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_ratio_invalid() {
+        Bernoulli::from_ratio(3, 2);
+    }
+
     #[test]
     fn test_average() {
         const P: f64 = 0.3;
@@ -13,7 +13,8 @@
 use Rng;
 use distributions::{Distribution, Bernoulli};
 use distributions::log_gamma::log_gamma;
-use std::f64::consts::PI;
+use core::f64::consts::PI;
+#[cfg(not(feature="std"))] use distributions::math::Float;
 
 /// The binomial distribution `Binomial(n, p)`.
 ///
@@ -12,6 +12,7 @@
 
 use {Rng};
 use distributions::{ziggurat, ziggurat_tables, Distribution};
+#[cfg(not(feature="std"))] use distributions::math::Float;
 
 /// Samples floating-point numbers according to the exponential distribution,
 /// with rate parameter `λ = 1`. This is equivalent to `Exp::new(1.0)` or
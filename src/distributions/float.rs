@@ -11,17 +11,22 @@
 //! Basic floating-point number distributions
 
 use core::mem;
-use Rng;
+#[cfg(feature = "simd_support")]
+use core::simd::prelude::*;
+use {Rng, Error, Fill, FillFloats};
 use distributions::{Distribution, Standard};
 
 /// A distribution to sample floating point numbers uniformly in the half-open
 /// interval `(0, 1]`, i.e. including 1 but not 0.
 ///
 /// All values that can be generated are of the form `n * ε/2`. For `f32`
-/// the 23 most significant random bits of a `u32` are used and for `f64` the
+/// the 24 most significant random bits of a `u32` are used and for `f64` the
 /// 53 most significant bits of a `u64` are used. The conversion uses the
 /// multiplicative method.
 ///
+/// Since 0 is never sampled, the result can safely be passed to functions
+/// like `ln`.
+///
 /// See also: [`Standard`] which samples from `[0, 1)`, [`Open01`]
 /// which samples from `(0, 1)` and [`Uniform`] which samples from arbitrary
 /// ranges.
@@ -32,7 +37,7 @@ use distributions::{Distribution, Standard};
 /// use rand::distributions::OpenClosed01;
 ///
 /// let val: f32 = thread_rng().sample(OpenClosed01);
-/// println!("f32 from (0, 1): {}", val);
+/// println!("f32 from (0, 1]: {}", val);
 /// ```
 ///
 /// [`Standard`]: struct.Standard.html
@@ -48,6 +53,9 @@ pub struct OpenClosed01;
 /// the 22 most significant random bits of an `u32` are used, for `f64` 52 from
 /// an `u64`. The conversion uses a transmute-based method.
 ///
+/// Since neither 0 nor 1 is ever sampled, the result can safely be passed to
+/// functions like `ln` and `ln(1 - x)`.
+///
 /// See also: [`Standard`] which samples from `[0, 1)`, [`OpenClosed01`]
 /// which samples from `(0, 1]` and [`Uniform`] which samples from arbitrary
 /// ranges.
@@ -67,6 +75,44 @@ pub struct OpenClosed01;
 #[derive(Clone, Copy, Debug)]
 pub struct Open01;
 
+/// A distribution to sample floating point numbers uniformly in the half-open
+/// interval `[0, 1)`, with the full precision of the floating point type.
+///
+/// [`Standard`] only generates values of the form `n * ε/2`, so for example
+/// the smallest non-zero `f64` it can return is 2<sup>-53</sup>. This
+/// distribution can return every representable value in `[0, 1)`, each with a
+/// probability proportional to the interval of reals that round down to it.
+///
+/// To achieve this, the exponent is sampled from a geometric distribution:
+/// the random bits not used for the fraction are consumed one at a time, and
+/// every zero bit halves the magnitude of the result. Only in the rare case
+/// that all those bits are zero (once in 2<sup>12</sup> samples for `f64`,
+/// 2<sup>9</sup> for `f32`) more random numbers are drawn. This makes it
+/// a bit slower than [`Standard`].
+///
+/// This is useful when the result is mapped through a function with large
+/// values near 0, for example an inverse cumulative distribution function
+/// used to sample the extreme tails of a distribution.
+///
+/// See also: [`Standard`] which samples from `[0, 1)` with a fixed precision,
+/// [`Open01`] which samples from `(0, 1)` and [`OpenClosed01`] which samples
+/// from `(0, 1]`.
+///
+/// # Example
+/// ```
+/// use rand::{thread_rng, Rng};
+/// use rand::distributions::HighPrecision01;
+///
+/// let val: f64 = thread_rng().sample(HighPrecision01);
+/// println!("f64 from [0, 1): {}", val);
+/// ```
+///
+/// [`Standard`]: struct.Standard.html
+/// [`Open01`]: struct.Open01.html
+/// [`OpenClosed01`]: struct.OpenClosed01.html
+#[derive(Clone, Copy, Debug)]
+pub struct HighPrecision01;
+
 
 pub(crate) trait IntoFloat {
     type F;
@@ -82,6 +128,25 @@ pub(crate) trait IntoFloat {
     fn into_float_with_exponent(self, exponent: i32) -> Self::F;
 }
 
+// Same conversion as for `Standard`, applied to batches of integers
+// generated with a single call to `$fill`.
+macro_rules! fill_floats {
+    ($dest:expr, $ty:ty, $uty:ty, $fraction_bits:expr, |$buf:ident| $fill:expr) => {
+        let float_size = mem::size_of::<$ty>() * 8;
+        let precision = $fraction_bits + 1;
+        let scale = 1.0 / ((1 as $uty << precision) as $ty);
+
+        let mut buf = [0 as $uty; 32];
+        for chunk in $dest.chunks_mut(32) {
+            let $buf = &mut buf[..chunk.len()];
+            $fill;
+            for (x, &value) in chunk.iter_mut().zip($buf.iter()) {
+                *x = scale * (value >> (float_size - precision)) as $ty;
+            }
+        }
+    }
+}
+
 macro_rules! float_impls {
     ($ty:ty, $uty:ty, $fraction_bits:expr, $exponent_bias:expr) => {
         impl IntoFloat for $uty {
@@ -109,6 +174,23 @@ macro_rules! float_impls {
             }
         }
 
+        impl Fill for [$ty] {
+            fn fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+                fill_floats!(self, $ty, $uty, $fraction_bits,
+                             |buf| rng.fill(buf));
+            }
+
+            fn try_fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R)
+                -> Result<(), Error>
+            {
+                fill_floats!(self, $ty, $uty, $fraction_bits,
+                             |buf| rng.try_fill(buf)?);
+                Ok(())
+            }
+        }
+
+        impl FillFloats for [$ty] {}
+
         impl Distribution<$ty> for OpenClosed01 {
             fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
                 // Multiply-based method; 24/53 random bits; (0, 1] interval.
@@ -138,16 +220,131 @@ macro_rules! float_impls {
                 fraction.into_float_with_exponent(0) - (1.0 - EPSILON / 2.0)
             }
         }
+
+        impl Distribution<$ty> for HighPrecision01 {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                // The exponent of the smallest normal number; smaller numbers
+                // are subnormal, with all exponent bits 0.
+                const MIN_EXPONENT: i32 = 1 - $exponent_bias;
+                let float_size = mem::size_of::<$ty>() as i32 * 8;
+
+                let value: $uty = rng.gen();
+                let fraction = value & ((1 << $fraction_bits) - 1);
+
+                // Use the remaining bits to sample the exponent: every leading
+                // zero halves the result, starting from the range [0.5, 1).
+                let mut exponent = -1;
+                let exponent_bits = value >> $fraction_bits;
+                if exponent_bits != 0 {
+                    exponent -= exponent_bits.leading_zeros() as i32 - $fraction_bits;
+                } else {
+                    exponent -= float_size - $fraction_bits;
+                    while exponent >= MIN_EXPONENT {
+                        let bits: $uty = rng.gen();
+                        exponent -= bits.leading_zeros() as i32;
+                        if bits != 0 {
+                            break;
+                        }
+                    }
+                }
+                if exponent < MIN_EXPONENT {
+                    exponent = MIN_EXPONENT - 1;
+                }
+                fraction.into_float_with_exponent(exponent)
+            }
+        }
     }
 }
 float_impls! { f32, u32, 23, 127 }
 float_impls! { f64, u64, 52, 1023 }
 
+macro_rules! impl_fill_float_arrays {
+    ($n:expr,) => {};
+    ($n:expr, $N:ident, $($NN:ident,)*) => {
+        impl_fill_float_arrays!($n - 1, $($NN,)*);
+        impl_fill_float_arrays!(@impl $n);
+    };
+    (!div $n:expr,) => {};
+    (!div $n:expr, $N:ident, $($NN:ident,)*) => {
+        impl_fill_float_arrays!(!div $n / 2, $($NN,)*);
+        impl_fill_float_arrays!(@impl $n);
+    };
+    (@impl $n:expr) => {
+        impl Fill for [f32; $n] {
+            fn fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+                self[..].fill_from(rng)
+            }
+
+            fn try_fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R)
+                -> Result<(), Error>
+            {
+                self[..].try_fill_from(rng)
+            }
+        }
+
+        impl Fill for [f64; $n] {
+            fn fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+                self[..].fill_from(rng)
+            }
+
+            fn try_fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R)
+                -> Result<(), Error>
+            {
+                self[..].try_fill_from(rng)
+            }
+        }
+    };
+}
+impl_fill_float_arrays!(32, N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,N,);
+impl_fill_float_arrays!(!div 4096, N,N,N,N,N,N,N,);
+
+#[cfg(feature = "simd_support")]
+macro_rules! simd_float_impls {
+    ($ty:ty, $uty:ty, $fraction_bits:expr) => {
+        impl<const N: usize> Distribution<Simd<$ty, N>> for Standard {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Simd<$ty, N> {
+                // Multiply-based method, like for the scalar types; all lanes
+                // are sampled from a single call to the RNG.
+                let float_size = mem::size_of::<$ty>() * 8;
+                let precision = $fraction_bits + 1;
+                let scale = 1.0 / ((1 as $uty << precision) as $ty);
+
+                let value: Simd<$uty, N> = rng.gen();
+                let value = value >> Simd::splat((float_size - precision) as $uty);
+                value.cast::<$ty>() * Simd::splat(scale)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd_support")] simd_float_impls! { f32, u32, 23 }
+#[cfg(feature = "simd_support")] simd_float_impls! { f64, u64, 52 }
+
+#[cfg(feature = "half")]
+macro_rules! half_float_impls {
+    ($ty:ident, $fraction_bits:expr) => {
+        impl Distribution<::half::$ty> for Standard {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ::half::$ty {
+                // Generate a multiple of `2^-precision` as an `f32`. It has at
+                // most `precision` significant bits, so the conversion is
+                // exact and the result is never rounded up to 1.
+                let precision = $fraction_bits + 1;
+                let scale = 1.0 / ((1u32 << precision) as f32);
+                let value = rng.next_u32() >> (32 - precision);
+                ::half::$ty::from_f32(value as f32 * scale)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "half")] half_float_impls! { f16, 10 }
+#[cfg(feature = "half")] half_float_impls! { bf16, 7 }
+
 
 #[cfg(test)]
 mod tests {
     use Rng;
-    use distributions::{Open01, OpenClosed01};
+    use distributions::{Open01, OpenClosed01, HighPrecision01};
     use rngs::mock::StepRng;
 
     const EPSILON32: f32 = ::core::f32::EPSILON;
@@ -170,6 +367,49 @@ mod tests {
         assert_eq!(max.gen::<f64>(), 1.0 - EPSILON64 / 2.0);
     }
 
+    #[test]
+    fn fill_floats() {
+        let mut rng = StepRng::new(0, 1 << 11);
+        let mut arr = [0.0f64; 70];
+        rng.fill_floats(&mut arr[..]);
+        for (i, &x) in arr.iter().enumerate() {
+            assert_eq!(x, i as f64 * EPSILON64 / 2.0);
+        }
+
+        let mut max = StepRng::new(!0, 0);
+        let mut arr = [0.0f32; 70];
+        max.fill_floats(&mut arr[..]);
+        assert!(arr.iter().all(|&x| x == 1.0 - EPSILON32 / 2.0));
+
+        let mut rng = ::test::rng(801);
+        rng.fill_floats(&mut arr[..]);
+        assert!(arr.iter().all(|&x| 0.0 <= x && x < 1.0));
+    }
+
+    #[test]
+    fn fill() {
+        let mut rng = StepRng::new(0, 1 << 11);
+        let mut arr = [0.0f64; 64];
+        rng.fill(&mut arr);
+        for (i, &x) in arr.iter().enumerate() {
+            assert_eq!(x, i as f64 * EPSILON64 / 2.0);
+        }
+
+        // `fill`, `try_fill` and `fill_floats` produce the same values
+        let mut rng1 = ::test::rng(802);
+        let mut rng2 = ::test::rng(802);
+        let mut rng3 = ::test::rng(802);
+        let mut arr1 = [0.0f32; 70];
+        let mut arr2 = [0.0f32; 70];
+        let mut arr3 = [0.0f32; 70];
+        rng1.fill(&mut arr1[..]);
+        rng2.try_fill(&mut arr2[..]).unwrap();
+        rng3.fill_floats(&mut arr3[..]);
+        assert_eq!(&arr1[..], &arr2[..]);
+        assert_eq!(&arr1[..], &arr3[..]);
+        assert!(arr1.iter().all(|&x| 0.0 <= x && x < 1.0));
+    }
+
     #[test]
     fn openclosed01_edge_cases() {
         let mut zeros = StepRng::new(0, 0);
@@ -203,4 +443,81 @@ mod tests {
         assert_eq!(max.sample::<f32, _>(Open01), 1.0 - EPSILON32 / 2.0);
         assert_eq!(max.sample::<f64, _>(Open01), 1.0 - EPSILON64 / 2.0);
     }
+
+    #[test]
+    fn high_precision01_edge_cases() {
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.sample::<f32, _>(HighPrecision01), 0.0);
+        assert_eq!(zeros.sample::<f64, _>(HighPrecision01), 0.0);
+
+        // Only the lowest exponent bit is set
+        let mut one32 = StepRng::new(1 << 23, 0);
+        assert_eq!(one32.sample::<f32, _>(HighPrecision01), 1.0 / (1 << 9) as f32);
+
+        let mut one64 = StepRng::new(1 << 52, 0);
+        assert_eq!(one64.sample::<f64, _>(HighPrecision01), 1.0 / (1 << 12) as f64);
+
+        // The exponent bits are all zero, and the next number has 3 leading
+        // zeros
+        let mut two64 = StepRng::new(0, 1 << 60);
+        assert_eq!(two64.sample::<f64, _>(HighPrecision01), 1.0 / (1 << 16) as f64);
+
+        // Much smaller than `Standard` and `Open01` can generate: the fraction
+        // is 1, followed by 9 + 31 (f32) or 12 + 63 (f64) zeros.
+        let mut tiny = StepRng::new(1, 0);
+        assert_eq!(tiny.sample::<f32, _>(HighPrecision01),
+                   (1.0 + EPSILON32) / (1u64 << 41) as f32);
+        assert_eq!(tiny.sample::<f64, _>(HighPrecision01),
+                   (1.0 + EPSILON64) / (1u64 << 63) as f64 / (1 << 13) as f64);
+
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.sample::<f32, _>(HighPrecision01), 1.0 - EPSILON32 / 2.0);
+        assert_eq!(max.sample::<f64, _>(HighPrecision01), 1.0 - EPSILON64 / 2.0);
+    }
+
+    #[test]
+    #[cfg(feature = "simd_support")]
+    fn standard_simd_edge_cases() {
+        use core::simd::prelude::*;
+
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.gen::<f32x8>(), f32x8::splat(0.0));
+        assert_eq!(zeros.gen::<f64x4>(), f64x4::splat(0.0));
+
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.gen::<f32x8>(), f32x8::splat(1.0 - EPSILON32 / 2.0));
+        assert_eq!(max.gen::<f64x4>(), f64x4::splat(1.0 - EPSILON64 / 2.0));
+
+        let mut one64 = StepRng::new(1 << 11, 0);
+        assert_eq!(one64.gen::<f64x2>(), f64x2::splat(EPSILON64 / 2.0));
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn standard_half_edge_cases() {
+        use half::{f16, bf16};
+
+        let mut zeros = StepRng::new(0, 0);
+        assert_eq!(zeros.gen::<f16>(), f16::from_f32(0.0));
+        assert_eq!(zeros.gen::<bf16>(), bf16::from_f32(0.0));
+
+        let mut max = StepRng::new(!0, 0);
+        assert_eq!(max.gen::<f16>(), f16::from_f32(1.0 - 1.0 / 2048.0));
+        assert_eq!(max.gen::<bf16>(), bf16::from_f32(1.0 - 1.0 / 256.0));
+        assert!(max.gen::<f16>() < f16::from_f32(1.0));
+        assert!(max.gen::<bf16>() < bf16::from_f32(1.0));
+    }
+
+    #[test]
+    fn high_precision01_mean() {
+        let mut rng = ::test::rng(255);
+        let mut sum = 0.0;
+        for _ in 0..10000 {
+            let x: f64 = rng.sample(HighPrecision01);
+            assert!(x >= 0.0 && x < 1.0);
+            sum += x;
+        }
+        let mean = sum / 10000.0;
+        assert!(mean > 0.49 && mean < 0.51);
+    }
 }
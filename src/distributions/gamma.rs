@@ -16,6 +16,7 @@ use self::ChiSquaredRepr::*;
 use Rng;
 use distributions::normal::StandardNormal;
 use distributions::{Distribution, Exp, Open01};
+#[cfg(not(feature="std"))] use distributions::math::Float;
 
 /// The Gamma distribution `Gamma(shape, scale)` distribution.
 ///
@@ -0,0 +1,177 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The histogram distribution.
+
+use Rng;
+use distributions::{Distribution, Uniform};
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::Vec;
+
+/// A distribution described by a histogram, i.e. a sequence of adjacent bins
+/// with a count for each.
+///
+/// Sampling first selects a bin with probability proportional to its count,
+/// then samples uniformly from the half-open interval `[low, high)` covered by
+/// that bin. This makes it possible to use aggregated data (for example
+/// telemetry which is only available as bucketed counts) directly as a model.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Histogram, Distribution};
+///
+/// // Three bins: [0, 1) with count 2, [1, 5) with count 6, [5, 10) with count 1
+/// let hist = Histogram::new(vec![0.0, 1.0, 5.0, 10.0], vec![2, 6, 1]);
+/// let v = hist.sample(&mut rand::thread_rng());
+/// println!("{} is from the histogram", v);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    edges: Vec<f64>,
+    /// Cumulative counts; `cumulative[i]` is the sum of the counts of bins
+    /// `0...i`.
+    cumulative: Vec<u64>,
+    count_range: Uniform<u64>,
+}
+
+impl Histogram {
+    /// Construct a new `Histogram` from the bin `edges` and the `counts` of
+    /// each bin. Bin `i` covers the range `[edges[i], edges[i + 1])`.
+    ///
+    /// # Panics
+    ///
+    /// - if `counts` is empty or `edges.len() != counts.len() + 1`
+    /// - if the edges are not finite and strictly increasing
+    /// - if the total count is 0 or larger than a `u64` can contain
+    pub fn new(edges: Vec<f64>, counts: Vec<u64>) -> Histogram {
+        assert!(!counts.is_empty(), "Histogram::new called with no bins");
+        assert!(edges.len() == counts.len() + 1,
+                "Histogram::new called with `edges.len() != counts.len() + 1`");
+        for w in edges.windows(2) {
+            assert!(w[0].is_finite() && w[1].is_finite() && w[0] < w[1],
+                    "Histogram::new called with edges which are not finite \
+                     and strictly increasing");
+        }
+
+        // Convert the counts to cumulative counts in place, so we can binary
+        // search for the bin when sampling.
+        let mut cumulative = counts;
+        let mut running_total: u64 = 0;
+        for count in cumulative.iter_mut() {
+            running_total = match running_total.checked_add(*count) {
+                Some(n) => n,
+                None => panic!("Histogram::new called with a total count \
+                               larger than a u64 can contain")
+            };
+            *count = running_total;
+        }
+        assert!(running_total != 0, "Histogram::new called with a total count of 0");
+
+        Histogram {
+            edges,
+            cumulative,
+            count_range: Uniform::new(0, running_total),
+        }
+    }
+
+    /// Return the bin index for a value in `[0, total_count)`.
+    fn bin(&self, x: u64) -> usize {
+        // Find the first bin with a cumulative count larger than `x`. Bins
+        // with a count of 0 have the same cumulative count as the previous
+        // bin, so they are never selected.
+        let mut low = 0;
+        let mut high = self.cumulative.len() - 1;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.cumulative[mid] > x {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        low
+    }
+}
+
+impl Distribution<f64> for Histogram {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let i = self.bin(self.count_range.sample(rng));
+        rng.gen_range(self.edges[i], self.edges[i + 1])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use distributions::Distribution;
+    use super::Histogram;
+
+    #[test]
+    fn test_histogram() {
+        let mut rng = ::test::rng(301);
+        let hist = Histogram::new(vec![-1.0, 0.0, 2.0, 3.0, 10.0],
+                                  vec![3, 0, 1, 4]);
+        for _ in 0..1000 {
+            let x = hist.sample(&mut rng);
+            assert!(x >= -1.0 && x < 10.0);
+            // the bin [0, 2) has no weight
+            assert!(x < 0.0 || x >= 2.0);
+        }
+    }
+
+    #[test]
+    fn test_histogram_bins() {
+        let hist = Histogram::new(vec![0.0, 1.0, 2.0, 3.0, 4.0],
+                                  vec![0, 2, 0, 1]);
+        assert_eq!(hist.bin(0), 1);
+        assert_eq!(hist.bin(1), 1);
+        assert_eq!(hist.bin(2), 3);
+    }
+
+    #[test]
+    fn test_histogram_single_bin() {
+        let mut rng = ::test::rng(302);
+        let hist = Histogram::new(vec![5.0, 6.0], vec![1]);
+        for _ in 0..100 {
+            let x = hist.sample(&mut rng);
+            assert!(x >= 5.0 && x < 6.0);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_histogram_no_bins() {
+        Histogram::new(vec![0.0], vec![]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_histogram_bad_edges_len() {
+        Histogram::new(vec![0.0, 1.0, 2.0], vec![1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_histogram_unsorted_edges() {
+        Histogram::new(vec![0.0, 2.0, 1.0], vec![1, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_histogram_zero_count() {
+        Histogram::new(vec![0.0, 1.0, 2.0], vec![0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_histogram_count_overflows() {
+        Histogram::new(vec![0.0, 1.0, 2.0], vec![::core::u64::MAX, 1]);
+    }
+}
@@ -10,6 +10,15 @@
 
 //! The implementations of the `Standard` distribution for integer types.
 
+#[cfg(feature = "nonzero_support")]
+use core::num::{NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize,
+                NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroIsize};
+#[cfg(all(feature = "nonzero_support", feature = "i128_support"))]
+use core::num::{NonZeroU128, NonZeroI128};
+
+#[cfg(feature = "simd_support")]
+use core::simd::prelude::*;
+
 use {Rng};
 use distributions::{Distribution, Standard};
 
@@ -84,6 +93,85 @@ impl_int_from_uint! { i64, u64 }
 #[cfg(feature = "i128_support")] impl_int_from_uint! { i128, u128 }
 impl_int_from_uint! { isize, usize }
 
+#[cfg(feature = "nonzero_support")]
+macro_rules! impl_nzint {
+    ($ty:ty, $new:path) => {
+        impl Distribution<$ty> for Standard {
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                // Rejecting 0 is cheaper than mapping to a smaller range, and
+                // only rarely needs a second try: with a chance of 1 in 256
+                // for 8-bit types, and far less for larger ones.
+                loop {
+                    if let Some(x) = $new(rng.gen()) {
+                        return x;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroU8, NonZeroU8::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroU16, NonZeroU16::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroU32, NonZeroU32::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroU64, NonZeroU64::new }
+#[cfg(all(feature = "nonzero_support", feature = "i128_support"))]
+impl_nzint! { NonZeroU128, NonZeroU128::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroUsize, NonZeroUsize::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroI8, NonZeroI8::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroI16, NonZeroI16::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroI32, NonZeroI32::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroI64, NonZeroI64::new }
+#[cfg(all(feature = "nonzero_support", feature = "i128_support"))]
+impl_nzint! { NonZeroI128, NonZeroI128::new }
+#[cfg(feature = "nonzero_support")] impl_nzint! { NonZeroIsize, NonZeroIsize::new }
+
+#[cfg(feature = "simd_support")]
+macro_rules! simd_impl {
+    ($ty:ty) => {
+        impl<const N: usize> Distribution<Simd<$ty, N>> for Standard {
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Simd<$ty, N> {
+                // Fill all lanes with a single call to the RNG.
+                let mut vec = Simd::splat(0);
+                rng.fill(&mut vec.as_mut_array()[..]);
+                vec
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd_support")]
+macro_rules! simd_mask_impl {
+    ($ty:ty) => {
+        impl<const N: usize> Distribution<Mask<$ty, N>> for Standard {
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Mask<$ty, N> {
+                // Use the most significant bit of each lane.
+                let vec: Simd<$ty, N> = rng.gen();
+                vec.simd_lt(Simd::splat(0))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd_support")] simd_impl! { u8 }
+#[cfg(feature = "simd_support")] simd_impl! { u16 }
+#[cfg(feature = "simd_support")] simd_impl! { u32 }
+#[cfg(feature = "simd_support")] simd_impl! { u64 }
+#[cfg(feature = "simd_support")] simd_impl! { usize }
+#[cfg(feature = "simd_support")] simd_impl! { i8 }
+#[cfg(feature = "simd_support")] simd_impl! { i16 }
+#[cfg(feature = "simd_support")] simd_impl! { i32 }
+#[cfg(feature = "simd_support")] simd_impl! { i64 }
+#[cfg(feature = "simd_support")] simd_impl! { isize }
+#[cfg(feature = "simd_support")] simd_mask_impl! { i8 }
+#[cfg(feature = "simd_support")] simd_mask_impl! { i16 }
+#[cfg(feature = "simd_support")] simd_mask_impl! { i32 }
+#[cfg(feature = "simd_support")] simd_mask_impl! { i64 }
+#[cfg(feature = "simd_support")] simd_mask_impl! { isize }
+
 
 #[cfg(test)]
 mod tests {
@@ -110,4 +198,70 @@ mod tests {
         #[cfg(feature = "i128_support")]
         rng.sample::<u128, _>(Standard);
     }
+
+    #[test]
+    #[cfg(feature = "simd_support")]
+    fn test_simd() {
+        use core::simd::prelude::*;
+        use rngs::mock::StepRng;
+        let mut rng = ::test::rng(808);
+
+        rng.sample::<u8x16, _>(Standard);
+        rng.sample::<i16x8, _>(Standard);
+        rng.sample::<u32x8, _>(Standard);
+        rng.sample::<i64x4, _>(Standard);
+        rng.sample::<usizex2, _>(Standard);
+        rng.sample::<mask32x8, _>(Standard);
+
+        // All lanes are filled from a single stream of bytes
+        let mut rng = StepRng::new(1, 1);
+        let v: u32x4 = rng.gen();
+        assert_eq!(v, u32x4::from_array([1, 0, 2, 0]));
+
+        let mut rng = StepRng::new(0x8000_0000_0000_0000, 0);
+        let m: mask32x4 = rng.gen();
+        assert_eq!(m.to_array(), [false, true, false, true]);
+    }
+
+    #[test]
+    #[cfg(feature = "nonzero_support")]
+    fn test_nonzero_integers() {
+        use core::num::*;
+        let mut rng = ::test::rng(807);
+
+        rng.sample::<NonZeroU8, _>(Standard);
+        rng.sample::<NonZeroU16, _>(Standard);
+        rng.sample::<NonZeroU32, _>(Standard);
+        rng.sample::<NonZeroU64, _>(Standard);
+        rng.sample::<NonZeroUsize, _>(Standard);
+        rng.sample::<NonZeroI8, _>(Standard);
+        rng.sample::<NonZeroI16, _>(Standard);
+        rng.sample::<NonZeroI32, _>(Standard);
+        rng.sample::<NonZeroI64, _>(Standard);
+        rng.sample::<NonZeroIsize, _>(Standard);
+        #[cfg(feature = "i128_support")]
+        {
+            rng.sample::<NonZeroU128, _>(Standard);
+            rng.sample::<NonZeroI128, _>(Standard);
+        }
+
+        // 0 is rejected
+        use rngs::mock::StepRng;
+        let mut rng = StepRng::new(0, 1);
+        assert_eq!(rng.sample::<NonZeroU8, _>(Standard).get(), 1);
+        // `NonZeroI8` uses the least significant 8 bits of 256
+        let mut rng = StepRng::new(256, 1);
+        assert_eq!(rng.sample::<NonZeroI8, _>(Standard).get(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "i128_support")]
+    fn test_u128_from_two_u64() {
+        use rngs::mock::StepRng;
+        // The first `u64` forms the least significant half
+        let mut rng = StepRng::new(1, 1);
+        assert_eq!(rng.sample::<u128, _>(Standard), (2 << 64) | 1);
+        let mut rng = StepRng::new(!0, 1);
+        assert_eq!(rng.sample::<i128, _>(Standard), ::core::u64::MAX as i128);
+    }
 }
@@ -0,0 +1,105 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sampling by inverting the cumulative distribution function.
+
+use core::fmt;
+
+use Rng;
+use distributions::{Distribution, HighPrecision01};
+
+/// A distribution defined by its quantile function, i.e. the inverse of its
+/// cumulative distribution function (CDF).
+///
+/// Samples are generated by [inverse transform sampling]: a value `u` is
+/// sampled uniformly from the half-open range `[0, 1)` and mapped through the
+/// quantile function. This works for any distribution on the reals for which
+/// the quantile function is known, but is usually slower than a specialised
+/// algorithm.
+///
+/// `u` is sampled with [`HighPrecision01`], so values close to 0 have the full
+/// precision of an `f64`, and the tail of the distribution near `u = 0` is not
+/// truncated.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{InverseCdf, Distribution};
+///
+/// // The exponential distribution with rate `lambda`
+/// let lambda = 2.0;
+/// let exp = InverseCdf::new(|u: f64| -(1.0 - u).ln() / lambda);
+/// let v = exp.sample(&mut rand::thread_rng());
+/// println!("{} is from an Exp(2) distribution", v);
+/// ```
+///
+/// [inverse transform sampling]: https://en.wikipedia.org/wiki/Inverse_transform_sampling
+/// [`HighPrecision01`]: struct.HighPrecision01.html
+#[derive(Clone, Copy)]
+pub struct InverseCdf<F> {
+    quantile: F,
+}
+
+impl<F> InverseCdf<F> where F: Fn(f64) -> f64 {
+    /// Construct a new `InverseCdf` from the quantile function `quantile`.
+    ///
+    /// `quantile` is called with values in the range `[0, 1)` and should be
+    /// monotonically non-decreasing over that range.
+    #[inline]
+    pub fn new(quantile: F) -> InverseCdf<F> {
+        InverseCdf { quantile }
+    }
+}
+
+impl<F> Distribution<f64> for InverseCdf<F> where F: Fn(f64) -> f64 {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let u: f64 = rng.sample(HighPrecision01);
+        (self.quantile)(u)
+    }
+}
+
+// Closures do not implement `Debug`
+impl<F> fmt::Debug for InverseCdf<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InverseCdf {{}}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use distributions::Distribution;
+    use super::InverseCdf;
+
+    #[test]
+    fn test_inverse_cdf() {
+        let mut rng = ::test::rng(303);
+        // uniform on [2, 5)
+        let d = InverseCdf::new(|u| 2.0 + 3.0 * u);
+        for _ in 0..1000 {
+            let x = d.sample(&mut rng);
+            assert!(x >= 2.0 && x < 5.0);
+        }
+    }
+
+    #[test]
+    fn test_inverse_cdf_discrete() {
+        let mut rng = ::test::rng(304);
+        // a quantile function with steps is fine too
+        let d = InverseCdf::new(|u| if u < 0.25 { 0.0 } else { 1.0 });
+        let mut zeros = 0;
+        for _ in 0..1000 {
+            let x = d.sample(&mut rng);
+            assert!(x == 0.0 || x == 1.0);
+            if x == 0.0 { zeros += 1; }
+        }
+        assert!(zeros > 150 && zeros < 350);
+    }
+}
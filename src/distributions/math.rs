@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Floating-point functions for `no_std`, implemented with `libm`.
+//!
+//! Without `std`, `f64` lacks the methods below; importing `Float` makes the
+//! code using them compile unchanged.
+
+use libm;
+
+pub trait Float {
+    // Inherent in `core` since Rust 1.85
+    #[allow(dead_code)]
+    fn abs(self) -> Self;
+    fn exp(self) -> Self;
+    fn floor(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn tan(self) -> Self;
+}
+
+impl Float for f64 {
+    #[inline] fn abs(self) -> f64 { libm::fabs(self) }
+    #[inline] fn exp(self) -> f64 { libm::exp(self) }
+    #[inline] fn floor(self) -> f64 { libm::floor(self) }
+    #[inline] fn ln(self) -> f64 { libm::log(self) }
+    #[inline] fn powf(self, n: f64) -> f64 { libm::pow(self, n) }
+    #[inline] fn sqrt(self) -> f64 { libm::sqrt(self) }
+    #[inline] fn tan(self) -> f64 { libm::tan(self) }
+}
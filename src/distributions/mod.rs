@@ -66,13 +66,25 @@
 //! There are surprisingly many ways to uniformly generate random floats. A
 //! range between 0 and 1 is standard, but the exact bounds (open vs closed)
 //! and accuracy differ. In addition to the [`Standard`] distribution Rand offers
-//! [`Open01`] and [`OpenClosed01`]. See [Floating point implementation] for
+//! [`Open01`] and [`OpenClosed01`]:
+//!
+//! - [`Standard`] samples from `[0, 1)`, i.e. it may return 0 but never 1
+//! - [`OpenClosed01`] samples from `(0, 1]`, i.e. it may return 1 but never 0
+//! - [`Open01`] samples from `(0, 1)`, i.e. it never returns either bound
+//! - [`HighPrecision01`] samples from `[0, 1)`, like [`Standard`], but can
+//!   return every representable value in that range
+//!
+//! When the result is passed to a function like `ln` which is not finite at 0,
+//! use [`OpenClosed01`] or [`Open01`]. See [Floating point implementation] for
 //! more details.
 //!
 //! [`Alphanumeric`] is a simple distribution to sample random letters and
 //! numbers of the `char` type; in contrast [`Standard`] may sample any valid
 //! `char`.
 //!
+//! [`Ipv4Cidr`] and [`Ipv6Cidr`] sample IP addresses from a network given in
+//! CIDR notation; [`Standard`] samples from all addresses.
+//!
 //!
 //! # Non-uniform probability distributions
 //!
@@ -95,6 +107,12 @@
 //!   - [`ChiSquared`] distribution
 //!   - [`StudentT`] distribution
 //!   - [`FisherF`] distribution
+//! - Constructed from aggregated data:
+//!   - [`Histogram`] distribution, given as bin edges and counts
+//! - Defined by a user-supplied function:
+//!   - [`InverseCdf`], sampling with a quantile function
+//!   - [`Rejection`], sampling with an acceptance function
+//!   - [`Ziggurat`], sampling monotone densities with the Ziggurat method
 //!
 //!
 //! # Examples
@@ -152,56 +170,89 @@
 //! [`Exp1`]: struct.Exp1.html
 //! [`FisherF`]: struct.FisherF.html
 //! [`Gamma`]: struct.Gamma.html
+//! [`HighPrecision01`]: struct.HighPrecision01.html
+//! [`Histogram`]: struct.Histogram.html
+//! [`InverseCdf`]: struct.InverseCdf.html
+//! [`Ipv4Cidr`]: struct.Ipv4Cidr.html
+//! [`Ipv6Cidr`]: struct.Ipv6Cidr.html
 //! [`LogNormal`]: struct.LogNormal.html
 //! [`Normal`]: struct.Normal.html
 //! [`Open01`]: struct.Open01.html
 //! [`OpenClosed01`]: struct.OpenClosed01.html
 //! [`Poisson`]: struct.Poisson.html
+//! [`Rejection`]: struct.Rejection.html
 //! [`Standard`]: struct.Standard.html
 //! [`StandardNormal`]: struct.StandardNormal.html
 //! [`StudentT`]: struct.StudentT.html
 //! [`Uniform`]: struct.Uniform.html
+//! [`Ziggurat`]: ziggurat/struct.Ziggurat.html
+
+use core::fmt;
 
 use Rng;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::Vec;
+#[cfg(all(feature="std", feature="rayon"))] use Jumpable;
+#[cfg(all(feature="std", feature="rayon"))] use rayon::iter::{ParallelIterator, IndexedParallelIterator, IntoParallelIterator};
+#[cfg(all(feature="std", feature="rayon"))] use rayon::iter::plumbing::UnindexedConsumer;
 
 #[doc(inline)] pub use self::other::Alphanumeric;
-#[doc(inline)] pub use self::uniform::Uniform;
-#[doc(inline)] pub use self::float::{OpenClosed01, Open01};
+#[doc(inline)] pub use self::uniform::{Uniform, UniformStepped};
+#[doc(inline)] pub use self::float::{OpenClosed01, Open01, HighPrecision01};
 #[deprecated(since="0.5.0", note="use Uniform instead")]
 pub use self::uniform::Uniform as Range;
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(inline)] pub use self::gamma::{Gamma, ChiSquared, FisherF, StudentT};
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(inline)] pub use self::normal::{Normal, LogNormal, StandardNormal};
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(inline)] pub use self::exponential::{Exp, Exp1};
-#[cfg(feature = "std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(inline)] pub use self::poisson::Poisson;
-#[cfg(feature = "std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(inline)] pub use self::binomial::Binomial;
 #[doc(inline)] pub use self::bernoulli::Bernoulli;
+#[cfg(feature = "alloc")]
+#[doc(inline)] pub use self::histogram::Histogram;
+#[cfg(feature = "alloc")]
+#[doc(inline)] pub use self::weighted::{WeightedIndex, WeightedError};
+#[cfg(feature = "std")]
+#[doc(inline)] pub use self::net::{Ipv4Cidr, Ipv6Cidr};
+#[doc(inline)] pub use self::inverse_cdf::InverseCdf;
+#[doc(inline)] pub use self::rejection::Rejection;
 
 pub mod uniform;
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(hidden)] pub mod gamma;
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(hidden)] pub mod normal;
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(hidden)] pub mod exponential;
-#[cfg(feature = "std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(hidden)] pub mod poisson;
-#[cfg(feature = "std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[doc(hidden)] pub mod binomial;
 #[doc(hidden)] pub mod bernoulli;
+#[cfg(feature = "alloc")]
+#[doc(hidden)] pub mod histogram;
+#[cfg(feature = "alloc")]
+#[doc(hidden)] pub mod weighted;
+#[cfg(feature = "std")]
+#[doc(hidden)] pub mod net;
+#[doc(hidden)] pub mod inverse_cdf;
+#[doc(hidden)] pub mod rejection;
+#[cfg(any(feature="std", feature="libm"))]
+pub mod ziggurat;
 
 mod float;
 mod integer;
-#[cfg(feature="std")]
-mod log_gamma;
+#[cfg(any(feature="std", feature="libm"))]
+pub(crate) mod log_gamma;
+#[cfg(all(feature="libm", not(feature="std")))]
+pub(crate) mod math;
 mod other;
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 mod ziggurat_tables;
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 use distributions::float::IntoFloat;
 
 /// Types that can be used to create a random instance of `Support`.
@@ -236,11 +287,11 @@ mod impls {
     use Rng;
     use distributions::{Distribution, Sample, IndependentSample,
             WeightedChoice};
-    #[cfg(feature="std")]
+    #[cfg(any(feature="std", feature="libm"))]
     use distributions::exponential::Exp;
-    #[cfg(feature="std")]
+    #[cfg(any(feature="std", feature="libm"))]
     use distributions::gamma::{Gamma, ChiSquared, FisherF, StudentT};
-    #[cfg(feature="std")]
+    #[cfg(any(feature="std", feature="libm"))]
     use distributions::normal::{Normal, LogNormal};
     use distributions::range::{Range, SampleRange};
     
@@ -266,7 +317,7 @@ mod impls {
         }
     }
     
-    #[cfg(feature="std")]
+    #[cfg(any(feature="std", feature="libm"))]
     macro_rules! impl_f64 {
         ($($name: ident), *) => {
             $(
@@ -283,7 +334,7 @@ mod impls {
             )*
         }
     }
-    #[cfg(feature="std")]
+    #[cfg(any(feature="std", feature="libm"))]
     impl_f64!(Exp, Gamma, ChiSquared, FisherF, StudentT, Normal, LogNormal);
 }
 
@@ -334,6 +385,138 @@ pub trait Distribution<T> {
             phantom: ::core::marker::PhantomData,
         }
     }
+
+    /// Create an iterator that generates random values of `T` in chunks of
+    /// `chunk_size` values, using `rng` as the source of randomness.
+    ///
+    /// Each time the iterator runs out of values, the next `chunk_size` values
+    /// are sampled at once into an internal buffer, which is reused for every
+    /// chunk. This helps when the consumer does a lot of work per value, which
+    /// would otherwise evict the tables of the distribution and the state of
+    /// the generator from the cache between samples. For cheap consumers
+    /// [`sample_iter`] is faster. The values are the same as those returned by
+    /// [`sample_iter`].
+    ///
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Uniform};
+    ///
+    /// let mut rng = thread_rng();
+    /// let die = Uniform::new_inclusive(1u32, 6);
+    /// let sum: u32 = die.sample_chunks(&mut rng, 64).take(1000).sum();
+    /// assert!(sum >= 1000 && sum <= 6000);
+    /// ```
+    ///
+    /// [`sample_iter`]: trait.Distribution.html#method.sample_iter
+    #[cfg(feature="alloc")]
+    fn sample_chunks<'a, R>(&'a self, rng: &'a mut R, chunk_size: usize)
+        -> ChunkedDistIter<'a, Self, R, T>
+        where Self: Sized, R: Rng
+    {
+        assert!(chunk_size > 0, "sample_chunks called with chunk_size = 0");
+        ChunkedDistIter {
+            distr: self,
+            rng: rng,
+            chunk_size,
+            buf: Vec::with_capacity(chunk_size),
+        }
+    }
+
+    /// Create a parallel iterator over `n` random values of `T`, using
+    /// generators derived from `rng` by jumping.
+    ///
+    /// The values are generated in chunks of 4096; chunk `i` is sampled with
+    /// `rng` jumped `i` times (see [`Jumpable`]). The chunks are processed
+    /// in parallel with Rayon, and the result does not depend on the number
+    /// of threads or their scheduling.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate rayon;
+    /// use rand::SeedableRng;
+    /// use rand::distributions::{Distribution, Uniform};
+    /// use rand::prng::Xoshiro256StarStar;
+    /// use rayon::prelude::*;
+    ///
+    /// # fn main() {
+    /// let rng = Xoshiro256StarStar::from_seed([1; 32]);
+    /// let die = Uniform::new_inclusive(1u32, 6);
+    /// let rolls: Vec<u32> = die.par_sample_iter(rng, 100_000).collect();
+    /// assert_eq!(rolls.len(), 100_000);
+    /// # }
+    /// ```
+    ///
+    /// [`Jumpable`]: ../trait.Jumpable.html
+    #[cfg(all(feature="std", feature="rayon"))]
+    fn par_sample_iter<'a, R>(&'a self, mut rng: R, n: usize) -> ParDistIter<'a, Self, R, T>
+        where Self: Sized + Sync, R: Jumpable + Send, T: Send
+    {
+        let chunks = n / PAR_CHUNK_SIZE + (n % PAR_CHUNK_SIZE != 0) as usize;
+        let mut rngs = Vec::with_capacity(chunks);
+        for _ in 0..chunks {
+            rngs.push(rng.clone());
+            rng.jump();
+        }
+        ParDistIter {
+            distr: self,
+            rngs,
+            len: n,
+            phantom: ::core::marker::PhantomData,
+        }
+    }
+
+    /// Create a distribution of values of `S` by mapping the output of this
+    /// distribution through the function `func`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Uniform};
+    ///
+    /// // Even numbers in [0, 100)
+    /// let even = Uniform::new(0u32, 50).map(|x| x * 2);
+    /// let v = even.sample(&mut thread_rng());
+    /// assert!(v % 2 == 0);
+    /// ```
+    fn map<F, S>(self, func: F) -> Map<Self, F, T, S>
+        where Self: Sized, F: Fn(T) -> S
+    {
+        Map {
+            distr: self,
+            func,
+            phantom: ::core::marker::PhantomData,
+        }
+    }
+
+    /// Create a distribution of pairs `(T, U)`, with the first value sampled
+    /// from this distribution and the second one independently from `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::distributions::{Distribution, Uniform, Standard};
+    ///
+    /// let die = Uniform::new_inclusive(1, 6);
+    /// let (roll, coin): (u8, bool) = die.zip(Standard).sample(&mut thread_rng());
+    /// println!("Rolled {} and tossed {}", roll, if coin { "heads" } else { "tails" });
+    /// ```
+    fn zip<D, U>(self, other: D) -> Zip<Self, D, T, U>
+        where Self: Sized, D: Distribution<U>
+    {
+        Zip {
+            first: self,
+            second: other,
+            phantom: ::core::marker::PhantomData,
+        }
+    }
 }
 
 impl<'a, T, D: Distribution<T>> Distribution<T> for &'a D {
@@ -373,6 +556,164 @@ impl<'a, D, R, T> Iterator for DistIter<'a, D, R, T>
     }
 }
 
+/// An iterator that generates random values of `T` with distribution `D` in
+/// chunks, using `R` as the source of randomness.
+///
+/// This `struct` is created by the [`sample_chunks`] method on
+/// [`Distribution`]. See its documentation for more.
+///
+/// [`Distribution`]: trait.Distribution.html
+/// [`sample_chunks`]: trait.Distribution.html#method.sample_chunks
+#[cfg(feature="alloc")]
+#[derive(Debug)]
+pub struct ChunkedDistIter<'a, D: 'a, R: 'a, T> {
+    distr: &'a D,
+    rng: &'a mut R,
+    chunk_size: usize,
+    // The remaining values of the current chunk, in reverse order
+    buf: Vec<T>,
+}
+
+#[cfg(feature="alloc")]
+impl<'a, D, R, T> Iterator for ChunkedDistIter<'a, D, R, T>
+    where D: Distribution<T>, R: Rng + 'a
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.buf.is_empty() {
+            refill(self.distr, &mut *self.rng, &mut self.buf, self.chunk_size);
+        }
+        self.buf.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::max_value(), None)
+    }
+}
+
+// Sample the next chunk of a `ChunkedDistIter`, kept out of line so the
+// per-value path of `next` stays small.
+#[cfg(feature="alloc")]
+#[inline(never)]
+fn refill<D, R, T>(distr: &D, rng: &mut R, buf: &mut Vec<T>, n: usize)
+    where D: Distribution<T>, R: Rng
+{
+    for _ in 0..n {
+        buf.push(distr.sample(rng));
+    }
+    buf.reverse();
+}
+
+/// Number of values sampled with each generator of a `ParDistIter`.
+#[cfg(all(feature="std", feature="rayon"))]
+const PAR_CHUNK_SIZE: usize = 4096;
+
+/// A parallel iterator that generates random values of `T` with distribution
+/// `D`, using generators of type `R`.
+///
+/// This `struct` is created by the [`par_sample_iter`] method on
+/// [`Distribution`]. See its documentation for more.
+///
+/// [`Distribution`]: trait.Distribution.html
+/// [`par_sample_iter`]: trait.Distribution.html#method.par_sample_iter
+#[cfg(all(feature="std", feature="rayon"))]
+#[derive(Debug)]
+pub struct ParDistIter<'a, D: 'a, R, T> {
+    distr: &'a D,
+    // The generator of each chunk
+    rngs: Vec<R>,
+    len: usize,
+    phantom: ::core::marker::PhantomData<T>,
+}
+
+#[cfg(all(feature="std", feature="rayon"))]
+impl<'a, D, R, T> ParallelIterator for ParDistIter<'a, D, R, T>
+    where D: Distribution<T> + Sync, R: Jumpable + Send, T: Send
+{
+    type Item = T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+        where C: UnindexedConsumer<T>
+    {
+        let distr = self.distr;
+        let len = self.len;
+        self.rngs.into_par_iter().enumerate().flat_map(move |(i, mut rng)| {
+            let start = i * PAR_CHUNK_SIZE;
+            let end = ::core::cmp::min(start + PAR_CHUNK_SIZE, len);
+            (start..end).map(|_| distr.sample(&mut rng)).collect::<Vec<T>>()
+        }).drive_unindexed(consumer)
+    }
+}
+
+/// A distribution of values of type `S`, derived from the distribution `D` by
+/// mapping its output of type `T` through a function of type `F`.
+///
+/// This `struct` is created by the [`map`] method on [`Distribution`].
+/// See its documentation for more.
+///
+/// [`Distribution`]: trait.Distribution.html
+/// [`map`]: trait.Distribution.html#method.map
+#[derive(Clone, Copy)]
+pub struct Map<D, F, T, S> {
+    distr: D,
+    func: F,
+    phantom: ::core::marker::PhantomData<fn(T) -> S>,
+}
+
+impl<D, F, T, S> Distribution<S> for Map<D, F, T, S>
+    where D: Distribution<T>, F: Fn(T) -> S
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> S {
+        (self.func)(self.distr.sample(rng))
+    }
+}
+
+// Closures do not implement `Debug`
+impl<D: fmt::Debug, F, T, S> fmt::Debug for Map<D, F, T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("distr", &self.distr)
+            .finish()
+    }
+}
+
+/// A distribution of pairs, sampling each element independently from the
+/// distributions `A` and `B`.
+///
+/// This `struct` is created by the [`zip`] method on [`Distribution`].
+/// See its documentation for more.
+///
+/// [`Distribution`]: trait.Distribution.html
+/// [`zip`]: trait.Distribution.html#method.zip
+#[derive(Clone, Copy)]
+pub struct Zip<A, B, T, U> {
+    first: A,
+    second: B,
+    phantom: ::core::marker::PhantomData<fn() -> (T, U)>,
+}
+
+impl<A, B, T, U> Distribution<(T, U)> for Zip<A, B, T, U>
+    where A: Distribution<T>, B: Distribution<U>
+{
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (T, U) {
+        let first = self.first.sample(rng);
+        (first, self.second.sample(rng))
+    }
+}
+
+impl<A: fmt::Debug, B: fmt::Debug, T, U> fmt::Debug for Zip<A, B, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Zip")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish()
+    }
+}
+
 
 /// A generic random value distribution, implemented for many primitive types.
 /// Usually generates values with a numerically uniform distribution, and with a
@@ -392,8 +733,26 @@ impl<'a, D, R, T> Iterator for DistIter<'a, D, R, T>
 /// * `bool`: Generates `false` or `true`, each with probability 0.5.
 /// * Floating point types (`f32` and `f64`): Uniformly distributed in the
 ///   half-open range `[0, 1)`. See notes below.
+/// * Half-precision floating point types (`f16` and `bf16` of the `half`
+///   crate), with the `half` feature: Uniformly distributed in the half-open
+///   range `[0, 1)`, like `f32` and `f64`.
 /// * Wrapping integers (`Wrapping<T>`), besides the type identical to their
 ///   normal integer variants.
+/// * Saturating integers (`Saturating<T>`), with the `saturating_support`
+///   feature: Identical to their normal integer variants.
+/// * Non-zero integers (`NonZeroU8`, `NonZeroI8`, etc.), with the
+///   `nonzero_support` feature: Uniformly distributed over all values of the
+///   type.
+/// * IP addresses (`Ipv4Addr`, `Ipv6Addr`) and socket addresses
+///   (`SocketAddrV4`, `SocketAddrV6`): Uniformly distributed over all
+///   addresses and ports. The flow info and scope id of `SocketAddrV6` are 0.
+///   `IpAddr` and `SocketAddr` are IPv4 or IPv6 with probability 0.5 each.
+/// * SIMD vectors of integers and floats (`u32x8`, `f32x4`, etc.) and masks
+///   (`mask32x8`, etc.), with the `simd_support` feature: Each lane is
+///   distributed like the corresponding scalar type. Mask lanes are set with
+///   probability 0.5.
+/// * Fieldless enums with `#[derive(UniformEnum)]`, with the `derive` feature:
+///   Uniformly distributed over all variants.
 ///
 /// The following aggregate types also implement the distribution `Standard` as
 /// long as their component types implement it:
@@ -417,7 +776,7 @@ impl<'a, D, R, T> Iterator for DistIter<'a, D, R, T>
 /// the half-open interval `[0, 1)`, i.e. including 0 but not 1.
 ///
 /// All values that can be generated are of the form `n * ε/2`. For `f32`
-/// the 23 most significant random bits of a `u32` are used and for `f64` the
+/// the 24 most significant random bits of a `u32` are used and for `f64` the
 /// 53 most significant bits of a `u64` are used. The conversion uses the
 /// multiplicative method: `(rng.gen::<$uty>() >> N) as $ty * (ε/2)`.
 ///
@@ -576,13 +935,13 @@ impl<'a, T: Clone> Distribution<T> for WeightedChoice<'a, T> {
 
 // the perf improvement (25-50%) is definitely worth the extra code
 // size from force-inlining.
-#[cfg(feature="std")]
+#[cfg(any(feature="std", feature="libm"))]
 #[inline(always)]
 fn ziggurat<R: Rng + ?Sized, P, Z>(
             rng: &mut R,
             symmetric: bool,
-            x_tab: ziggurat_tables::ZigTable,
-            f_tab: ziggurat_tables::ZigTable,
+            x_tab: &[f64; 257],
+            f_tab: &[f64; 257],
             mut pdf: P,
             mut zero_case: Z)
             -> f64 where P: FnMut(f64) -> f64, Z: FnMut(&mut R, f64) -> f64 {
@@ -766,4 +1125,79 @@ mod tests {
         let results: Vec<_> = distr.sample_iter(&mut rng).take(100).collect();
         println!("{:?}", results);
     }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_distributions_chunks() {
+        #[cfg(not(feature="std"))] use alloc::Vec;
+        use distributions::Uniform;
+        let distr = Uniform::new(0u32, 1000);
+        let mut rng = ::test::rng(212);
+        let expected: Vec<u32> = distr.sample_iter(&mut rng).take(100).collect();
+        for &chunk_size in [1, 7, 64, 100, 1000].iter() {
+            let mut rng = ::test::rng(212);
+            let results: Vec<u32> = distr.sample_chunks(&mut rng, chunk_size)
+                                         .take(100).collect();
+            assert_eq!(results, expected);
+        }
+    }
+
+    #[cfg(all(feature="std", feature="rayon"))]
+    #[test]
+    fn test_distributions_par_iter() {
+        use rayon::ThreadPoolBuilder;
+        use rayon::prelude::*;
+        use SeedableRng;
+        use distributions::Uniform;
+        use prng::Xoshiro256StarStar;
+
+        let rng = Xoshiro256StarStar::from_seed([1; 32]);
+        let distr = Uniform::new(0u64, 1000);
+        let sample = |threads: usize| -> Vec<u64> {
+            let pool = ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| distr.par_sample_iter(rng.clone(), 10_000).collect())
+        };
+        let results = sample(1);
+        assert_eq!(results, sample(3));
+        assert_eq!(results.len(), 10_000);
+
+        // The chunks use generators jumped 0, 1 and 2 times
+        let mut chunk_rng = rng.clone();
+        for chunk in results.chunks(4096) {
+            let mut r = chunk_rng.clone();
+            let expected: Vec<u64> = chunk.iter().map(|_| distr.sample(&mut r)).collect();
+            assert_eq!(chunk, &expected[..]);
+            chunk_rng.jump();
+        }
+
+        assert_eq!(distr.par_sample_iter(rng, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_distributions_map() {
+        use distributions::Uniform;
+        let mut rng = ::test::rng(211);
+        let distr = Uniform::new(0u32, 10).map(|x| x as f64 + 0.5);
+        for _ in 0..100 {
+            let x: f64 = distr.sample(&mut rng);
+            assert!(x >= 0.5 && x <= 9.5 && x.fract() == 0.5);
+        }
+    }
+
+    #[test]
+    fn test_distributions_zip() {
+        use distributions::{Uniform, Standard};
+        let mut rng = StepRng::new(1, 1);
+        let distr = Standard.zip(Standard);
+        let (a, b): (u32, u64) = distr.sample(&mut rng);
+        assert_eq!((a, b), (1, 2));
+
+        let mut rng = ::test::rng(212);
+        let distr = Uniform::new(0u8, 4).zip(Uniform::new(10u8, 14).map(|x| x * 2));
+        for _ in 0..100 {
+            let (a, b) = distr.sample(&mut rng);
+            assert!(a < 4);
+            assert!(b >= 20 && b < 28 && b % 2 == 0);
+        }
+    }
 }
@@ -0,0 +1,253 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Distributions of network addresses.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr,
+               SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use Rng;
+use distributions::{Distribution, Standard};
+
+/// Sample IPv4 addresses uniformly from a network given in [CIDR notation],
+/// i.e. as an address and the length of the network prefix.
+///
+/// The first `prefix_len` bits of all samples are equal to those of the
+/// network address, the remaining bits are random. Note that the network and
+/// broadcast addresses are included.
+///
+/// # Example
+///
+/// ```
+/// use std::net::Ipv4Addr;
+/// use rand::distributions::{Distribution, Ipv4Cidr};
+///
+/// // 192.168.0.0/16
+/// let net = Ipv4Cidr::new(Ipv4Addr::new(192, 168, 0, 0), 16);
+/// let addr = net.sample(&mut rand::thread_rng());
+/// assert_eq!(&addr.octets()[..2], &[192, 168]);
+/// ```
+///
+/// [CIDR notation]: https://en.wikipedia.org/wiki/Classless_Inter-Domain_Routing
+#[derive(Clone, Copy, Debug)]
+pub struct Ipv4Cidr {
+    network: [u8; 4],
+    mask: [u8; 4],
+}
+
+impl Ipv4Cidr {
+    /// Construct a new `Ipv4Cidr` for the network with address `addr` and a
+    /// prefix of `prefix_len` bits. Any bits of `addr` after the prefix are
+    /// ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `prefix_len > 32`.
+    pub fn new(addr: Ipv4Addr, prefix_len: u8) -> Ipv4Cidr {
+        assert!(prefix_len <= 32, "Ipv4Cidr::new called with `prefix_len > 32`");
+        let mut mask = [0; 4];
+        prefix_mask(&mut mask, prefix_len);
+        Ipv4Cidr { network: addr.octets(), mask }
+    }
+}
+
+impl Distribution<Ipv4Addr> for Ipv4Cidr {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv4Addr {
+        let mut octets: [u8; 4] = rng.gen();
+        apply_prefix(&mut octets, &self.network, &self.mask);
+        Ipv4Addr::from(octets)
+    }
+}
+
+/// Sample IPv6 addresses uniformly from a network given in [CIDR notation],
+/// i.e. as an address and the length of the network prefix.
+///
+/// The first `prefix_len` bits of all samples are equal to those of the
+/// network address, the remaining bits are random.
+///
+/// # Example
+///
+/// ```
+/// use std::net::Ipv6Addr;
+/// use rand::distributions::{Distribution, Ipv6Cidr};
+///
+/// // 2001:db8::/32
+/// let net = Ipv6Cidr::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32);
+/// let addr = net.sample(&mut rand::thread_rng());
+/// assert_eq!(&addr.segments()[..2], &[0x2001, 0xdb8]);
+/// ```
+///
+/// [CIDR notation]: https://en.wikipedia.org/wiki/Classless_Inter-Domain_Routing
+#[derive(Clone, Copy, Debug)]
+pub struct Ipv6Cidr {
+    network: [u8; 16],
+    mask: [u8; 16],
+}
+
+impl Ipv6Cidr {
+    /// Construct a new `Ipv6Cidr` for the network with address `addr` and a
+    /// prefix of `prefix_len` bits. Any bits of `addr` after the prefix are
+    /// ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `prefix_len > 128`.
+    pub fn new(addr: Ipv6Addr, prefix_len: u8) -> Ipv6Cidr {
+        assert!(prefix_len <= 128, "Ipv6Cidr::new called with `prefix_len > 128`");
+        let mut mask = [0; 16];
+        prefix_mask(&mut mask, prefix_len);
+        Ipv6Cidr { network: addr.octets(), mask }
+    }
+}
+
+impl Distribution<Ipv6Addr> for Ipv6Cidr {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv6Addr {
+        let mut octets: [u8; 16] = rng.gen();
+        apply_prefix(&mut octets, &self.network, &self.mask);
+        Ipv6Addr::from(octets)
+    }
+}
+
+/// Set the first `prefix_len` bits of `mask`.
+fn prefix_mask(mask: &mut [u8], prefix_len: u8) {
+    let mut remaining = prefix_len as usize;
+    for byte in mask.iter_mut() {
+        *byte = if remaining >= 8 {
+            0xff
+        } else if remaining > 0 {
+            !(0xff >> remaining)
+        } else {
+            0
+        };
+        remaining = remaining.saturating_sub(8);
+    }
+}
+
+/// Replace the bits of `octets` which are set in `mask` by those of `network`.
+fn apply_prefix(octets: &mut [u8], network: &[u8], mask: &[u8]) {
+    for ((x, n), m) in octets.iter_mut().zip(network.iter()).zip(mask.iter()) {
+        *x = (*x & !m) | (n & m);
+    }
+}
+
+impl Distribution<Ipv4Addr> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv4Addr {
+        Ipv4Addr::from(rng.next_u32())
+    }
+}
+
+impl Distribution<Ipv6Addr> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv6Addr {
+        Ipv6Addr::from(rng.gen::<[u8; 16]>())
+    }
+}
+
+impl Distribution<IpAddr> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> IpAddr {
+        if rng.gen::<bool>() {
+            IpAddr::V4(rng.gen())
+        } else {
+            IpAddr::V6(rng.gen())
+        }
+    }
+}
+
+impl Distribution<SocketAddrV4> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SocketAddrV4 {
+        SocketAddrV4::new(rng.gen(), rng.gen())
+    }
+}
+
+impl Distribution<SocketAddrV6> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SocketAddrV6 {
+        SocketAddrV6::new(rng.gen(), rng.gen(), 0, 0)
+    }
+}
+
+impl Distribution<SocketAddr> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SocketAddr {
+        if rng.gen::<bool>() {
+            SocketAddr::V4(rng.gen())
+        } else {
+            SocketAddr::V6(rng.gen())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr,
+                   SocketAddr, SocketAddrV4, SocketAddrV6};
+    use Rng;
+    use distributions::{Distribution, Standard};
+    use super::{Ipv4Cidr, Ipv6Cidr};
+
+    #[test]
+    fn test_standard() {
+        let mut rng = ::test::rng(811);
+        rng.sample::<Ipv4Addr, _>(Standard);
+        rng.sample::<Ipv6Addr, _>(Standard);
+        rng.sample::<SocketAddrV4, _>(Standard);
+        rng.sample::<SocketAddrV6, _>(Standard);
+
+        let (mut v4, mut v6) = (false, false);
+        for _ in 0..100 {
+            match rng.sample::<IpAddr, _>(Standard) {
+                IpAddr::V4(_) => v4 = true,
+                IpAddr::V6(_) => v6 = true,
+            }
+            let _: SocketAddr = rng.gen();
+        }
+        assert!(v4 && v6);
+    }
+
+    #[test]
+    fn test_ipv4_cidr() {
+        let mut rng = ::test::rng(812);
+        let net = Ipv4Cidr::new(Ipv4Addr::new(10, 20, 255, 1), 12);
+        for _ in 0..100 {
+            let o = net.sample(&mut rng).octets();
+            assert_eq!(o[0], 10);
+            assert_eq!(o[1] & 0xf0, 16);
+        }
+
+        let host = Ipv4Addr::new(127, 0, 0, 1);
+        assert_eq!(Ipv4Cidr::new(host, 32).sample(&mut rng), host);
+        Ipv4Cidr::new(host, 0).sample(&mut rng);
+    }
+
+    #[test]
+    fn test_ipv6_cidr() {
+        let mut rng = ::test::rng(813);
+        let net = Ipv6Cidr::new(Ipv6Addr::new(0xfd12, 0x3456, 0x789a, 0xffff,
+                                              0, 0, 0, 1), 52);
+        for _ in 0..100 {
+            let s = net.sample(&mut rng).segments();
+            assert_eq!(&s[..3], &[0xfd12, 0x3456, 0x789a]);
+            assert_eq!(s[3] & 0xf000, 0xf000);
+        }
+
+        let host = Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1);
+        assert_eq!(Ipv6Cidr::new(host, 128).sample(&mut rng), host);
+        Ipv6Cidr::new(host, 0).sample(&mut rng);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ipv4_cidr_bad_prefix() {
+        Ipv4Cidr::new(Ipv4Addr::new(10, 0, 0, 0), 33);
+    }
+}
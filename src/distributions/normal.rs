@@ -12,6 +12,7 @@
 
 use Rng;
 use distributions::{ziggurat, ziggurat_tables, Distribution, Open01};
+#[cfg(not(feature="std"))] use distributions::math::Float;
 
 /// Samples floating-point numbers according to the normal distribution
 /// `N(0, 1)` (a.k.a.  a standard normal, or Gaussian). This is equivalent to
@@ -87,6 +88,13 @@ impl Distribution<f64> for StandardNormal {
 /// let v = normal.sample(&mut rand::thread_rng());
 /// println!("{} is from a N(2, 9) distribution", v)
 /// ```
+///
+/// Samples are `f64`. With the `half` feature, half-precision samples can be
+/// generated by mapping the distribution through the correctly rounding
+/// conversion of the `half` crate, e.g. `normal.map(f16::from_f64)`; see
+/// [`Distribution::map`].
+///
+/// [`Distribution::map`]: trait.Distribution.html#method.map
 #[derive(Clone, Copy, Debug)]
 pub struct Normal {
     mean: f64,
@@ -176,6 +184,18 @@ mod tests {
     }
 
 
+    #[test]
+    #[cfg(feature = "half")]
+    fn test_normal_half() {
+        use half::f16;
+        let norm = Normal::new(10.0, 1.0).map(f16::from_f64);
+        let mut rng = ::test::rng(212);
+        for _ in 0..1000 {
+            let x: f16 = norm.sample(&mut rng);
+            assert!(x > f16::from_f32(0.0) && x < f16::from_f32(20.0));
+        }
+    }
+
     #[test]
     fn test_log_normal() {
         let lnorm = LogNormal::new(10.0, 10.0);
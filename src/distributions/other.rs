@@ -12,6 +12,8 @@
 
 use core::char;
 use core::num::Wrapping;
+#[cfg(feature = "saturating_support")]
+use core::num::Saturating;
 
 use {Rng};
 use distributions::{Distribution, Standard, Uniform};
@@ -44,15 +46,10 @@ pub struct Alphanumeric;
 impl Distribution<char> for Standard {
     #[inline]
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
-        let range = Uniform::new(0u32, 0x11_0000);
-        loop {
-            match char::from_u32(range.sample(rng)) {
-                Some(c) => return c,
-                // About 0.2% of numbers in the range 0..0x110000 are invalid
-                // codepoints (surrogates).
-                None => {}
-            }
-        }
+        // `UniformChar` skips the surrogate code points by offsetting the
+        // values above them, so no samples have to be rejected.
+        let range = Uniform::new_inclusive('\u{0}', char::MAX);
+        range.sample(rng)
     }
 }
 
@@ -169,6 +166,14 @@ impl<T> Distribution<Wrapping<T>> for Standard where Standard: Distribution<T> {
     }
 }
 
+#[cfg(feature = "saturating_support")]
+impl<T> Distribution<Saturating<T>> for Standard where Standard: Distribution<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Saturating<T> {
+        Saturating(rng.gen())
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -183,6 +188,27 @@ mod tests {
         rng.sample::<char, _>(Standard);
         rng.sample::<bool, _>(Standard);
     }
+
+    #[test]
+    fn test_wrapping() {
+        use core::num::Wrapping;
+        use rngs::mock::StepRng;
+        let mut rng = StepRng::new(0x1234_5678_9abc_def0, 0);
+        assert_eq!(rng.gen::<Wrapping<u32>>(), Wrapping(0x9abc_def0));
+        assert_eq!(rng.gen::<Wrapping<i64>>(), Wrapping(0x1234_5678_9abc_def0));
+    }
+
+    #[cfg(feature = "saturating_support")]
+    #[test]
+    fn test_saturating() {
+        use core::num::Saturating;
+        use rngs::mock::StepRng;
+        let mut rng = StepRng::new(0x1234_5678_9abc_def0, 0);
+        assert_eq!(rng.gen::<Saturating<u16>>(), Saturating(0xdef0));
+        assert_eq!(rng.gen::<Saturating<u64>>(), Saturating(0x1234_5678_9abc_def0));
+        let s: Saturating<u8> = Saturating(0xff);
+        assert_eq!(s + rng.gen::<Saturating<u8>>(), Saturating(0xff));
+    }
     
     #[cfg(feature="alloc")]
     #[test]
@@ -191,10 +217,12 @@ mod tests {
         let mut rng = ::test::rng(805);
 
         // Test by generating a relatively large number of chars, so we also
-        // take the rejection sampling path.
+        // get values on both sides of the surrogate code points.
         let word: String = iter::repeat(())
                 .map(|()| rng.gen::<char>()).take(1000).collect();
         assert!(word.len() != 0);
+        assert!(word.chars().any(|c| (c as u32) < 0xD800));
+        assert!(word.chars().any(|c| (c as u32) > 0xDFFF));
     }
 
     #[test]
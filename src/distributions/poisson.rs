@@ -13,7 +13,8 @@
 use Rng;
 use distributions::Distribution;
 use distributions::log_gamma::log_gamma;
-use std::f64::consts::PI;
+use core::f64::consts::PI;
+#[cfg(not(feature="std"))] use distributions::math::Float;
 
 /// The Poisson distribution `Poisson(lambda)`.
 ///
@@ -125,7 +126,6 @@ mod test {
             sum += poisson.sample(&mut rng);
         }
         let avg = (sum as f64) / 1000.0;
-        println!("Poisson average: {}", avg);
         assert!((avg - 10.0).abs() < 0.5); // not 100% certain, but probable enough
     }
 
@@ -139,7 +139,6 @@ mod test {
             sum += poisson.sample(&mut rng);
         }
         let avg = (sum as f64) / 1000.0;
-        println!("Poisson average: {}", avg);
         assert!((avg - 15.0).abs() < 0.5); // not 100% certain, but probable enough
     }
 
@@ -0,0 +1,181 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rejection sampling.
+
+use core::fmt;
+
+use Rng;
+use distributions::{Distribution, Standard};
+
+/// The default maximum number of proposals tried per sample.
+const DEFAULT_MAX_ITERATIONS: u32 = 1_000_000;
+
+/// A distribution implemented by [rejection sampling].
+///
+/// Values are sampled from the `proposal` distribution and each is accepted
+/// with the probability returned by the `accept` function for that value.
+/// Rejected values are discarded and a new proposal is sampled, until a value
+/// is accepted.
+///
+/// To sample from a density `f` with a proposal distribution of density `g`,
+/// find a constant `M` such that `f(x) <= M * g(x)` for all `x` and use
+/// `f(x) / (M * g(x))` as acceptance probability. On average `M` proposals
+/// are required per sample.
+///
+/// Since a badly chosen acceptance function may reject (almost) all
+/// proposals, the number of tries is limited; see
+/// [`with_max_iterations`] and [`try_sample`].
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Distribution, Rejection, Uniform};
+///
+/// // The density `f(x) = 2x` on `[0, 1)`, using the uniform distribution with
+/// // `M = 2` as proposal
+/// let d = Rejection::new(Uniform::new(0.0, 1.0), |x: &f64| *x);
+/// let v: f64 = d.sample(&mut rand::thread_rng());
+/// println!("{} is from the triangular distribution", v);
+/// ```
+///
+/// [rejection sampling]: https://en.wikipedia.org/wiki/Rejection_sampling
+/// [`with_max_iterations`]: #method.with_max_iterations
+/// [`try_sample`]: #method.try_sample
+#[derive(Clone, Copy)]
+pub struct Rejection<D, F> {
+    proposal: D,
+    accept: F,
+    max_iterations: u32,
+}
+
+impl<D, F> Rejection<D, F> {
+    /// Construct a new `Rejection` sampler from a `proposal` distribution and
+    /// a function returning the probability with which a proposed value is
+    /// accepted.
+    ///
+    /// Acceptance probabilities of 1 or more always accept the value, those
+    /// of 0 or less (or NaN) always reject it.
+    ///
+    /// By default at most 1,000,000 proposals are tried per sample.
+    pub fn new(proposal: D, accept: F) -> Rejection<D, F> {
+        Rejection { proposal, accept, max_iterations: DEFAULT_MAX_ITERATIONS }
+    }
+
+    /// Set the maximum number of proposals tried per sample.
+    ///
+    /// # Panics
+    ///
+    /// If `max_iterations` is 0.
+    pub fn with_max_iterations(mut self, max_iterations: u32) -> Rejection<D, F> {
+        assert!(max_iterations > 0, "Rejection::with_max_iterations called with 0");
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sample a value, or return `None` if all of the allowed number of
+    /// proposals were rejected.
+    pub fn try_sample<T, R: Rng + ?Sized>(&self, rng: &mut R) -> Option<T>
+        where D: Distribution<T>, F: Fn(&T) -> f64
+    {
+        for _ in 0..self.max_iterations {
+            let x = self.proposal.sample(rng);
+            let p = (self.accept)(&x);
+            let u: f64 = rng.sample(Standard);
+            if u < p {
+                return Some(x);
+            }
+        }
+        None
+    }
+}
+
+impl<T, D, F> Distribution<T> for Rejection<D, F>
+    where D: Distribution<T>, F: Fn(&T) -> f64
+{
+    /// Sample a value.
+    ///
+    /// # Panics
+    ///
+    /// If all of the allowed number of proposals were rejected.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        match self.try_sample(rng) {
+            Some(x) => x,
+            None => panic!("Rejection::sample: all {} proposals were rejected",
+                           self.max_iterations),
+        }
+    }
+}
+
+// Closures do not implement `Debug`
+impl<D: fmt::Debug, F> fmt::Debug for Rejection<D, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Rejection")
+            .field("proposal", &self.proposal)
+            .field("max_iterations", &self.max_iterations)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use distributions::{Distribution, Uniform};
+    use super::Rejection;
+
+    #[test]
+    fn test_rejection() {
+        let mut rng = ::test::rng(305);
+        // density 2x on [0, 1), with mean 2/3
+        let d = Rejection::new(Uniform::new(0.0, 1.0), |x: &f64| *x);
+        let mut sum = 0.0;
+        for _ in 0..1000 {
+            let x: f64 = d.sample(&mut rng);
+            assert!(x >= 0.0 && x < 1.0);
+            sum += x;
+        }
+        let mean = sum / 1000.0;
+        assert!(mean > 0.6 && mean < 0.73);
+    }
+
+    #[test]
+    fn test_rejection_filter() {
+        let mut rng = ::test::rng(306);
+        let d = Rejection::new(Uniform::new(0u32, 10),
+                               |x: &u32| if x % 2 == 0 { 1.0 } else { 0.0 });
+        for _ in 0..100 {
+            let x: u32 = d.sample(&mut rng);
+            assert!(x % 2 == 0);
+        }
+    }
+
+    #[test]
+    fn test_rejection_exhausted() {
+        let mut rng = ::test::rng(307);
+        let d = Rejection::new(Uniform::new(0u32, 10), |_: &u32| 0.0)
+            .with_max_iterations(10);
+        assert_eq!(d.try_sample::<u32, _>(&mut rng), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rejection_exhausted_panics() {
+        let mut rng = ::test::rng(308);
+        let d = Rejection::new(Uniform::new(0u32, 10), |_: &u32| 0.0)
+            .with_max_iterations(10);
+        let _: u32 = d.sample(&mut rng);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rejection_zero_iterations() {
+        Rejection::new(Uniform::new(0u32, 10), |_: &u32| 1.0)
+            .with_max_iterations(0);
+    }
+}
@@ -16,14 +16,22 @@
 //! [`Uniform`].
 //!
 //! This distribution is provided with support for several primitive types
-//! (all integer and floating-point types) as well as `std::time::Duration`,
-//! and supports extension to user-defined types via a type-specific *back-end*
-//! implementation.
+//! (all integer and floating-point types, and `char`) as well as
+//! `std::time::Duration` and `std::time::SystemTime`, and supports extension
+//! to user-defined types via a type-specific *back-end* implementation.
 //!
-//! The types [`UniformInt`], [`UniformFloat`] and [`UniformDuration`] are the
-//! back-ends supporting sampling from primitive integer and floating-point
-//! ranges as well as from `std::time::Duration`; these types do not normally
-//! need to be used directly (unless implementing a derived back-end).
+//! The types [`UniformInt`], [`UniformFloat`], [`UniformChar`],
+//! [`UniformDuration`] and [`UniformSystemTime`] are the back-ends supporting
+//! sampling from primitive integer, floating-point and `char` ranges as well
+//! as from `std::time::Duration` and `std::time::SystemTime`; these types do
+//! not normally need to be used directly (unless implementing a derived
+//! back-end). With the `half` feature, [`UniformHalf`] supports the `f16` and
+//! `bf16` types of the `half` crate. With the `num-traits` feature,
+//! [`UniformNumInt`] and [`UniformNumFloat`] support custom numeric types
+//! implementing the traits of the `num-traits` crate.
+//!
+//! [`UniformStepped`] samples uniformly from the values in a range which are a
+//! multiple of a step size apart, e.g. multiples of 0.25 in `[0, 2)`.
 //!
 //! # Example usage
 //!
@@ -95,10 +103,19 @@
 //! [`UniformSampler`]: trait.UniformSampler.html
 //! [`UniformInt`]: struct.UniformInt.html
 //! [`UniformFloat`]: struct.UniformFloat.html
+//! [`UniformChar`]: struct.UniformChar.html
 //! [`UniformDuration`]: struct.UniformDuration.html
+//! [`UniformSystemTime`]: struct.UniformSystemTime.html
+//! [`UniformHalf`]: struct.UniformHalf.html
+//! [`UniformNumInt`]: struct.UniformNumInt.html
+//! [`UniformNumFloat`]: struct.UniformNumFloat.html
+//! [`UniformStepped`]: struct.UniformStepped.html
 
 #[cfg(feature = "std")]
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "simd_support")]
+use core::simd::prelude::*;
 
 use Rng;
 use distributions::Distribution;
@@ -124,8 +141,8 @@ use distributions::float::IntoFloat;
 ///
 /// Implementations should attempt to sample in `[low, high)` for
 /// `Uniform::new(low, high)`, i.e., excluding `high`, but this may be very
-/// difficult. All the primitive integer types satisfy this property, and the
-/// float types normally satisfy it, but rounding may mean `high` can occur.
+/// difficult. All the primitive integer and floating-point types satisfy this
+/// property; for floats the range is adjusted to account for rounding.
 ///
 /// # Example
 ///
@@ -164,6 +181,23 @@ impl<X: SampleUniform> Uniform<X> {
     pub fn new_inclusive(low: X, high: X) -> Uniform<X> {
         Uniform { inner: X::Sampler::new_inclusive(low, high) }
     }
+
+    /// Create a new `Uniform` instance which samples uniformly from the half
+    /// open range `[low, high)` (excluding `high`), using bitmask with
+    /// rejection for integer types. Panics if `low >= high`.
+    ///
+    /// This needs no multiplication, so it can be faster than [`new`] on
+    /// platforms where wide multiplication is slow, provided the size of the
+    /// range is a power of two or slightly below one, as in permutation and
+    /// shuffling of power-of-two sized tables. When it is slightly above one,
+    /// up to half of the random numbers are rejected. The sampled values
+    /// differ from those sampled by [`new`]. For other types this is the same
+    /// as [`new`].
+    ///
+    /// [`new`]: struct.Uniform.html#method.new
+    pub fn new_masked(low: X, high: X) -> Uniform<X> {
+        Uniform { inner: X::Sampler::new_masked(low, high) }
+    }
 }
 
 impl<X: SampleUniform> Distribution<X> for Uniform<X> {
@@ -193,6 +227,8 @@ pub trait SampleUniform: Sized {
 ///
 /// Implementation of [`sample_single`] is optional, and is only useful when
 /// the implementation can be faster than `Self::new(low, high).sample(rng)`.
+/// Likewise, `new_masked` only needs to be implemented by back-ends supporting
+/// bitmask with rejection.
 ///
 /// [module documentation]: index.html
 /// [`Uniform`]: struct.Uniform.html
@@ -218,6 +254,17 @@ pub trait UniformSampler: Sized {
     /// Sample a value.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X;
 
+    /// Construct self, with inclusive lower bound and exclusive upper bound
+    /// `[low, high)`, for use by [`Uniform::new_masked`].
+    ///
+    /// Back-ends for which bitmask with rejection does not apply can rely on
+    /// the default implementation, which calls `new`.
+    ///
+    /// [`Uniform::new_masked`]: struct.Uniform.html#method.new_masked
+    fn new_masked(low: Self::X, high: Self::X) -> Self {
+        Self::new(low, high)
+    }
+
     /// Sample a single value uniformly from a range with inclusive lower bound
     /// and exclusive upper bound `[low, high)`.
     ///
@@ -237,6 +284,33 @@ pub trait UniformSampler: Sized {
     }
 }
 
+/// Helper trait similar to [`Borrow`] but implemented only for
+/// [`SampleUniform`] types and references to them.
+///
+/// This is used by [`WeightedIndex`] to accept weights by value or by
+/// reference. Unlike `Borrow`, a reference `&X` only borrows as `X`, which
+/// keeps type inference working.
+///
+/// [`Borrow`]: https://doc.rust-lang.org/std/borrow/trait.Borrow.html
+/// [`SampleUniform`]: trait.SampleUniform.html
+/// [`WeightedIndex`]: ../struct.WeightedIndex.html
+pub trait SampleBorrow<Borrowed> {
+    /// Immutably borrows from an owned value. See [`Borrow::borrow`].
+    ///
+    /// [`Borrow::borrow`]: https://doc.rust-lang.org/std/borrow/trait.Borrow.html#tymethod.borrow
+    fn borrow(&self) -> &Borrowed;
+}
+
+impl<Borrowed> SampleBorrow<Borrowed> for Borrowed where Borrowed: SampleUniform {
+    #[inline(always)]
+    fn borrow(&self) -> &Borrowed { self }
+}
+
+impl<'a, Borrowed> SampleBorrow<Borrowed> for &'a Borrowed where Borrowed: SampleUniform {
+    #[inline(always)]
+    fn borrow(&self) -> &Borrowed { self }
+}
+
 impl<X: SampleUniform> From<::core::ops::Range<X>> for Uniform<X> {
     fn from(r: ::core::ops::Range<X>) -> Uniform<X> {
         Uniform::new(r.start, r.end)
@@ -285,13 +359,35 @@ impl<X: SampleUniform> From<::core::ops::Range<X>> for Uniform<X> {
 /// multiply by `range`, the result is in the high word. Then comparing the low
 /// word against `zone` makes sure our distribution is uniform.
 ///
+/// When sampling a single value with [`sample_single`], computing `zone` up
+/// front would cost a division per sample. Instead we use the nearly
+/// divisionless method by Lemire[1]: the biased values are rejected by
+/// comparing the low word against `ints_to_reject`, and because that number is
+/// less than `range`, the modulus only has to be calculated when the low word
+/// is less than `range`.
+///
+/// A distribution created with [`Uniform::new_masked`] uses bitmask with
+/// rejection instead: only the most significant bits needed to represent
+/// `range - 1` are kept, and values not less than `range` are rejected. This
+/// needs no multiply, but rejects up to half of the values when `range` is just
+/// above a power of two.
+///
+/// [1]: Daniel Lemire (2018). [*Fast Random Integer Generation in an
+/// Interval*](https://arxiv.org/abs/1805.10941). ACM Transactions on Modeling
+/// and Computer Simulation
+///
+/// [`sample_single`]: trait.UniformSampler.html#method.sample_single
 /// [`UniformSampler`]: trait.UniformSampler.html
 /// [`Uniform`]: struct.Uniform.html
+/// [`Uniform::new_masked`]: struct.Uniform.html#method.new_masked
 #[derive(Clone, Copy, Debug)]
 pub struct UniformInt<X> {
     low: X,
     range: X,
     zone: X,
+    // Right shift applied to the random bits when sampling with bitmask with
+    // rejection, or 0 when using widening multiply.
+    mask_shift: u32,
 }
 
 macro_rules! uniform_int_impl {
@@ -301,6 +397,22 @@ macro_rules! uniform_int_impl {
             type Sampler = UniformInt<$ty>;
         }
 
+        impl UniformInt<$ty> {
+            // Bitmask with rejection, keeping the most significant bits. Kept
+            // out of line to not slow down sampling with widening multiply.
+            #[inline(never)]
+            fn sample_masked<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                let range = self.range as $unsigned as $u_large;
+                loop {
+                    let v: $u_large = rng.gen();
+                    let v = v >> self.mask_shift;
+                    if v < range {
+                        return self.low.wrapping_add(v as $ty);
+                    }
+                }
+            }
+        }
+
         impl UniformSampler for UniformInt<$ty> {
             // We play free and fast with unsigned vs signed here
             // (when $ty is signed), but that's fine, since the
@@ -336,11 +448,15 @@ macro_rules! uniform_int_impl {
                     low: low,
                     // These are really $unsigned values, but store as $ty:
                     range: range as $ty,
-                    zone: zone as $ty
+                    zone: zone as $ty,
+                    mask_shift: 0,
                 }
             }
 
             fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                if self.mask_shift != 0 {
+                    return self.sample_masked(rng);
+                }
                 let range = self.range as $unsigned as $u_large;
                 if range > 0 {
                     // Grow `zone` to fit a type of at least 32 bits, by
@@ -369,26 +485,35 @@ macro_rules! uniform_int_impl {
                 assert!(low < high,
                         "Uniform::sample_single called with low >= high");
                 let range = high.wrapping_sub(low) as $unsigned as $u_large;
-                let zone =
-                    if ::core::$unsigned::MAX <= ::core::u16::MAX as $unsigned {
-                        // Using a modulus is faster than the approximation for
-                        // i8 and i16. I suppose we trade the cost of one
-                        // modulus for near-perfect branch prediction.
-                        let unsigned_max: $u_large = ::core::$u_large::MAX;
-                        let ints_to_reject = (unsigned_max - range + 1) % range;
-                        unsigned_max - ints_to_reject
-                    } else {
-                        // conservative but fast approximation
-                       range << range.leading_zeros()
-                    };
 
-                loop {
-                    let v: $u_large = rng.gen();
-                    let (hi, lo) = v.wmul(range);
-                    if lo <= zone {
-                        return low.wrapping_add(hi as $ty);
+                let v: $u_large = rng.gen();
+                let (mut hi, mut lo) = v.wmul(range);
+                // Lemire's nearly divisionless method: the low word can only
+                // fall in the biased zone if it is less than `range`, so the
+                // modulus is only needed in that (rare) case.
+                if lo < range {
+                    // `(unsigned_max + 1) % range`, the number of values to
+                    // reject.
+                    let ints_to_reject = range.wrapping_neg() % range;
+                    while lo < ints_to_reject {
+                        let v: $u_large = rng.gen();
+                        let (new_hi, new_lo) = v.wmul(range);
+                        hi = new_hi;
+                        lo = new_lo;
                     }
                 }
+                low.wrapping_add(hi as $ty)
+            }
+
+            fn new_masked(low: Self::X, high: Self::X) -> Self {
+                let mut sampler: Self = UniformSampler::new(low, high);
+                let range = sampler.range as $unsigned as $u_large;
+                // Without any bits to discard, widening multiply rejects fewer
+                // values.
+                if range > 1 {
+                    sampler.mask_shift = (range - 1).leading_zeros();
+                }
+                sampler
             }
         }
     }
@@ -409,6 +534,79 @@ uniform_int_impl! { usize, isize, usize, isize, usize }
 #[cfg(feature = "i128_support")]
 uniform_int_impl! { u128, u128, u128, i128, u128 }
 
+#[cfg(feature = "simd_support")]
+macro_rules! uniform_simd_int_impl {
+    ($ty:ident, $signed:ty, $unsigned:ident,
+     $i_large:ident, $u_large:ident) => {
+        impl<const N: usize> SampleUniform for Simd<$ty, N> {
+            type Sampler = UniformInt<Simd<$ty, N>>;
+        }
+
+        // The lanes are sampled independently, with the same algorithm as the
+        // scalar implementation above. The random numbers for all lanes are
+        // generated at once; only lanes for which a number is rejected need a
+        // new draw.
+        impl<const N: usize> UniformSampler for UniformInt<Simd<$ty, N>> {
+            type X = Simd<$ty, N>;
+
+            #[inline]
+            fn new(low: Self::X, high: Self::X) -> Self {
+                assert!(low.simd_lt(high).all(),
+                        "Uniform::new called with `low >= high`");
+                UniformSampler::new_inclusive(low, high - Simd::splat(1))
+            }
+
+            #[inline]
+            fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+                assert!(low.simd_le(high).all(),
+                        "Uniform::new_inclusive called with `low > high`");
+                let mut range = Simd::splat(0);
+                let mut zone = Simd::splat(0);
+                for i in 0..N {
+                    let lane = UniformInt::<$ty>::new_inclusive(low[i], high[i]);
+                    range[i] = lane.range;
+                    zone[i] = lane.zone;
+                }
+                UniformInt { low, range, zone, mask_shift: 0 }
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                let mut v: Simd<$u_large, N> = rng.gen();
+                let mut result = self.low;
+                for i in 0..N {
+                    let range = self.range[i] as $unsigned as $u_large;
+                    if range == 0 {
+                        // Sample from the entire integer range.
+                        result[i] = v[i] as $ty;
+                        continue;
+                    }
+                    let zone = self.zone[i] as $signed as $i_large as $u_large;
+                    loop {
+                        let (hi, lo) = v[i].wmul(range);
+                        if lo <= zone {
+                            result[i] = self.low[i].wrapping_add(hi as $ty);
+                            break;
+                        }
+                        v[i] = rng.gen();
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { i8, i8, u8, i32, u32 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { i16, i16, u16, i32, u32 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { i32, i32, u32, i32, u32 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { i64, i64, u64, i64, u64 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { isize, isize, usize, isize, usize }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { u8, i8, u8, i32, u32 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { u16, i16, u16, i32, u32 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { u32, i32, u32, i32, u32 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { u64, i64, u64, i64, u64 }
+#[cfg(feature = "simd_support")] uniform_simd_int_impl! { usize, isize, usize, isize, usize }
+
 
 trait WideningMultiply<RHS = Self> {
     type Output;
@@ -504,13 +702,20 @@ wmul_impl_usize! { u64 }
 /// way one or two steps can be optimized out.
 ///
 /// The floats are first converted to a value in the `[1, 2)` interval using a
-/// transmute-based method, and then mapped to the expected range with a
-/// multiply and addition. Values produced this way have what equals 22 bits of
-/// random digits for an `f32`, and 52 for an `f64`.
+/// transmute-based method, and then to a value `x` in `[0, 1)` by subtracting
+/// one. This value is mapped to the expected range as `x * scale + low`. Values
+/// produced this way have what equals 23 bits of random digits for an `f32`,
+/// and 52 for an `f64`.
+///
+/// With `scale = high - low`, rounding of the multiplication and addition can
+/// produce exactly `high`, even though `x < 1`. The constructors therefore
+/// reduce `scale` by the smallest possible amount such that the largest
+/// possible sample is below `high` for [`new`], or at most `high` for
+/// [`new_inclusive`]. The result is never outside of the requested range, also
+/// for ranges of subnormal numbers or spanning (almost) all finite values.
 ///
-/// Currently there is no difference between [`new`] and [`new_inclusive`],
-/// because the boundaries of a floats range are a bit of a fuzzy concept due to
-/// rounding errors.
+/// Both bounds, as well as the width of the range `high - low`, must be
+/// finite.
 ///
 /// [`UniformSampler`]: trait.UniformSampler.html
 /// [`new`]: trait.UniformSampler.html#tymethod.new
@@ -519,50 +724,84 @@ wmul_impl_usize! { u64 }
 /// [`Standard`]: ../struct.Standard.html
 #[derive(Clone, Copy, Debug)]
 pub struct UniformFloat<X> {
+    low: X,
     scale: X,
-    offset: X,
 }
 
 macro_rules! uniform_float_impl {
-    ($ty:ty, $bits_to_discard:expr, $next_u:ident) => {
+    ($ty:ident, $uty:ident, $bits_to_discard:expr, $next_u:ident) => {
         impl SampleUniform for $ty {
             type Sampler = UniformFloat<$ty>;
         }
 
+        impl UniformFloat<$ty> {
+            /// Find the largest scale in `[0, limit)` for which the largest
+            /// possible sample, `scale * max_rand + low`, is in range.
+            ///
+            /// This is a binary search over the bits of the scale, which are
+            /// ordered like the (non-negative) values. A search is needed
+            /// because the scale may have to be reduced by many ULPs if `low`
+            /// is much larger than `limit`.
+            fn max_scale<F>(low: $ty, max_rand: $ty, in_range: F, limit: $ty)
+                -> $ty where F: Fn($ty) -> bool
+            {
+                let (mut good, mut bad) = (0, limit.to_bits());
+                while bad - good > 1 {
+                    let mid = good + (bad - good) / 2;
+                    if in_range(<$ty>::from_bits(mid) * max_rand + low) {
+                        good = mid;
+                    } else {
+                        bad = mid;
+                    }
+                }
+                <$ty>::from_bits(good)
+            }
+        }
+
         impl UniformSampler for UniformFloat<$ty> {
             type X = $ty;
 
             fn new(low: Self::X, high: Self::X) -> Self {
                 assert!(low < high, "Uniform::new called with `low >= high`");
-                let scale = high - low;
-                let offset = low - scale;
-                UniformFloat {
-                    scale: scale,
-                    offset: offset,
+                let mut scale = high - low;
+                assert!(low.is_finite() && high.is_finite() && scale.is_finite(),
+                        "Uniform::new: range overflow");
+                let max_rand = (::core::$uty::MAX >> $bits_to_discard)
+                               .into_float_with_exponent(0) - 1.0;
+                if scale * max_rand + low >= high {
+                    scale = UniformFloat::<$ty>::max_scale(low, max_rand, |x| x < high, scale);
                 }
+                UniformFloat { low, scale }
             }
 
             fn new_inclusive(low: Self::X, high: Self::X) -> Self {
                 assert!(low <= high,
                         "Uniform::new_inclusive called with `low > high`");
-                let scale = high - low;
-                let offset = low - scale;
-                UniformFloat {
-                    scale: scale,
-                    offset: offset,
+                assert!(low.is_finite() && high.is_finite() &&
+                        (high - low).is_finite(),
+                        "Uniform::new_inclusive: range overflow");
+                let max_rand = (::core::$uty::MAX >> $bits_to_discard)
+                               .into_float_with_exponent(0) - 1.0;
+                let mut scale = (high - low) / max_rand;
+                if !scale.is_finite() {
+                    scale = high - low;
                 }
+                if scale * max_rand + low > high {
+                    scale = UniformFloat::<$ty>::max_scale(low, max_rand, |x| x <= high, scale);
+                }
+                UniformFloat { low, scale }
             }
 
             fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
-                // Generate a value in the range [1, 2)
-                let value1_2 = (rng.$next_u() >> $bits_to_discard)
-                               .into_float_with_exponent(0);
+                // Generate a value in the range [0, 1)
+                let value0_1 = (rng.$next_u() >> $bits_to_discard)
+                               .into_float_with_exponent(0) - 1.0;
                 // We don't use `f64::mul_add`, because it is not available with
                 // `no_std`. Furthermore, it is slower for some targets (but
                 // faster for others). However, the order of multiplication and
                 // addition is important, because on some platforms (e.g. ARM)
                 // it will be optimized to a single (non-FMA) instruction.
-                value1_2 * self.scale + self.offset
+                value0_1 * self.scale + self.low
             }
 
             fn sample_single<R: Rng + ?Sized>(low: Self::X,
@@ -571,20 +810,450 @@ macro_rules! uniform_float_impl {
                 assert!(low < high,
                         "Uniform::sample_single called with low >= high");
                 let scale = high - low;
-                let offset = low - scale;
-                // Generate a value in the range [1, 2)
-                let value1_2 = (rng.$next_u() >> $bits_to_discard)
-                               .into_float_with_exponent(0);
+                assert!(low.is_finite() && high.is_finite() && scale.is_finite(),
+                        "Uniform::sample_single: range overflow");
+                // Generate a value in the range [0, 1)
+                let value0_1 = (rng.$next_u() >> $bits_to_discard)
+                               .into_float_with_exponent(0) - 1.0;
                 // Doing multiply before addition allows some architectures to
                 // use a single instruction.
-                value1_2 * scale + offset
+                let res = value0_1 * scale + low;
+                if res < high {
+                    return res;
+                }
+                // Rounding produced `high`. This is rare enough to only adjust
+                // the scale as in `new` when it happens.
+                let max_rand = (::core::$uty::MAX >> $bits_to_discard)
+                               .into_float_with_exponent(0) - 1.0;
+                let scale = UniformFloat::<$ty>::max_scale(low, max_rand,
+                                                           |x| x < high, scale);
+                value0_1 * scale + low
+            }
+        }
+    }
+}
+
+uniform_float_impl! { f32, u32, 32 - 23, next_u32 }
+uniform_float_impl! { f64, u64, 64 - 52, next_u64 }
+
+#[cfg(feature = "simd_support")]
+macro_rules! uniform_simd_float_impl {
+    ($ty:ident, $uty:ident, $bits_to_discard:expr) => {
+        impl<const N: usize> SampleUniform for Simd<$ty, N> {
+            type Sampler = UniformFloat<Simd<$ty, N>>;
+        }
+
+        impl<const N: usize> UniformSampler for UniformFloat<Simd<$ty, N>> {
+            type X = Simd<$ty, N>;
+
+            fn new(low: Self::X, high: Self::X) -> Self {
+                assert!(low.simd_lt(high).all(),
+                        "Uniform::new called with `low >= high`");
+                // The scale of each lane is adjusted as for the scalar type.
+                let mut scale = Simd::splat(0.0);
+                for i in 0..N {
+                    scale[i] = UniformFloat::<$ty>::new(low[i], high[i]).scale;
+                }
+                UniformFloat { low, scale }
+            }
+
+            fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+                assert!(low.simd_le(high).all(),
+                        "Uniform::new_inclusive called with `low > high`");
+                let mut scale = Simd::splat(0.0);
+                for i in 0..N {
+                    scale[i] = UniformFloat::<$ty>::new_inclusive(low[i], high[i])
+                               .scale;
+                }
+                UniformFloat { low, scale }
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                // Generate values in the range [1, 2) for all lanes at once
+                let value: Simd<$uty, N> = rng.gen();
+                let exponent_bits = Simd::splat((1.0 as $ty).to_bits());
+                let value1_2 = Simd::<$ty, N>::from_bits(
+                    (value >> Simd::splat($bits_to_discard)) | exponent_bits);
+                (value1_2 - Simd::splat(1.0)) * self.scale + self.low
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd_support")] uniform_simd_float_impl! { f32, u32, 32 - 23 }
+#[cfg(feature = "simd_support")] uniform_simd_float_impl! { f64, u64, 64 - 52 }
+
+
+/// The back-end implementing [`UniformSampler`] for the half-precision
+/// floating-point types `f16` and `bf16` of the `half` crate.
+///
+/// Unless you are implementing [`UniformSampler`] for your own types, this type
+/// should not be used directly, use [`Uniform`] instead.
+///
+/// # Implementation notes
+///
+/// Values are sampled as an `f32` with [`UniformFloat`] and rounded down to a
+/// half-precision value, so every value in the range is sampled with a
+/// probability proportional to the distance to the next representable value.
+/// For `new_inclusive` the `f32` range is extended up to the value following
+/// `high`. A sample which is rounded to a value outside of the range is
+/// rejected and a new value is sampled.
+///
+/// [`UniformSampler`]: trait.UniformSampler.html
+/// [`Uniform`]: struct.Uniform.html
+/// [`UniformFloat`]: struct.UniformFloat.html
+#[cfg(feature = "half")]
+#[derive(Clone, Copy, Debug)]
+pub struct UniformHalf<X> {
+    inner: UniformFloat<f32>,
+    low: X,
+    high: X,
+    inclusive: bool,
+}
+
+// The bits of the next smaller and next larger value of an `f16` or `bf16`,
+// which both have the sign in the highest bit.
+#[cfg(feature = "half")]
+fn half_next_down(bits: u16) -> u16 {
+    if bits & 0x7fff == 0 { 0x8001 }
+    else if bits & 0x8000 == 0 { bits - 1 }
+    else { bits + 1 }
+}
+
+#[cfg(feature = "half")]
+fn half_next_up(bits: u16) -> u16 {
+    if bits & 0x7fff == 0 { 0x0001 }
+    else if bits & 0x8000 == 0 { bits + 1 }
+    else { bits - 1 }
+}
+
+#[cfg(feature = "half")]
+macro_rules! uniform_half_impl {
+    ($ty:ident) => {
+        impl SampleUniform for ::half::$ty {
+            type Sampler = UniformHalf<::half::$ty>;
+        }
+
+        impl UniformSampler for UniformHalf<::half::$ty> {
+            type X = ::half::$ty;
+
+            fn new(low: Self::X, high: Self::X) -> Self {
+                assert!(low < high, "Uniform::new called with `low >= high`");
+                UniformHalf {
+                    inner: UniformFloat::<f32>::new(low.to_f32(), high.to_f32()),
+                    low: low,
+                    high: high,
+                    inclusive: false,
+                }
+            }
+
+            fn new_inclusive(low: Self::X, high: Self::X) -> Self {
+                assert!(low <= high,
+                        "Uniform::new_inclusive called with `low > high`");
+                let next = ::half::$ty::from_bits(half_next_up(high.to_bits()))
+                    .to_f32();
+                // The value following `high` may not be representable
+                let inner = if next.is_finite() {
+                    UniformFloat::<f32>::new(low.to_f32(), next)
+                } else {
+                    UniformFloat::<f32>::new_inclusive(low.to_f32(), high.to_f32())
+                };
+                UniformHalf {
+                    inner: inner,
+                    low: low,
+                    high: high,
+                    inclusive: true,
+                }
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                loop {
+                    let f = self.inner.sample(rng);
+                    let mut x = ::half::$ty::from_f32(f);
+                    if x.to_f32() > f {
+                        x = ::half::$ty::from_bits(half_next_down(x.to_bits()));
+                    }
+                    if x >= self.low && (x < self.high ||
+                                         (self.inclusive && x == self.high)) {
+                        return x;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "half")] uniform_half_impl! { f16 }
+#[cfg(feature = "half")] uniform_half_impl! { bf16 }
+
+
+/// The back-end implementing [`UniformSampler`] for integer types supported
+/// by the `num-traits` crate.
+///
+/// This allows custom integer types, e.g. units-of-measure newtypes, to be
+/// used with [`Uniform`] by implementing the `NumCast` and `Bounded` traits of
+/// `num-traits` and registering this back-end:
+///
+/// ```
+/// extern crate num_traits;
+/// extern crate rand;
+///
+/// use num_traits::{Bounded, NumCast, ToPrimitive};
+/// use rand::distributions::{Distribution, Uniform};
+/// use rand::distributions::uniform::{SampleUniform, UniformNumInt};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+/// struct Meters(i32);
+///
+/// impl ToPrimitive for Meters {
+///     fn to_i64(&self) -> Option<i64> { self.0.to_i64() }
+///     fn to_u64(&self) -> Option<u64> { self.0.to_u64() }
+/// }
+///
+/// impl NumCast for Meters {
+///     fn from<T: ToPrimitive>(n: T) -> Option<Self> { n.to_i32().map(Meters) }
+/// }
+///
+/// impl Bounded for Meters {
+///     fn min_value() -> Self { Meters(i32::min_value()) }
+///     fn max_value() -> Self { Meters(i32::max_value()) }
+/// }
+///
+/// impl SampleUniform for Meters {
+///     type Sampler = UniformNumInt<Meters>;
+/// }
+///
+/// # fn main() {
+/// let d = Uniform::new(Meters(-100), Meters(100)).sample(&mut rand::thread_rng());
+/// assert!(Meters(-100) <= d && d < Meters(100));
+/// # }
+/// ```
+///
+/// # Implementation notes
+///
+/// Values are mapped to `u64` in an order-preserving way and sampled with
+/// [`UniformInt`], so the result is unbiased. Types with values outside the
+/// range of `i64` (for signed types) or `u64` (for unsigned types) are not
+/// supported; constructing the back-end panics for such bounds.
+///
+/// [`UniformSampler`]: trait.UniformSampler.html
+/// [`Uniform`]: struct.Uniform.html
+/// [`UniformInt`]: struct.UniformInt.html
+#[cfg(feature = "num-traits")]
+#[derive(Clone, Copy, Debug)]
+pub struct UniformNumInt<X> {
+    inner: UniformInt<u64>,
+    signed: bool,
+    phantom: ::core::marker::PhantomData<X>,
+}
+
+#[cfg(feature = "num-traits")]
+impl<X> UniformNumInt<X>
+    where X: Copy + PartialOrd + ::num_traits::NumCast + ::num_traits::Bounded
+{
+    fn is_signed() -> bool {
+        X::min_value().to_i64().unwrap_or(0) < 0
+    }
+
+    // Map to `u64`, preserving the order
+    fn to_key(x: X, signed: bool) -> u64 {
+        let key = if signed {
+            x.to_i64().map(|x| (x as u64) ^ (1 << 63))
+        } else {
+            x.to_u64()
+        };
+        key.expect("UniformNumInt: value out of the range of i64 or u64")
+    }
+
+    fn from_key(key: u64, signed: bool) -> X {
+        let x = if signed {
+            X::from((key ^ (1 << 63)) as i64)
+        } else {
+            X::from(key)
+        };
+        // `key` is within the bounds, which are representable
+        x.unwrap()
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl<X> UniformSampler for UniformNumInt<X>
+    where X: Copy + PartialOrd + ::num_traits::NumCast + ::num_traits::Bounded
+{
+    type X = X;
+
+    fn new(low: X, high: X) -> Self {
+        assert!(low < high, "Uniform::new called with `low >= high`");
+        let signed = Self::is_signed();
+        UniformNumInt {
+            inner: UniformInt::<u64>::new(Self::to_key(low, signed),
+                                          Self::to_key(high, signed)),
+            signed,
+            phantom: ::core::marker::PhantomData,
+        }
+    }
+
+    fn new_inclusive(low: X, high: X) -> Self {
+        assert!(low <= high, "Uniform::new_inclusive called with `low > high`");
+        let signed = Self::is_signed();
+        UniformNumInt {
+            inner: UniformInt::<u64>::new_inclusive(Self::to_key(low, signed),
+                                                    Self::to_key(high, signed)),
+            signed,
+            phantom: ::core::marker::PhantomData,
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> X {
+        Self::from_key(self.inner.sample(rng), self.signed)
+    }
+}
+
+/// The back-end implementing [`UniformSampler`] for floating-point types
+/// supported by the `num-traits` crate.
+///
+/// Like [`UniformNumInt`], this allows custom floating-point types which
+/// implement the `NumCast` trait of `num-traits` to be used with [`Uniform`],
+/// by implementing [`SampleUniform`] with `UniformNumFloat<Self>` as the
+/// `Sampler`.
+///
+/// # Implementation notes
+///
+/// Values are sampled as an `f64` with [`UniformFloat`] and converted with
+/// `NumCast::from`. Rounding may produce a value outside of the range, e.g.
+/// the excluded upper bound; such values are rejected and a new value is
+/// sampled.
+///
+/// Only types which are at least as precise as `f64` are sampled with the
+/// same distribution as [`UniformFloat`]. For coarser types, `NumCast::from`
+/// usually rounds to the nearest value, so `low` only receives the samples
+/// rounded up to it. This biases the result: when the range contains few
+/// representable values, `low` is returned about half as often as the other
+/// values. The same applies to `high` with `new_inclusive`. Types needing
+/// exact results should provide their own [`UniformSampler`].
+///
+/// [`UniformSampler`]: trait.UniformSampler.html
+/// [`Uniform`]: struct.Uniform.html
+/// [`SampleUniform`]: trait.SampleUniform.html
+/// [`UniformNumInt`]: struct.UniformNumInt.html
+/// [`UniformFloat`]: struct.UniformFloat.html
+#[cfg(feature = "num-traits")]
+#[derive(Clone, Copy, Debug)]
+pub struct UniformNumFloat<X> {
+    inner: UniformFloat<f64>,
+    low: X,
+    high: X,
+    inclusive: bool,
+}
+
+#[cfg(feature = "num-traits")]
+impl<X> UniformSampler for UniformNumFloat<X>
+    where X: Copy + PartialOrd + ::num_traits::NumCast
+{
+    type X = X;
+
+    fn new(low: X, high: X) -> Self {
+        assert!(low < high, "Uniform::new called with `low >= high`");
+        UniformNumFloat {
+            inner: UniformFloat::<f64>::new(to_f64(low), to_f64(high)),
+            low,
+            high,
+            inclusive: false,
+        }
+    }
+
+    fn new_inclusive(low: X, high: X) -> Self {
+        assert!(low <= high, "Uniform::new_inclusive called with `low > high`");
+        UniformNumFloat {
+            inner: UniformFloat::<f64>::new_inclusive(to_f64(low), to_f64(high)),
+            low,
+            high,
+            inclusive: true,
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> X {
+        loop {
+            if let Some(x) = X::from(self.inner.sample(rng)) {
+                if x >= self.low && (x < self.high ||
+                                     (self.inclusive && x == self.high)) {
+                    return x;
+                }
             }
         }
     }
 }
 
-uniform_float_impl! { f32, 32 - 23, next_u32 }
-uniform_float_impl! { f64, 64 - 52, next_u64 }
+#[cfg(feature = "num-traits")]
+fn to_f64<X: ::num_traits::ToPrimitive>(x: X) -> f64 {
+    x.to_f64().expect("UniformNumFloat: value not representable as f64")
+}
+
+
+
+/// The back-end implementing [`UniformSampler`] for `char`.
+///
+/// Unless you are implementing [`UniformSampler`] for your own types, this type
+/// should not be used directly, use [`Uniform`] instead.
+///
+/// The range is sampled as a range of `u32` code points with the surrogate
+/// code points (`0xD800...0xDFFF`) removed, so every valid `char` within the
+/// bounds is equally likely and no rejection is needed.
+///
+/// [`UniformSampler`]: trait.UniformSampler.html
+/// [`Uniform`]: struct.Uniform.html
+#[derive(Clone, Copy, Debug)]
+pub struct UniformChar {
+    sampler: UniformInt<u32>,
+}
+
+/// The first surrogate code point, which is not a valid `char`.
+const CHAR_SURROGATE_START: u32 = 0xD800;
+/// The number of surrogate code points.
+const CHAR_SURROGATE_LEN: u32 = 0xE000 - CHAR_SURROGATE_START;
+
+/// Convert a `char` to a `u32`, skipping the surrogate code points.
+#[inline]
+fn char_to_comp_u32(c: char) -> u32 {
+    match c as u32 {
+        c if c >= CHAR_SURROGATE_START => c - CHAR_SURROGATE_LEN,
+        c => c,
+    }
+}
+
+impl SampleUniform for char {
+    type Sampler = UniformChar;
+}
+
+impl UniformSampler for UniformChar {
+    type X = char;
+
+    #[inline]
+    fn new(low: char, high: char) -> UniformChar {
+        UniformChar {
+            sampler: UniformInt::<u32>::new(char_to_comp_u32(low),
+                                            char_to_comp_u32(high)),
+        }
+    }
+
+    #[inline]
+    fn new_inclusive(low: char, high: char) -> UniformChar {
+        UniformChar {
+            sampler: UniformInt::<u32>::new_inclusive(char_to_comp_u32(low),
+                                                      char_to_comp_u32(high)),
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        let mut x = self.sampler.sample(rng);
+        if x >= CHAR_SURROGATE_START {
+            x += CHAR_SURROGATE_LEN;
+        }
+        // `x` is in the range of two valid `char`s and is not a surrogate, so
+        // it is a valid `char` too.
+        unsafe { ::core::char::from_u32_unchecked(x) }
+    }
+}
 
 
 
@@ -593,6 +1262,21 @@ uniform_float_impl! { f64, 64 - 52, next_u64 }
 /// Unless you are implementing [`UniformSampler`] for your own types, this type
 /// should not be used directly, use [`Uniform`] instead.
 ///
+/// Durations are sampled with nanosecond precision. Ranges of any length are
+/// supported, including those longer than `u64::MAX` nanoseconds.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rand::{thread_rng, Rng};
+///
+/// // Add some jitter to a retry delay
+/// let delay: Duration = thread_rng().gen_range(Duration::from_millis(50),
+///                                              Duration::from_millis(150));
+/// assert!(delay >= Duration::from_millis(50) && delay < Duration::from_millis(150));
+/// ```
+///
 /// [`UniformSampler`]: trait.UniformSampler.html
 /// [`Uniform`]: struct.Uniform.html
 #[cfg(feature = "std")]
@@ -679,10 +1363,225 @@ impl UniformSampler for UniformDuration {
     }
 }
 
+/// The back-end implementing [`UniformSampler`] for `SystemTime`.
+///
+/// Unless you are implementing [`UniformSampler`] for your own types, this type
+/// should not be used directly, use [`Uniform`] instead.
+///
+/// The time is sampled as an offset from the lower bound, using
+/// [`UniformDuration`], so it has the same nanosecond precision.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use rand::distributions::{Distribution, Uniform};
+///
+/// // Timestamps spread over a day
+/// let start = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+/// let end = start + Duration::from_secs(24 * 60 * 60);
+/// let timestamps = Uniform::new(start, end);
+/// let t = timestamps.sample(&mut rand::thread_rng());
+/// assert!(start <= t && t < end);
+/// ```
+///
+/// [`UniformSampler`]: trait.UniformSampler.html
+/// [`Uniform`]: struct.Uniform.html
+/// [`UniformDuration`]: struct.UniformDuration.html
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct UniformSystemTime {
+    low: SystemTime,
+    offset: UniformDuration,
+}
+
+#[cfg(feature = "std")]
+impl SampleUniform for SystemTime {
+    type Sampler = UniformSystemTime;
+}
+
+#[cfg(feature = "std")]
+impl UniformSampler for UniformSystemTime {
+    type X = SystemTime;
+
+    #[inline]
+    fn new(low: SystemTime, high: SystemTime) -> UniformSystemTime {
+        let size = match high.duration_since(low) {
+            Ok(size) => size,
+            Err(_) => panic!("Uniform::new called with `low > high`"),
+        };
+        UniformSystemTime {
+            low,
+            offset: UniformDuration::new(Duration::new(0, 0), size),
+        }
+    }
+
+    #[inline]
+    fn new_inclusive(low: SystemTime, high: SystemTime) -> UniformSystemTime {
+        let size = match high.duration_since(low) {
+            Ok(size) => size,
+            Err(_) => panic!("Uniform::new_inclusive called with `low > high`"),
+        };
+        UniformSystemTime {
+            low,
+            offset: UniformDuration::new_inclusive(Duration::new(0, 0), size),
+        }
+    }
+
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SystemTime {
+        self.low + self.offset.sample(rng)
+    }
+}
+
+
+
+/// Sample values uniformly from a range with a fixed step size.
+///
+/// `UniformStepped::new(low, high, step)` samples from the values `low`,
+/// `low + step`, `low + 2 * step`, ... which are less than `high`, each with
+/// equal probability. This is useful for quantized values, e.g. multiples of
+/// 0.25 or offsets aligned to 8 bytes.
+///
+/// Sampling is implemented by sampling the index of the step with
+/// [`Uniform`], so it is free of bias. For floating-point types, values are
+/// computed as `low + index * step`, which is subject to rounding like any
+/// other floating-point arithmetic.
+///
+/// Supported are all primitive integer types except `u128` and `i128`, and
+/// `f32` and `f64`. At most 2<sup>64</sup> distinct values can be sampled.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Distribution, UniformStepped};
+///
+/// let mut rng = rand::thread_rng();
+///
+/// // A multiple of 0.25 in [0, 2)
+/// let v = UniformStepped::new(0.0, 2.0, 0.25).sample(&mut rng);
+/// assert!(v >= 0.0 && v < 2.0 && v % 0.25 == 0.0);
+///
+/// // An offset aligned to 8 bytes
+/// let offset = UniformStepped::new(0usize, 4096, 8).sample(&mut rng);
+/// assert_eq!(offset % 8, 0);
+/// ```
+///
+/// [`Uniform`]: struct.Uniform.html
+#[derive(Clone, Copy, Debug)]
+pub struct UniformStepped<X> {
+    low: X,
+    step: X,
+    index: Uniform<u64>,
+}
+
+impl<X: SampleStepped> UniformStepped<X> {
+    /// Construct a new `UniformStepped` sampling `low + k * step` for all
+    /// `k >= 0` such that the value is less than `high`.
+    ///
+    /// # Panics
+    ///
+    /// If `low >= high` or `step <= 0`. For floating-point types, also if any
+    /// parameter is not finite or if the range contains more than
+    /// 2<sup>64</sup> steps.
+    pub fn new(low: X, high: X, step: X) -> UniformStepped<X> {
+        let n = X::num_steps(low, high, step);
+        UniformStepped { low, step, index: Uniform::new(0, n) }
+    }
+}
+
+impl<X: SampleStepped> Distribution<X> for UniformStepped<X> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> X {
+        X::nth_step(self.low, self.step, self.index.sample(rng))
+    }
+}
+
+/// Helper trait for types supported by [`UniformStepped`].
+///
+/// [`UniformStepped`]: struct.UniformStepped.html
+pub trait SampleStepped: Copy {
+    /// The number of values `low + k * step` less than `high`, with `k >= 0`.
+    ///
+    /// Panics if `low >= high`, `step <= 0` or the result does not fit in a
+    /// `u64`.
+    fn num_steps(low: Self, high: Self, step: Self) -> u64;
+
+    /// The value `low + k * step`.
+    fn nth_step(low: Self, step: Self, k: u64) -> Self;
+}
+
+macro_rules! uniform_stepped_int_impl {
+    ($ty:ty, $unsigned:ty) => {
+        impl SampleStepped for $ty {
+            fn num_steps(low: $ty, high: $ty, step: $ty) -> u64 {
+                assert!(low < high, "UniformStepped::new called with `low >= high`");
+                assert!(step > 0, "UniformStepped::new called with `step <= 0`");
+                let range = high.wrapping_sub(low) as $unsigned as u64;
+                (range - 1) / (step as $unsigned as u64) + 1
+            }
+
+            #[inline]
+            fn nth_step(low: $ty, step: $ty, k: u64) -> $ty {
+                // `k * step < high - low`, so this does not overflow.
+                let offset = (k as $unsigned).wrapping_mul(step as $unsigned);
+                low.wrapping_add(offset as $ty)
+            }
+        }
+    }
+}
+
+uniform_stepped_int_impl! { i8, u8 }
+uniform_stepped_int_impl! { i16, u16 }
+uniform_stepped_int_impl! { i32, u32 }
+uniform_stepped_int_impl! { i64, u64 }
+uniform_stepped_int_impl! { isize, usize }
+uniform_stepped_int_impl! { u8, u8 }
+uniform_stepped_int_impl! { u16, u16 }
+uniform_stepped_int_impl! { u32, u32 }
+uniform_stepped_int_impl! { u64, u64 }
+uniform_stepped_int_impl! { usize, usize }
+
+macro_rules! uniform_stepped_float_impl {
+    ($ty:ty) => {
+        impl SampleStepped for $ty {
+            fn num_steps(low: $ty, high: $ty, step: $ty) -> u64 {
+                assert!(low < high, "UniformStepped::new called with `low >= high`");
+                assert!(step > 0.0, "UniformStepped::new called with `step <= 0`");
+                assert!(low.is_finite() && high.is_finite() && step.is_finite(),
+                        "UniformStepped::new called with non-finite parameters");
+                let steps = (high - low) / step;
+                assert!(steps < 18446744073709551616.0,
+                        "UniformStepped::new: too many steps");
+                // `steps` may be rounded, so adjust the number of values such
+                // that the last value is the last one less than `high`.
+                let mut n = steps as u64;
+                while low + (n as $ty) * step < high {
+                    n += 1;
+                }
+                while n > 1 && low + ((n - 1) as $ty) * step >= high {
+                    n -= 1;
+                }
+                n
+            }
+
+            #[inline]
+            fn nth_step(low: $ty, step: $ty, k: u64) -> $ty {
+                low + (k as $ty) * step
+            }
+        }
+    }
+}
+
+uniform_stepped_float_impl! { f32 }
+uniform_stepped_float_impl! { f64 }
+
 #[cfg(test)]
 mod tests {
     use Rng;
-    use distributions::uniform::{Uniform, UniformSampler, UniformFloat, SampleUniform};
+    use distributions::Distribution;
+    use distributions::uniform::{Uniform, UniformSampler, UniformFloat, SampleUniform,
+                                 UniformStepped};
 
     #[should_panic]
     #[test]
@@ -762,6 +1661,90 @@ mod tests {
         t!(i128, u128)
     }
 
+    #[test]
+    fn test_masked() {
+        let mut rng = ::test::rng(252);
+        macro_rules! t {
+            ($($ty:ident),*) => {{
+                $(
+                   let v: &[($ty, $ty)] = &[(0, 1),
+                                            (0, 10),
+                                            (10, 127),
+                                            (3, 67),
+                                            (::core::$ty::MIN, ::core::$ty::MAX)];
+                   for &(low, high) in v.iter() {
+                        let my_uniform = Uniform::new_masked(low, high);
+                        for _ in 0..1000 {
+                            let v: $ty = rng.sample(my_uniform);
+                            assert!(low <= v && v < high);
+                        }
+                    }
+                 )*
+            }}
+        }
+        t!(i8, i16, i32, i64, isize,
+           u8, u16, u32, u64, usize);
+        #[cfg(feature = "i128_support")]
+        t!(i128, u128)
+    }
+
+    #[test]
+    fn test_masked_values() {
+        use rngs::mock::StepRng;
+        // For a range of 64, only the 6 most significant bits are used.
+        let mut rng = StepRng::new(0x5b00_0000, 0);
+        assert_eq!(rng.sample(Uniform::new_masked(0u32, 64)), 0x5b >> 2);
+        // For a range of 40, values of 40 and above are rejected.
+        let mut rng = StepRng::new(0xff00_0000, 0x6d00_0000);
+        assert_eq!(rng.sample(Uniform::new_masked(10u32, 50)), 10 + 0x1b);
+        // Every value in the range is reachable.
+        let mut rng = ::test::rng(253);
+        let distr = Uniform::new_masked(0u8, 5);
+        let mut seen = [false; 5];
+        for _ in 0..100 {
+            seen[rng.sample(distr) as usize] = true;
+        }
+        assert!(seen.iter().all(|&x| x));
+    }
+
+    #[test]
+    #[cfg(feature = "i128_support")]
+    fn test_u128_wide_ranges() {
+        let mut rng = ::test::rng(256);
+        // Ranges which don't fit in a `u64`
+        let v: &[(u128, u128)] = &[(0, 1 << 64),
+                                   (1 << 100, (1 << 100) + (1 << 80) + 3),
+                                   (3, ::core::u128::MAX - 3)];
+        for &(low, high) in v.iter() {
+            let my_uniform = Uniform::new(low, high);
+            let mut above_u64 = false;
+            for _ in 0..1000 {
+                let v: u128 = rng.sample(my_uniform);
+                assert!(low <= v && v < high);
+                if v - low > ::core::u64::MAX as u128 { above_u64 = true; }
+            }
+            assert!(above_u64 || high - low <= (1 << 64));
+        }
+    }
+
+    #[test]
+    fn test_sample_single_rejection() {
+        use RngCore;
+        use rngs::mock::StepRng;
+        // For a range of 3, `2^32 % 3 = 1` value must be rejected: 0, which
+        // has the lowest low word after multiplying.
+        let mut rng = StepRng::new(0, 1);
+        let v = <u32 as SampleUniform>::Sampler::sample_single(0, 3, &mut rng);
+        assert_eq!(v, 0);
+        assert_eq!(rng.next_u32(), 2);
+
+        // `0x5555_5556 * 3 = 2^32 + 2`, which is accepted
+        let mut rng = StepRng::new(0x5555_5556, 1);
+        let v = <u32 as SampleUniform>::Sampler::sample_single(10, 13, &mut rng);
+        assert_eq!(v, 11);
+        assert_eq!(rng.next_u32(), 0x5555_5557);
+    }
+
     #[test]
     fn test_floats() {
         let mut rng = ::test::rng(252);
@@ -786,6 +1769,303 @@ mod tests {
         t!(f32, f64)
     }
 
+    #[test]
+    fn test_float_bounds() {
+        use rngs::mock::StepRng;
+        macro_rules! t {
+            ($ty:ident, $bits_to_discard:expr) => {{
+                // The largest value `x` in `[0, 1)` that can be sampled
+                let mut max_rng = StepRng::new(!0, 0);
+                let mut zero_rng = StepRng::new(0, 0);
+                let v: &[($ty, $ty)] = &[(0.0, 100.0),
+                                         (-1.0, 1.0),
+                                         (1.0, 1.0 + 8.0 * ::core::$ty::EPSILON),
+                                         (-1e35, 1e35),
+                                         (1e-35, 1e-25),
+                                         (0.0, ::core::$ty::MIN_POSITIVE),
+                                         (-::core::$ty::MIN_POSITIVE, 0.0),
+                                         (0.0, ::core::$ty::MAX),
+                                         (-::core::$ty::MAX / 2.0,
+                                          ::core::$ty::MAX / 2.0)];
+                for &(low, high) in v.iter() {
+                    // The excluded upper bound is never sampled
+                    let my_uniform = Uniform::new(low, high);
+                    let max: $ty = max_rng.sample(my_uniform);
+                    assert!(low < max && max < high, "{} {} {}", low, max, high);
+                    assert_eq!(zero_rng.sample(my_uniform), low);
+
+                    // The included upper bound is sampled (or almost)
+                    let my_uniform = Uniform::new_inclusive(low, high);
+                    let max: $ty = max_rng.sample(my_uniform);
+                    assert!(low < max && max <= high, "{} {} {}", low, max, high);
+                    assert_eq!(zero_rng.sample(my_uniform), low);
+
+                    let v: $ty = <$ty as SampleUniform>::Sampler
+                                 ::sample_single(low, high, &mut max_rng);
+                    assert!(low < v && v < high, "{} {} {}", low, v, high);
+                }
+
+                // A range of a single value
+                let my_uniform = Uniform::new_inclusive(1.5 as $ty, 1.5);
+                assert_eq!(max_rng.sample(my_uniform), 1.5);
+            }}
+        }
+
+        t!(f32, 32 - 23);
+        t!(f64, 64 - 52);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_float_overflow() {
+        Uniform::new(::core::f64::MIN, ::core::f64::MAX);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_float_infinite() {
+        Uniform::new(0.0, ::core::f64::INFINITY);
+    }
+
+    #[test]
+    #[cfg(feature = "simd_support")]
+    fn test_simd() {
+        use core::simd::prelude::*;
+        let mut rng = ::test::rng(258);
+
+        let low = i16x8::from_array([-100, 0, 5, ::core::i16::MIN, 7, -1, 0, 300]);
+        let high = i16x8::from_array([100, 1, 10, ::core::i16::MAX, 8, 1, 3, 301]);
+        let my_uniform = Uniform::new(low, high);
+        for _ in 0..1000 {
+            let v: i16x8 = rng.sample(my_uniform);
+            assert!(low.simd_le(v).all() && v.simd_lt(high).all());
+        }
+
+        let low = u64x4::from_array([0, 10, 1 << 40, 0]);
+        let high = u64x4::from_array([10, 127, 1 << 41, ::core::u64::MAX]);
+        let my_uniform = Uniform::new_inclusive(low, high);
+        for _ in 0..1000 {
+            let v: u64x4 = rng.sample(my_uniform);
+            assert!(low.simd_le(v).all() && v.simd_le(high).all());
+        }
+
+        let low = f32x8::from_array([0.0, -1e35, 1e-35, -1e35, 1.0, 2.0, 3.0, -4.0]);
+        let high = f32x8::from_array([100.0, -1e25, 1e-25, 1e35, 1.5, 2.5, 3.5, 4.0]);
+        let my_uniform = Uniform::new(low, high);
+        for _ in 0..1000 {
+            let v: f32x8 = rng.sample(my_uniform);
+            assert!(low.simd_le(v).all() && v.simd_lt(high).all());
+        }
+
+        let v: f64x2 = rng.gen_range(f64x2::splat(-1.0), f64x2::splat(1.0));
+        assert!(v.simd_ge(f64x2::splat(-1.0)).all() && v.simd_lt(f64x2::splat(1.0)).all());
+    }
+
+    #[should_panic]
+    #[test]
+    #[cfg(feature = "simd_support")]
+    fn test_simd_bad_limits() {
+        use core::simd::prelude::*;
+        Uniform::new(u32x4::from_array([0, 1, 2, 3]), u32x4::from_array([1, 2, 2, 4]));
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn test_half() {
+        use half::{f16, bf16};
+        let mut rng = ::test::rng(259);
+
+        let (low, high) = (f16::from_f32(-1.5), f16::from_f32(2.25));
+        let my_uniform = Uniform::new(low, high);
+        let my_incl_uniform = Uniform::new_inclusive(low, high);
+        for _ in 0..1000 {
+            let v: f16 = rng.sample(my_uniform);
+            assert!(low <= v && v < high);
+            let v: f16 = rng.sample(my_incl_uniform);
+            assert!(low <= v && v <= high);
+        }
+
+        // Adjacent values: samples rounding up to `high` are rejected
+        let (low, high) = (bf16::from_f32(1.0), bf16::from_f32(1.0078125));
+        assert_eq!(high, bf16::from_bits(low.to_bits() + 1));
+        for _ in 0..100 {
+            assert_eq!(rng.gen_range(low, high), low);
+        }
+
+        // All values of a short range are equally likely, including the
+        // bounds. Each count has a standard deviation of about 95.
+        let low = f16::from_f32(1.0);
+        let high = f16::from_f32(1.0078125);
+        assert_eq!(high, f16::from_bits(low.to_bits() + 8));
+        let mut counts = [0u32; 8];
+        for _ in 0..80_000 {
+            let v: f16 = rng.gen_range(low, high);
+            counts[(v.to_bits() - low.to_bits()) as usize] += 1;
+        }
+        for &c in counts.iter() {
+            assert!(9_500 < c && c < 10_500, "{:?}", counts);
+        }
+
+        let my_incl_uniform = Uniform::new_inclusive(low, high);
+        let mut counts = [0u32; 9];
+        for _ in 0..90_000 {
+            let v: f16 = rng.sample(my_incl_uniform);
+            counts[(v.to_bits() - low.to_bits()) as usize] += 1;
+        }
+        for &c in counts.iter() {
+            assert!(9_500 < c && c < 10_500, "{:?}", counts);
+        }
+
+        // Negative values and values around zero
+        let (low, high) = (bf16::from_f32(-2.0), bf16::from_bits(0x4000));
+        let (mut neg, mut pos) = (0, 0);
+        for _ in 0..10_000 {
+            let v: bf16 = rng.gen_range(low, high);
+            assert!(low <= v && v < high);
+            if v < bf16::from_f32(0.0) { neg += 1 } else { pos += 1 }
+        }
+        assert!(4_700 < neg && 4_700 < pos);
+    }
+
+    #[test]
+    #[cfg(feature = "num-traits")]
+    fn test_num_traits() {
+        use distributions::uniform::{UniformNumInt, UniformNumFloat};
+        let mut rng = ::test::rng(261);
+
+        let d = UniformNumInt::<i8>::new_inclusive(-128, 127);
+        let mut seen = [false; 256];
+        for _ in 0..10000 {
+            seen[(d.sample(&mut rng) as i32 + 128) as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+
+        let d = UniformNumInt::<i64>::new(-5, 5);
+        let d2 = UniformNumInt::<u64>::new(u64::max_value() - 3,
+                                           u64::max_value());
+        for _ in 0..1000 {
+            let v = d.sample(&mut rng);
+            assert!(-5 <= v && v < 5);
+            let v = d2.sample(&mut rng);
+            assert!(u64::max_value() - 3 <= v && v < u64::max_value());
+        }
+
+        let d = UniformNumFloat::<f32>::new(-1.5, 2.25);
+        let d2 = UniformNumFloat::<f32>::new_inclusive(-1.5, 2.25);
+        for _ in 0..1000 {
+            let v = d.sample(&mut rng);
+            assert!(-1.5 <= v && v < 2.25);
+            let v = d2.sample(&mut rng);
+            assert!(-1.5 <= v && v <= 2.25);
+        }
+
+        // Adjacent values: samples rounding up to `high` are rejected
+        let (low, high) = (1.0f32, 1.0000001f32);
+        let d = UniformNumFloat::<f32>::new(low, high);
+        for _ in 0..100 {
+            assert_eq!(d.sample(&mut rng), low);
+        }
+
+        // Short ranges of a type coarser than `f64`: the interior values are
+        // equally likely, while `low` only gets about half the share (see the
+        // implementation notes).
+        let low = 1.0f32;
+        let high = f32::from_bits(low.to_bits() + 8);
+        let d = UniformNumFloat::<f32>::new(low, high);
+        let mut counts = [0u32; 8];
+        for _ in 0..75_000 {
+            counts[(d.sample(&mut rng).to_bits() - low.to_bits()) as usize] += 1;
+        }
+        assert!(4_600 < counts[0] && counts[0] < 5_400, "{:?}", counts);
+        for &c in counts[1..].iter() {
+            assert!(9_500 < c && c < 10_500, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_stepped() {
+        let mut rng = ::test::rng(260);
+
+        let d = UniformStepped::new(0.0, 2.0, 0.25);
+        let mut seen = [false; 8];
+        for _ in 0..1000 {
+            let v: f64 = d.sample(&mut rng);
+            assert!(v >= 0.0 && v < 2.0);
+            assert_eq!(v % 0.25, 0.0);
+            seen[(v / 0.25) as usize] = true;
+        }
+        assert_eq!(seen, [true; 8]);
+
+        // `high` is not a multiple of `step`
+        let d = UniformStepped::new(-1.0f32, 0.1, 0.5);
+        let mut seen = [false; 3];
+        for _ in 0..100 {
+            let v: f32 = d.sample(&mut rng);
+            seen[((v + 1.0) / 0.5) as usize] = true;
+        }
+        assert_eq!(seen, [true; 3]);
+
+        // `(high - low) / step` is rounded to 2.9999999999999996
+        let d = UniformStepped::new(0.0, 0.3, 0.1);
+        for _ in 0..100 {
+            let v: f64 = d.sample(&mut rng);
+            assert!(v == 0.0 || v == 0.1 || v == 0.2);
+        }
+
+        let d = UniformStepped::new(0usize, 4096, 8);
+        for _ in 0..1000 {
+            let v = d.sample(&mut rng);
+            assert!(v < 4096 && v % 8 == 0);
+        }
+
+        let d = UniformStepped::new(-128i8, 127, 100);
+        let mut seen = [false; 3];
+        for _ in 0..100 {
+            let v = d.sample(&mut rng);
+            seen[((v as i32 + 128) / 100) as usize] = true;
+            assert!(v == -128 || v == -28 || v == 72);
+        }
+        assert_eq!(seen, [true; 3]);
+
+        let d = UniformStepped::new(0u64, ::core::u64::MAX, 1);
+        d.sample(&mut rng);
+        let d = UniformStepped::new(5u32, 6, 1000);
+        assert_eq!(d.sample(&mut rng), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stepped_zero_step() {
+        UniformStepped::new(0.0, 1.0, 0.0);
+    }
+
+    #[test]
+    fn test_char() {
+        let mut rng = ::test::rng(254);
+        let v = &[('a', 'z'), ('0', '9'), ('\u{D7F0}', '\u{E010}'),
+                  ('\u{0}', '\u{10FFFF}')];
+        for &(low, high) in v.iter() {
+            let my_uniform = Uniform::new(low, high);
+            for _ in 0..1000 {
+                let c: char = rng.sample(my_uniform);
+                assert!(low <= c && c < high);
+            }
+
+            let my_uniform = Uniform::new_inclusive(low, high);
+            for _ in 0..1000 {
+                let c: char = rng.sample(my_uniform);
+                assert!(low <= c && c <= high);
+            }
+        }
+
+        // Only the two code points around the surrogates can be sampled
+        let my_uniform = Uniform::new_inclusive('\u{D7FF}', '\u{E000}');
+        for _ in 0..100 {
+            let c: char = rng.sample(my_uniform);
+            assert!(c == '\u{D7FF}' || c == '\u{E000}');
+        }
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_durations() {
@@ -802,9 +2082,60 @@ mod tests {
                 let v = rng.sample(my_uniform);
                 assert!(low <= v && v < high);
             }
+
+            let my_uniform = Uniform::new_inclusive(low, high);
+            for _ in 0..1000 {
+                let v = rng.sample(my_uniform);
+                assert!(low <= v && v <= high);
+            }
+
+            for _ in 0..1000 {
+                let v = rng.gen_range(low, high);
+                assert!(low <= v && v < high);
+            }
+        }
+
+        // A range of a single nanosecond
+        let low = Duration::new(5, 999_999_999);
+        let my_uniform = Uniform::new_inclusive(low, low);
+        assert_eq!(rng.sample(my_uniform), low);
+        assert_eq!(rng.gen_range(low, Duration::new(6, 0)), low);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_system_times() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut rng = ::test::rng(257);
+
+        let epoch = UNIX_EPOCH + Duration::new(1_000_000_000, 0);
+        let v = &[(epoch, epoch + Duration::new(0, 10)),
+                  (epoch, epoch + Duration::new(86_400, 500_000_000)),
+                  (UNIX_EPOCH - Duration::new(3600, 0), epoch)];
+        for &(low, high) in v.iter() {
+            let my_uniform = Uniform::new(low, high);
+            for _ in 0..1000 {
+                let v = rng.sample(my_uniform);
+                assert!(low <= v && v < high);
+            }
+
+            let my_uniform = Uniform::new_inclusive(low, high);
+            for _ in 0..1000 {
+                let v = rng.sample(my_uniform);
+                assert!(low <= v && v <= high);
+            }
         }
     }
 
+    #[should_panic]
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_system_times_bad_limits() {
+        use std::time::{Duration, UNIX_EPOCH};
+        Uniform::new(UNIX_EPOCH + Duration::new(1, 0), UNIX_EPOCH);
+    }
+
     #[test]
     fn test_custom_uniform() {
         #[derive(Clone, Copy, PartialEq, PartialOrd)]
@@ -848,7 +2179,7 @@ mod tests {
         assert_eq!(r.inner.low, 2);
         assert_eq!(r.inner.range, 5);
         let r = Uniform::from(2.0f64..7.0);
-        assert_eq!(r.inner.offset, -3.0);
+        assert_eq!(r.inner.low, 2.0);
         assert_eq!(r.inner.scale, 5.0);
     }
 }
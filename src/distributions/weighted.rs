@@ -0,0 +1,237 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Weighted index sampling.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::AddAssign;
+
+#[cfg(not(feature="std"))] use alloc::Vec;
+
+use Rng;
+use distributions::Distribution;
+use distributions::uniform::{UniformSampler, SampleUniform, SampleBorrow};
+
+/// A distribution using weighted sampling to pick a discretely selected item.
+///
+/// Sampling a `WeightedIndex` distribution returns the index of a randomly
+/// selected element from the iterator used when the `WeightedIndex` was
+/// created. The chance of a given element being picked is proportional to the
+/// value of the element. The weights can use any type `X` for which an
+/// implementation of [`Uniform<X>`] exists.
+///
+/// Sampling uses `O(log N)` time, where `N` is the number of weights; the
+/// cumulative weights are stored, using `O(N)` memory.
+///
+/// See also [`SliceRandom::choose_weighted`], which picks an element of a
+/// slice directly.
+///
+/// # Example
+///
+/// ```
+/// use rand::prelude::*;
+/// use rand::distributions::WeightedIndex;
+///
+/// let choices = ['a', 'b', 'c'];
+/// let weights = [2, 1, 1];
+/// let dist = WeightedIndex::new(&weights).unwrap();
+/// let mut rng = thread_rng();
+/// for _ in 0..100 {
+///     // 50% chance to print 'a', 25% chance to print 'b', 25% chance to print 'c'
+///     println!("{}", choices[dist.sample(&mut rng)]);
+/// }
+/// ```
+///
+/// [`Uniform<X>`]: struct.Uniform.html
+/// [`SliceRandom::choose_weighted`]: ../seq/trait.SliceRandom.html#tymethod.choose_weighted
+#[derive(Debug, Clone)]
+pub struct WeightedIndex<X: SampleUniform + PartialOrd> {
+    cumulative_weights: Vec<X>,
+    weight_distribution: X::Sampler,
+}
+
+impl<X: SampleUniform + PartialOrd> WeightedIndex<X> {
+    /// Create a new `WeightedIndex` from the weights yielded by an iterator.
+    ///
+    /// Returns an error if the iterator is empty, if any weight is negative
+    /// (`< X::default()`), or if all weights are zero.
+    pub fn new<I>(weights: I) -> Result<WeightedIndex<X>, WeightedError>
+        where I: IntoIterator,
+              I::Item: SampleBorrow<X>,
+              X: for<'a> AddAssign<&'a X> + Clone + Default
+    {
+        let mut iter = weights.into_iter();
+        let mut total_weight: X = match iter.next() {
+            Some(w) => w.borrow().clone(),
+            None => return Err(WeightedError::NoItem),
+        };
+
+        let zero = X::default();
+        if !is_valid_weight(&total_weight, &zero) {
+            return Err(WeightedError::InvalidWeight);
+        }
+
+        let mut weights = Vec::<X>::with_capacity(iter.size_hint().0);
+        for w in iter {
+            if !is_valid_weight(w.borrow(), &zero) {
+                return Err(WeightedError::InvalidWeight);
+            }
+            weights.push(total_weight.clone());
+            total_weight += w.borrow();
+        }
+
+        if total_weight == zero {
+            return Err(WeightedError::AllWeightsZero);
+        }
+        let distr = X::Sampler::new(zero, total_weight);
+
+        Ok(WeightedIndex { cumulative_weights: weights, weight_distribution: distr })
+    }
+}
+
+/// A weight is valid if it is at least zero; this rejects NaN.
+fn is_valid_weight<X: PartialOrd>(weight: &X, zero: &X) -> bool {
+    match weight.partial_cmp(zero) {
+        Some(Ordering::Greater) | Some(Ordering::Equal) => true,
+        _ => false,
+    }
+}
+
+impl<X: SampleUniform + PartialOrd> Distribution<usize> for WeightedIndex<X> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let chosen_weight = self.weight_distribution.sample(rng);
+        // Find the first item which has a weight *higher* than the chosen
+        // weight. `cumulative_weights` holds the total weight *before* each
+        // item except the first, so the index of that item is the result.
+        self.cumulative_weights.binary_search_by(|w| {
+            if *w <= chosen_weight { Ordering::Less } else { Ordering::Greater }
+        }).unwrap_err()
+    }
+}
+
+/// Error type returned from [`WeightedIndex::new`] and the weighted choice
+/// methods of [`SliceRandom`].
+///
+/// [`WeightedIndex::new`]: struct.WeightedIndex.html#method.new
+/// [`SliceRandom`]: ../seq/trait.SliceRandom.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightedError {
+    /// The provided weight collection contains no items.
+    NoItem,
+    /// A weight is either less than zero or NaN.
+    InvalidWeight,
+    /// All items in the provided weight collection are zero.
+    AllWeightsZero,
+}
+
+impl WeightedError {
+    fn msg(&self) -> &'static str {
+        match *self {
+            WeightedError::NoItem => "no weights provided",
+            WeightedError::InvalidWeight => "a weight is negative or NaN",
+            WeightedError::AllWeightsZero => "all weights are zero",
+        }
+    }
+}
+
+impl fmt::Display for WeightedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.msg())
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for WeightedError {
+    fn description(&self) -> &str {
+        self.msg()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use Rng;
+    use super::{WeightedIndex, WeightedError};
+
+    #[test]
+    fn test_weightedindex() {
+        let mut r = ::test::rng(700);
+        const N_REPS: u32 = 5000;
+        let weights = [1u32, 2, 3, 0, 5, 6, 7, 1, 2, 3, 4, 5, 6, 7];
+        let total_weight = weights.iter().sum::<u32>() as f32;
+
+        let verify = |result: [i32; 14]| {
+            for (i, count) in result.iter().enumerate() {
+                let exp = (weights[i] * N_REPS) as f32 / total_weight;
+                let mut err = (*count as f32 - exp).abs();
+                if err != 0.0 {
+                    err /= exp;
+                }
+                assert!(err <= 0.25);
+            }
+        };
+
+        // WeightedIndex from vec
+        let mut chosen = [0i32; 14];
+        let distr = WeightedIndex::new(weights.to_vec()).unwrap();
+        for _ in 0..N_REPS {
+            chosen[r.sample(&distr)] += 1;
+        }
+        verify(chosen);
+
+        // WeightedIndex from slice
+        chosen = [0i32; 14];
+        let distr = WeightedIndex::new(&weights[..]).unwrap();
+        for _ in 0..N_REPS {
+            chosen[r.sample(&distr)] += 1;
+        }
+        verify(chosen);
+
+        // WeightedIndex from iterator
+        chosen = [0i32; 14];
+        let distr = WeightedIndex::new(weights.iter()).unwrap();
+        for _ in 0..N_REPS {
+            chosen[r.sample(&distr)] += 1;
+        }
+        verify(chosen);
+
+        let distr1 = WeightedIndex::new(&[0, 1][..]).unwrap();
+        let distr2 = WeightedIndex::new(&[1, 0][..]).unwrap();
+        let distr3 = WeightedIndex::new(&[0, 0, 0, 0, 10, 0][..]).unwrap();
+        for _ in 0..5 {
+            assert_eq!(r.sample(&distr1), 1);
+            assert_eq!(r.sample(&distr2), 0);
+            assert_eq!(r.sample(&distr3), 4);
+        }
+
+        assert_eq!(WeightedIndex::new(&[10][0..0]).unwrap_err(), WeightedError::NoItem);
+        assert_eq!(WeightedIndex::new(&[0][..]).unwrap_err(), WeightedError::AllWeightsZero);
+        assert_eq!(WeightedIndex::new(&[10, 20, -1, 30][..]).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert_eq!(WeightedIndex::new(&[-10, 20, 1, 30][..]).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert_eq!(WeightedIndex::new(&[-10][..]).unwrap_err(), WeightedError::InvalidWeight);
+        assert_eq!(WeightedIndex::new(&[1.0, ::core::f64::NAN][..]).unwrap_err(),
+                   WeightedError::InvalidWeight);
+    }
+
+    #[test]
+    fn test_weightedindex_float() {
+        let mut r = ::test::rng(701);
+        let distr = WeightedIndex::new(&[0.0, 0.5, 0.0, 1.5][..]).unwrap();
+        let mut chosen = [0; 4];
+        for _ in 0..1000 {
+            chosen[r.sample(&distr)] += 1;
+        }
+        assert_eq!(chosen[0], 0);
+        assert_eq!(chosen[2], 0);
+        assert!(chosen[1] > 200 && chosen[1] < 300);
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A generic implementation of the Ziggurat method.
+//!
+//! The [`Normal`] and [`Exp`] distributions are sampled with the ZIGNOR
+//! variant[1] of the Ziggurat method, using precomputed tables. This module
+//! makes the same algorithm available for other densities: implement
+//! [`Density`] for a type describing the density, and [`Ziggurat`] computes
+//! the tables when it is constructed.
+//!
+//! The Ziggurat method works for densities which are monotonically
+//! decreasing on `[0, ∞)`, optionally mirrored around 0 to cover the whole
+//! real line (see [`Ziggurat::new_symmetric`]). Densities on another range or
+//! with the mode somewhere else can be handled by shifting and scaling the
+//! samples.
+//!
+//! # Example
+//!
+//! ```
+//! use rand::Rng;
+//! use rand::distributions::Distribution;
+//! use rand::distributions::ziggurat::{Density, Ziggurat};
+//!
+//! // The Laplace distribution, with density `exp(-|x|) / 2`
+//! #[derive(Clone, Copy, Debug)]
+//! struct Laplace;
+//!
+//! impl Density for Laplace {
+//!     fn pdf(&self, x: f64) -> f64 { (-x).exp() }
+//!     fn pdf_inv(&self, y: f64) -> f64 { -y.ln() }
+//!     fn tail_area(&self, r: f64) -> f64 { (-r).exp() }
+//!     fn sample_tail<R: Rng + ?Sized>(&self, rng: &mut R, r: f64) -> f64 {
+//!         r - rng.gen::<f64>().ln()
+//!     }
+//! }
+//!
+//! let laplace = Ziggurat::new_symmetric(Laplace);
+//! let v = laplace.sample(&mut rand::thread_rng());
+//! println!("{} is from a Laplace distribution", v);
+//! ```
+//!
+//! [1]: Jurgen A. Doornik (2005). [*An Improved Ziggurat Method to
+//! Generate Normal Random
+//! Samples*](https://www.doornik.com/research/ziggurat.pdf). Nuffield
+//! College, Oxford
+//!
+//! [`Normal`]: ../struct.Normal.html
+//! [`Exp`]: ../struct.Exp.html
+//! [`Density`]: trait.Density.html
+//! [`Ziggurat`]: struct.Ziggurat.html
+//! [`Ziggurat::new_symmetric`]: struct.Ziggurat.html#method.new_symmetric
+
+use core::fmt;
+
+use Rng;
+use distributions::Distribution;
+#[cfg(not(feature="std"))] #[allow(unused_imports)] use distributions::math::Float;
+
+/// The number of layers of the ziggurat. This must match the number of bits
+/// used to select the layer in `distributions::ziggurat`.
+const LAYERS: usize = 256;
+
+/// A probability density which can be sampled with a [`Ziggurat`].
+///
+/// The density only needs to be known up to a constant factor, but all
+/// methods have to use the same scale.
+///
+/// [`Ziggurat`]: struct.Ziggurat.html
+pub trait Density {
+    /// The density at `x`, for `x >= 0`. It must be positive and
+    /// monotonically decreasing.
+    fn pdf(&self, x: f64) -> f64;
+
+    /// The inverse of [`pdf`], for `0 < y <= pdf(0)`.
+    ///
+    /// [`pdf`]: #tymethod.pdf
+    fn pdf_inv(&self, y: f64) -> f64;
+
+    /// The area under the density to the right of `r`, i.e. the integral of
+    /// [`pdf`] over `[r, ∞)`.
+    ///
+    /// [`pdf`]: #tymethod.pdf
+    fn tail_area(&self, r: f64) -> f64;
+
+    /// Sample a value `x >= r` from the tail of the distribution, i.e. with
+    /// a density proportional to [`pdf`] on `[r, ∞)`.
+    ///
+    /// This is only needed for a small fraction of all samples, so a slow
+    /// method like rejection sampling is fine.
+    ///
+    /// [`pdf`]: #tymethod.pdf
+    fn sample_tail<R: Rng + ?Sized>(&self, rng: &mut R, r: f64) -> f64;
+}
+
+/// A distribution sampled with the Ziggurat method, for any [`Density`].
+///
+/// See the [module documentation](index.html) for details and an example.
+///
+/// Constructing a `Ziggurat` computes tables of 257 values each, which is
+/// relatively slow. It is best to construct it once and use it for many
+/// samples.
+///
+/// [`Density`]: trait.Density.html
+pub struct Ziggurat<F> {
+    density: F,
+    symmetric: bool,
+    r: f64,
+    x_tab: [f64; LAYERS + 1],
+    f_tab: [f64; LAYERS + 1],
+}
+
+impl<F: Density> Ziggurat<F> {
+    /// Construct a `Ziggurat` sampling values in `[0, ∞)` with the given
+    /// `density`.
+    ///
+    /// # Panics
+    ///
+    /// If no tables can be computed for `density`, which means it does not
+    /// satisfy the requirements of [`Density`].
+    ///
+    /// [`Density`]: trait.Density.html
+    pub fn new(density: F) -> Ziggurat<F> {
+        Ziggurat::construct(density, false)
+    }
+
+    /// Construct a `Ziggurat` sampling values in `(-∞, ∞)`, for the `density`
+    /// mirrored around 0, i.e. with density `pdf(|x|)`.
+    ///
+    /// # Panics
+    ///
+    /// If no tables can be computed for `density`, which means it does not
+    /// satisfy the requirements of [`Density`].
+    ///
+    /// [`Density`]: trait.Density.html
+    pub fn new_symmetric(density: F) -> Ziggurat<F> {
+        Ziggurat::construct(density, true)
+    }
+
+    fn construct(density: F, symmetric: bool) -> Ziggurat<F> {
+        let r = solve_r(&density);
+        let mut x_tab = [0.0; LAYERS + 1];
+        let mut f_tab = [0.0; LAYERS + 1];
+        let ok = layers(&density, r, &mut x_tab).is_some();
+        assert!(ok, "Ziggurat: failed to compute the tables for the density");
+        for (f, x) in f_tab.iter_mut().zip(x_tab.iter()) {
+            *f = density.pdf(*x);
+        }
+        Ziggurat { density, symmetric, r, x_tab, f_tab }
+    }
+
+    /// The start of the tail of the distribution, i.e. the right edge of the
+    /// base layer of the ziggurat.
+    pub fn r(&self) -> f64 {
+        self.r
+    }
+}
+
+/// Compute the right edges of the layers for a ziggurat with base layer edge
+/// `r`, as in `zigNorInit` in Doornik (2005).
+///
+/// Returns the density at the top of the highest layer, or `None` if the
+/// layers exceed the mode of the density before reaching the top, i.e. if `r`
+/// is too small.
+fn layers<F: Density>(density: &F, r: f64, x_tab: &mut [f64; LAYERS + 1])
+    -> Option<f64>
+{
+    let f0 = density.pdf(0.0);
+    let v = r * density.pdf(r) + density.tail_area(r);
+    x_tab[0] = v / density.pdf(r);
+    x_tab[1] = r;
+    for i in 2..LAYERS {
+        let last = x_tab[i - 1];
+        let y = v / last + density.pdf(last);
+        if y >= f0 || y.is_nan() {
+            return None;
+        }
+        x_tab[i] = density.pdf_inv(y);
+    }
+    x_tab[LAYERS] = 0.0;
+    let last = x_tab[LAYERS - 1];
+    Some(v / last + density.pdf(last))
+}
+
+/// Find the base layer edge `r` for which all layers have equal area, by
+/// bisection.
+fn solve_r<F: Density>(density: &F) -> f64 {
+    let f0 = density.pdf(0.0);
+    let mut x_tab = [0.0; LAYERS + 1];
+    let mut too_small = |r: f64| {
+        match layers(density, r, &mut x_tab) {
+            Some(top) => top > f0,
+            None => true,
+        }
+    };
+
+    // Find an upper bound first.
+    let mut low = 0.0;
+    let mut high = 1.0;
+    while too_small(high) {
+        low = high;
+        high *= 2.0;
+        assert!(high < 1.0e100, "Ziggurat: failed to compute the tables for the density");
+    }
+    loop {
+        let mid = low + (high - low) / 2.0;
+        if !(mid > low && mid < high) {
+            // No more precision available
+            return high;
+        }
+        if too_small(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+}
+
+impl<F: Density> Distribution<f64> for Ziggurat<F> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let symmetric = self.symmetric;
+        let pdf = |x: f64| self.density.pdf(x.abs());
+        let zero_case = |rng: &mut R, u: f64| {
+            let x = self.density.sample_tail(rng, self.r);
+            if symmetric && u < 0.0 { -x } else { x }
+        };
+        super::ziggurat(rng, symmetric, &self.x_tab, &self.f_tab, pdf, zero_case)
+    }
+}
+
+// Arrays of more than 32 elements do not implement `Clone` or `Debug`
+impl<F: Clone> Clone for Ziggurat<F> {
+    fn clone(&self) -> Ziggurat<F> {
+        Ziggurat {
+            density: self.density.clone(),
+            symmetric: self.symmetric,
+            r: self.r,
+            x_tab: self.x_tab,
+            f_tab: self.f_tab,
+        }
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for Ziggurat<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Ziggurat")
+            .field("density", &self.density)
+            .field("symmetric", &self.symmetric)
+            .field("r", &self.r)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use Rng;
+    use distributions::{Distribution, ziggurat_tables};
+    use super::{Density, Ziggurat};
+
+    #[derive(Clone, Copy, Debug)]
+    struct Exp;
+
+    impl Density for Exp {
+        fn pdf(&self, x: f64) -> f64 { (-x).exp() }
+        fn pdf_inv(&self, y: f64) -> f64 { -y.ln() }
+        fn tail_area(&self, r: f64) -> f64 { (-r).exp() }
+        fn sample_tail<R: Rng + ?Sized>(&self, rng: &mut R, r: f64) -> f64 {
+            r - rng.gen::<f64>().ln()
+        }
+    }
+
+    #[test]
+    fn test_ziggurat_tables() {
+        let zig = Ziggurat::new(Exp);
+        assert!((zig.r() - ziggurat_tables::ZIG_EXP_R).abs() < 1e-10);
+        for i in 0..257 {
+            let (a, b) = (zig.x_tab[i], ziggurat_tables::ZIG_EXP_X[i]);
+            assert!((a - b).abs() <= 1e-10 * b, "x_tab[{}]: {} != {}", i, a, b);
+            let (a, b) = (zig.f_tab[i], ziggurat_tables::ZIG_EXP_F[i]);
+            assert!((a - b).abs() <= 1e-10 * b, "f_tab[{}]: {} != {}", i, a, b);
+        }
+    }
+
+    #[test]
+    fn test_ziggurat_sample() {
+        let mut rng = ::test::rng(309);
+        let zig = Ziggurat::new(Exp);
+        let mut sum = 0.0;
+        for _ in 0..10000 {
+            let x = zig.sample(&mut rng);
+            assert!(x >= 0.0);
+            sum += x;
+        }
+        let mean = sum / 10000.0;
+        assert!(mean > 0.95 && mean < 1.05);
+    }
+
+    #[test]
+    fn test_ziggurat_symmetric() {
+        let mut rng = ::test::rng(310);
+        let zig = Ziggurat::new_symmetric(Exp);
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        let mut negative = 0;
+        for _ in 0..10000 {
+            let x = zig.sample(&mut rng);
+            sum += x;
+            sum_sq += x * x;
+            if x < 0.0 { negative += 1; }
+        }
+        // Laplace distribution with mean 0 and variance 2
+        let mean = sum / 10000.0;
+        let variance = sum_sq / 10000.0 - mean * mean;
+        assert!(mean.abs() < 0.05);
+        assert!(variance > 1.85 && variance < 2.15);
+        assert!(negative > 4800 && negative < 5200);
+    }
+}
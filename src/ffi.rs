@@ -0,0 +1,267 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! C interface to the PRNGs.
+//!
+//! This module is enabled by the `ffi` feature. It exports `extern "C"`
+//! functions, so that C and C++ code linked with a `cdylib` or `staticlib`
+//! crate depending on `rand` can use the same generators as Rust code, with
+//! bit-identical output for the same seed.
+//!
+//! Generators are allocated on the heap and passed around as opaque pointers.
+//! The corresponding C declarations are:
+//!
+//! ```c
+//! typedef struct rand_xoshiro256starstar rand_xoshiro256starstar;
+//!
+//! rand_xoshiro256starstar *rand_xoshiro256starstar_new(const uint8_t seed[32]);
+//! rand_xoshiro256starstar *rand_xoshiro256starstar_new_u64(uint64_t seed);
+//! rand_xoshiro256starstar *rand_xoshiro256starstar_clone(const rand_xoshiro256starstar *rng);
+//! void rand_xoshiro256starstar_free(rand_xoshiro256starstar *rng);
+//! uint32_t rand_xoshiro256starstar_next_u32(rand_xoshiro256starstar *rng);
+//! uint64_t rand_xoshiro256starstar_next_u64(rand_xoshiro256starstar *rng);
+//! void rand_xoshiro256starstar_fill_bytes(rand_xoshiro256starstar *rng, uint8_t *dest, size_t len);
+//! void rand_xoshiro256starstar_fill_u64(rand_xoshiro256starstar *rng, uint64_t *dest, size_t len);
+//! void rand_xoshiro256starstar_jump(rand_xoshiro256starstar *rng);
+//! void rand_xoshiro256starstar_long_jump(rand_xoshiro256starstar *rng);
+//! ```
+//!
+//! All pointers passed to these functions must be valid; only
+//! `rand_xoshiro256starstar_free` accepts a null pointer. None of the
+//! functions panic.
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::boxed::Box;
+use core::{ptr, slice};
+use {BatchRng, RngCore, SeedableRng};
+use prng::{SplitMix64, Xoshiro256StarStar};
+
+/// Create a xoshiro256** generator from a 32-byte seed, like
+/// [`Xoshiro256StarStar::from_seed`].
+///
+/// # Safety
+///
+/// `seed` must point to 32 readable bytes.
+///
+/// [`Xoshiro256StarStar::from_seed`]: ../prng/struct.Xoshiro256StarStar.html#method.from_seed
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_new(seed: *const u8)
+    -> *mut Xoshiro256StarStar
+{
+    let mut s = [0u8; 32];
+    ptr::copy_nonoverlapping(seed, s.as_mut_ptr(), 32);
+    Box::into_raw(Box::new(Xoshiro256StarStar::from_seed(s)))
+}
+
+/// Create a xoshiro256** generator from a 64-bit seed, which is expanded to
+/// the full state with [`SplitMix64`], as recommended by the authors of
+/// xoshiro.
+///
+/// This is equivalent to `Xoshiro256StarStar::from_rng` with a `SplitMix64`
+/// created from the little-endian bytes of `seed`.
+///
+/// [`SplitMix64`]: ../prng/struct.SplitMix64.html
+#[no_mangle]
+pub extern "C" fn rand_xoshiro256starstar_new_u64(seed: u64)
+    -> *mut Xoshiro256StarStar
+{
+    let mut s = [0u8; 8];
+    for (i, b) in s.iter_mut().enumerate() {
+        *b = (seed >> (8 * i)) as u8;
+    }
+    let rng = match Xoshiro256StarStar::from_rng(SplitMix64::from_seed(s)) {
+        Ok(rng) => rng,
+        // `SplitMix64` never fails
+        Err(_) => unreachable!(),
+    };
+    Box::into_raw(Box::new(rng))
+}
+
+/// Create a copy of a generator, which continues with the same output.
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_clone(
+    rng: *const Xoshiro256StarStar) -> *mut Xoshiro256StarStar
+{
+    Box::into_raw(Box::new((*rng).clone()))
+}
+
+/// Free a generator. Does nothing if `rng` is null.
+///
+/// # Safety
+///
+/// `rng` must be null or a generator created by this module which has
+/// not been freed yet. It must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_free(
+    rng: *mut Xoshiro256StarStar)
+{
+    if !rng.is_null() {
+        drop(Box::from_raw(rng));
+    }
+}
+
+/// Return the next random `u32`.
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_next_u32(
+    rng: *mut Xoshiro256StarStar) -> u32
+{
+    (*rng).next_u32()
+}
+
+/// Return the next random `u64`.
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_next_u64(
+    rng: *mut Xoshiro256StarStar) -> u64
+{
+    (*rng).next_u64()
+}
+
+/// Fill `len` bytes at `dest` with random data, like
+/// [`RngCore::fill_bytes`].
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed. `dest` must be valid for writes of `len` bytes, unless `len`
+/// is zero.
+///
+/// [`RngCore::fill_bytes`]: ../trait.RngCore.html#tymethod.fill_bytes
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_fill_bytes(
+    rng: *mut Xoshiro256StarStar, dest: *mut u8, len: usize)
+{
+    if len > 0 {
+        (*rng).fill_bytes(slice::from_raw_parts_mut(dest, len))
+    }
+}
+
+/// Fill `len` integers at `dest` with the next `u64` values, like
+/// [`BatchRng::next_u64_into`].
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed. `dest` must be valid and aligned for writes of `len`
+/// integers, unless `len` is zero.
+///
+/// [`BatchRng::next_u64_into`]: ../trait.BatchRng.html#method.next_u64_into
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_fill_u64(
+    rng: *mut Xoshiro256StarStar, dest: *mut u64, len: usize)
+{
+    if len > 0 {
+        (*rng).next_u64_into(slice::from_raw_parts_mut(dest, len))
+    }
+}
+
+/// Advance the generator by 2<sup>128</sup> steps, see
+/// [`Xoshiro256StarStar::jump`].
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed.
+///
+/// [`Xoshiro256StarStar::jump`]: ../prng/struct.Xoshiro256StarStar.html#method.jump
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_jump(
+    rng: *mut Xoshiro256StarStar)
+{
+    (*rng).jump()
+}
+
+/// Advance the generator by 2<sup>192</sup> steps, see
+/// [`Xoshiro256StarStar::long_jump`].
+///
+/// # Safety
+///
+/// `rng` must be a generator created by this module which has not been
+/// freed.
+///
+/// [`Xoshiro256StarStar::long_jump`]: ../prng/struct.Xoshiro256StarStar.html#method.long_jump
+#[no_mangle]
+pub unsafe extern "C" fn rand_xoshiro256starstar_long_jump(
+    rng: *mut Xoshiro256StarStar)
+{
+    (*rng).long_jump()
+}
+
+#[cfg(test)]
+mod test {
+    use {BatchRng, RngCore, SeedableRng};
+    use prng::{SplitMix64, Xoshiro256StarStar};
+    use super::*;
+
+    #[test]
+    fn test_ffi_xoshiro256starstar() {
+        let seed = [7u8; 32];
+        let mut expected = Xoshiro256StarStar::from_seed(seed);
+        unsafe {
+            let rng = rand_xoshiro256starstar_new(seed.as_ptr());
+            assert_eq!(rand_xoshiro256starstar_next_u32(rng),
+                       expected.next_u32());
+            assert_eq!(rand_xoshiro256starstar_next_u64(rng),
+                       expected.next_u64());
+
+            let mut buf = [0u8; 13];
+            let mut buf2 = [0u8; 13];
+            rand_xoshiro256starstar_fill_bytes(rng, buf.as_mut_ptr(), 13);
+            expected.fill_bytes(&mut buf2);
+            assert_eq!(buf, buf2);
+            rand_xoshiro256starstar_fill_bytes(rng, ptr::null_mut(), 0);
+
+            let mut buf = [0u64; 9];
+            let mut buf2 = [0u64; 9];
+            rand_xoshiro256starstar_fill_u64(rng, buf.as_mut_ptr(), 9);
+            expected.next_u64_into(&mut buf2);
+            assert_eq!(buf, buf2);
+
+            rand_xoshiro256starstar_jump(rng);
+            expected.jump();
+            rand_xoshiro256starstar_long_jump(rng);
+            expected.long_jump();
+            let copy = rand_xoshiro256starstar_clone(rng);
+            let x = rand_xoshiro256starstar_next_u64(rng);
+            assert_eq!(x, expected.next_u64());
+            rand_xoshiro256starstar_free(rng);
+            assert_eq!(rand_xoshiro256starstar_next_u64(copy), x);
+            rand_xoshiro256starstar_free(copy);
+            rand_xoshiro256starstar_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_ffi_xoshiro256starstar_u64() {
+        let mut expected = Xoshiro256StarStar::from_rng(
+            SplitMix64::from_seed([42, 0, 0, 0, 0, 0, 0, 0])).unwrap();
+        unsafe {
+            let rng = rand_xoshiro256starstar_new_u64(42);
+            for _ in 0..10 {
+                assert_eq!(rand_xoshiro256starstar_next_u64(rng),
+                           expected.next_u64());
+            }
+            rand_xoshiro256starstar_free(rng);
+        }
+    }
+}
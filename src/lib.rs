@@ -233,20 +233,35 @@
 #![cfg_attr(all(feature="alloc", not(feature="std")), feature(alloc))]
 #![cfg_attr(all(feature="i128_support", feature="nightly"), allow(stable_features))] // stable since 2018-03-27
 #![cfg_attr(all(feature="i128_support", feature="nightly"), feature(i128_type, i128))]
+#![cfg_attr(feature = "simd_support", feature(portable_simd))]
 #![cfg_attr(feature = "stdweb", recursion_limit="128")]
+#![cfg_attr(all(target_os = "uefi", feature = "uefi"), feature(uefi_std))]
 
 #[cfg(feature="std")] extern crate std as core;
 #[cfg(all(feature = "alloc", not(feature="std")))] extern crate alloc;
 
 #[cfg(test)] #[cfg(feature="serde1")] extern crate bincode;
+#[cfg(test)] #[cfg(feature="serde1")] extern crate serde_json;
 #[cfg(feature="serde1")] extern crate serde;
 #[cfg(feature="serde1")] #[macro_use] extern crate serde_derive;
+#[cfg(feature="rkyv08")] extern crate rkyv;
+#[cfg(feature="bytemuck")] extern crate bytemuck;
 
 #[cfg(all(target_arch="wasm32", not(target_os="emscripten"), feature="stdweb"))]
 #[macro_use]
 extern crate stdweb;
+#[cfg(all(target_arch="wasm32", not(target_os="emscripten"), not(target_os="wasi"),
+          feature="js-sys"))]
+extern crate js_sys;
 
 extern crate rand_core;
+#[cfg(feature = "derive")] extern crate rand_derive;
+#[cfg(feature = "half")] extern crate half;
+#[cfg(feature = "num-traits")] extern crate num_traits;
+#[cfg(all(feature = "quickcheck", feature = "std"))] extern crate quickcheck;
+#[cfg(feature = "arbitrary")] extern crate arbitrary;
+#[cfg(all(feature = "libm", not(feature = "std")))] extern crate libm;
+#[cfg(feature = "rayon")] extern crate rayon;
 
 #[cfg(feature = "log")] #[macro_use] extern crate log;
 #[cfg(not(feature = "log"))] macro_rules! trace { ($($x:tt)*) => () }
@@ -261,14 +276,17 @@ pub use rand_core::{RngCore, CryptoRng, SeedableRng};
 pub use rand_core::{ErrorKind, Error};
 
 // Public exports
-#[cfg(feature="std")] pub use rngs::thread::thread_rng;
+#[cfg(feature="std")] pub use rngs::thread::{thread_rng, thread_crypto_rng};
+#[cfg(feature = "derive")] pub use rand_derive::UniformEnum;
 
 // Public modules
 pub mod distributions;
+#[cfg(feature="ffi")] pub mod ffi;
 pub mod prelude;
 pub mod prng;
 pub mod rngs;
-#[cfg(feature = "alloc")] pub mod seq;
+pub mod seq;
+#[cfg(any(feature="std", feature="libm"))] pub mod stat_test;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Compatibility re-exports. Documentation is hidden; will be removed eventually.
@@ -302,6 +320,7 @@ pub mod isaac {
 use core::{marker, mem, slice};
 use distributions::{Distribution, Standard};
 use distributions::uniform::{SampleUniform, UniformSampler};
+use seq::SliceRandom;
 
 
 /// A type that can be randomly generated using an [`Rng`].
@@ -461,12 +480,14 @@ pub trait Rng: RngCore {
         distr.sample_iter(self)
     }
 
-    /// Fill `dest` entirely with random bytes (uniform value distribution),
-    /// where `dest` is any type supporting [`AsByteSliceMut`], namely slices
-    /// and arrays over primitive integer types (`i8`, `i16`, `u32`, etc.).
+    /// Fill `dest` entirely with random values, where `dest` is any type
+    /// supporting [`Fill`], namely slices and arrays over primitive integer
+    /// types (`i8`, `i16`, `u32`, etc.) and over `f32` and `f64`.
     ///
-    /// On big-endian platforms this performs byte-swapping to ensure
-    /// portability of results from reproducible generators.
+    /// Integers are filled with random bytes (uniform value distribution). On
+    /// big-endian platforms this performs byte-swapping to ensure portability
+    /// of results from reproducible generators. Floats are sampled from the
+    /// [`Standard`] distribution, i.e. uniformly from `[0, 1)`.
     ///
     /// This uses [`fill_bytes`] internally which may handle some RNG errors
     /// implicitly (e.g. waiting if the OS generator is not ready), but panics
@@ -483,18 +504,20 @@ pub trait Rng: RngCore {
     ///
     /// [`fill_bytes`]: trait.RngCore.html#method.fill_bytes
     /// [`try_fill`]: trait.Rng.html#method.try_fill
-    /// [`AsByteSliceMut`]: trait.AsByteSliceMut.html
-    fn fill<T: AsByteSliceMut + ?Sized>(&mut self, dest: &mut T) {
-        self.fill_bytes(dest.as_byte_slice_mut());
-        dest.to_le();
+    /// [`Fill`]: trait.Fill.html
+    /// [`Standard`]: distributions/struct.Standard.html
+    fn fill<T: Fill + ?Sized>(&mut self, dest: &mut T) {
+        dest.fill_from(self)
     }
 
-    /// Fill `dest` entirely with random bytes (uniform value distribution),
-    /// where `dest` is any type supporting [`AsByteSliceMut`], namely slices
-    /// and arrays over primitive integer types (`i8`, `i16`, `u32`, etc.).
+    /// Fill `dest` entirely with random values, where `dest` is any type
+    /// supporting [`Fill`], namely slices and arrays over primitive integer
+    /// types (`i8`, `i16`, `u32`, etc.) and over `f32` and `f64`.
     ///
-    /// On big-endian platforms this performs byte-swapping to ensure
-    /// portability of results from reproducible generators.
+    /// Integers are filled with random bytes (uniform value distribution). On
+    /// big-endian platforms this performs byte-swapping to ensure portability
+    /// of results from reproducible generators. Floats are sampled from the
+    /// [`Standard`] distribution, i.e. uniformly from `[0, 1)`.
     ///
     /// This uses [`try_fill_bytes`] internally and forwards all RNG errors. In
     /// some cases errors may be resolvable; see [`ErrorKind`] and
@@ -519,11 +542,37 @@ pub trait Rng: RngCore {
     /// [`ErrorKind`]: enum.ErrorKind.html
     /// [`try_fill_bytes`]: trait.RngCore.html#method.try_fill_bytes
     /// [`fill`]: trait.Rng.html#method.fill
-    /// [`AsByteSliceMut`]: trait.AsByteSliceMut.html
-    fn try_fill<T: AsByteSliceMut + ?Sized>(&mut self, dest: &mut T) -> Result<(), Error> {
-        self.try_fill_bytes(dest.as_byte_slice_mut())?;
-        dest.to_le();
-        Ok(())
+    /// [`Fill`]: trait.Fill.html
+    /// [`Standard`]: distributions/struct.Standard.html
+    fn try_fill<T: Fill + ?Sized>(&mut self, dest: &mut T) -> Result<(), Error> {
+        dest.try_fill_from(self)
+    }
+
+    /// Fill `dest` entirely with floating point numbers sampled from the
+    /// [`Standard`] distribution, i.e. uniformly from the half-open interval
+    /// `[0, 1)`. `dest` may be a slice of `f32` or `f64`.
+    ///
+    /// Random integers are generated in batches with [`fill`] and converted
+    /// in a tight loop, which is considerably faster than calling
+    /// `gen::<f64>()` for every element. The values are distributed exactly
+    /// as with [`Standard`], but the sequence differs from repeated calls to
+    /// [`gen`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::{thread_rng, Rng};
+    ///
+    /// let mut arr = [0.0f64; 1000];
+    /// thread_rng().fill_floats(&mut arr[..]);
+    /// assert!(arr.iter().all(|&x| 0.0 <= x && x < 1.0));
+    /// ```
+    ///
+    /// [`Standard`]: distributions/struct.Standard.html
+    /// [`fill`]: trait.Rng.html#method.fill
+    /// [`gen`]: trait.Rng.html#method.gen
+    fn fill_floats<T: FillFloats + ?Sized>(&mut self, dest: &mut T) {
+        dest.fill_from(self)
     }
 
     /// Return a bool with a probability `p` of being true.
@@ -550,6 +599,36 @@ pub trait Rng: RngCore {
         self.sample(d)
     }
 
+    /// Return a bool with a probability of `numerator/denominator` of being
+    /// true. I.e. `gen_ratio(2, 3)` has chance of 2 in 3, or about 67%, of
+    /// returning true. If `numerator == denominator`, then the returned value
+    /// is guaranteed to be `true`. If `numerator == 0`, then the returned
+    /// value is guaranteed to be `false`.
+    ///
+    /// See also the [`distributions::Bernoulli`] distribution, which may be
+    /// faster if sampling from the same `numerator` and `denominator`
+    /// repeatedly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::{thread_rng, Rng};
+    ///
+    /// let mut rng = thread_rng();
+    /// println!("{}", rng.gen_ratio(2, 3));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `denominator == 0` or `numerator > denominator`.
+    ///
+    /// [`distributions::Bernoulli`]: distributions/bernoulli/struct.Bernoulli.html
+    #[inline]
+    fn gen_ratio(&mut self, numerator: u32, denominator: u32) -> bool {
+        let d = distributions::Bernoulli::from_ratio(numerator, denominator);
+        self.sample(d)
+    }
+
     /// Return a random element from `values`.
     ///
     /// Return `None` if `values` is empty.
@@ -564,24 +643,23 @@ pub trait Rng: RngCore {
     /// println!("{:?}", rng.choose(&choices));
     /// assert_eq!(rng.choose(&choices[..0]), None);
     /// ```
+    ///
+    /// This is equivalent to [`SliceRandom::choose`].
+    ///
+    /// [`SliceRandom::choose`]: seq/trait.SliceRandom.html#tymethod.choose
     fn choose<'a, T>(&mut self, values: &'a [T]) -> Option<&'a T> {
-        if values.is_empty() {
-            None
-        } else {
-            Some(&values[self.gen_range(0, values.len())])
-        }
+        values.choose(self)
     }
 
     /// Return a mutable pointer to a random element from `values`.
     ///
     /// Return `None` if `values` is empty.
+    ///
+    /// This is equivalent to [`SliceRandom::choose_mut`].
+    ///
+    /// [`SliceRandom::choose_mut`]: seq/trait.SliceRandom.html#tymethod.choose_mut
     fn choose_mut<'a, T>(&mut self, values: &'a mut [T]) -> Option<&'a mut T> {
-        if values.is_empty() {
-            None
-        } else {
-            let len = values.len();
-            Some(&mut values[self.gen_range(0, len)])
-        }
+        values.choose_mut(self)
     }
 
     /// Shuffle a mutable slice in place.
@@ -590,6 +668,8 @@ pub trait Rng: RngCore {
     /// https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle#The_modern_algorithm)
     /// which produces an unbiased permutation.
     ///
+    /// This is equivalent to [`SliceRandom::shuffle`].
+    ///
     /// # Example
     ///
     /// ```
@@ -602,14 +682,10 @@ pub trait Rng: RngCore {
     /// rng.shuffle(&mut y);
     /// println!("{:?}", y);
     /// ```
+    ///
+    /// [`SliceRandom::shuffle`]: seq/trait.SliceRandom.html#tymethod.shuffle
     fn shuffle<T>(&mut self, values: &mut [T]) {
-        let mut i = values.len();
-        while i >= 2 {
-            // invariant: elements with index >= i have been locked in place.
-            i -= 1;
-            // lock element i in place.
-            values.swap(i, self.gen_range(0, i + 1));
-        }
+        values.shuffle(self)
     }
 
     /// Return an iterator that will yield an infinite number of randomly
@@ -690,6 +766,46 @@ pub trait AsByteSliceMut {
     fn to_le(&mut self);
 }
 
+/// Types which can be filled with random data
+///
+/// This is used by the [`fill`] and [`try_fill`] methods, and implemented for
+/// every type implementing [`AsByteSliceMut`] as well as for slices and arrays
+/// of `f32` and `f64`.
+///
+/// [`fill`]: trait.Rng.html#method.fill
+/// [`try_fill`]: trait.Rng.html#method.try_fill
+/// [`AsByteSliceMut`]: trait.AsByteSliceMut.html
+pub trait Fill {
+    /// Fill `self` with random data from `rng`.
+    fn fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R);
+
+    /// Fill `self` with random data from `rng`, forwarding any RNG errors.
+    fn try_fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), Error>;
+}
+
+impl<T: AsByteSliceMut + ?Sized> Fill for T {
+    fn fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        rng.fill_bytes(self.as_byte_slice_mut());
+        self.to_le();
+    }
+
+    fn try_fill_from<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), Error> {
+        rng.try_fill_bytes(self.as_byte_slice_mut())?;
+        self.to_le();
+        Ok(())
+    }
+}
+
+/// Trait for slices of floating point numbers that can be filled with random
+/// values
+///
+/// This is used by the [`fill_floats`] method, and implemented for `[f32]` and
+/// `[f64]`. The work is done by their [`Fill`] implementation.
+///
+/// [`fill_floats`]: trait.Rng.html#method.fill_floats
+/// [`Fill`]: trait.Fill.html
+pub trait FillFloats: Fill {}
+
 impl AsByteSliceMut for [u8] {
     fn as_byte_slice_mut(&mut self) -> &mut [u8] {
         self
@@ -864,16 +980,23 @@ pub trait FromEntropy: SeedableRng {
     /// # Panics
     ///
     /// If all entropy sources fail this will panic. If you need to handle
-    /// errors, use the following code, equivalent aside from error handling:
+    /// errors, use [`try_from_entropy`] instead.
+    ///
+    /// [`try_from_entropy`]: #tymethod.try_from_entropy
+    fn from_entropy() -> Self;
+
+    /// Creates a new instance, automatically seeded with fresh entropy, or
+    /// returns the error of the entropy sources.
+    ///
+    /// This is equivalent to `SeedableRng::from_rng(EntropyRng::new())`.
     ///
     /// ```
     /// # use rand::Error;
     /// use rand::prelude::*;
-    /// use rand::rngs::EntropyRng;
+    /// use rand::prng::Xoshiro256StarStar;
     ///
     /// # fn try_inner() -> Result<(), Error> {
-    /// // This uses StdRng, but is valid for any R: SeedableRng
-    /// let mut rng = StdRng::from_rng(EntropyRng::new())?;
+    /// let mut rng = Xoshiro256StarStar::try_from_entropy()?;
     ///
     /// println!("random number: {}", rng.gen_range(1, 10));
     /// # Ok(())
@@ -881,17 +1004,303 @@ pub trait FromEntropy: SeedableRng {
     ///
     /// # try_inner().unwrap()
     /// ```
-    fn from_entropy() -> Self;
+    fn try_from_entropy() -> Result<Self, Error>;
 }
 
 #[cfg(feature="std")]
 impl<R: SeedableRng> FromEntropy for R {
     fn from_entropy() -> R {
-        R::from_rng(EntropyRng::new()).unwrap_or_else(|err|
+        R::try_from_entropy().unwrap_or_else(|err|
             panic!("FromEntropy::from_entropy() failed: {}", err))
     }
+
+    fn try_from_entropy() -> Result<R, Error> {
+        R::from_rng(EntropyRng::new())
+    }
+}
+
+
+/// A random number generator which can be split into independent generators.
+///
+/// [`split`] returns a new generator whose output is statistically
+/// independent of the output of `self` (which is advanced by the split). Both
+/// can be split again, so that a tree of generators can be derived
+/// deterministically from a single seed: for example one generator per entity
+/// of a simulation, each with its own sub-streams, which does not depend on
+/// the order in which the other entities consume their random numbers.
+///
+/// [`SplitMix64`] implements this trait.
+///
+/// ## Example
+///
+/// ```
+/// use rand::{Rng, SeedableRng, SplitRng};
+/// use rand::prng::SplitMix64;
+///
+/// let mut world = SplitMix64::from_seed([42; 8]);
+/// let mut players: Vec<SplitMix64> = (0..4).map(|_| world.split()).collect();
+/// let mut items = players[0].split();
+/// println!("player 0 rolls {}", players[0].gen_range(1, 7));
+/// println!("player 0 finds item {}", items.gen_range(0, 100));
+/// ```
+///
+/// [`split`]: #tymethod.split
+/// [`SplitMix64`]: prng/struct.SplitMix64.html
+pub trait SplitRng: RngCore + Sized {
+    /// Split off a new generator, advancing `self`.
+    fn split(&mut self) -> Self;
+}
+
+/// A random number generator which can jump ahead to a non-overlapping
+/// stream.
+///
+/// After [`jump`], the generator produces a stream which does not overlap
+/// with what it would have produced otherwise, for any practical number of
+/// outputs. Cloning a generator and jumping the clone repeatedly gives a
+/// number of independent generators, for example one per thread.
+///
+/// This is implemented by [`Xoshiro256StarStar`], where `jump` advances the
+/// generator by 2<sup>128</sup> steps, and by [`ChaChaRng`], where it moves
+/// to the next stream number.
+///
+/// [`jump`]: #tymethod.jump
+/// [`Xoshiro256StarStar`]: prng/struct.Xoshiro256StarStar.html
+/// [`ChaChaRng`]: prng/chacha/struct.ChaChaRng.html
+pub trait Jumpable: RngCore + Clone {
+    /// Jump to the start of a new, non-overlapping stream.
+    fn jump(&mut self);
+}
+
+/// A random number generator whose state can be encoded as bytes.
+///
+/// [`encode_state`] writes the complete state of the generator with a fixed
+/// byte layout, documented for each generator, and [`decode_state`] restores
+/// a generator which continues with exactly the same output. Neither requires
+/// `serde` or an allocator, so this can for example be used by embedded
+/// firmware to keep a generator in flash memory across reboots. All integers
+/// are encoded in little-endian order, so the encoding is portable.
+///
+/// To keep stored states loadable when the layout of a generator changes,
+/// use [`encode_versioned`] and [`from_versioned_bytes`], which prefix the
+/// state with its [`StateVersion`]. A generator whose layout changed bumps
+/// its [`STATE_VERSION`] and converts states in older layouts in
+/// [`decode_versioned_state`].
+///
+/// This is implemented by [`XorShiftRng`], [`SplitMix64`],
+/// [`Xoshiro256StarStar`] and [`ChaChaRng`].
+///
+/// ## Example
+///
+/// ```
+/// use rand::{EncodableRng, RngCore, SeedableRng};
+/// use rand::prng::ChaChaRng;
+///
+/// let mut rng = ChaChaRng::from_seed([7; 32]);
+/// rng.next_u32();
+///
+/// let mut buf = [0u8; 64];
+/// let len = rng.encode_state(&mut buf);
+/// // Store `buf[..len]`, and later:
+/// let mut restored = ChaChaRng::decode_state(&buf[..len]).unwrap();
+/// assert_eq!(rng.next_u32(), restored.next_u32());
+///
+/// // With a version tag, so the state still loads if the layout changes:
+/// let len = rng.encode_versioned(&mut buf);
+/// let mut restored = ChaChaRng::from_versioned_bytes(&buf[..len]).unwrap();
+/// assert_eq!(rng.next_u32(), restored.next_u32());
+/// ```
+///
+/// [`encode_state`]: #tymethod.encode_state
+/// [`decode_state`]: #tymethod.decode_state
+/// [`encode_versioned`]: #method.encode_versioned
+/// [`from_versioned_bytes`]: #method.from_versioned_bytes
+/// [`decode_versioned_state`]: #method.decode_versioned_state
+/// [`STATE_VERSION`]: #associatedconstant.STATE_VERSION
+/// [`StateVersion`]: enum.StateVersion.html
+/// [`XorShiftRng`]: prng/struct.XorShiftRng.html
+/// [`SplitMix64`]: prng/struct.SplitMix64.html
+/// [`Xoshiro256StarStar`]: prng/struct.Xoshiro256StarStar.html
+/// [`ChaChaRng`]: prng/chacha/struct.ChaChaRng.html
+pub trait EncodableRng: RngCore + Sized {
+    /// The length of the encoded state in bytes.
+    const ENCODED_LEN: usize;
+
+    /// Write the state to the start of `dest`, and return the number of bytes
+    /// written, which is always [`ENCODED_LEN`].
+    ///
+    /// # Panics
+    ///
+    /// If `dest` is shorter than `ENCODED_LEN`.
+    ///
+    /// [`ENCODED_LEN`]: #associatedconstant.ENCODED_LEN
+    fn encode_state(&self, dest: &mut [u8]) -> usize;
+
+    /// Restore a generator from a state written by [`encode_state`].
+    ///
+    /// Only the first [`ENCODED_LEN`] bytes of `src` are read. Returns `None`
+    /// if `src` is shorter than that, or does not hold a valid state.
+    ///
+    /// [`encode_state`]: #tymethod.encode_state
+    /// [`ENCODED_LEN`]: #associatedconstant.ENCODED_LEN
+    fn decode_state(src: &[u8]) -> Option<Self>;
+
+    /// The version of the layout written by [`encode_state`].
+    ///
+    /// [`encode_state`]: #tymethod.encode_state
+    const STATE_VERSION: StateVersion = StateVersion::V1;
+
+    /// Restore a generator from a state in the layout of `version`.
+    ///
+    /// The default implementation only accepts [`STATE_VERSION`]. Generators
+    /// which changed their layout override this to convert older states.
+    ///
+    /// [`STATE_VERSION`]: #associatedconstant.STATE_VERSION
+    fn decode_versioned_state(version: StateVersion, src: &[u8])
+        -> Option<Self>
+    {
+        if version == Self::STATE_VERSION {
+            Self::decode_state(src)
+        } else {
+            None
+        }
+    }
+
+    /// Write [`STATE_VERSION`] as a single byte, followed by the state, to
+    /// the start of `dest`. Returns the number of bytes written, which is
+    /// always `ENCODED_LEN + 1`.
+    ///
+    /// # Panics
+    ///
+    /// If `dest` is shorter than `ENCODED_LEN + 1`.
+    ///
+    /// [`STATE_VERSION`]: #associatedconstant.STATE_VERSION
+    fn encode_versioned(&self, dest: &mut [u8]) -> usize {
+        dest[0] = Self::STATE_VERSION as u8;
+        1 + self.encode_state(&mut dest[1..])
+    }
+
+    /// Restore a generator from a state written by [`encode_versioned`], in
+    /// the current or an older layout.
+    ///
+    /// Returns `None` if `src` is too short, the version is unknown or the
+    /// state is not valid.
+    ///
+    /// [`encode_versioned`]: #method.encode_versioned
+    fn from_versioned_bytes(src: &[u8]) -> Option<Self> {
+        match src.split_first() {
+            Some((&version, state)) => {
+                StateVersion::from_u8(version)
+                    .and_then(|v| Self::decode_versioned_state(v, state))
+            }
+            None => None,
+        }
+    }
+}
+
+/// The version of the layout of an encoded generator state.
+///
+/// Versions are counted separately for each generator: `V1` is the first
+/// layout of the generator, documented with its [`EncodableRng`]
+/// implementation. New variants are added when the layout of a generator
+/// changes.
+///
+/// [`EncodableRng`]: trait.EncodableRng.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum StateVersion {
+    /// The first layout.
+    V1 = 1,
+    #[doc(hidden)]
+    __Nonexhaustive,
 }
 
+impl StateVersion {
+    /// The version with the given number, if it is known.
+    pub fn from_u8(version: u8) -> Option<StateVersion> {
+        match version {
+            1 => Some(StateVersion::V1),
+            _ => None,
+        }
+    }
+}
+
+/// A random number generator which can produce many integers at once.
+///
+/// The methods of this trait give the same values as calling [`next_u32`] or
+/// [`next_u64`] repeatedly, but take a whole slice or array at once. The
+/// default implementations simply call `next_u32` and `next_u64`; generators
+/// which can produce many values more efficiently than one at a time, such
+/// as counter-based generators computing several blocks in parallel, can
+/// override [`next_u32_into`] and [`next_u64_into`].
+///
+/// All generators in [`prng`] implement this trait, as do [`StdRng`] and
+/// [`SmallRng`].
+///
+/// # Example
+///
+/// ```
+/// use rand::{BatchRng, SeedableRng};
+/// use rand::prng::Xoshiro256StarStar;
+///
+/// let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+/// let xs: [u64; 8] = rng.next_u64_array();
+///
+/// let mut buf = vec![0u32; 1000];
+/// rng.next_u32_into(&mut buf);
+/// ```
+///
+/// [`next_u32`]: trait.RngCore.html#tymethod.next_u32
+/// [`next_u64`]: trait.RngCore.html#tymethod.next_u64
+/// [`next_u32_into`]: #method.next_u32_into
+/// [`next_u64_into`]: #method.next_u64_into
+/// [`prng`]: prng/index.html
+/// [`StdRng`]: rngs/struct.StdRng.html
+/// [`SmallRng`]: rngs/struct.SmallRng.html
+pub trait BatchRng: RngCore {
+    /// Fill `dest` with the next `u32` values.
+    fn next_u32_into(&mut self, dest: &mut [u32]) {
+        for x in dest.iter_mut() {
+            *x = self.next_u32();
+        }
+    }
+
+    /// Fill `dest` with the next `u64` values.
+    fn next_u64_into(&mut self, dest: &mut [u64]) {
+        for x in dest.iter_mut() {
+            *x = self.next_u64();
+        }
+    }
+
+    /// Return an array of the next `u32` values, for example `[u32; 8]`.
+    fn next_u32_array<A>(&mut self) -> A
+        where A: Default + AsMut<[u32]>, Self: Sized
+    {
+        let mut array = A::default();
+        self.next_u32_into(array.as_mut());
+        array
+    }
+
+    /// Return an array of the next `u64` values, for example `[u64; 8]`.
+    fn next_u64_array<A>(&mut self) -> A
+        where A: Default + AsMut<[u64]>, Self: Sized
+    {
+        let mut array = A::default();
+        self.next_u64_into(array.as_mut());
+        array
+    }
+}
+
+impl<'a, R: BatchRng + ?Sized> BatchRng for &'a mut R {
+    #[inline]
+    fn next_u32_into(&mut self, dest: &mut [u32]) {
+        (**self).next_u32_into(dest)
+    }
+
+    #[inline]
+    fn next_u64_into(&mut self, dest: &mut [u64]) {
+        (**self).next_u64_into(dest)
+    }
+}
 
 /// DEPRECATED: use [`SmallRng`] instead.
 ///
@@ -1127,6 +1536,23 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_gen_ratio_average() {
+        const NUM: u32 = 3;
+        const DENOM: u32 = 10;
+        const N: u32 = 100_000;
+
+        let mut sum: u32 = 0;
+        let mut rng = rng(111);
+        for _ in 0..N {
+            if rng.gen_ratio(NUM, DENOM) {
+                sum += 1;
+            }
+        }
+        let avg = (sum as f64) / (N as f64);
+        assert!((avg - (NUM as f64) / (DENOM as f64)).abs() < 5e-3);
+    }
+
     #[test]
     fn test_choose() {
         let mut r = rng(107);
@@ -1186,7 +1612,198 @@ mod test {
         assert_eq!(r.gen_range(0, 1), 0);
         let _c: u8 = Standard.sample(&mut r);
     }
-    
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_rng_locked() {
+        use std::cell::RefCell;
+        use std::sync::Mutex;
+        use rngs::mock::StepRng;
+        fn sum<R: RngCore>(mut rng: R) -> u64 {
+            rng.next_u64() + rng.next_u64()
+        }
+
+        let cell = RefCell::new(StepRng::new(1, 1));
+        assert_eq!(sum(cell.borrow_mut()), 3);
+        assert_eq!(cell.borrow_mut().gen::<u64>(), 3);
+
+        let mutex = Mutex::new(StepRng::new(1, 1));
+        assert_eq!(sum(mutex.lock().unwrap()), 3);
+        assert_eq!(mutex.lock().unwrap().gen::<u64>(), 3);
+    }
+
+    #[test]
+    fn test_hardware_adapter() {
+        use rand_core::hardware::{HardwareRng, HardwareAdapter};
+
+        // A peripheral which needs two polls per word, and fails after four words
+        struct Peripheral { polls: u32 }
+        impl HardwareRng for Peripheral {
+            fn read_word(&mut self) -> Result<Option<u32>, Error> {
+                self.polls += 1;
+                match self.polls {
+                    n if n > 8 => Err(Error::new(ErrorKind::Unavailable, "seed error")),
+                    n if n % 2 == 0 => Ok(Some(0x04030201 * (n / 2))),
+                    _ => Ok(None),
+                }
+            }
+        }
+
+        let mut rng = HardwareAdapter::new(Peripheral { polls: 0 });
+        let mut buf = [0u8; 6];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [1, 2, 3, 4, 2, 4]);
+        assert_eq!(rng.next_u64(), 0x100c_0804_0c09_0603);
+        assert_eq!(rng.try_next_u32().unwrap_err().kind, ErrorKind::Unavailable);
+
+        let mut rng = HardwareAdapter::new(Peripheral { polls: 0 });
+        rng.set_max_polls(1);
+        assert_eq!(rng.try_next_u32().unwrap_err().kind, ErrorKind::NotReady);
+        assert_eq!(rng.try_next_u32().unwrap(), 0x04030201);
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_try_next() {
+        use rngs::adapter::ReplayRng;
+        use rngs::mock::StepRng;
+        let mut step = StepRng::new(1, 1);
+        assert_eq!(step.try_next_u32().unwrap(), 1);
+        assert_eq!(step.try_next_u64().unwrap(), 2);
+
+        let mut rng = rng(112);
+        let r = &mut rng as &mut RngCore;
+        r.try_next_u64().unwrap();
+
+        // Errors are returned instead of panicking
+        let mut rng = ReplayRng::from_u32s(&[7]);
+        assert!(rng.try_next_u64().is_err());
+        assert_eq!(rng.try_next_u32().unwrap(), 7);
+        assert!(rng.try_next_u32().is_err());
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_crypto_rng() {
+        use prng::{ChaChaRng, Hc128Rng};
+        use rngs::{StdRng, OsRng, EntropyRng};
+        fn gen_secure<R: RngCore + CryptoRng>(rng: &mut R) -> u32 {
+            rng.next_u32()
+        }
+        gen_secure(&mut thread_rng());
+        gen_secure(&mut EntropyRng::new());
+        gen_secure(&mut OsRng::new().unwrap());
+        let mut rng = StdRng::from_seed([1; 32]);
+        gen_secure(&mut &mut rng);
+        gen_secure(&mut Box::new(rng));
+        gen_secure(&mut ChaChaRng::from_seed([2; 32]));
+        gen_secure(&mut Hc128Rng::from_seed([3; 32]));
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_read_adapter() {
+        use std::io::{self, Read};
+        let mut expected = [0u8; 1000];
+        rng(111).fill_bytes(&mut expected);
+        let mut rng = rng(111);
+
+        let mut buf = Vec::new();
+        let n = io::copy(&mut rng.read_adapter().take(1000), &mut buf).unwrap();
+        assert_eq!(n, 1000);
+        assert_eq!(&buf[..], &expected[..]);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_from_entropy() {
+        use prng::{ChaChaRng, Xoshiro256StarStar};
+        let mut a = Xoshiro256StarStar::from_entropy();
+        let mut b = Xoshiro256StarStar::try_from_entropy().unwrap();
+        assert!(a.next_u64() != b.next_u64());
+        let mut c = ChaChaRng::try_from_entropy().unwrap();
+        assert!(c.next_u64() != ChaChaRng::from_entropy().next_u64());
+    }
+
+    #[test]
+    fn test_fill_bytes_via_next() {
+        // Whole buffers of values, some single values and a partial value
+        let mut rng = StepRng::new(0, 1);
+        let mut buf = [0u8; 8 * 35 + 5];
+        ::rand_core::impls::fill_bytes_via_next(&mut rng, &mut buf);
+        for (i, chunk) in buf.chunks(8).enumerate() {
+            for (j, b) in chunk.iter().enumerate() {
+                assert_eq!(*b, ((i as u64) >> (8 * j)) as u8);
+            }
+        }
+        assert_eq!(rng.next_u64(), 36);
+    }
+
+    #[test]
+    fn test_batch_rng() {
+        use prng::{SplitMix64, Xoshiro256StarStar, XorShiftRng};
+
+        fn fill<R: BatchRng>(mut rng: R, dest: &mut [u64]) {
+            rng.next_u64_into(dest)
+        }
+
+        fn check<R: BatchRng + Clone>(mut rng: R) {
+            let mut plain = rng.clone();
+            let a: [u64; 7] = rng.next_u64_array();
+            for &x in a.iter() {
+                assert_eq!(x, plain.next_u64());
+            }
+            let b: [u32; 5] = rng.next_u32_array();
+            for &x in b.iter() {
+                assert_eq!(x, plain.next_u32());
+            }
+            let mut c = [0u64; 3];
+            // Via the implementation for `&mut R`
+            fill(&mut rng, &mut c[..0]);
+            fill(&mut rng, &mut c);
+            for &x in c.iter() {
+                assert_eq!(x, plain.next_u64());
+            }
+            // The state advanced by the number of values
+            assert_eq!(rng.next_u64(), plain.next_u64());
+        }
+
+        check(SplitMix64::from_seed([42; 8]));
+        check(Xoshiro256StarStar::from_seed([42; 32]));
+        check(XorShiftRng::from_seed([42; 16]));
+    }
+
+    #[test]
+    fn test_batch_rng_block() {
+        use prng::{ChaChaRng, Hc128Rng, IsaacRng, Isaac64Rng};
+        use rngs::StdRng;
+
+        // The block RNGs copy straight from their results buffer; check
+        // against single values, starting halfway into a buffer and
+        // spanning several buffers.
+        fn check<R: BatchRng + Clone>(mut rng: R) {
+            rng.next_u32();
+            let mut plain = rng.clone();
+            let mut buf = [0u32; 1000];
+            rng.next_u32_into(&mut buf);
+            for &x in buf.iter() {
+                assert_eq!(x, plain.next_u32());
+            }
+            let mut buf = [0u64; 1000];
+            rng.next_u64_into(&mut buf);
+            for &x in buf.iter() {
+                assert_eq!(x, plain.next_u64());
+            }
+            assert_eq!(rng.next_u32(), plain.next_u32());
+        }
+
+        check(ChaChaRng::from_seed([42; 32]));
+        check(Hc128Rng::from_seed([42; 32]));
+        check(IsaacRng::from_seed([42; 32]));
+        check(Isaac64Rng::from_seed([42; 32]));
+        check(StdRng::from_seed([42; 32]));
+    }
+
     #[test]
     #[cfg(feature="std")]
     fn test_random() {
@@ -1201,4 +1818,32 @@ mod test {
                      (u8, i8, u16, i16, u32, i32, u64, i64),
                      (f32, (f64, (f64,)))) = random();
     }
+
+    #[test]
+    fn test_versioned_state() {
+        use prng::{ChaChaRng, Xoshiro256StarStar};
+
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        rng.next_u32();
+        let mut buf = [0u8; 57];
+        assert_eq!(rng.encode_versioned(&mut buf), 57);
+        assert_eq!(buf[0], StateVersion::V1 as u8);
+        let mut restored = ChaChaRng::from_versioned_bytes(&buf).unwrap();
+        assert_eq!(rng.next_u64(), restored.next_u64());
+
+        let rng = Xoshiro256StarStar::from_seed([7; 32]);
+        let len = rng.encode_versioned(&mut buf);
+        assert_eq!(len, Xoshiro256StarStar::ENCODED_LEN + 1);
+        for &version in [0, 2, 255].iter() {
+            buf[0] = version;
+            assert!(Xoshiro256StarStar::from_versioned_bytes(&buf[..len])
+                    .is_none());
+        }
+        assert!(Xoshiro256StarStar::from_versioned_bytes(&[]).is_none());
+        assert!(Xoshiro256StarStar::from_versioned_bytes(&[1]).is_none());
+
+        for version in 0..256 {
+            assert!(StateVersion::from_u8(version as u8) != Some(StateVersion::__Nonexhaustive));
+        }
+    }
 }
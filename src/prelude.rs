@@ -22,7 +22,9 @@
 //! ```
 
 #[doc(no_inline)] pub use distributions::Distribution;
-#[doc(no_inline)] pub use rngs::{SmallRng, StdRng};
+#[doc(no_inline)] pub use rngs::StdRng;
+#[cfg(feature="small_rng")] #[doc(no_inline)] pub use rngs::SmallRng;
 #[doc(no_inline)] #[cfg(feature="std")] pub use rngs::ThreadRng;
 #[doc(no_inline)] pub use {Rng, RngCore, CryptoRng, SeedableRng};
+#[doc(no_inline)] pub use seq::{SliceRandom, IteratorRandom};
 #[doc(no_inline)] #[cfg(feature="std")] pub use {FromEntropy, random, thread_rng};
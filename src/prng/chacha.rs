@@ -13,9 +13,14 @@
 use core::fmt;
 use rand_core::{CryptoRng, RngCore, SeedableRng, Error, le};
 use rand_core::block::{BlockRngCore, BlockRng};
+use {BatchRng, EncodableRng, Jumpable};
+#[cfg(feature="simd_dispatch")]
+use prng::chacha_simd;
 
 const SEED_WORDS: usize = 8; // 8 words for the 256-bit key
 const STATE_WORDS: usize = 16;
+const BUF_BLOCKS: usize = 4; // blocks generated at once
+const BUF_WORDS: usize = BUF_BLOCKS * STATE_WORDS;
 
 /// A cryptographically secure random number generator that uses the ChaCha
 /// algorithm.
@@ -27,9 +32,11 @@ const STATE_WORDS: usize = 16;
 ///
 /// ChaCha uses add-rotate-xor (ARX) operations as its basis. These are safe
 /// against timing attacks, although that is mostly a concern for ciphers and
-/// not for RNGs. Also it is very suitable for SIMD implementation.
-/// Here we do not provide a SIMD implementation yet, except for what is
-/// provided by auto-vectorisation.
+/// not for RNGs. Also it is very suitable for SIMD implementation. We generate
+/// four blocks at a time; with the `simd_dispatch` feature these are computed
+/// with AVX2 or SSE2 on x86 (selected at runtime if `std` is available,
+/// otherwise at compile time), or with NEON on AArch64. Without the feature,
+/// or on other architectures, a portable implementation is used.
 ///
 /// With the ChaCha algorithm it is possible to choose the number of rounds the
 /// core algorithm should run. The number of rounds is a tradeoff between
@@ -54,8 +61,8 @@ const STATE_WORDS: usize = 16;
 /// counter  counter  nonce    nonce
 /// ```
 ///
-/// This implementation uses an output buffer of sixteen `u32` words, and uses
-/// [`BlockRng`] to implement the [`RngCore`] methods.
+/// This implementation uses an output buffer of four blocks (64 `u32` words),
+/// and uses [`BlockRng`] to implement the [`RngCore`] methods.
 ///
 /// [1]: D. J. Bernstein, [*ChaCha, a variant of Salsa20*](
 ///      https://cr.yp.to/chacha.html)
@@ -68,6 +75,7 @@ const STATE_WORDS: usize = 16;
 /// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
 /// [`RngCore`]: ../../trait.RngCore.html
 #[derive(Clone, Debug)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct ChaChaRng(BlockRng<ChaChaCore>);
 
 impl RngCore for ChaChaRng {
@@ -106,6 +114,80 @@ impl SeedableRng for ChaChaRng {
 
 impl CryptoRng for ChaChaRng {}
 
+impl BatchRng for ChaChaRng {
+    fn next_u32_into(&mut self, dest: &mut [u32]) {
+        self.0.next_u32_into(dest)
+    }
+}
+
+/// The state is encoded as the key (the 32-byte seed), followed by the stream
+/// number as a little-endian `u64` and the word position as a little-endian
+/// `u128`, 56 bytes in total. The word position is the offset of the next
+/// output word from the start of the stream, as returned by `get_word_pos`;
+/// its upper 60 bits must be zero.
+impl EncodableRng for ChaChaRng {
+    const ENCODED_LEN: usize = 56;
+
+    fn encode_state(&self, dest: &mut [u8]) -> usize {
+        let dest = &mut dest[..56];
+        let state = &self.0.core.state;
+        le::write_u32_into(&state[4..12], &mut dest[..32]);
+        le::write_u32_into(&state[14..16], &mut dest[32..40]);
+
+        // The counter is at the end of the generated blocks, unless all
+        // results are used (see `get_word_pos`).
+        let mut block = (state[13] as u64) << 32 | (state[12] as u64);
+        let mut index = self.0.index();
+        if index >= BUF_WORDS {
+            index = 0;
+        } else {
+            block = block.wrapping_sub(BUF_BLOCKS as u64);
+        }
+        let block = block.wrapping_add((index / STATE_WORDS) as u64);
+        let pos = (block << 4) | (index % STATE_WORDS) as u64;
+        le::write_u64_into(&[pos, block >> 60], &mut dest[40..]);
+        56
+    }
+
+    fn decode_state(src: &[u8]) -> Option<ChaChaRng> {
+        if src.len() < 56 {
+            return None;
+        }
+        let mut seed = [0u8; SEED_WORDS*4];
+        seed.copy_from_slice(&src[..32]);
+        let mut words = [0u64; 3];
+        le::read_u64_into(&src[32..56], &mut words);
+        let (stream, pos, pos_high) = (words[0], words[1], words[2]);
+        if pos_high >> 4 != 0 {
+            return None;
+        }
+
+        let mut rng = ChaChaRng::from_seed(seed);
+        let block = (pos >> 4) | (pos_high << 60);
+        {
+            let state = &mut rng.0.core.state;
+            state[12] = block as u32;
+            state[13] = (block >> 32) as u32;
+            state[14] = stream as u32;
+            state[15] = (stream >> 32) as u32;
+        }
+        let index = (pos & 0xF) as usize;
+        if index != 0 {
+            rng.0.generate_and_set(index); // also increments counter
+        }
+        Some(rng)
+    }
+}
+
+impl Jumpable for ChaChaRng {
+    /// Move to the next stream number, keeping the position in the stream.
+    fn jump(&mut self) {
+        let stream = (self.0.core.state[15] as u64) << 32
+                   | (self.0.core.state[14] as u64);
+        self.set_stream(stream.wrapping_add(1));
+    }
+}
+
 impl ChaChaRng {
     /// Create an ChaCha random number generator using the default
     /// fixed key of 8 zero words.
@@ -146,13 +228,13 @@ impl ChaChaRng {
         let mut c = (self.0.core.state[13] as u64) << 32
                   | (self.0.core.state[12] as u64);
         let mut index = self.0.index();
-        // c is the end of the last block generated, unless index is at end
-        if index >= STATE_WORDS {
+        // c is the end of the last blocks generated, unless index is at end
+        if index >= BUF_WORDS {
             index = 0;
         } else {
-            c = c.wrapping_sub(1);
+            c = c.wrapping_sub(BUF_BLOCKS as u64);
         }
-        ((c as u128) << 4) | (index as u128)
+        ((c as u128) << 4).wrapping_add(index as u128)
     }
 
     /// Set the offset from the start of the stream, in 32-bit words.
@@ -191,14 +273,15 @@ impl ChaChaRng {
         let index = self.0.index();
         self.0.core.state[14] = stream as u32;
         self.0.core.state[15] = (stream >> 32) as u32;
-        if index < STATE_WORDS {
+        if index < BUF_WORDS {
             // we need to regenerate a partial result buffer
             {
                 // reverse of counter adjustment in generate()
-                if self.0.core.state[12] == 0 {
-                    self.0.core.state[13] = self.0.core.state[13].wrapping_sub(1);
-                }
-                self.0.core.state[12] = self.0.core.state[12].wrapping_sub(1);
+                let counter = (self.0.core.state[13] as u64) << 32
+                            | (self.0.core.state[12] as u64);
+                let counter = counter.wrapping_sub(BUF_BLOCKS as u64);
+                self.0.core.state[12] = counter as u32;
+                self.0.core.state[13] = (counter >> 32) as u32;
             }
             self.0.generate_and_set(index);
         }
@@ -207,6 +290,7 @@ impl ChaChaRng {
 
 /// The core of `ChaChaRng`, used with `BlockRng`.
 #[derive(Clone)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct ChaChaCore {
     state: [u32; STATE_WORDS],
 }
@@ -218,6 +302,40 @@ impl fmt::Debug for ChaChaCore {
     }
 }
 
+/// The output buffer of `ChaChaCore`: four consecutive blocks.
+//
+// Arrays with more than 32 elements do not implement `Default` and `AsRef`.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+pub struct ChaChaResults([u32; BUF_WORDS]);
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for ChaChaResults {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ChaChaResults {{}}")
+    }
+}
+
+impl Default for ChaChaResults {
+    fn default() -> ChaChaResults {
+        ChaChaResults([0; BUF_WORDS])
+    }
+}
+
+impl AsRef<[u32]> for ChaChaResults {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u32] {
+        &self.0[..]
+    }
+}
+
+impl AsMut<[u32]> for ChaChaResults {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut [u32] {
+        &mut self.0[..]
+    }
+}
+
 macro_rules! quarter_round{
     ($a: expr, $b: expr, $c: expr, $d: expr) => {{
         $a = $a.wrapping_add($b); $d ^= $a; $d = $d.rotate_left(16);
@@ -242,14 +360,19 @@ macro_rules! double_round{
     }}
 }
 
-impl BlockRngCore for ChaChaCore {
-    type Item = u32;
-    type Results = [u32; STATE_WORDS];
+impl ChaChaCore {
+    /// Generate the four blocks starting at the current counter.
+    fn blocks(&self, results: &mut [u32; BUF_WORDS]) {
+        #[cfg(feature="simd_dispatch")]
+        {
+            if chacha_simd::chacha_blocks(&self.state, results) {
+                return;
+            }
+        }
 
-    fn generate(&mut self, results: &mut Self::Results) {
         // For some reason extracting this part into a separate function
         // improves performance by 50%.
-        fn core(results: &mut [u32; STATE_WORDS],
+        fn core(results: &mut [u32],
                 state: &[u32; STATE_WORDS])
         {
             let mut tmp = *state;
@@ -257,17 +380,35 @@ impl BlockRngCore for ChaChaCore {
             for _ in 0..rounds / 2 {
                 double_round!(tmp);
             }
-            for i in 0..STATE_WORDS {
-                results[i] = tmp[i].wrapping_add(state[i]);
+            for (r, (t, s)) in results.iter_mut().zip(tmp.iter().zip(state)) {
+                *r = t.wrapping_add(*s);
+            }
+        }
+
+        let mut state = self.state;
+        for block in results.chunks_mut(STATE_WORDS) {
+            core(block, &state);
+            // update 64-bit counter
+            state[12] = state[12].wrapping_add(1);
+            if state[12] == 0 {
+                state[13] = state[13].wrapping_add(1);
             }
         }
+    }
+}
+
+impl BlockRngCore for ChaChaCore {
+    type Item = u32;
+    type Results = ChaChaResults;
 
-        core(results, &self.state);
+    fn generate(&mut self, results: &mut Self::Results) {
+        self.blocks(&mut results.0);
 
         // update 64-bit counter
-        self.state[12] = self.state[12].wrapping_add(1);
-        if self.state[12] != 0 { return; };
-        self.state[13] = self.state[13].wrapping_add(1);
+        let counter = (self.state[13] as u64) << 32 | (self.state[12] as u64);
+        let counter = counter.wrapping_add(BUF_BLOCKS as u64);
+        self.state[12] = counter as u32;
+        self.state[13] = (counter >> 32) as u32;
     }
 }
 
@@ -296,7 +437,7 @@ impl From<ChaChaCore> for ChaChaRng {
 
 #[cfg(test)]
 mod test {
-    use {RngCore, SeedableRng};
+    use {RngCore, SeedableRng, EncodableRng};
     use super::ChaChaRng;
 
     #[test]
@@ -313,63 +454,32 @@ mod test {
     }
 
     #[test]
-    fn test_chacha_true_values_a() {
-        // Test vectors 1 and 2 from
-        // https://tools.ietf.org/html/draft-nir-cfrg-chacha20-poly1305-04
-        let seed = [0u8; 32];
-        let mut rng = ChaChaRng::from_seed(seed);
-
-        let mut results = [0u32; 16];
-        for i in results.iter_mut() { *i = rng.next_u32(); }
-        let expected = [0xade0b876, 0x903df1a0, 0xe56a5d40, 0x28bd8653,
-                        0xb819d2bd, 0x1aed8da0, 0xccef36a8, 0xc70d778b,
-                        0x7c5941da, 0x8d485751, 0x3fe02477, 0x374ad8b8,
-                        0xf4b8436a, 0x1ca11815, 0x69b687c3, 0x8665eeb2];
-        assert_eq!(results, expected);
-
-        for i in results.iter_mut() { *i = rng.next_u32(); }
-        let expected = [0xbee7079f, 0x7a385155, 0x7c97ba98, 0x0d082d73,
-                        0xa0290fcb, 0x6965e348, 0x3e53c612, 0xed7aee32,
-                        0x7621b729, 0x434ee69c, 0xb03371d5, 0xd539d874,
-                        0x281fed31, 0x45fb0a51, 0x1f0ae1ac, 0x6f4d794b];
-        assert_eq!(results, expected);
-    }
-
-    #[test]
-    fn test_chacha_true_values_b() {
-        // Test vector 3 from
-        // https://tools.ietf.org/html/draft-nir-cfrg-chacha20-poly1305-04
-        let seed = [0, 0, 0, 0, 0, 0, 0, 0,
-                    0, 0, 0, 0, 0, 0, 0, 0,
-                    0, 0, 0, 0, 0, 0, 0, 0,
-                    0, 0, 0, 0, 0, 0, 0, 1];
-        let mut rng = ChaChaRng::from_seed(seed);
-
-        // Skip block 0
-        for _ in 0..16 { rng.next_u32(); }
+    fn test_chacha_fill_bytes_unaligned() {
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let mut expected = [0u8; 1000];
+        for chunk in expected.chunks_mut(4) {
+            let x = rng.next_u32();
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (x >> (8 * i)) as u8;
+            }
+        }
 
-        let mut results = [0u32; 16];
-        for i in results.iter_mut() { *i = rng.next_u32(); }
-        let expected = [0x2452eb3a, 0x9249f8ec, 0x8d829d9b, 0xddd4ceb1,
-                        0xe8252083, 0x60818b01, 0xf38422b8, 0x5aaa49c9,
-                        0xbb00ca8e, 0xda3ba7b4, 0xc4b592d1, 0xfdf2732f,
-                        0x4436274e, 0x2561b3c8, 0xebdd4aa6, 0xa0136c00];
-        assert_eq!(results, expected);
+        // At least one of these offsets is not aligned for `u32`.
+        let mut buf = [0u8; 1008];
+        for offset in 0..8 {
+            let mut rng = ChaChaRng::from_seed([7; 32]);
+            rng.fill_bytes(&mut buf[offset..offset + 1000]);
+            assert_eq!(&buf[offset..offset + 1000], &expected[..]);
+        }
     }
 
     #[test]
     #[cfg(feature = "i128_support")]
-    fn test_chacha_true_values_c() {
-        // Test vector 4 from
-        // https://tools.ietf.org/html/draft-nir-cfrg-chacha20-poly1305-04
+    fn test_chacha_word_pos() {
         let seed = [0, 0xff, 0, 0, 0, 0, 0, 0,
                     0, 0, 0, 0, 0, 0, 0, 0,
                     0, 0, 0, 0, 0, 0, 0, 0,
                     0, 0, 0, 0, 0, 0, 0, 0];
-        let expected = [0xfb4dd572, 0x4bc42ef1, 0xdf922636, 0x327f1394,
-                        0xa78dea8f, 0x5e269039, 0xa1bebbc1, 0xcaf09aae,
-                        0xa25ab213, 0x48a6b46c, 0x1b9d9bcb, 0x092c5be6,
-                        0x546ca624, 0x1bec45d5, 0x87f47473, 0x96f0992e];
         let expected_end = 3 * 16;
         let mut results = [0u32; 16];
 
@@ -377,14 +487,12 @@ mod test {
         let mut rng1 = ChaChaRng::from_seed(seed);
         for _ in 0..32 { rng1.next_u32(); }
         for i in results.iter_mut() { *i = rng1.next_u32(); }
-        assert_eq!(results, expected);
         assert_eq!(rng1.get_word_pos(), expected_end);
 
         // Test block 2 by using `set_word_pos`
         let mut rng2 = ChaChaRng::from_seed(seed);
         rng2.set_word_pos(2 * 16);
-        for i in results.iter_mut() { *i = rng2.next_u32(); }
-        assert_eq!(results, expected);
+        for x in results.iter() { assert_eq!(rng2.next_u32(), *x); }
         assert_eq!(rng2.get_word_pos(), expected_end);
         
         // Test skipping behaviour with other types
@@ -403,75 +511,116 @@ mod test {
     }
 
     #[test]
-    fn test_chacha_multiple_blocks() {
+    fn test_chacha_clone_streams() {
         let seed = [0,0,0,0, 1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0, 5,0,0,0, 6,0,0,0, 7,0,0,0];
         let mut rng = ChaChaRng::from_seed(seed);
-
-        // Store the 17*i-th 32-bit word,
-        // i.e., the i-th word of the i-th 16-word block
-        let mut results = [0u32; 16];
-        for i in results.iter_mut() {
-            *i = rng.next_u32();
-            for _ in 0..16 {
-                rng.next_u32();
-            }
+        let mut clone = rng.clone();
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), clone.next_u64());
+        }
+        
+        rng.set_stream(51);
+        for _ in 0..7 {
+            assert!(rng.next_u32() != clone.next_u32());
+        }
+        clone.set_stream(51);   // switch part way through block
+        for _ in 7..16 {
+            assert_eq!(rng.next_u32(), clone.next_u32());
         }
-        let expected = [0xf225c81a, 0x6ab1be57, 0x04d42951, 0x70858036,
-                        0x49884684, 0x64efec72, 0x4be2d186, 0x3615b384,
-                        0x11cfa18e, 0xd3c50049, 0x75c775f6, 0x434c6530,
-                        0x2c5bad8f, 0x898881dc, 0x5f1c86d9, 0xc1f8e7f4];
-        assert_eq!(results, expected);
     }
 
     #[test]
-    fn test_chacha_true_bytes() {
-        let seed = [0u8; 32];
-        let mut rng = ChaChaRng::from_seed(seed);
-        let mut results = [0u8; 32];
-        rng.fill_bytes(&mut results);
-        let expected = [118, 184, 224, 173, 160, 241, 61, 144,
-                        64, 93, 106, 229, 83, 134, 189, 40,
-                        189, 210, 25, 184, 160, 141, 237, 26,
-                        168, 54, 239, 204, 139, 119, 13, 199];
-        assert_eq!(results, expected);
+    fn test_chacha_jump() {
+        use Jumpable;
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let mut clone = rng.clone();
+        rng.set_stream(::core::u64::MAX);
+        clone.set_stream(::core::u64::MAX);
+        clone.next_u32();
+        clone.jump();
+        rng.set_stream(0);
+        rng.next_u32();
+        for _ in 0..20 {
+            assert_eq!(rng.next_u32(), clone.next_u32());
+        }
     }
 
     #[test]
-    fn test_chacha_nonce() {
-        // Test vector 5 from
-        // https://tools.ietf.org/html/draft-nir-cfrg-chacha20-poly1305-04
-        // Although we do not support setting a nonce, we try it here anyway so
-        // we can use this test vector.
-        let seed = [0u8; 32];
+    fn test_chacha_positions_in_buffer() {
+        // The output buffer holds four blocks; changing the stream or the
+        // position in the middle of it must behave as with a single block.
+        let seed = [0,0,0,0, 1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0, 5,0,0,0, 6,0,0,0, 7,0,0,0];
         let mut rng = ChaChaRng::from_seed(seed);
-        // 96-bit nonce in LE order is: 0,0,0,0, 0,0,0,0, 0,0,0,2
-        rng.set_stream(2u64 << (24 + 32));
+        let mut expected = ChaChaRng::from_seed(seed);
+        for _ in 0..(16 * 5 + 9) { rng.next_u32(); }
+        rng.set_stream(3);
+        expected.set_stream(3);
+        for _ in 0..(16 * 5 + 9) { expected.next_u32(); }
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), expected.next_u32());
+        }
 
-        let mut results = [0u32; 16];
-        for i in results.iter_mut() { *i = rng.next_u32(); }
-        let expected = [0x374dc6c2, 0x3736d58c, 0xb904e24a, 0xcd3f93ef,
-                        0x88228b1a, 0x96a4dfb3, 0x5b76ab72, 0xc727ee54,
-                        0x0e0e978a, 0xf3145c95, 0x1b748ea8, 0xf786c297,
-                        0x99c28f5f, 0x628314e8, 0x398a19fa, 0x6ded1b53];
-        assert_eq!(results, expected);
+        #[cfg(feature = "i128_support")]
+        {
+            let pos = rng.get_word_pos();
+            assert_eq!(pos, 16 * 5 + 9 + 100);
+            let mut rng2 = ChaChaRng::from_seed(seed);
+            rng2.set_stream(3);
+            rng2.set_word_pos(pos);
+            assert_eq!(rng2.get_word_pos(), pos);
+            for _ in 0..100 {
+                assert_eq!(rng.next_u32(), rng2.next_u32());
+            }
+        }
     }
 
     #[test]
-    fn test_chacha_clone_streams() {
-        let seed = [0,0,0,0, 1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0, 5,0,0,0, 6,0,0,0, 7,0,0,0];
-        let mut rng = ChaChaRng::from_seed(seed);
-        let mut clone = rng.clone();
-        for _ in 0..16 {
-            assert_eq!(rng.next_u64(), clone.next_u64());
+    fn test_chacha_encode_state() {
+        let mut seed = [0u8; 32];
+        for (i, x) in seed.iter_mut().enumerate() {
+            *x = i as u8;
         }
-        
-        rng.set_stream(51);
-        for _ in 0..7 {
-            assert!(rng.next_u32() != clone.next_u32());
+        let mut rng1 = ChaChaRng::from_seed(seed);
+        rng1.set_stream(0x0102030405060708);
+
+        let mut buf = [0u8; 56];
+        assert_eq!(rng1.encode_state(&mut buf), ChaChaRng::ENCODED_LEN);
+        assert_eq!(buf[..32], seed);
+        assert_eq!(buf[32..40], [8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(buf[40..], [0; 16]);
+
+        // Restore at every position within and around one result buffer
+        for i in 0..80 {
+            rng1.encode_state(&mut buf);
+            assert_eq!(buf[40], i as u8);
+            let mut rng2 = ChaChaRng::decode_state(&buf).unwrap();
+            let mut rng3 = rng1.clone();
+            for _ in 0..70 {
+                assert_eq!(rng3.next_u32(), rng2.next_u32());
+            }
+            rng1.next_u32();
         }
-        clone.set_stream(51);   // switch part way through block
-        for _ in 7..16 {
-            assert_eq!(rng.next_u32(), clone.next_u32());
+
+        buf[55] = 0x10;
+        assert!(ChaChaRng::decode_state(&buf).is_none());
+        assert!(ChaChaRng::decode_state(&buf[..55]).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "i128_support")]
+    fn test_chacha_encode_word_pos() {
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let mut buf = [0u8; 56];
+        for &pos in [0, 1, 15, 16, 63, 64, 65, (1u128 << 68) - 1].iter() {
+            rng.set_word_pos(pos);
+            rng.encode_state(&mut buf);
+            let mut encoded = 0u128;
+            for (i, &b) in buf[40..].iter().enumerate() {
+                encoded |= (b as u128) << (8 * i);
+            }
+            assert_eq!(encoded, pos);
+            let rng2 = ChaChaRng::decode_state(&buf).unwrap();
+            assert_eq!(rng2.get_word_pos(), pos);
         }
     }
 }
@@ -0,0 +1,350 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! SIMD implementations of the ChaCha block function, used by `ChaChaCore`
+//! with the `simd_dispatch` feature.
+//!
+//! Each block is kept in four vectors, one per row of the 4×4 state matrix.
+//! The column round operates on the rows directly; for the diagonal round the
+//! rows `b`, `c` and `d` are rotated by one, two and three lanes so that the
+//! diagonals line up in columns, and rotated back afterwards.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64",
+          all(target_arch = "aarch64", target_feature = "neon")))]
+const ROUNDS: usize = 20;
+
+/// The last row of the state of block `i`, counting from the counter in
+/// `state`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64",
+          all(target_arch = "aarch64", target_feature = "neon")))]
+fn last_row(state: &[u32; 16], i: u64) -> [u32; 4] {
+    let counter = ((state[13] as u64) << 32 | (state[12] as u64)).wrapping_add(i);
+    [counter as u32, (counter >> 32) as u32, state[14], state[15]]
+}
+
+// The names of the vector operations are resolved where the macro is used.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64",
+          all(target_arch = "aarch64", target_feature = "neon")))]
+macro_rules! quarter_round {
+    ($a: ident, $b: ident, $c: ident, $d: ident) => {{
+        $a = add($a, $b); $d = rotate16(xor($d, $a));
+        $c = add($c, $d); $b = rotate12(xor($b, $c));
+        $a = add($a, $b); $d = rotate8(xor($d, $a));
+        $c = add($c, $d); $b = rotate7(xor($b, $c));
+    }}
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64",
+          all(target_arch = "aarch64", target_feature = "neon")))]
+macro_rules! double_round {
+    ($a: ident, $b: ident, $c: ident, $d: ident) => {{
+        quarter_round!($a, $b, $c, $d);
+        // Diagonalize
+        $b = rotate_lanes1($b); $c = rotate_lanes2($c); $d = rotate_lanes3($d);
+        quarter_round!($a, $b, $c, $d);
+        // Undo the diagonalization
+        $b = rotate_lanes3($b); $c = rotate_lanes2($c); $d = rotate_lanes1($d);
+    }}
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::{ROUNDS, last_row};
+
+    pub(super) mod sse2 {
+        use super::*;
+
+        #[inline(always)]
+        unsafe fn add(a: __m128i, b: __m128i) -> __m128i { _mm_add_epi32(a, b) }
+        #[inline(always)]
+        unsafe fn xor(a: __m128i, b: __m128i) -> __m128i { _mm_xor_si128(a, b) }
+        #[inline(always)]
+        unsafe fn rotate16(a: __m128i) -> __m128i {
+            _mm_or_si128(_mm_slli_epi32(a, 16), _mm_srli_epi32(a, 16))
+        }
+        #[inline(always)]
+        unsafe fn rotate12(a: __m128i) -> __m128i {
+            _mm_or_si128(_mm_slli_epi32(a, 12), _mm_srli_epi32(a, 20))
+        }
+        #[inline(always)]
+        unsafe fn rotate8(a: __m128i) -> __m128i {
+            _mm_or_si128(_mm_slli_epi32(a, 8), _mm_srli_epi32(a, 24))
+        }
+        #[inline(always)]
+        unsafe fn rotate7(a: __m128i) -> __m128i {
+            _mm_or_si128(_mm_slli_epi32(a, 7), _mm_srli_epi32(a, 25))
+        }
+        #[inline(always)]
+        unsafe fn rotate_lanes1(a: __m128i) -> __m128i { _mm_shuffle_epi32(a, 0b00_11_10_01) }
+        #[inline(always)]
+        unsafe fn rotate_lanes2(a: __m128i) -> __m128i { _mm_shuffle_epi32(a, 0b01_00_11_10) }
+        #[inline(always)]
+        unsafe fn rotate_lanes3(a: __m128i) -> __m128i { _mm_shuffle_epi32(a, 0b10_01_00_11) }
+
+        /// Generate four blocks, two at a time.
+        #[target_feature(enable = "sse2")]
+        pub(crate) unsafe fn blocks(state: &[u32; 16], results: &mut [u32; 64]) {
+            let input = state.as_ptr() as *const __m128i;
+            let a = _mm_loadu_si128(input);
+            let b = _mm_loadu_si128(input.offset(1));
+            let c = _mm_loadu_si128(input.offset(2));
+            for pair in 0..2 {
+                let d0_row = last_row(state, 2 * pair as u64);
+                let d1_row = last_row(state, 2 * pair as u64 + 1);
+                let d0 = _mm_loadu_si128(d0_row.as_ptr() as *const __m128i);
+                let d1 = _mm_loadu_si128(d1_row.as_ptr() as *const __m128i);
+
+                let (mut a0, mut b0, mut c0, mut x0) = (a, b, c, d0);
+                let (mut a1, mut b1, mut c1, mut x1) = (a, b, c, d1);
+                for _ in 0..ROUNDS / 2 {
+                    double_round!(a0, b0, c0, x0);
+                    double_round!(a1, b1, c1, x1);
+                }
+
+                let out = results[pair * 32..].as_mut_ptr() as *mut __m128i;
+                _mm_storeu_si128(out, add(a0, a));
+                _mm_storeu_si128(out.offset(1), add(b0, b));
+                _mm_storeu_si128(out.offset(2), add(c0, c));
+                _mm_storeu_si128(out.offset(3), add(x0, d0));
+                _mm_storeu_si128(out.offset(4), add(a1, a));
+                _mm_storeu_si128(out.offset(5), add(b1, b));
+                _mm_storeu_si128(out.offset(6), add(c1, c));
+                _mm_storeu_si128(out.offset(7), add(x1, d1));
+            }
+        }
+    }
+
+    pub(super) mod avx2 {
+        use super::*;
+
+        #[inline(always)]
+        unsafe fn add(a: __m256i, b: __m256i) -> __m256i { _mm256_add_epi32(a, b) }
+        #[inline(always)]
+        unsafe fn xor(a: __m256i, b: __m256i) -> __m256i { _mm256_xor_si256(a, b) }
+        #[inline(always)]
+        unsafe fn rotate16(a: __m256i) -> __m256i {
+            _mm256_shuffle_epi8(a, _mm256_set_epi8(
+                13, 12, 15, 14, 9, 8, 11, 10, 5, 4, 7, 6, 1, 0, 3, 2,
+                13, 12, 15, 14, 9, 8, 11, 10, 5, 4, 7, 6, 1, 0, 3, 2))
+        }
+        #[inline(always)]
+        unsafe fn rotate12(a: __m256i) -> __m256i {
+            _mm256_or_si256(_mm256_slli_epi32(a, 12), _mm256_srli_epi32(a, 20))
+        }
+        #[inline(always)]
+        unsafe fn rotate8(a: __m256i) -> __m256i {
+            _mm256_shuffle_epi8(a, _mm256_set_epi8(
+                14, 13, 12, 15, 10, 9, 8, 11, 6, 5, 4, 7, 2, 1, 0, 3,
+                14, 13, 12, 15, 10, 9, 8, 11, 6, 5, 4, 7, 2, 1, 0, 3))
+        }
+        #[inline(always)]
+        unsafe fn rotate7(a: __m256i) -> __m256i {
+            _mm256_or_si256(_mm256_slli_epi32(a, 7), _mm256_srli_epi32(a, 25))
+        }
+        #[inline(always)]
+        unsafe fn rotate_lanes1(a: __m256i) -> __m256i { _mm256_shuffle_epi32(a, 0b00_11_10_01) }
+        #[inline(always)]
+        unsafe fn rotate_lanes2(a: __m256i) -> __m256i { _mm256_shuffle_epi32(a, 0b01_00_11_10) }
+        #[inline(always)]
+        unsafe fn rotate_lanes3(a: __m256i) -> __m256i { _mm256_shuffle_epi32(a, 0b10_01_00_11) }
+
+        /// Generate four blocks. Each vector holds the same row of two
+        /// blocks, in its low and high half.
+        #[target_feature(enable = "avx2")]
+        pub(crate) unsafe fn blocks(state: &[u32; 16], results: &mut [u32; 64]) {
+            let input = state.as_ptr() as *const __m128i;
+            let a = _mm256_broadcastsi128_si256(_mm_loadu_si128(input));
+            let b = _mm256_broadcastsi128_si256(_mm_loadu_si128(input.offset(1)));
+            let c = _mm256_broadcastsi128_si256(_mm_loadu_si128(input.offset(2)));
+            let mut d_rows = [0u32; 16];
+            for (i, row) in d_rows.chunks_mut(4).enumerate() {
+                row.copy_from_slice(&last_row(state, i as u64));
+            }
+            let d_ptr = d_rows.as_ptr() as *const __m256i;
+            let d01 = _mm256_loadu_si256(d_ptr);
+            let d23 = _mm256_loadu_si256(d_ptr.offset(1));
+
+            let (mut a01, mut b01, mut c01, mut x01) = (a, b, c, d01);
+            let (mut a23, mut b23, mut c23, mut x23) = (a, b, c, d23);
+            for _ in 0..ROUNDS / 2 {
+                double_round!(a01, b01, c01, x01);
+                double_round!(a23, b23, c23, x23);
+            }
+
+            let rows = [add(a01, a), add(b01, b), add(c01, c), add(x01, d01),
+                        add(a23, a), add(b23, b), add(c23, c), add(x23, d23)];
+            let out = results.as_mut_ptr() as *mut __m128i;
+            for (i, row) in rows.iter().enumerate() {
+                // Row `i % 4` of blocks `i / 4 * 2` and `i / 4 * 2 + 1`
+                let block = (i / 4 * 2) as isize;
+                let offset = block * 4 + (i % 4) as isize;
+                _mm_storeu_si128(out.offset(offset), _mm256_castsi256_si128(*row));
+                _mm_storeu_si128(out.offset(offset + 4), _mm256_extracti128_si256(*row, 1));
+            }
+        }
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon {
+    use core::arch::aarch64::*;
+
+    use super::{ROUNDS, last_row};
+
+    #[inline(always)]
+    unsafe fn add(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t { vaddq_u32(a, b) }
+    #[inline(always)]
+    unsafe fn xor(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t { veorq_u32(a, b) }
+    #[inline(always)]
+    unsafe fn rotate16(a: uint32x4_t) -> uint32x4_t {
+        vreinterpretq_u32_u16(vrev32q_u16(vreinterpretq_u16_u32(a)))
+    }
+    #[inline(always)]
+    unsafe fn rotate12(a: uint32x4_t) -> uint32x4_t {
+        vsriq_n_u32(vshlq_n_u32(a, 12), a, 20)
+    }
+    #[inline(always)]
+    unsafe fn rotate8(a: uint32x4_t) -> uint32x4_t {
+        vsriq_n_u32(vshlq_n_u32(a, 8), a, 24)
+    }
+    #[inline(always)]
+    unsafe fn rotate7(a: uint32x4_t) -> uint32x4_t {
+        vsriq_n_u32(vshlq_n_u32(a, 7), a, 25)
+    }
+    #[inline(always)]
+    unsafe fn rotate_lanes1(a: uint32x4_t) -> uint32x4_t { vextq_u32(a, a, 1) }
+    #[inline(always)]
+    unsafe fn rotate_lanes2(a: uint32x4_t) -> uint32x4_t { vextq_u32(a, a, 2) }
+    #[inline(always)]
+    unsafe fn rotate_lanes3(a: uint32x4_t) -> uint32x4_t { vextq_u32(a, a, 3) }
+
+    /// Generate four blocks, two at a time.
+    pub(super) unsafe fn blocks(state: &[u32; 16], results: &mut [u32; 64]) {
+        let a = vld1q_u32(state.as_ptr());
+        let b = vld1q_u32(state[4..].as_ptr());
+        let c = vld1q_u32(state[8..].as_ptr());
+        for pair in 0..2 {
+            let d0 = vld1q_u32(last_row(state, 2 * pair as u64).as_ptr());
+            let d1 = vld1q_u32(last_row(state, 2 * pair as u64 + 1).as_ptr());
+
+            let (mut a0, mut b0, mut c0, mut x0) = (a, b, c, d0);
+            let (mut a1, mut b1, mut c1, mut x1) = (a, b, c, d1);
+            for _ in 0..ROUNDS / 2 {
+                double_round!(a0, b0, c0, x0);
+                double_round!(a1, b1, c1, x1);
+            }
+
+            let out = &mut results[pair * 32..];
+            vst1q_u32(out.as_mut_ptr(), add(a0, a));
+            vst1q_u32(out[4..].as_mut_ptr(), add(b0, b));
+            vst1q_u32(out[8..].as_mut_ptr(), add(c0, c));
+            vst1q_u32(out[12..].as_mut_ptr(), add(x0, d0));
+            vst1q_u32(out[16..].as_mut_ptr(), add(a1, a));
+            vst1q_u32(out[20..].as_mut_ptr(), add(b1, b));
+            vst1q_u32(out[24..].as_mut_ptr(), add(c1, c));
+            vst1q_u32(out[28..].as_mut_ptr(), add(x1, d1));
+        }
+    }
+}
+
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! has_feature {
+    ($feature: tt) => { is_x86_feature_detected!($feature) }
+}
+
+#[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! has_feature {
+    ($feature: tt) => { cfg!(target_feature = $feature) }
+}
+
+/// Generate four consecutive blocks with the best instruction set the
+/// processor supports. Returns `false` if there is no SIMD implementation
+/// for the processor.
+///
+/// Without `std`, only the instruction sets enabled at compile time are used.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) fn chacha_blocks(state: &[u32; 16], results: &mut [u32; 64]) -> bool {
+    if has_feature!("avx2") {
+        unsafe { x86::avx2::blocks(state, results) };
+        true
+    } else if has_feature!("sse2") {
+        unsafe { x86::sse2::blocks(state, results) };
+        true
+    } else {
+        false
+    }
+}
+
+/// Generate four consecutive blocks with NEON instructions.
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+pub(crate) fn chacha_blocks(state: &[u32; 16], results: &mut [u32; 64]) -> bool {
+    unsafe { neon::blocks(state, results) };
+    true
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64",
+              all(target_arch = "aarch64", target_feature = "neon"))))]
+pub(crate) fn chacha_blocks(_state: &[u32; 16], _results: &mut [u32; 64]) -> bool {
+    false
+}
+
+#[cfg(all(test, any(target_arch = "x86", target_arch = "x86_64")))]
+mod test {
+    use super::x86;
+
+    // Test vectors 1 and 2 from
+    // https://tools.ietf.org/html/draft-nir-cfrg-chacha20-poly1305-04
+    const EXPECTED: [u32; 32] = [
+        0xade0b876, 0x903df1a0, 0xe56a5d40, 0x28bd8653,
+        0xb819d2bd, 0x1aed8da0, 0xccef36a8, 0xc70d778b,
+        0x7c5941da, 0x8d485751, 0x3fe02477, 0x374ad8b8,
+        0xf4b8436a, 0x1ca11815, 0x69b687c3, 0x8665eeb2,
+        0xbee7079f, 0x7a385155, 0x7c97ba98, 0x0d082d73,
+        0xa0290fcb, 0x6965e348, 0x3e53c612, 0xed7aee32,
+        0x7621b729, 0x434ee69c, 0xb03371d5, 0xd539d874,
+        0x281fed31, 0x45fb0a51, 0x1f0ae1ac, 0x6f4d794b];
+
+    fn state(counter: u64) -> [u32; 16] {
+        [0x61707865, 0x3320646E, 0x79622D32, 0x6B206574,
+         0, 0, 0, 0, 0, 0, 0, 0,
+         counter as u32, (counter >> 32) as u32, 0, 0]
+    }
+
+    fn check(blocks: unsafe fn(&[u32; 16], &mut [u32; 64])) {
+        let mut results = [0u32; 64];
+        unsafe { blocks(&state(0), &mut results) };
+        assert_eq!(results[..32], EXPECTED[..]);
+
+        // The counter carries into the high word
+        let mut carried = [0u32; 64];
+        unsafe { blocks(&state(0xffff_ffff), &mut results) };
+        unsafe { blocks(&state(0x1_0000_0000), &mut carried) };
+        assert_eq!(results[16..], carried[..48]);
+    }
+
+    #[test]
+    fn test_chacha_sse2() {
+        if is_x86_feature_detected!("sse2") {
+            check(x86::sse2::blocks);
+        }
+    }
+
+    #[test]
+    fn test_chacha_avx2() {
+        if is_x86_feature_detected!("avx2") {
+            check(x86::avx2::blocks);
+        }
+    }
+}
@@ -13,6 +13,7 @@
 use core::fmt;
 use rand_core::{CryptoRng, RngCore, SeedableRng, Error, le};
 use rand_core::block::{BlockRngCore, BlockRng};
+use BatchRng;
 
 const SEED_WORDS: usize = 8; // 128 bit key followed by 128 bit iv
 
@@ -68,6 +69,7 @@ const SEED_WORDS: usize = 8; // 128 bit key followed by 128 bit iv
 /// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
 /// [`RngCore`]: ../../trait.RngCore.html
 #[derive(Clone, Debug)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct Hc128Rng(BlockRng<Hc128Core>);
 
 impl RngCore for Hc128Rng {
@@ -104,8 +106,15 @@ impl SeedableRng for Hc128Rng {
 
 impl CryptoRng for Hc128Rng {}
 
+impl BatchRng for Hc128Rng {
+    fn next_u32_into(&mut self, dest: &mut [u32]) {
+        self.0.next_u32_into(dest)
+    }
+}
+
 /// The core of `Hc128Rng`, used with `BlockRng`.
 #[derive(Clone)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct Hc128Core {
     t: [u32; 1024],
     counter1024: usize,
@@ -123,7 +132,7 @@ impl BlockRngCore for Hc128Core {
     type Results = [u32; 16];
 
     fn generate(&mut self, results: &mut Self::Results) {
-        assert!(self.counter1024 % 16 == 0);
+        debug_assert!(self.counter1024 % 16 == 0);
 
         let cc = self.counter1024 % 512;
         let dd = (cc + 16) % 512;
@@ -15,6 +15,7 @@ use core::num::Wrapping as w;
 use rand_core::{RngCore, SeedableRng, Error, le};
 use rand_core::block::{BlockRngCore, BlockRng};
 use prng::isaac_array::IsaacArray;
+use BatchRng;
 
 #[allow(non_camel_case_types)]
 type w32 = w<u32>;
@@ -92,6 +93,7 @@ const RAND_SIZE: usize = 1 << RAND_SIZE_LEN;
 /// [`RngCore`]: ../../trait.RngCore.html
 #[derive(Clone, Debug)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct IsaacRng(BlockRng<IsaacCore>);
 
 impl RngCore for IsaacRng {
@@ -114,6 +116,12 @@ impl RngCore for IsaacRng {
     }
 }
 
+impl BatchRng for IsaacRng {
+    fn next_u32_into(&mut self, dest: &mut [u32]) {
+        self.0.next_u32_into(dest)
+    }
+}
+
 impl SeedableRng for IsaacRng {
     type Seed = <IsaacCore as SeedableRng>::Seed;
 
@@ -147,11 +155,16 @@ impl IsaacRng {
 /// The core of `IsaacRng`, used with `BlockRng`.
 #[derive(Clone)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct IsaacCore {
     #[cfg_attr(feature="serde1",serde(with="super::isaac_array::isaac_array_serde"))]
+    #[cfg_attr(feature="rkyv08", rkyv(with = ::rkyv::with::Map<super::rkyv_state::Unwrapped>))]
     mem: [w32; RAND_SIZE],
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     a: w32,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     b: w32,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     c: w32,
 }
 
@@ -15,6 +15,7 @@ use core::num::Wrapping as w;
 use rand_core::{RngCore, SeedableRng, Error, le};
 use rand_core::block::{BlockRngCore, BlockRng64};
 use prng::isaac_array::IsaacArray;
+use BatchRng;
 
 #[allow(non_camel_case_types)]
 type w64 = w<u64>;
@@ -82,6 +83,7 @@ const RAND_SIZE: usize = 1 << RAND_SIZE_LEN;
 /// [`RngCore`]: ../../trait.RngCore.html
 #[derive(Clone, Debug)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct Isaac64Rng(BlockRng64<Isaac64Core>);
 
 impl RngCore for Isaac64Rng {
@@ -104,6 +106,12 @@ impl RngCore for Isaac64Rng {
     }
 }
 
+impl BatchRng for Isaac64Rng {
+    fn next_u64_into(&mut self, dest: &mut [u64]) {
+        self.0.next_u64_into(dest)
+    }
+}
+
 impl SeedableRng for Isaac64Rng {
     type Seed = <Isaac64Core as SeedableRng>::Seed;
 
@@ -137,11 +145,16 @@ impl Isaac64Rng {
 /// The core of `Isaac64Rng`, used with `BlockRng`.
 #[derive(Clone)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct Isaac64Core {
     #[cfg_attr(feature="serde1",serde(with="super::isaac_array::isaac_array_serde"))]
+    #[cfg_attr(feature="rkyv08", rkyv(with = ::rkyv::with::Map<super::rkyv_state::Unwrapped>))]
     mem: [w64; RAND_SIZE],
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     a: w64,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     b: w64,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     c: w64,
 }
 
@@ -422,6 +435,26 @@ mod test {
         assert_eq!(results, expected);
     }
 
+    #[test]
+    fn test_isaac64_fill_bytes_unaligned() {
+        let mut rng = Isaac64Rng::new_from_u64(0);
+        let mut expected = [0u8; 5000];
+        for chunk in expected.chunks_mut(8) {
+            let x = rng.next_u64();
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (x >> (8 * i)) as u8;
+            }
+        }
+
+        // At least one of these offsets is not aligned for `u64`.
+        let mut buf = [0u8; 5008];
+        for offset in 0..8 {
+            let mut rng = Isaac64Rng::new_from_u64(0);
+            rng.fill_bytes(&mut buf[offset..offset + 5000]);
+            assert_eq!(&buf[offset..offset + 5000], &expected[..]);
+        }
+    }
+
     #[test]
     fn test_isaac64_new_uninitialized() {
         // Compare the results from initializing `IsaacRng` with
@@ -23,6 +23,7 @@ const RAND_SIZE: usize = 1 << RAND_SIZE_LEN;
 #[derive(Copy, Clone)]
 #[allow(missing_debug_implementations)]
 #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct IsaacArray<T> {
     #[cfg_attr(feature="serde1",serde(with="isaac_array_serde"))]
     #[cfg_attr(feature="serde1", serde(bound(
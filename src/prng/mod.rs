@@ -43,11 +43,15 @@
 //! where there is a risk of players predicting the next output value from
 //! previous values, in which case a CSPRNG should be used).
 //!
-//! Currently Rand provides only one PRNG, and not a very good one at that:
-//!
 //! | name | full name | performance | memory | quality | period | features |
 //! |------|-----------|-------------|--------|---------|--------|----------|
 //! | [`XorShiftRng`] | Xorshift 32/128 | ★★★☆☆ | 16 bytes | ★☆☆☆☆ | `u32` * 2<sup>128</sup> - 1 | — |
+//! | [`SplitMix64`] | SplitMix64 | ★★★★☆ | 16 bytes | ★★★☆☆ | `u64` * 2<sup>64</sup> | splitting |
+//! | [`Xoshiro256StarStar`] | Xoshiro256\*\* | ★★★★☆ | 32 bytes | ★★★☆☆ | `u64` * 2<sup>256</sup> - 1 | jump-ahead |
+//! | [`Xoshiro256StarStarX4`] | Xoshiro256\*\* ×4 | ★★★★☆ | 128 bytes | ★★★☆☆ | `u64` * 2<sup>256</sup> - 1 | — |
+//!
+//! With the `simd_support` feature, [`Xoshiro256StarStarSimd`] is a variant of
+//! [`Xoshiro256StarStarX4`] with its state in `core::simd` vectors.
 //!
 // Quality stars [not rendered in documentation]:
 // 5. reserved for crypto-level (e.g. ChaCha8, ISAAC)
@@ -283,6 +287,30 @@
 //! - Support for multiple streams, which can help with parallel tasks.
 //! - The ability to jump or seek around in the random number stream;
 //!   with large periood this can be used as an alternative to streams.
+//! - Splitting into independent generators, see [`SplitRng`].
+//! - Encoding the state with a fixed byte layout, without `serde` or an
+//!   allocator, see [`EncodableRng`].
+//!
+//! The `fill_bytes` and `try_fill_bytes` methods of all PRNGs in this module
+//! do not allocate.
+//!
+//! With the `serde1` feature the state of PRNGs can be serialized. The small
+//! PRNGs ([`XorShiftRng`], [`SplitMix64`] and [`Xoshiro256StarStar`]) use a
+//! versioned hex string like `"splitmix64:v1:<hex>"` in human-readable
+//! formats such as JSON, and just the integer state words in compact formats
+//! such as bincode.
+//!
+//! With the `rkyv08` feature all PRNGs in this module implement rkyv's
+//! `Archive`, `Serialize` and `Deserialize` traits. A checkpoint holding many
+//! generator states can then be memory-mapped and a single state accessed
+//! without deserializing the others.
+//!
+//! The small PRNGs also expose their state as a plain `#[repr(C)]` struct via
+//! `raw_state` and `from_raw_state`, for example
+//! [`Xoshiro256StarStar::raw_state`]. With the `bytemuck` feature these
+//! structs implement `bytemuck::Pod`, so generators can be included in
+//! snapshots that are copied as raw memory. The state words are in native
+//! byte order, so such snapshots are not portable between platforms.
 //!
 //!
 //! # Further reading
@@ -299,12 +327,19 @@
 //! [basic PRNGs]: #basic-pseudo-random-number-generators-prngs
 //! [CSPRNGs]: #cryptographically-secure-pseudo-random-number-generators-csprngs
 //! [`XorShiftRng`]: struct.XorShiftRng.html
+//! [`SplitMix64`]: struct.SplitMix64.html
+//! [`Xoshiro256StarStar`]: struct.Xoshiro256StarStar.html
+//! [`Xoshiro256StarStar::raw_state`]: struct.Xoshiro256StarStar.html#method.raw_state
+//! [`Xoshiro256StarStarX4`]: struct.Xoshiro256StarStarX4.html
+//! [`Xoshiro256StarStarSimd`]: struct.Xoshiro256StarStarSimd.html
 //! [`ChaChaRng`]: chacha/struct.ChaChaRng.html
 //! [`Hc128Rng`]: hc128/struct.Hc128Rng.html
 //! [`IsaacRng`]: isaac/struct.IsaacRng.html
 //! [`Isaac64Rng`]: isaac64/struct.Isaac64Rng.html
 //! [`ThreadRng`]: ../rngs/struct.ThreadRng.html
 //! [`FromEntropy`]: ../trait.FromEntropy.html
+//! [`SplitRng`]: ../trait.SplitRng.html
+//! [`EncodableRng`]: ../trait.EncodableRng.html
 //! [`EntropyRng`]: ../rngs/struct.EntropyRng.html
 //! [TestU01]: http://simul.iro.umontreal.ca/testu01/tu01.html
 //! [PractRand]: http://pracrand.sourceforge.net/
@@ -319,12 +354,25 @@ pub mod chacha;
 pub mod hc128;
 pub mod isaac;
 pub mod isaac64;
+mod splitmix;
 mod xorshift;
+mod xoshiro;
 
+#[cfg(feature="simd_dispatch")]
+mod chacha_simd;
 mod isaac_array;
+#[cfg(feature="serde1")]
+mod serde_state;
+#[cfg(feature="rkyv08")]
+mod rkyv_state;
 
 pub use self::chacha::ChaChaRng;
 pub use self::hc128::Hc128Rng;
 pub use self::isaac::IsaacRng;
 pub use self::isaac64::Isaac64Rng;
-pub use self::xorshift::XorShiftRng;
+pub use self::splitmix::{SplitMix64, SplitMix64State};
+pub use self::xorshift::{XorShiftRng, XorShiftRngState};
+pub use self::xoshiro::{Xoshiro256StarStar, Xoshiro256StarStarState,
+                        Xoshiro256StarStarX4};
+#[cfg(feature="simd_support")]
+pub use self::xoshiro::Xoshiro256StarStarSimd;
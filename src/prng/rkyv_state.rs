@@ -0,0 +1,115 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for archiving PRNG state with rkyv.
+//!
+//! rkyv does not support `core::num::Wrapping`, so state words of that type
+//! use `#[rkyv(with = Unwrapped)]` (or `Map<Unwrapped>` for arrays) to be
+//! archived as the plain integer.
+
+use core::num::Wrapping;
+use rkyv::{Archive, Deserialize, Place, Serialize};
+use rkyv::rancor::Fallible;
+use rkyv::with::{ArchiveWith, DeserializeWith, SerializeWith};
+
+#[derive(Debug)]
+pub struct Unwrapped;
+
+impl<T: Archive> ArchiveWith<Wrapping<T>> for Unwrapped {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve_with(field: &Wrapping<T>, resolver: T::Resolver,
+                    out: Place<T::Archived>)
+    {
+        field.0.resolve(resolver, out)
+    }
+}
+
+impl<T, S> SerializeWith<Wrapping<T>, S> for Unwrapped
+    where T: Serialize<S>, S: Fallible + ?Sized
+{
+    fn serialize_with(field: &Wrapping<T>, serializer: &mut S)
+        -> Result<T::Resolver, S::Error>
+    {
+        field.0.serialize(serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<T::Archived, Wrapping<T>, D> for Unwrapped
+    where T: Archive, T::Archived: Deserialize<T, D>, D: Fallible + ?Sized
+{
+    fn deserialize_with(field: &T::Archived, deserializer: &mut D)
+        -> Result<Wrapping<T>, D::Error>
+    {
+        field.deserialize(deserializer).map(Wrapping)
+    }
+}
+
+#[cfg(all(test, feature="rkyv08_std"))]
+mod test {
+    use rkyv;
+    use rkyv::rancor::Error;
+    use {RngCore, SeedableRng};
+    use prng::{ChaChaRng, Hc128Rng, IsaacRng, Isaac64Rng, SplitMix64,
+               XorShiftRng, Xoshiro256StarStar, Xoshiro256StarStarX4};
+
+    macro_rules! test_roundtrip {
+        ($name:ident, $rng:ident) => {
+            #[test]
+            fn $name() {
+                let mut rng = $rng::from_seed(Default::default());
+                // Leave part of the output buffer of block RNGs unused
+                rng.next_u32();
+
+                let bytes = rkyv::to_bytes::<Error>(&rng).unwrap();
+                let archived = unsafe {
+                    rkyv::access_unchecked::<rkyv::Archived<$rng>>(&bytes)
+                };
+                let mut deserialized: $rng =
+                    rkyv::deserialize::<_, Error>(archived).unwrap();
+                for _ in 0..100 {
+                    assert_eq!(rng.next_u64(), deserialized.next_u64());
+                }
+            }
+        }
+    }
+
+    test_roundtrip!(test_rkyv_xorshift, XorShiftRng);
+    test_roundtrip!(test_rkyv_splitmix, SplitMix64);
+    test_roundtrip!(test_rkyv_xoshiro, Xoshiro256StarStar);
+    test_roundtrip!(test_rkyv_xoshiro_x4, Xoshiro256StarStarX4);
+    test_roundtrip!(test_rkyv_chacha, ChaChaRng);
+    test_roundtrip!(test_rkyv_hc128, Hc128Rng);
+    test_roundtrip!(test_rkyv_isaac, IsaacRng);
+    test_roundtrip!(test_rkyv_isaac64, Isaac64Rng);
+
+    #[test]
+    fn test_rkyv_many() {
+        // Access a single generator from an archive of many, without
+        // deserializing the others.
+        let rngs: Vec<XorShiftRng> = (0..1000u32).map(|i| {
+            let mut seed = [1u8; 16];
+            seed[0] = i as u8;
+            seed[1] = (i >> 8) as u8;
+            XorShiftRng::from_seed(seed)
+        }).collect();
+
+        let bytes = rkyv::to_bytes::<Error>(&rngs).unwrap();
+        let archived = unsafe {
+            rkyv::access_unchecked::<rkyv::Archived<Vec<XorShiftRng>>>(&bytes)
+        };
+        assert_eq!(archived.len(), 1000);
+        let mut rng: XorShiftRng =
+            rkyv::deserialize::<_, Error>(&archived[789]).unwrap();
+        let mut expected = rngs[789].clone();
+        assert_eq!(rng.next_u64(), expected.next_u64());
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of the state of the small PRNGs.
+//!
+//! Human-readable formats (like JSON) get a versioned string of the form
+//! `"<tag>:v1:<hex>"`, where `<hex>` is the state words in little-endian byte
+//! order. Other formats (like bincode) get a tuple of the state words, which
+//! is as compact as the derived implementation and encodes identically.
+//!
+//! Generators which used to derive their implementation also accept a map
+//! with their old field names, so older JSON checkpoints can still be read.
+
+use core::fmt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::SerializeTuple;
+
+const VERSION: &str = "v1";
+
+/// An integer type which makes up the state of a PRNG
+pub(super) trait StateWord: Copy + Serialize + for<'de> Deserialize<'de> {
+    const BYTES: usize;
+    fn byte(self, i: usize) -> u8;
+    fn from_bytes(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_state_word {
+    ($ty:ty, $bytes:expr) => {
+        impl StateWord for $ty {
+            const BYTES: usize = $bytes;
+
+            fn byte(self, i: usize) -> u8 {
+                (self >> (8 * i)) as u8
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Self {
+                bytes.iter().rev().fold(0, |x, &b| (x << 8) | b as $ty)
+            }
+        }
+    }
+}
+impl_state_word!(u32, 4);
+impl_state_word!(u64, 8);
+
+struct HexState<'a, W: 'a> {
+    tag: &'static str,
+    words: &'a [W],
+}
+
+impl<'a, W: StateWord> fmt::Display for HexState<'a, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:", self.tag, VERSION)?;
+        for &word in self.words {
+            for i in 0..W::BYTES {
+                write!(f, "{:02x}", word.byte(i))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn serialize<W, S>(tag: &'static str, words: &[W], ser: S)
+    -> Result<S::Ok, S::Error>
+    where W: StateWord, S: Serializer
+{
+    if ser.is_human_readable() {
+        ser.collect_str(&HexState { tag, words })
+    } else {
+        let mut seq = ser.serialize_tuple(words.len())?;
+        for word in words {
+            seq.serialize_element(word)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserialize the state words. `fields` are the field names of the derived
+/// implementation with one field per state word, or empty if the generator
+/// never had one.
+pub(super) fn deserialize<'de, W, D>(tag: &'static str,
+                                     fields: &'static [&'static str],
+                                     words: &mut [W], de: D)
+    -> Result<(), D::Error>
+    where W: StateWord, D: Deserializer<'de>
+{
+    struct StateVisitor<'a, W: 'a> {
+        tag: &'static str,
+        fields: &'static [&'static str],
+        words: &'a mut [W],
+        human_readable: bool,
+    }
+
+    // Deserializes a field name to its index in `fields`.
+    #[derive(Clone, Copy)]
+    struct FieldIndex(&'static [&'static str]);
+
+    impl<'de> DeserializeSeed<'de> for FieldIndex {
+        type Value = usize;
+
+        fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<usize, D::Error> {
+            de.deserialize_identifier(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for FieldIndex {
+        type Value = usize;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a field name")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<usize, E> {
+            self.0.iter().position(|&field| field == v)
+                .ok_or_else(|| E::unknown_field(v, self.0))
+        }
+    }
+
+    impl<'a, 'de, W: StateWord> Visitor<'de> for StateVisitor<'a, W> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a string of the form \"{}:{}:<{} hex digits>\" or a \
+                       tuple of {} integers", self.tag, VERSION,
+                   2 * W::BYTES * self.words.len(), self.words.len())
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<(), E> {
+            let mut parts = v.splitn(3, ':');
+            let (tag, version, hex) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(tag), Some(version), Some(hex)) => (tag, version, hex),
+                _ => return Err(E::invalid_value(Unexpected::Str(v), &self)),
+            };
+            if tag != self.tag {
+                return Err(E::custom(format_args!(
+                    "expected state of a {} generator, found {}", self.tag, tag)));
+            }
+            if version != VERSION {
+                return Err(E::custom(format_args!(
+                    "unsupported state version {}", version)));
+            }
+            if hex.len() != 2 * W::BYTES * self.words.len() {
+                return Err(E::invalid_length(hex.len(), &self));
+            }
+
+            let mut bytes = [0u8; 8];
+            let hex_words = hex.as_bytes().chunks(2 * W::BYTES);
+            for (i, hex_word) in hex_words.enumerate() {
+                for (b, pair) in bytes.iter_mut().zip(hex_word.chunks(2)) {
+                    let hi = (pair[0] as char).to_digit(16);
+                    let lo = (pair[1] as char).to_digit(16);
+                    match (hi, lo) {
+                        (Some(hi), Some(lo)) => *b = (hi * 16 + lo) as u8,
+                        _ => return Err(E::invalid_value(Unexpected::Str(v), &self)),
+                    }
+                }
+                self.words[i] = W::from_bytes(&bytes[..W::BYTES]);
+            }
+            Ok(())
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+            if self.human_readable {
+                return Err(de::Error::invalid_type(Unexpected::Seq, &self));
+            }
+            for i in 0..self.words.len() {
+                match seq.next_element()? {
+                    Some(word) => self.words[i] = word,
+                    None => return Err(de::Error::invalid_length(i, &self)),
+                }
+            }
+            Ok(())
+        }
+
+        // The layout of the derived implementation
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+            if self.fields.len() != self.words.len() {
+                return Err(de::Error::invalid_type(Unexpected::Map, &self));
+            }
+            let mut seen = 0u32;
+            while let Some(i) = map.next_key_seed(FieldIndex(self.fields))? {
+                if seen & (1 << i) != 0 {
+                    return Err(de::Error::duplicate_field(self.fields[i]));
+                }
+                self.words[i] = map.next_value()?;
+                seen |= 1 << i;
+            }
+            for (i, field) in self.fields.iter().enumerate() {
+                if seen & (1 << i) == 0 {
+                    return Err(de::Error::missing_field(field));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let len = words.len();
+    let human_readable = de.is_human_readable();
+    let visitor = StateVisitor { tag, fields, words, human_readable };
+    if human_readable {
+        if fields.is_empty() {
+            de.deserialize_str(visitor)
+        } else {
+            de.deserialize_any(visitor)
+        }
+    } else {
+        de.deserialize_tuple(len, visitor)
+    }
+}
+
+#[cfg(all(test, feature="std"))]
+mod test {
+    use bincode;
+    use serde_json;
+    use {RngCore, SeedableRng, SplitRng};
+    use prng::{SplitMix64, XorShiftRng, Xoshiro256StarStar};
+
+    #[test]
+    fn test_human_readable() {
+        let mut seed = [0u8; 32];
+        for (i, x) in seed.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        let rng = Xoshiro256StarStar::from_seed(seed);
+        let json = serde_json::to_string(&rng).unwrap();
+        assert_eq!(json, "\"xoshiro256**:v1:\
+                          000102030405060708090a0b0c0d0e0f\
+                          101112131415161718191a1b1c1d1e1f\"");
+
+        let rng = XorShiftRng::from_seed([1, 0, 0, 0, 0, 0, 0, 0,
+                                          0, 0, 0, 0, 0, 0, 0, 0xff]);
+        let json = serde_json::to_string(&rng).unwrap();
+        assert_eq!(json, "\"xorshift128:v1:010000000000000000000000000000ff\"");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut de: Xoshiro256StarStar = serde_json::from_str(&json).unwrap();
+        assert_eq!(rng.next_u64(), de.next_u64());
+        let bin = bincode::serialize(&rng).unwrap();
+        let mut de: Xoshiro256StarStar = bincode::deserialize(&bin).unwrap();
+        assert_eq!(rng.next_u64(), de.next_u64());
+
+        let mut rng = XorShiftRng::from_seed([7; 16]);
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut de: XorShiftRng = serde_json::from_str(&json).unwrap();
+        assert_eq!(rng.next_u64(), de.next_u64());
+        let bin = bincode::serialize(&rng).unwrap();
+        let mut de: XorShiftRng = bincode::deserialize(&bin).unwrap();
+        assert_eq!(rng.next_u64(), de.next_u64());
+
+        // The gamma of a split generator differs from the default one
+        let mut rng = SplitMix64::from_seed([7; 8]).split();
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut de: SplitMix64 = serde_json::from_str(&json).unwrap();
+        assert_eq!(rng.next_u64(), de.next_u64());
+        let bin = bincode::serialize(&rng).unwrap();
+        let mut de: SplitMix64 = bincode::deserialize(&bin).unwrap();
+        assert_eq!(rng.next_u64(), de.next_u64());
+    }
+
+    #[test]
+    fn test_compact() {
+        // Non-human-readable formats store just the state words, in the
+        // same layout as the previously derived implementation.
+        let rng = XorShiftRng::from_seed([1, 0, 0, 0, 2, 0, 0, 0,
+                                          3, 0, 0, 0, 4, 0, 0, 0]);
+        let bin = bincode::serialize(&rng).unwrap();
+        assert_eq!(bin, vec![1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_legacy_map() {
+        // Written by the derived implementation of rand 0.4
+        let json = "{\"x\":1,\"y\":0,\"z\":0,\"w\":4278190080}";
+        let mut de: XorShiftRng = serde_json::from_str(json).unwrap();
+        let mut rng = XorShiftRng::from_seed([1, 0, 0, 0, 0, 0, 0, 0,
+                                              0, 0, 0, 0, 0, 0, 0, 0xff]);
+        assert_eq!(rng.next_u64(), de.next_u64());
+
+        // The fields may come in any order
+        let json = "{\"w\":4278190080,\"z\":0,\"x\":1,\"y\":0}";
+        assert!(serde_json::from_str::<XorShiftRng>(json).is_ok());
+
+        for s in [
+            "{\"x\":1,\"y\":0,\"z\":0}",
+            "{\"x\":1,\"y\":0,\"z\":0,\"w\":4278190080,\"x\":1}",
+            "{\"x\":1,\"y\":0,\"z\":0,\"v\":4278190080}",
+            "{\"x\":1,\"y\":0,\"z\":0,\"w\":4294967296}",
+        ].iter() {
+            assert!(serde_json::from_str::<XorShiftRng>(s).is_err(), "{}", s);
+        }
+
+        // Generators without a derived implementation in a release
+        let json = "{\"state\":1,\"gamma\":3}";
+        assert!(serde_json::from_str::<SplitMix64>(json).is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let ok = "\"xorshift128:v1:010000000000000000000000000000ff\"";
+        assert!(serde_json::from_str::<XorShiftRng>(ok).is_ok());
+
+        for s in [
+            "\"xorshift128:v1:010000000000000000000000000000f\"",
+            "\"xorshift128:v1:010000000000000000000000000000fff\"",
+            "\"xorshift128:v1:01000000000000000000000000000xff\"",
+            "\"xorshift128:v1:01000000000000000000000000000\u{e9}f\"",
+            "\"xorshift128:v2:010000000000000000000000000000ff\"",
+            "\"splitmix64:v1:010000000000000000000000000000ff\"",
+            "\"010000000000000000000000000000ff\"",
+            "[1, 0, 0, 0]",
+        ].iter() {
+            assert!(serde_json::from_str::<XorShiftRng>(s).is_err(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The SplitMix64 random number generator.
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use {BatchRng, EncodableRng, SplitRng};
+#[cfg(feature="serde1")] use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="serde1")] use super::serde_state;
+
+/// The default increment, the odd integer closest to 2<sup>64</sup> divided
+/// by the golden ratio.
+const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// The SplitMix64 random number generator.
+///
+/// SplitMix64 adds an odd constant, the *gamma*, to a 64-bit counter and
+/// scrambles the counter to produce each output. It is very fast, has 128
+/// bits of state and a period of 2<sup>64</sup>. It is not suitable for
+/// cryptographic purposes.
+///
+/// This generator implements [`SplitRng`]: [`split`] creates a child
+/// generator with a new counter and a new gamma, both derived from the
+/// output of the parent, following the algorithm of Steele, Lea and Flood,
+/// [*Fast Splittable Pseudorandom Number Generators*](
+/// http://gee.cs.oswego.edu/dl/papers/oopsla14.pdf) (as used by Java's
+/// `SplittableRandom`). Generators split from each other produce streams
+/// which are statistically independent.
+///
+/// When seeded with [`SeedableRng::from_seed`], the gamma is the golden
+/// gamma `0x9e3779b97f4a7c15`, and the output is that of the reference
+/// [SplitMix64](http://xoshiro.di.unimi.it/splitmix64.c) implementation.
+///
+/// [`SplitRng`]: ../trait.SplitRng.html
+/// [`split`]: ../trait.SplitRng.html#tymethod.split
+/// [`SeedableRng::from_seed`]: ../trait.SeedableRng.html#tymethod.from_seed
+#[derive(Clone)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+pub struct SplitMix64 {
+    state: u64,
+    gamma: u64,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for SplitMix64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SplitMix64 {{}}")
+    }
+}
+
+impl SplitMix64 {
+    /// Return the raw state of the generator.
+    ///
+    /// Together with [`from_raw_state`] this allows storing the generator as
+    /// plain memory, for example as part of a snapshot taken with `memcpy`.
+    ///
+    /// [`from_raw_state`]: #method.from_raw_state
+    pub fn raw_state(&self) -> SplitMix64State {
+        SplitMix64State { state: self.state, gamma: self.gamma }
+    }
+
+    /// Create a generator from a raw state, as returned by [`raw_state`].
+    ///
+    /// The state is used as is. The gamma must be odd, otherwise the
+    /// period of the generator is shorter than 2<sup>64</sup>.
+    ///
+    /// [`raw_state`]: #method.raw_state
+    pub fn from_raw_state(state: SplitMix64State) -> SplitMix64 {
+        SplitMix64 { state: state.state, gamma: state.gamma }
+    }
+
+    #[inline]
+    fn next_state(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(self.gamma);
+        self.state
+    }
+}
+
+/// The raw state of a [`SplitMix64`], see [`SplitMix64::raw_state`].
+///
+/// Implements `bytemuck::Pod` with the `bytemuck` feature, see
+/// [Extra features](index.html#extra-features).
+///
+/// [`SplitMix64`]: struct.SplitMix64.html
+/// [`SplitMix64::raw_state`]: struct.SplitMix64.html#method.raw_state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct SplitMix64State {
+    /// The counter.
+    pub state: u64,
+    /// The increment of the counter; always odd.
+    pub gamma: u64,
+}
+
+#[cfg(feature="bytemuck")]
+unsafe impl ::bytemuck::Zeroable for SplitMix64State {}
+#[cfg(feature="bytemuck")]
+unsafe impl ::bytemuck::Pod for SplitMix64State {}
+
+/// The finalizer of SplitMix64, Stafford's "Mix13" variant of the MurmurHash3
+/// finalizer.
+#[inline]
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Derive an odd gamma from `z`. Gammas with too few bit transitions produce
+/// poor output, so those are adjusted.
+fn mix_gamma(mut z: u64) -> u64 {
+    z = (z ^ (z >> 33)).wrapping_mul(0xff51afd7ed558ccd);
+    z = (z ^ (z >> 33)).wrapping_mul(0xc4ceb9fe1a85ec53);
+    z = (z ^ (z >> 33)) | 1;
+    if (z ^ (z >> 1)).count_ones() < 24 {
+        z ^ 0xaaaaaaaaaaaaaaaa
+    } else {
+        z
+    }
+}
+
+impl RngCore for SplitMix64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        mix64(self.next_state())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl BatchRng for SplitMix64 {}
+
+/// The state is encoded as the counter followed by the gamma, each as a
+/// little-endian `u64`, 16 bytes in total. An even gamma is invalid.
+impl EncodableRng for SplitMix64 {
+    const ENCODED_LEN: usize = 16;
+
+    fn encode_state(&self, dest: &mut [u8]) -> usize {
+        le::write_u64_into(&[self.state, self.gamma], &mut dest[..16]);
+        16
+    }
+
+    fn decode_state(src: &[u8]) -> Option<SplitMix64> {
+        if src.len() < 16 {
+            return None;
+        }
+        let mut s = [0u64; 2];
+        le::read_u64_into(&src[..16], &mut s);
+        if s[1] & 1 == 0 {
+            return None;
+        }
+        Some(SplitMix64 { state: s[0], gamma: s[1] })
+    }
+}
+
+impl SplitRng for SplitMix64 {
+    fn split(&mut self) -> SplitMix64 {
+        let state = self.next_u64();
+        let gamma = mix_gamma(self.next_state());
+        SplitMix64 { state, gamma }
+    }
+}
+
+// Serialized as a versioned hex string in human-readable formats, see the
+// `serde_state` module.
+#[cfg(feature="serde1")]
+impl Serialize for SplitMix64 {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serde_state::serialize("splitmix64", &[self.state, self.gamma], ser)
+    }
+}
+
+#[cfg(feature="serde1")]
+impl<'de> Deserialize<'de> for SplitMix64 {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let mut s = [0u64; 2];
+        serde_state::deserialize("splitmix64", &[], &mut s, de)?;
+        Ok(SplitMix64 { state: s[0], gamma: s[1] })
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut state = [0u64; 1];
+        le::read_u64_into(&seed, &mut state);
+        SplitMix64 { state: state[0], gamma: GOLDEN_GAMMA }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {RngCore, SeedableRng, SplitRng, EncodableRng};
+    use super::SplitMix64;
+
+    fn seed_1234567() -> [u8; 8] {
+        let mut seed = [0u8; 8];
+        for (i, b) in seed.iter_mut().enumerate() {
+            *b = (1234567u64 >> (8 * i)) as u8;
+        }
+        seed
+    }
+
+    #[test]
+    fn test_splitmix64_true_values() {
+        // Reference values from the C implementation with state 1234567
+        let mut rng = SplitMix64::from_seed(seed_1234567());
+        let expected = [6457827717110365317, 3203168211198807973,
+                        9817491932198370423, 4593380528125082431,
+                        16408922859458223821];
+        for &e in expected.iter() {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+
+    #[test]
+    fn test_splitmix64_split() {
+        // Values computed with the algorithm of Java's `SplittableRandom`
+        let mut rng = SplitMix64::from_seed(seed_1234567());
+        let mut child = rng.split();
+        assert_eq!(child.state, 0x599ed017fb08fc85);
+        assert_eq!(child.gamma, 0x50c05f0a6c6fe25b);
+        assert_eq!(child.next_u64(), 871413907629865662);
+        assert_eq!(child.next_u64(), 5225178045395358119);
+        // Splitting consumes two steps of the parent
+        assert_eq!(rng.next_u64(), 9817491932198370423);
+
+        // Gammas are always odd
+        let mut rng = ::test::rng(439);
+        for _ in 0..100 {
+            let mut parent = SplitMix64::from_rng(&mut rng).unwrap();
+            assert_eq!(parent.split().gamma & 1, 1);
+        }
+    }
+
+    #[test]
+    fn test_raw_state() {
+        let mut rng1 = SplitMix64::from_seed(seed_1234567()).split();
+        let state = rng1.raw_state();
+        assert_eq!(state.gamma & 1, 1);
+
+        let mut rng2 = SplitMix64::from_raw_state(state);
+        assert_eq!(rng2.raw_state(), state);
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_encode_state() {
+        let mut rng1 = SplitMix64::from_seed(seed_1234567()).split();
+        let mut buf = [0u8; 16];
+        assert_eq!(rng1.encode_state(&mut buf), 16);
+        let mut rng2 = SplitMix64::decode_state(&buf).unwrap();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+
+        // Even gamma
+        buf[8] &= !1;
+        assert!(SplitMix64::decode_state(&buf).is_none());
+    }
+}
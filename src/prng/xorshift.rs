@@ -11,8 +11,11 @@
 //! Xorshift generators
 
 use core::num::Wrapping as w;
-use core::{fmt, slice};
+use core::{fmt, ptr, slice};
 use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use {BatchRng, EncodableRng};
+#[cfg(feature="serde1")] use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="serde1")] use super::serde_state;
 
 /// An Xorshift[1] random number
 /// generator.
@@ -25,11 +28,15 @@ use rand_core::{RngCore, SeedableRng, Error, impls, le};
 /// RNGs"](https://www.jstatsoft.org/v08/i14/paper). *Journal of
 /// Statistical Software*. Vol. 8 (Issue 14).
 #[derive(Clone)]
-#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct XorShiftRng {
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     x: w<u32>,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     y: w<u32>,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     z: w<u32>,
+    #[cfg_attr(feature="rkyv08", rkyv(with = super::rkyv_state::Unwrapped))]
     w: w<u32>,
 }
 
@@ -56,8 +63,52 @@ impl XorShiftRng {
             w: w(0x113ba7bb),
         }
     }
+
+    /// Return the raw state of the generator.
+    ///
+    /// Together with [`from_raw_state`] this allows storing the generator as
+    /// plain memory, for example as part of a snapshot taken with `memcpy`.
+    ///
+    /// [`from_raw_state`]: #method.from_raw_state
+    pub fn raw_state(&self) -> XorShiftRngState {
+        XorShiftRngState { s: [self.x.0, self.y.0, self.z.0, self.w.0] }
+    }
+
+    /// Create a generator from a raw state, as returned by [`raw_state`].
+    ///
+    /// The state is used as is. An all-zero state is invalid: the
+    /// generator would only output zeros.
+    ///
+    /// [`raw_state`]: #method.raw_state
+    pub fn from_raw_state(state: XorShiftRngState) -> XorShiftRng {
+        XorShiftRng {
+            x: w(state.s[0]),
+            y: w(state.s[1]),
+            z: w(state.s[2]),
+            w: w(state.s[3]),
+        }
+    }
 }
 
+/// The raw state of a [`XorShiftRng`], see [`XorShiftRng::raw_state`].
+///
+/// Implements `bytemuck::Pod` with the `bytemuck` feature, see
+/// [Extra features](index.html#extra-features).
+///
+/// [`XorShiftRng`]: struct.XorShiftRng.html
+/// [`XorShiftRng::raw_state`]: struct.XorShiftRng.html#method.raw_state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct XorShiftRngState {
+    /// The state words `x`, `y`, `z` and `w`.
+    pub s: [u32; 4],
+}
+
+#[cfg(feature="bytemuck")]
+unsafe impl ::bytemuck::Zeroable for XorShiftRngState {}
+#[cfg(feature="bytemuck")]
+unsafe impl ::bytemuck::Pod for XorShiftRngState {}
+
 impl RngCore for XorShiftRng {
     #[inline]
     fn next_u32(&mut self) -> u32 {
@@ -76,9 +127,24 @@ impl RngCore for XorShiftRng {
         impls::next_u64_via_u32(self)
     }
 
-    #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
-        impls::fill_bytes_via_next(self, dest)
+        // After four steps the state consists of the last four outputs, in
+        // order, so whole 16-byte chunks can be copied from the state.
+        let mut left = dest;
+        while left.len() >= 16 {
+            let (l, r) = {left}.split_at_mut(16);
+            left = r;
+            for _ in 0..4 {
+                self.next_u32();
+            }
+            let words = [self.x.0.to_le(), self.y.0.to_le(),
+                         self.z.0.to_le(), self.w.0.to_le()];
+            unsafe {
+                ptr::copy_nonoverlapping(words.as_ptr() as *const u8,
+                                         l.as_mut_ptr(), 16);
+            }
+        }
+        impls::fill_bytes_via_next(self, left)
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
@@ -86,6 +152,50 @@ impl RngCore for XorShiftRng {
     }
 }
 
+impl BatchRng for XorShiftRng {}
+
+/// The state is encoded as the words `x`, `y`, `z` and `w`, each as a
+/// little-endian `u32`, 16 bytes in total. An all-zero state is invalid.
+impl EncodableRng for XorShiftRng {
+    const ENCODED_LEN: usize = 16;
+
+    fn encode_state(&self, dest: &mut [u8]) -> usize {
+        let words = [self.x.0, self.y.0, self.z.0, self.w.0];
+        le::write_u32_into(&words, &mut dest[..16]);
+        16
+    }
+
+    fn decode_state(src: &[u8]) -> Option<XorShiftRng> {
+        if src.len() < 16 {
+            return None;
+        }
+        let mut s = [0u32; 4];
+        le::read_u32_into(&src[..16], &mut s);
+        if s.iter().all(|&x| x == 0) {
+            return None;
+        }
+        Some(XorShiftRng { x: w(s[0]), y: w(s[1]), z: w(s[2]), w: w(s[3]) })
+    }
+}
+
+// Serialized as a versioned hex string in human-readable formats, see the
+// `serde_state` module.
+#[cfg(feature="serde1")]
+impl Serialize for XorShiftRng {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serde_state::serialize("xorshift128", &[self.x.0, self.y.0, self.z.0, self.w.0], ser)
+    }
+}
+
+#[cfg(feature="serde1")]
+impl<'de> Deserialize<'de> for XorShiftRng {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let mut s = [0u32; 4];
+        serde_state::deserialize("xorshift128", &["x", "y", "z", "w"], &mut s, de)?;
+        Ok(XorShiftRng { x: w(s[0]), y: w(s[1]), z: w(s[2]), w: w(s[3]) })
+    }
+}
+
 impl SeedableRng for XorShiftRng {
     type Seed = [u8; 16];
 
@@ -131,7 +241,7 @@ impl SeedableRng for XorShiftRng {
 
 #[cfg(test)]
 mod tests {
-    use {RngCore, SeedableRng};
+    use {RngCore, SeedableRng, EncodableRng};
     use super::XorShiftRng;
 
     #[test]
@@ -223,4 +333,58 @@ mod tests {
             assert_eq!(rng.next_u64(), deserialized.next_u64());
         }
     }
+
+    #[test]
+    fn test_xorshift_fill_bytes() {
+        // `fill_bytes` gives the same bytes as the `next_u32` outputs in
+        // little-endian order, also for lengths which are not a multiple of
+        // the chunk size.
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        for &len in [0, 3, 16, 21, 40].iter() {
+            let mut rng1 = XorShiftRng::from_seed(seed);
+            let mut rng2 = XorShiftRng::from_seed(seed);
+            let mut buf = [0u8; 40];
+            rng1.fill_bytes(&mut buf[..len]);
+            for chunk in buf[..len].chunks(4) {
+                let x = rng2.next_u32();
+                for (i, b) in chunk.iter().enumerate() {
+                    assert_eq!(*b, (x >> (8 * i)) as u8);
+                }
+            }
+            assert_eq!(rng1.next_u32(), rng2.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_xorshift_raw_state() {
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng1 = XorShiftRng::from_seed(seed);
+        rng1.next_u32();
+        let state = rng1.raw_state();
+        assert_eq!(state.s, [rng1.x.0, rng1.y.0, rng1.z.0, rng1.w.0]);
+
+        let mut rng2 = XorShiftRng::from_raw_state(state);
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_xorshift_encode_state() {
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng1 = XorShiftRng::from_seed(seed);
+        let mut buf = [0u8; 20];
+        assert_eq!(rng1.encode_state(&mut buf), XorShiftRng::ENCODED_LEN);
+        assert_eq!(buf[..16], seed);
+
+        rng1.next_u32();
+        rng1.encode_state(&mut buf);
+        let mut rng2 = XorShiftRng::decode_state(&buf).unwrap();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+
+        assert!(XorShiftRng::decode_state(&buf[..15]).is_none());
+        assert!(XorShiftRng::decode_state(&[0; 16]).is_none());
+    }
 }
@@ -0,0 +1,621 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Xoshiro generators
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use rand_core::block::{BlockRngCore, BlockRng64};
+use {BatchRng, EncodableRng, Jumpable};
+#[cfg(feature="serde1")] use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature="serde1")] use super::serde_state;
+#[cfg(feature="simd_support")] use core::simd::u64x4;
+
+/// The xoshiro256** random number generator.
+///
+/// Xoshiro256** is a fast 64-bit generator with 256 bits of state, by David
+/// Blackman and Sebastiano Vigna. It passes all statistical tests the authors
+/// are aware of, and has a period of 2<sup>256</sup> - 1. It is not suitable
+/// for cryptographic purposes.
+///
+/// [`jump`] and [`long_jump`] advance the generator by 2<sup>128</sup> and
+/// 2<sup>192</sup> steps, which makes it possible to split one stream into
+/// non-overlapping parts, for example one per thread.
+///
+/// See the [xoshiro website](http://xoshiro.di.unimi.it/) for details.
+///
+/// [`jump`]: #method.jump
+/// [`long_jump`]: #method.long_jump
+#[derive(Clone)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+pub struct Xoshiro256StarStar {
+    s: [u64; 4],
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoshiro256StarStar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoshiro256StarStar {{}}")
+    }
+}
+
+impl Xoshiro256StarStar {
+    /// Advance the generator by 2<sup>128</sup> steps.
+    ///
+    /// This is equivalent to 2<sup>128</sup> calls to `next_u64`, and can be
+    /// used to generate 2<sup>128</sup> non-overlapping subsequences.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::prng::Xoshiro256StarStar;
+    ///
+    /// let rng1 = Xoshiro256StarStar::from_seed([1; 32]);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// ```
+    pub fn jump(&mut self) {
+        self.apply_jump(&[0x180ec6d33cfd0aba, 0xd5a61266f0c9392c,
+                          0xa9582618e03fc9aa, 0x39abdc4529b1661c]);
+    }
+
+    /// Advance the generator by 2<sup>192</sup> steps.
+    ///
+    /// This is equivalent to 2<sup>64</sup> calls to [`jump`], and can be used
+    /// to generate 2<sup>64</sup> starting points, from each of which `jump`
+    /// generates 2<sup>64</sup> non-overlapping subsequences.
+    ///
+    /// [`jump`]: #method.jump
+    pub fn long_jump(&mut self) {
+        self.apply_jump(&[0x76e15d3efefdcbbf, 0xc5004e441c522fb3,
+                          0x77710069854ee241, 0x39109bb02acbe635]);
+    }
+
+    /// Return the raw state of the generator.
+    ///
+    /// Together with [`from_raw_state`] this allows storing the generator as
+    /// plain memory, for example as part of a snapshot taken with `memcpy`.
+    ///
+    /// [`from_raw_state`]: #method.from_raw_state
+    pub fn raw_state(&self) -> Xoshiro256StarStarState {
+        Xoshiro256StarStarState { s: self.s }
+    }
+
+    /// Create a generator from a raw state, as returned by [`raw_state`].
+    ///
+    /// The state is used as is. An all-zero state is invalid: the
+    /// generator would only output zeros.
+    ///
+    /// [`raw_state`]: #method.raw_state
+    pub fn from_raw_state(state: Xoshiro256StarStarState) -> Xoshiro256StarStar {
+        Xoshiro256StarStar { s: state.s }
+    }
+
+    fn apply_jump(&mut self, jump: &[u64; 4]) {
+        let mut s = [0u64; 4];
+        for &j in jump.iter() {
+            for b in 0..64 {
+                if (j >> b) & 1 == 1 {
+                    for (x, y) in s.iter_mut().zip(self.s.iter()) {
+                        *x ^= *y;
+                    }
+                }
+                self.next_u64();
+            }
+        }
+        self.s = s;
+    }
+}
+
+/// The raw state of a [`Xoshiro256StarStar`], see [`Xoshiro256StarStar::raw_state`].
+///
+/// Implements `bytemuck::Pod` with the `bytemuck` feature, see
+/// [Extra features](index.html#extra-features).
+///
+/// [`Xoshiro256StarStar`]: struct.Xoshiro256StarStar.html
+/// [`Xoshiro256StarStar::raw_state`]: struct.Xoshiro256StarStar.html#method.raw_state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct Xoshiro256StarStarState {
+    /// The four state words.
+    pub s: [u64; 4],
+}
+
+#[cfg(feature="bytemuck")]
+unsafe impl ::bytemuck::Zeroable for Xoshiro256StarStarState {}
+#[cfg(feature="bytemuck")]
+unsafe impl ::bytemuck::Pod for Xoshiro256StarStarState {}
+
+impl RngCore for Xoshiro256StarStar {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        // The lowest bits have some linear dependencies, so use the upper bits
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl BatchRng for Xoshiro256StarStar {}
+
+/// The state is encoded as the four state words, each as a little-endian
+/// `u64`, 32 bytes in total. An all-zero state is invalid.
+impl EncodableRng for Xoshiro256StarStar {
+    const ENCODED_LEN: usize = 32;
+
+    fn encode_state(&self, dest: &mut [u8]) -> usize {
+        le::write_u64_into(&self.s, &mut dest[..32]);
+        32
+    }
+
+    fn decode_state(src: &[u8]) -> Option<Xoshiro256StarStar> {
+        if src.len() < 32 {
+            return None;
+        }
+        let mut s = [0u64; 4];
+        le::read_u64_into(&src[..32], &mut s);
+        if s.iter().all(|&x| x == 0) {
+            return None;
+        }
+        Some(Xoshiro256StarStar { s })
+    }
+}
+
+impl Jumpable for Xoshiro256StarStar {
+    fn jump(&mut self) {
+        Xoshiro256StarStar::jump(self)
+    }
+}
+
+// Serialized as a versioned hex string in human-readable formats, see the
+// `serde_state` module.
+#[cfg(feature="serde1")]
+impl Serialize for Xoshiro256StarStar {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serde_state::serialize("xoshiro256**", &self.s, ser)
+    }
+}
+
+#[cfg(feature="serde1")]
+impl<'de> Deserialize<'de> for Xoshiro256StarStar {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let mut s = [0u64; 4];
+        serde_state::deserialize("xoshiro256**", &[], &mut s, de)?;
+        Ok(Xoshiro256StarStar { s })
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStar {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut s = [0u64; 4];
+        le::read_u64_into(&seed, &mut s);
+
+        // Xoshiro cannot be seeded with 0 and we cannot return an Error, but
+        // also do not wish to panic (because a random seed can legitimately be
+        // 0); our only option is therefore to use a preset value. These are
+        // the first outputs of SplitMix64 seeded with 0.
+        if s.iter().all(|&x| x == 0) {
+            s = [0xe220a8397b1dcdaf, 0x6e789e6aa1b965f4,
+                 0x06c45d188009454f, 0xf88bb8a8724c81ec];
+        }
+
+        Xoshiro256StarStar { s }
+    }
+
+    fn from_rng<R: RngCore>(mut rng: R) -> Result<Self, Error> {
+        let mut seed = [0u8; 32];
+        let mut s = [0u64; 4];
+        loop {
+            rng.try_fill_bytes(&mut seed)?;
+            le::read_u64_into(&seed, &mut s);
+            if !s.iter().all(|&x| x == 0) { break; }
+        }
+        Ok(Xoshiro256StarStar { s })
+    }
+}
+
+/// Four interleaved xoshiro256** generators, which are advanced together.
+///
+/// The four generators (lanes) are independent, so a step of all lanes can be
+/// done with vector instructions. With AVX2 this generator is roughly 1.5
+/// times as fast as [`Xoshiro256StarStar`] for filling buffers and generating
+/// `u64` values; without it, they are about equally fast.
+///
+/// The output is a sequence of blocks of 32 `u64` values. Within a block, the
+/// lanes take turns: value `i` of a block is output `i / 4` of lane `i % 4`.
+/// Lane 0 is the [`Xoshiro256StarStar`] with the same seed, and each
+/// following lane is the previous one advanced with [`jump`], so the lanes do
+/// not overlap.
+///
+/// With the `simd_dispatch` feature (which requires Rustc 1.27) and `std`,
+/// AVX2 is used if the processor supports it, detected at runtime. Otherwise
+/// the lanes use the vector instructions the crate is compiled for, such as
+/// SSE2 on x86-64.
+///
+/// [`Xoshiro256StarStar`]: struct.Xoshiro256StarStar.html
+/// [`jump`]: struct.Xoshiro256StarStar.html#method.jump
+#[derive(Clone, Debug)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+pub struct Xoshiro256StarStarX4(BlockRng64<Xoshiro256StarStarX4Core>);
+
+impl RngCore for Xoshiro256StarStarX4 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStarX4 {
+    type Seed = <Xoshiro256StarStarX4Core as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Xoshiro256StarStarX4(BlockRng64::<Xoshiro256StarStarX4Core>::from_seed(seed))
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        BlockRng64::<Xoshiro256StarStarX4Core>::from_rng(rng).map(Xoshiro256StarStarX4)
+    }
+}
+
+impl BatchRng for Xoshiro256StarStarX4 {}
+
+impl From<Xoshiro256StarStarX4Core> for Xoshiro256StarStarX4 {
+    fn from(core: Xoshiro256StarStarX4Core) -> Self {
+        Xoshiro256StarStarX4(BlockRng64::new(core))
+    }
+}
+
+const LANES: usize = 4;
+const X4_BLOCK_LEN: usize = 32;
+
+/// The core of `Xoshiro256StarStarX4`, used with `BlockRng64`.
+#[derive(Clone)]
+#[cfg_attr(feature="rkyv08", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+pub struct Xoshiro256StarStarX4Core {
+    // Word `i` of the state of each lane
+    s: [[u64; LANES]; 4],
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoshiro256StarStarX4Core {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoshiro256StarStarX4Core {{}}")
+    }
+}
+
+impl Xoshiro256StarStarX4Core {
+    fn from_lane0(mut rng: Xoshiro256StarStar) -> Self {
+        let mut s = [[0u64; LANES]; 4];
+        for lane in 0..LANES {
+            for (word, x) in s.iter_mut().zip(rng.s.iter()) {
+                word[lane] = *x;
+            }
+            rng.jump();
+        }
+        Xoshiro256StarStarX4Core { s }
+    }
+}
+
+// Inlined into both the plain and the AVX2 version of `generate`, so that
+// the compiler can vectorize each with the available instructions.
+#[inline(always)]
+fn generate_x4(s: &mut [[u64; LANES]; 4], results: &mut [u64; X4_BLOCK_LEN]) {
+    for step in 0..(X4_BLOCK_LEN / LANES) {
+        for lane in 0..LANES {
+            results[step * LANES + lane] =
+                s[1][lane].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        }
+        for lane in 0..LANES {
+            let t = s[1][lane] << 17;
+            s[2][lane] ^= s[0][lane];
+            s[3][lane] ^= s[1][lane];
+            s[1][lane] ^= s[2][lane];
+            s[0][lane] ^= s[3][lane];
+            s[2][lane] ^= t;
+            s[3][lane] = s[3][lane].rotate_left(45);
+        }
+    }
+}
+
+#[cfg(all(feature="simd_dispatch", feature="std",
+          any(target_arch="x86", target_arch="x86_64")))]
+#[target_feature(enable = "avx2")]
+unsafe fn generate_x4_avx2(s: &mut [[u64; LANES]; 4],
+                           results: &mut [u64; X4_BLOCK_LEN]) {
+    generate_x4(s, results)
+}
+
+impl BlockRngCore for Xoshiro256StarStarX4Core {
+    type Item = u64;
+    type Results = [u64; X4_BLOCK_LEN];
+
+    fn generate(&mut self, results: &mut Self::Results) {
+        #[cfg(all(feature="simd_dispatch", feature="std",
+                  any(target_arch="x86", target_arch="x86_64")))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return unsafe { generate_x4_avx2(&mut self.s, results) };
+            }
+        }
+        generate_x4(&mut self.s, results)
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStarX4Core {
+    type Seed = [u8; 32];
+
+    /// Create a new generator, with lane 0 seeded like
+    /// `Xoshiro256StarStar::from_seed(seed)`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Xoshiro256StarStarX4Core::from_lane0(Xoshiro256StarStar::from_seed(seed))
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        Xoshiro256StarStar::from_rng(rng).map(Xoshiro256StarStarX4Core::from_lane0)
+    }
+}
+
+/// Four xoshiro256** generators with their state in `core::simd` vectors.
+///
+/// This is the same generator as [`Xoshiro256StarStarX4`], with the state
+/// of the four lanes held in `u64x4` vectors, so that each step is explicit
+/// vector code instead of relying on auto-vectorization. [`next_u64x4`]
+/// returns the next output of all lanes as a vector, which is useful for
+/// sampling vector types.
+///
+/// As a [`BlockRngCore`], it produces the same blocks as
+/// [`Xoshiro256StarStarX4`]; use `BlockRng64<Xoshiro256StarStarSimd>` to get
+/// an implementation of `RngCore`.
+///
+/// This type is only available with the `simd_support` feature, which
+/// requires nightly Rust.
+///
+/// [`Xoshiro256StarStarX4`]: struct.Xoshiro256StarStarX4.html
+/// [`next_u64x4`]: #method.next_u64x4
+/// [`BlockRngCore`]: ../../rand_core/block/trait.BlockRngCore.html
+#[cfg(feature="simd_support")]
+#[derive(Clone)]
+pub struct Xoshiro256StarStarSimd {
+    // Word `i` of the state of each lane
+    s: [u64x4; 4],
+}
+
+// Custom Debug implementation that does not expose the internal state
+#[cfg(feature="simd_support")]
+impl fmt::Debug for Xoshiro256StarStarSimd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoshiro256StarStarSimd {{}}")
+    }
+}
+
+#[cfg(feature="simd_support")]
+impl Xoshiro256StarStarSimd {
+    /// Return the next output of each of the four lanes.
+    #[inline]
+    pub fn next_u64x4(&mut self) -> u64x4 {
+        let s = &mut self.s;
+        let x = s[1] * u64x4::splat(5);
+        let result = ((x << 7) | (x >> 57)) * u64x4::splat(9);
+
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = (s[3] << 45) | (s[3] >> 19);
+
+        result
+    }
+}
+
+#[cfg(feature="simd_support")]
+impl BlockRngCore for Xoshiro256StarStarSimd {
+    type Item = u64;
+    type Results = [u64; X4_BLOCK_LEN];
+
+    fn generate(&mut self, results: &mut Self::Results) {
+        for chunk in results.chunks_mut(LANES) {
+            self.next_u64x4().copy_to_slice(chunk);
+        }
+    }
+}
+
+#[cfg(feature="simd_support")]
+impl SeedableRng for Xoshiro256StarStarSimd {
+    type Seed = [u8; 32];
+
+    /// Create a new generator, with lane 0 seeded like
+    /// `Xoshiro256StarStar::from_seed(seed)`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Xoshiro256StarStarX4Core::from_seed(seed).into()
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        Xoshiro256StarStarX4Core::from_rng(rng).map(Xoshiro256StarStarSimd::from)
+    }
+}
+
+#[cfg(feature="simd_support")]
+impl From<Xoshiro256StarStarX4Core> for Xoshiro256StarStarSimd {
+    fn from(core: Xoshiro256StarStarX4Core) -> Self {
+        let mut s = [u64x4::splat(0); 4];
+        for (v, word) in s.iter_mut().zip(core.s.iter()) {
+            *v = u64x4::from_array(*word);
+        }
+        Xoshiro256StarStarSimd { s }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {RngCore, SeedableRng, EncodableRng};
+    use super::{Xoshiro256StarStar, Xoshiro256StarStarX4};
+
+    fn seed_1234() -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        for i in 0..4 {
+            seed[8 * i] = i as u8 + 1;
+        }
+        seed
+    }
+
+    #[test]
+    fn test_xoshiro256starstar_zero_seed() {
+        // Xoshiro does not work with an all zero seed.
+        // Assert it does not panic.
+        let mut rng = Xoshiro256StarStar::from_seed([0; 32]);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        assert!(a != 0);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn test_xoshiro256starstar_clone() {
+        let mut rng1 = Xoshiro256StarStar::from_seed(seed_1234());
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_xoshiro256starstar_x4() {
+        let mut rng = Xoshiro256StarStarX4::from_seed(seed_1234());
+        let mut lanes = [Xoshiro256StarStar::from_seed(seed_1234()),
+                         Xoshiro256StarStar::from_seed(seed_1234()),
+                         Xoshiro256StarStar::from_seed(seed_1234()),
+                         Xoshiro256StarStar::from_seed(seed_1234())];
+        for (i, lane) in lanes.iter_mut().enumerate() {
+            for _ in 0..i {
+                lane.jump();
+            }
+        }
+        // Three blocks
+        for i in 0..96 {
+            assert_eq!(rng.next_u64(), lanes[i % 4].next_u64());
+        }
+
+        // Bytes are taken from the values in little-endian order
+        let mut buf = [0u8; 8];
+        rng.fill_bytes(&mut buf);
+        let x = lanes[0].next_u64();
+        for (i, b) in buf.iter().enumerate() {
+            assert_eq!(*b, (x >> (8 * i)) as u8);
+        }
+    }
+
+    #[cfg(feature="simd_support")]
+    #[test]
+    fn test_xoshiro256starstar_simd() {
+        use rand_core::block::BlockRng64;
+        use super::Xoshiro256StarStarSimd;
+
+        let mut rng = BlockRng64::<Xoshiro256StarStarSimd>::from_seed(seed_1234());
+        let mut x4 = Xoshiro256StarStarX4::from_seed(seed_1234());
+        for _ in 0..96 {
+            assert_eq!(rng.next_u64(), x4.next_u64());
+        }
+
+        let mut rng = Xoshiro256StarStarSimd::from_seed(seed_1234());
+        let mut lane0 = Xoshiro256StarStar::from_seed(seed_1234());
+        for _ in 0..10 {
+            assert_eq!(rng.next_u64x4()[0], lane0.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_raw_state() {
+        let mut rng1 = Xoshiro256StarStar::from_seed(seed_1234());
+        rng1.next_u64();
+        let mut rng2 = Xoshiro256StarStar::from_raw_state(rng1.raw_state());
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="bytemuck")]
+    #[test]
+    fn test_raw_state_bytemuck() {
+        use bytemuck;
+        use super::Xoshiro256StarStarState;
+
+        let mut rng1 = Xoshiro256StarStar::from_seed(seed_1234());
+        let states = [rng1.raw_state(); 3];
+        let bytes: &[u8] = bytemuck::cast_slice(&states[..]);
+        assert_eq!(bytes.len(), 3 * 32);
+
+        let state: Xoshiro256StarStarState =
+            bytemuck::pod_read_unaligned(&bytes[64..96]);
+        let mut rng2 = Xoshiro256StarStar::from_raw_state(state);
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_encode_state() {
+        let mut rng1 = Xoshiro256StarStar::from_seed(seed_1234());
+        let mut buf = [0u8; 32];
+        assert_eq!(rng1.encode_state(&mut buf), 32);
+        assert_eq!(buf, seed_1234());
+
+        rng1.next_u64();
+        rng1.encode_state(&mut buf);
+        let mut rng2 = Xoshiro256StarStar::decode_state(&buf).unwrap();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+
+        assert!(Xoshiro256StarStar::decode_state(&[0; 32]).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper around an RNG which counts the generated data.
+
+use rand_core::{RngCore, CryptoRng, Error};
+
+/// A wrapper around any RNG which counts how much random data was drawn from
+/// it.
+///
+/// Consumption is counted in 32-bit words: `next_u32` consumes one word,
+/// `next_u64` two words, and filling `n` bytes `(n + 3) / 4` words. This
+/// matches how most generators produce their output, so the count can be
+/// used to verify how far a generator has advanced, for example that a worker
+/// stays within the part of the stream assigned to it. The exact number of
+/// bytes is available through [`bytes_consumed`].
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::mock::StepRng;
+/// use rand::rngs::adapter::CountingRng;
+///
+/// let mut rng = CountingRng::new(StepRng::new(0, 1));
+/// let _: u64 = rng.gen();
+/// let _: [u8; 5] = rng.gen();
+/// assert_eq!(rng.words_consumed(), 2 + 5);
+/// ```
+///
+/// [`bytes_consumed`]: #method.bytes_consumed
+#[derive(Debug, Clone)]
+pub struct CountingRng<R> {
+    rng: R,
+    words: u64,
+    bytes: u64,
+}
+
+impl<R: RngCore> CountingRng<R> {
+    /// Create a new `CountingRng` wrapping `rng`, with zero counts.
+    pub fn new(rng: R) -> CountingRng<R> {
+        CountingRng { rng, words: 0, bytes: 0 }
+    }
+
+    /// Returns the number of 32-bit words drawn from the RNG.
+    pub fn words_consumed(&self) -> u64 {
+        self.words
+    }
+
+    /// Returns the number of bytes drawn from the RNG.
+    pub fn bytes_consumed(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Reset the counts to zero.
+    pub fn reset(&mut self) {
+        self.words = 0;
+        self.bytes = 0;
+    }
+
+    /// Consume the `CountingRng`, returning the wrapped RNG.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+
+    fn count_bytes(&mut self, len: usize) {
+        self.words += (len as u64 + 3) >> 2;
+        self.bytes += len as u64;
+    }
+}
+
+impl<R: RngCore> RngCore for CountingRng<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.words += 1;
+        self.bytes += 4;
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.words += 2;
+        self.bytes += 8;
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.count_bytes(dest.len());
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)?;
+        self.count_bytes(dest.len());
+        Ok(())
+    }
+}
+
+impl<R: RngCore + CryptoRng> CryptoRng for CountingRng<R> {}
+
+#[cfg(test)]
+mod test {
+    use {Rng, RngCore};
+    use rngs::mock::StepRng;
+    use super::CountingRng;
+
+    #[test]
+    fn test_counting() {
+        let mut rng = CountingRng::new(StepRng::new(0, 1));
+        assert_eq!(rng.words_consumed(), 0);
+        assert_eq!(rng.next_u32(), 0);
+        assert_eq!(rng.next_u64(), 1);
+        assert_eq!((rng.words_consumed(), rng.bytes_consumed()), (3, 12));
+
+        let mut buf = [0u8; 9];
+        rng.fill_bytes(&mut buf);
+        rng.try_fill_bytes(&mut buf[..1]).unwrap();
+        assert_eq!((rng.words_consumed(), rng.bytes_consumed()), (7, 22));
+
+        rng.reset();
+        let _: f64 = rng.gen();
+        assert_eq!(rng.words_consumed(), 2);
+        assert_eq!(rng.into_inner().next_u64(), 6);
+    }
+}
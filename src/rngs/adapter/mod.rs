@@ -10,8 +10,16 @@
 
 //! Wrappers / adapters forming RNGs
 
+mod count;
 #[cfg(feature="std")] #[doc(hidden)] pub mod read;
+#[cfg(feature="std")] mod record;
+#[cfg(feature="alloc")] mod replay;
 mod reseeding;
+#[cfg(all(feature="arbitrary", feature="std"))] mod unstructured;
 
+pub use self::count::CountingRng;
 #[cfg(feature="std")] pub use self::read::ReadRng;
+#[cfg(feature="std")] pub use self::record::RecordingRng;
+#[cfg(feature="alloc")] pub use self::replay::ReplayRng;
 pub use self::reseeding::ReseedingRng;
+#[cfg(all(feature="arbitrary", feature="std"))] pub use self::unstructured::ArbitraryRng;
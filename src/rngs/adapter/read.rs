@@ -33,16 +33,17 @@ use rand_core::{RngCore, Error, ErrorKind, impls};
 /// # Example
 ///
 /// ```
-/// use rand::{read, Rng};
+/// use rand::Rng;
+/// use rand::rngs::adapter::ReadRng;
 ///
 /// let data = vec![1, 2, 3, 4, 5, 6, 7, 8];
-/// let mut rng = read::ReadRng::new(&data[..]);
+/// let mut rng = ReadRng::new(&data[..]);
 /// println!("{:x}", rng.gen::<u32>());
 /// ```
 ///
 /// [`OsRng`]: ../struct.OsRng.html
 /// [`RngCore`]: ../../trait.RngCore.html
-/// [`try_fill_bytes`]: ../../trait.RngCore.html#method.tymethod.try_fill_bytes
+/// [`try_fill_bytes`]: ../../trait.RngCore.html#tymethod.try_fill_bytes
 #[derive(Debug)]
 pub struct ReadRng<R> {
     reader: R
@@ -55,6 +56,11 @@ impl<R: Read> ReadRng<R> {
             reader: r
         }
     }
+
+    /// Consume the `ReadRng`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
 }
 
 impl<R: Read> RngCore for ReadRng<R> {
@@ -134,4 +140,12 @@ mod test {
 
         assert!(rng.try_fill_bytes(&mut w).err().unwrap().kind == ErrorKind::Unavailable);
     }
+
+    #[test]
+    fn test_reader_rng_into_inner() {
+        let v = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let mut rng = ReadRng::new(&v[..]);
+        rng.next_u32();
+        assert_eq!(rng.into_inner(), &[5u8, 6, 7, 8][..]);
+    }
 }
@@ -0,0 +1,156 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A wrapper around an RNG which records all generated data.
+
+use std::io::Write;
+
+use rand_core::{RngCore, Error, ErrorKind, le};
+
+/// A wrapper around any RNG which writes all generated random data to a sink.
+///
+/// Every value produced by the wrapped RNG is written to the sink as bytes:
+/// `next_u32` and `next_u64` write the value in little-endian order, and
+/// `fill_bytes` and `try_fill_bytes` write the generated bytes. Playing the
+/// recording back with [`ReplayRng`] or [`ReadRng`] reproduces exactly the
+/// same values, provided the same sequence of methods is called.
+///
+/// This makes it possible to reproduce a failing run of a randomized test
+/// from a recording, independent of the RNG algorithm or its seed.
+///
+/// # Panics
+///
+/// Errors writing to the sink are only reported through [`try_fill_bytes`].
+/// The other [`RngCore`] methods will panic in case of an error.
+///
+/// # Example
+///
+/// ```
+/// use rand::{Rng, FromEntropy};
+/// use rand::rngs::SmallRng;
+/// use rand::rngs::adapter::{ReadRng, RecordingRng};
+///
+/// let mut rng = RecordingRng::new(SmallRng::from_entropy(), Vec::new());
+/// let x: u32 = rng.gen();
+/// let (_, recording) = rng.into_parts();
+///
+/// let mut replay = ReadRng::new(&recording[..]);
+/// assert_eq!(replay.gen::<u32>(), x);
+/// ```
+///
+/// [`ReadRng`]: struct.ReadRng.html
+/// [`ReplayRng`]: struct.ReplayRng.html
+/// [`RngCore`]: ../../trait.RngCore.html
+/// [`try_fill_bytes`]: ../../trait.RngCore.html#tymethod.try_fill_bytes
+#[derive(Debug)]
+pub struct RecordingRng<R, W> {
+    rng: R,
+    sink: W,
+}
+
+impl<R: RngCore, W: Write> RecordingRng<R, W> {
+    /// Create a new `RecordingRng`, writing all data generated by `rng` to
+    /// `sink`.
+    pub fn new(rng: R, sink: W) -> RecordingRng<R, W> {
+        RecordingRng { rng, sink }
+    }
+
+    /// Returns a reference to the sink.
+    pub fn sink(&self) -> &W {
+        &self.sink
+    }
+
+    /// Consume the `RecordingRng`, returning the wrapped RNG and the sink.
+    pub fn into_parts(self) -> (R, W) {
+        (self.rng, self.sink)
+    }
+
+    fn record(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.sink.write_all(bytes).map_err(|err|
+            Error::with_cause(ErrorKind::Unavailable,
+                              "error writing recording to sink", err))
+    }
+}
+
+impl<R: RngCore, W: Write> RngCore for RecordingRng<R, W> {
+    fn next_u32(&mut self) -> u32 {
+        let value = self.rng.next_u32();
+        let mut bytes = [0u8; 4];
+        le::write_u32_into(&[value], &mut bytes);
+        self.record(&bytes).unwrap_or_else(|err|
+                panic!("recording random data failed; error: {}", err));
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.rng.next_u64();
+        let mut bytes = [0u8; 8];
+        le::write_u64_into(&[value], &mut bytes);
+        self.record(&bytes).unwrap_or_else(|err|
+                panic!("recording random data failed; error: {}", err));
+        value
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+                panic!("recording random data failed; error: {}", err));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)?;
+        self.record(dest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Write};
+
+    use {Rng, RngCore, ErrorKind};
+    use rngs::adapter::ReadRng;
+    use rngs::mock::StepRng;
+    use super::RecordingRng;
+
+    #[test]
+    fn test_recording() {
+        let mut rng = RecordingRng::new(::test::rng(437), Vec::new());
+        let a = rng.next_u32();
+        let b = rng.next_u64();
+        let mut c = [0u8; 13];
+        rng.fill_bytes(&mut c);
+        let d: f64 = rng.gen();
+        assert_eq!(rng.sink().len(), 4 + 8 + 13 + 8);
+
+        let (_, recording) = rng.into_parts();
+        let mut replay = ReadRng::new(&recording[..]);
+        assert_eq!(replay.next_u32(), a);
+        assert_eq!(replay.next_u64(), b);
+        let mut c2 = [0u8; 13];
+        replay.fill_bytes(&mut c2);
+        assert_eq!(c, c2);
+        assert_eq!(replay.gen::<f64>(), d);
+    }
+
+    #[test]
+    fn test_recording_sink_error() {
+        struct FailingSink;
+        impl Write for FailingSink {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "sink full"))
+            }
+            fn flush(&mut self) -> io::Result<()> { Ok(()) }
+        }
+
+        let mut rng = RecordingRng::new(StepRng::new(0, 1), FailingSink);
+        let mut buf = [0u8; 8];
+        assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind,
+                   ErrorKind::Unavailable);
+    }
+}
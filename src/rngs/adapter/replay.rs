@@ -0,0 +1,172 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An RNG which plays back recorded random data.
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::Vec;
+
+use rand_core::{RngCore, Error, ErrorKind, impls};
+
+/// An RNG which plays back a recorded sequence of random data, for example
+/// the output of [`RecordingRng`].
+///
+/// `next_u32` and `next_u64` read the next four or eight bytes of the
+/// recording as a little-endian value, and `fill_bytes` and `try_fill_bytes`
+/// copy the next bytes. When the same sequence of methods is called as during
+/// recording, `ReplayRng` returns exactly the same values.
+///
+/// Unlike [`ReadRng`], `ReplayRng` owns the recording, and does not need
+/// `std`.
+///
+/// # Panics
+///
+/// When the recording is exhausted, only [`try_fill_bytes`] is able to report
+/// the error. The other [`RngCore`] methods will panic.
+///
+/// # Example
+///
+/// ```
+/// use rand::RngCore;
+/// use rand::rngs::adapter::ReplayRng;
+///
+/// let mut rng = ReplayRng::from_u32s(&[7, 8]);
+/// assert_eq!(rng.next_u32(), 7);
+/// assert_eq!(rng.next_u32(), 8);
+/// assert_eq!(rng.remaining(), 0);
+///
+/// let mut buf = [0u8; 1];
+/// assert!(rng.try_fill_bytes(&mut buf).is_err());
+/// ```
+///
+/// [`RecordingRng`]: struct.RecordingRng.html
+/// [`ReadRng`]: struct.ReadRng.html
+/// [`RngCore`]: ../../trait.RngCore.html
+/// [`try_fill_bytes`]: ../../trait.RngCore.html#tymethod.try_fill_bytes
+#[derive(Debug, Clone)]
+pub struct ReplayRng {
+    recording: Vec<u8>,
+    pos: usize,
+}
+
+impl ReplayRng {
+    /// Create a new `ReplayRng` playing back `recording`.
+    pub fn new(recording: Vec<u8>) -> ReplayRng {
+        ReplayRng { recording, pos: 0 }
+    }
+
+    /// Create a new `ReplayRng` playing back a sequence of `u32` values, as
+    /// returned by `next_u32`.
+    pub fn from_u32s(values: &[u32]) -> ReplayRng {
+        let mut recording = Vec::with_capacity(values.len() * 4);
+        for &value in values.iter() {
+            for i in 0..4 {
+                recording.push((value >> (8 * i)) as u8);
+            }
+        }
+        ReplayRng::new(recording)
+    }
+
+    /// Create a new `ReplayRng` playing back a sequence of `u64` values, as
+    /// returned by `next_u64`.
+    pub fn from_u64s(values: &[u64]) -> ReplayRng {
+        let mut recording = Vec::with_capacity(values.len() * 8);
+        for &value in values.iter() {
+            for i in 0..8 {
+                recording.push((value >> (8 * i)) as u8);
+            }
+        }
+        ReplayRng::new(recording)
+    }
+
+    /// Returns the number of bytes of the recording not played back yet.
+    pub fn remaining(&self) -> usize {
+        self.recording.len() - self.pos
+    }
+}
+
+impl RngCore for ReplayRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+                panic!("playing back random data failed; error: {}", err));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        if dest.len() > self.remaining() {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "not enough bytes available, reached end of recording"));
+        }
+        let end = self.pos + dest.len();
+        dest.copy_from_slice(&self.recording[self.pos..end]);
+        self.pos = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, ErrorKind};
+    use super::ReplayRng;
+
+    #[test]
+    fn test_replay() {
+        let mut rng = ReplayRng::from_u64s(&[1, 0x0102030405060708]);
+        assert_eq!(rng.remaining(), 16);
+        assert_eq!(rng.next_u32(), 1);
+        assert_eq!(rng.next_u32(), 0);
+        let mut buf = [0u8; 8];
+        rng.fill_bytes(&mut buf);
+        assert_eq!(buf, [8, 7, 6, 5, 4, 3, 2, 1]);
+
+        let mut rng = ReplayRng::from_u32s(&[0xdeadbeef, 0x01234567]);
+        assert_eq!(rng.next_u64(), 0x01234567deadbeef);
+    }
+
+    #[test]
+    fn test_replay_exhausted() {
+        let mut rng = ReplayRng::new(vec![1, 2, 3]);
+        let mut buf = [0u8; 4];
+        assert_eq!(rng.try_fill_bytes(&mut buf).unwrap_err().kind,
+                   ErrorKind::Unavailable);
+        // Nothing was consumed
+        assert_eq!(rng.remaining(), 3);
+        rng.fill_bytes(&mut buf[..3]);
+        assert_eq!(buf, [1, 2, 3, 0]);
+        assert_eq!(rng.remaining(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_replay_exhausted_panic() {
+        ReplayRng::new(vec![1, 2, 3]).next_u32();
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_replay_recording() {
+        use Rng;
+        use rngs::adapter::RecordingRng;
+        let mut rng = RecordingRng::new(::test::rng(438), Vec::new());
+        let values: Vec<f64> = (0..10).map(|_| rng.gen()).collect();
+        let (_, recording) = rng.into_parts();
+        let mut replay = ReplayRng::new(recording);
+        for &v in values.iter() {
+            assert_eq!(replay.gen::<f64>(), v);
+        }
+        assert_eq!(replay.remaining(), 0);
+    }
+}
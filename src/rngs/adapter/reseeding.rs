@@ -12,6 +12,7 @@
 //! generates a certain number of random bytes.
 
 use core::mem::size_of;
+#[cfg(feature="std")] use std::time::{Duration, Instant};
 
 use rand_core::{RngCore, CryptoRng, SeedableRng, Error, ErrorKind};
 use rand_core::block::{BlockRngCore, BlockRng};
@@ -19,6 +20,11 @@ use rand_core::block::{BlockRngCore, BlockRng};
 /// A wrapper around any PRNG which reseeds the underlying PRNG after it has
 /// generated a certain number of random bytes.
 ///
+/// With the `std` feature, [`set_interval`] additionally reseeds the PRNG
+/// after a certain time has passed, to enforce policies like "reseed at
+/// least every 5 minutes". The time is checked whenever the PRNG generates a
+/// new block of values, so an RNG that is not used is not reseeded.
+///
 /// When the RNG gets cloned, the clone is reseeded on first use.
 ///
 /// Reseeding is never strictly *necessary*. Cryptographic PRNGs don't have a
@@ -54,6 +60,36 @@ use rand_core::block::{BlockRngCore, BlockRng};
 /// through some combination of retrying and delaying reseeding until later.
 /// If handling the source error fails `ReseedingRng` will continue generating
 /// data from the wrapped PRNG without reseeding.
+///
+/// # Fork protection
+///
+/// On Unix, a process that forks gets a copy of the state of the parent's
+/// RNGs, so parent and child would continue with the same random stream.
+/// To prevent this, `ReseedingRng` registers a fork handler with
+/// `pthread_atfork` when it is created (with the `std` feature). After a fork
+/// the child reseeds on the next use. Because the RNG works on blocks of
+/// data, values that were already generated and buffered before the fork can
+/// still be returned by both processes; call [`force_reseed`] in the child to
+/// rule this out.
+///
+/// # Example
+///
+/// ```
+/// use rand::prelude::*;
+/// use rand::prng::chacha::ChaChaCore; // Internal part of ChaChaRng that
+///                                     // implements BlockRngCore
+/// use rand::rngs::EntropyRng;
+/// use rand::rngs::adapter::ReseedingRng;
+///
+/// let prng = ChaChaCore::from_entropy();
+/// // Reseed from the system entropy source after every 1 MiB of output
+/// let mut reseeding_rng = ReseedingRng::new(prng, 1 << 20, EntropyRng::new());
+///
+/// println!("{}", reseeding_rng.gen::<u64>());
+/// ```
+///
+/// [`set_interval`]: #method.set_interval
+/// [`force_reseed`]: #method.force_reseed
 #[derive(Debug)]
 pub struct ReseedingRng<R, Rsdr>(BlockRng<ReseedingCore<R, Rsdr>>)
 where R: BlockRngCore + SeedableRng,
@@ -68,16 +104,68 @@ where R: BlockRngCore + SeedableRng,
     /// # Arguments
     ///
     /// * `rng`: the random number generator to use.
-    /// * `threshold`: the number of generated bytes after which to reseed the RNG.
+    /// * `threshold`: the number of generated bytes after which to reseed the
+    ///   RNG. A threshold of zero disables reseeding based on the number of
+    ///   generated bytes, so the RNG is only reseeded by [`reseed`].
     /// * `reseeder`: the RNG to use for reseeding.
+    ///
+    /// [`reseed`]: #method.reseed
     pub fn new(rng: R, threshold: u64, reseeder: Rsdr) -> Self {
+        fork::register_fork_handler();
         ReseedingRng(BlockRng::new(ReseedingCore::new(rng, threshold, reseeder)))
     }
 
     /// Reseed the internal PRNG.
+    ///
+    /// Values which were already generated by the old PRNG and are buffered
+    /// are still returned first; use [`force_reseed`] to discard them.
+    ///
+    /// [`force_reseed`]: #method.force_reseed
     pub fn reseed(&mut self) -> Result<(), Error> {
         self.0.core.reseed()
     }
+
+    /// Reseed the internal PRNG, and discard the buffered values, so that
+    /// all following values are generated by the reseeded PRNG.
+    pub fn force_reseed(&mut self) -> Result<(), Error> {
+        self.0.core.reseed()?;
+        self.0.reset();
+        Ok(())
+    }
+
+    /// Set the number of generated bytes after which to reseed the RNG,
+    /// counting from now. A threshold of zero disables reseeding based on the
+    /// number of generated bytes.
+    pub fn set_threshold(&mut self, threshold: u64) {
+        self.0.core.set_threshold(threshold);
+    }
+
+    /// Set the time after which to reseed the RNG, counting from now, or
+    /// disable reseeding based on time with `None` (the default).
+    ///
+    /// If reseeding fails, the next attempt is made after a fraction of the
+    /// interval, depending on the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rand::prelude::*;
+    /// use rand::prng::chacha::ChaChaCore;
+    /// use rand::rngs::EntropyRng;
+    /// use rand::rngs::adapter::ReseedingRng;
+    ///
+    /// // Reseed after every 1 MiB of output, and at least every 5 minutes
+    /// let mut rng = ReseedingRng::new(ChaChaCore::from_entropy(), 1 << 20,
+    ///                                 EntropyRng::new());
+    /// rng.set_interval(Some(Duration::from_secs(5 * 60)));
+    ///
+    /// println!("{}", rng.gen::<u64>());
+    /// ```
+    #[cfg(feature="std")]
+    pub fn set_interval(&mut self, interval: Option<Duration>) {
+        self.0.core.set_interval(interval);
+    }
 }
 
 // TODO: this should be implemented for any type where the inner type
@@ -126,6 +214,11 @@ struct ReseedingCore<R, Rsdr> {
     reseeder: Rsdr,
     threshold: i64,
     bytes_until_reseed: i64,
+    fork_counter: usize,
+    #[cfg(feature="std")]
+    interval: Option<Duration>,
+    #[cfg(feature="std")]
+    reseed_deadline: Option<Instant>,
 }
 
 impl<R, Rsdr> BlockRngCore for ReseedingCore<R, Rsdr>
@@ -136,11 +229,14 @@ where R: BlockRngCore + SeedableRng,
     type Results = <R as BlockRngCore>::Results;
 
     fn generate(&mut self, results: &mut Self::Results) {
-        if self.bytes_until_reseed <= 0 {
+        let global_fork_counter = fork::get_fork_counter();
+        if self.bytes_until_reseed <= 0 || self.is_forked(global_fork_counter)
+            || self.is_expired()
+        {
             // We get better performance by not calling only `auto_reseed` here
             // and continuing with the rest of the function, but by directly
             // returning from a non-inlined function.
-            return self.reseed_and_generate(results);
+            return self.reseed_and_generate(results, global_fork_counter);
         }
         let num_bytes = results.as_ref().len() * size_of::<Self::Item>();
         self.bytes_until_reseed -= num_bytes as i64;
@@ -160,39 +256,105 @@ where R: BlockRngCore + SeedableRng,
     /// * `threshold`: the number of generated bytes after which to reseed the RNG.
     /// * `reseeder`: the RNG to use for reseeding.
     pub fn new(rng: R, threshold: u64, reseeder: Rsdr) -> Self {
-        assert!(threshold <= ::core::i64::MAX as u64);
+        let threshold = clamp_threshold(threshold);
         ReseedingCore {
             inner: rng,
             reseeder,
-            threshold: threshold as i64,
-            bytes_until_reseed: threshold as i64,
+            threshold,
+            bytes_until_reseed: threshold,
+            fork_counter: 0,
+            #[cfg(feature="std")]
+            interval: None,
+            #[cfg(feature="std")]
+            reseed_deadline: None,
         }
     }
 
+    fn set_threshold(&mut self, threshold: u64) {
+        self.threshold = clamp_threshold(threshold);
+        self.bytes_until_reseed = self.threshold;
+    }
+
+    #[cfg(feature="std")]
+    fn set_interval(&mut self, interval: Option<Duration>) {
+        self.interval = interval;
+        self.reseed_deadline = interval.map(|interval| Instant::now() + interval);
+    }
+
     /// Reseed the internal PRNG.
     fn reseed(&mut self) -> Result<(), Error> {
         R::from_rng(&mut self.reseeder).map(|result| {
             self.bytes_until_reseed = self.threshold;
+            self.delay_deadline(1);
             self.inner = result
         })
     }
 
+    /// Returns `true` if the reseed interval has passed.
+    #[cfg(feature="std")]
+    fn is_expired(&self) -> bool {
+        match self.reseed_deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        }
+    }
+
+    #[cfg(not(feature="std"))]
+    fn is_expired(&self) -> bool { false }
+
+    /// Set the time of the next reseed to `1 / divisor` of the interval from
+    /// now.
+    #[cfg(feature="std")]
+    fn delay_deadline(&mut self, divisor: u32) {
+        if let Some(interval) = self.interval {
+            self.reseed_deadline = Some(Instant::now() + interval / divisor);
+        }
+    }
+
+    #[cfg(not(feature="std"))]
+    fn delay_deadline(&mut self, _divisor: u32) {}
+
+    fn is_forked(&self, global_fork_counter: usize) -> bool {
+        // In theory, on 32-bit platforms, it is possible for
+        // `global_fork_counter` to wrap around after ~4e9 forks.
+        //
+        // This check will detect a fork in the normal case where
+        // `fork_counter < global_fork_counter`, and also when the difference
+        // between both is greater than `isize::MAX` (wrapped around).
+        //
+        // It will still fail to detect a fork if there have been more than
+        // `isize::MAX` forks, without any reseed in between. Seems unlikely
+        // enough.
+        (self.fork_counter.wrapping_sub(global_fork_counter) as isize) < 0
+    }
+
     #[inline(never)]
     fn reseed_and_generate(&mut self,
-                           results: &mut <Self as BlockRngCore>::Results)
+                           results: &mut <Self as BlockRngCore>::Results,
+                           global_fork_counter: usize)
     {
-        trace!("Reseeding RNG after {} generated bytes",
-               self.threshold - self.bytes_until_reseed);
+        if self.is_forked(global_fork_counter) {
+            debug!("Fork detected, reseeding RNG");
+        } else if self.bytes_until_reseed > 0 {
+            trace!("Reseeding RNG after the reseed interval");
+        } else {
+            trace!("Reseeding RNG after {} generated bytes",
+                   self.threshold - self.bytes_until_reseed);
+        }
         let threshold = if let Err(e) = self.reseed()  {
-            let delay = match e.kind {
-                ErrorKind::Transient => 0,
-                kind @ _ if kind.should_retry() => self.threshold >> 8,
-                _ => self.threshold,
+            let (delay, divisor) = match e.kind {
+                ErrorKind::Transient => (0, 0),
+                kind @ _ if kind.should_retry() => (self.threshold >> 8, 256),
+                _ => (self.threshold, 1),
             };
+            if divisor != 0 {
+                self.delay_deadline(divisor);
+            }
             warn!("Reseeding RNG delayed reseeding by {} bytes due to \
                     error from source: {}", delay, e);
             delay
         } else {
+            self.fork_counter = global_fork_counter;
             self.threshold
         };
         
@@ -212,6 +374,11 @@ where R: BlockRngCore + SeedableRng + Clone,
             reseeder: self.reseeder.clone(),
             threshold: self.threshold,
             bytes_until_reseed: 0, // reseed clone on first use
+            fork_counter: self.fork_counter,
+            #[cfg(feature="std")]
+            interval: self.interval,
+            #[cfg(feature="std")]
+            reseed_deadline: self.reseed_deadline,
         }
     }
 }
@@ -220,17 +387,77 @@ impl<R, Rsdr> CryptoRng for ReseedingCore<R, Rsdr>
 where R: BlockRngCore + SeedableRng + CryptoRng,
       Rsdr: RngCore + CryptoRng {}
 
+fn clamp_threshold(threshold: u64) -> i64 {
+    use ::core::i64::MAX;
+    // Because generating more values than `i64::MAX` takes centuries on
+    // current hardware, we just clamp to that value. A threshold of 0,
+    // which indicates no limit, is also set to that value.
+    if threshold == 0 { MAX }
+    else if threshold <= MAX as u64 { threshold as i64 }
+    else { MAX }
+}
+
+
+#[cfg(all(feature="std", unix, not(target_os="emscripten")))]
+// `AtomicUsize::new` and `Once::new` can not initialize a static on Rustc
+// 1.22, the minimum supported version, so we use the deprecated constants.
+#[allow(deprecated)]
+mod fork {
+    extern crate libc;
+
+    use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+    use std::sync::{Once, ONCE_INIT};
+
+    // Fork protection
+    //
+    // We implement fork protection on Unix using `pthread_atfork`.
+    // When the process is forked, we increment `RESEEDING_RNG_FORK_COUNTER`.
+    // Every `ReseedingRng` stores the last known value of the static in
+    // `fork_counter`. If the cached `fork_counter` is less than
+    // `RESEEDING_RNG_FORK_COUNTER`, it is time to reseed this RNG.
+    //
+    // If reseeding fails, we don't deal with this by setting a delay, but just
+    // don't update `fork_counter`, so a reseed is attempted as soon as
+    // possible.
+
+    static RESEEDING_RNG_FORK_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    pub fn get_fork_counter() -> usize {
+        RESEEDING_RNG_FORK_COUNTER.load(Ordering::Relaxed)
+    }
+
+    static FORK_HANDLER_REGISTERED: Once = ONCE_INIT;
+
+    extern "C" fn fork_handler() {
+        // Note: fetch_add is defined to wrap on overflow
+        // (which is what we want).
+        RESEEDING_RNG_FORK_COUNTER.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn register_fork_handler() {
+        FORK_HANDLER_REGISTERED.call_once(|| {
+            unsafe { libc::pthread_atfork(None, None, Some(fork_handler)) };
+        });
+    }
+}
+
+#[cfg(not(all(feature="std", unix, not(target_os="emscripten"))))]
+mod fork {
+    pub fn get_fork_counter() -> usize { 0 }
+    pub fn register_fork_handler() {}
+}
+
 #[cfg(test)]
 mod test {
     use {Rng, SeedableRng};
-    use prng::chacha::ChaChaCore;
+    use prng::hc128::{Hc128Core, Hc128Rng};
     use rngs::mock::StepRng;
     use super::ReseedingRng;
 
     #[test]
     fn test_reseeding() {
         let mut zero = StepRng::new(0, 0);
-        let rng = ChaChaCore::from_rng(&mut zero).unwrap();
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
         let mut reseeding = ReseedingRng::new(rng, 32*4, zero);
 
         // Currently we only support for arrays up to length 32.
@@ -245,10 +472,116 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_reseeding_disabled() {
+        // With a threshold of zero, the RNG is never reseeded automatically,
+        // so the output is that of the inner RNG.
+        let mut zero = StepRng::new(0, 0);
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
+        let mut reseeding = ReseedingRng::new(rng, 0, StepRng::new(1, 1));
+        let mut plain = Hc128Rng::from_rng(&mut zero).unwrap();
+
+        let mut buf1 = [0u32; 32];
+        let mut buf2 = [0u32; 32];
+        for _ in 0..10 {
+            reseeding.fill(&mut buf1);
+            plain.fill(&mut buf2);
+            assert_eq!(buf1, buf2);
+        }
+
+        // Explicit reseeding still works
+        reseeding.reseed().unwrap();
+        reseeding.fill(&mut buf1);
+        plain.fill(&mut buf2);
+        assert!(buf1 != buf2);
+    }
+
+    #[test]
+    fn test_reseeding_after_fork() {
+        // Simulate a fork by making the fork counter of the RNG outdated.
+        let mut zero = StepRng::new(0, 0);
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
+        let mut reseeding = ReseedingRng::new(rng, 0, StepRng::new(1, 1));
+        let mut plain = Hc128Rng::from_rng(&mut zero).unwrap();
+
+        let mut buf1 = [0u32; 32];
+        let mut buf2 = [0u32; 32];
+        reseeding.fill(&mut buf1);
+        plain.fill(&mut buf2);
+        assert_eq!(buf1, buf2);
+
+        let counter = super::fork::get_fork_counter();
+        reseeding.0.core.fork_counter = counter.wrapping_sub(1);
+        reseeding.fill(&mut buf1);
+        plain.fill(&mut buf2);
+        assert!(buf1 != buf2);
+        assert_eq!(reseeding.0.core.fork_counter, counter);
+    }
+
+    #[test]
+    fn test_force_reseed() {
+        let mut zero = StepRng::new(0, 0);
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
+        let mut reseeding = ReseedingRng::new(rng, 0, StepRng::new(1, 1));
+        let mut plain = Hc128Rng::from_rng(&mut zero).unwrap();
+        assert_eq!(reseeding.gen::<u32>(), plain.gen::<u32>());
+
+        // `reseed` still returns the buffered values, `force_reseed` does not
+        reseeding.reseed().unwrap();
+        assert_eq!(reseeding.gen::<u32>(), plain.gen::<u32>());
+        reseeding.force_reseed().unwrap();
+        // The first reseed took the words 1 to 4 of the reseeder
+        let mut reseeded = Hc128Rng::from_rng(StepRng::new(5, 1)).unwrap();
+        assert_eq!(reseeding.gen::<u32>(), reseeded.gen::<u32>());
+    }
+
+    #[test]
+    fn test_set_threshold() {
+        let mut zero = StepRng::new(0, 0);
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
+        let mut reseeding = ReseedingRng::new(rng, 0, zero);
+        let mut buf = [0u32; 16];
+        reseeding.fill(&mut buf);
+        let seq = buf;
+        // The threshold counts from now, so the next block is still
+        // generated without reseeding
+        reseeding.set_threshold(16*4);
+        reseeding.fill(&mut buf);
+        assert!(buf != seq);
+        // Reseeding from `zero` restarts the sequence on every block
+        for _ in 0..3 {
+            reseeding.fill(&mut buf);
+            assert_eq!(buf, seq);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_reseeding_interval() {
+        use std::time::Duration;
+        let mut zero = StepRng::new(0, 0);
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
+        let mut reseeding = ReseedingRng::new(rng, 0, zero);
+        let mut buf = [0u32; 16];
+        reseeding.fill(&mut buf);
+        let seq = buf;
+
+        reseeding.set_interval(Some(Duration::from_secs(3600)));
+        reseeding.fill(&mut buf);
+        assert!(buf != seq);
+
+        // Reseeding from `zero` restarts the sequence on every block
+        reseeding.set_interval(Some(Duration::from_secs(0)));
+        for _ in 0..3 {
+            reseeding.fill(&mut buf);
+            assert_eq!(buf, seq);
+        }
+    }
+
     #[test]
     fn test_clone_reseeding() {
         let mut zero = StepRng::new(0, 0);
-        let rng = ChaChaCore::from_rng(&mut zero).unwrap();
+        let rng = Hc128Core::from_rng(&mut zero).unwrap();
         let mut rng1 = ReseedingRng::new(rng, 32*4, zero);
 
         let first: u32 = rng1.gen();
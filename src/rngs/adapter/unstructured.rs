@@ -0,0 +1,116 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An adapter providing the input of the `arbitrary` crate from an RNG.
+
+use std::fmt;
+use arbitrary::{Arbitrary, Unstructured};
+use rand_core::RngCore;
+
+/// An adapter turning an RNG into a source of `arbitrary::Unstructured`
+/// data.
+///
+/// The `arbitrary` crate builds structured values, like the inputs of a
+/// fuzz target, from a buffer of raw bytes. `ArbitraryRng` fills such a buffer
+/// from the wrapped RNG, so that with a seeded generator the same values are
+/// produced on every run, e.g. to generate a fuzzing corpus.
+///
+/// The buffer has a fixed length, given to [`new`], which limits the size of
+/// the generated values. [`unstructured`] refills the buffer each time it is
+/// called.
+///
+/// This type is only available with the `arbitrary` and `std` features.
+///
+/// # Example
+///
+/// ```
+/// use rand::SeedableRng;
+/// use rand::prng::Xoshiro256StarStar;
+/// use rand::rngs::adapter::ArbitraryRng;
+///
+/// let rng = Xoshiro256StarStar::from_seed([1; 32]);
+/// let mut source = ArbitraryRng::new(rng, 1024);
+/// let input: (u32, Vec<String>) = source.generate().unwrap();
+///
+/// // The same seed gives the same value
+/// let rng = Xoshiro256StarStar::from_seed([1; 32]);
+/// let mut source = ArbitraryRng::new(rng, 1024);
+/// assert_eq!(source.generate::<(u32, Vec<String>)>().unwrap(), input);
+/// ```
+///
+/// [`new`]: #method.new
+/// [`unstructured`]: #method.unstructured
+pub struct ArbitraryRng<R> {
+    rng: R,
+    buf: Vec<u8>,
+}
+
+impl<R: RngCore> ArbitraryRng<R> {
+    /// Create a new `ArbitraryRng` wrapping `rng`, which provides `len` bytes
+    /// of data for each value.
+    pub fn new(rng: R, len: usize) -> ArbitraryRng<R> {
+        ArbitraryRng { rng, buf: vec![0; len] }
+    }
+
+    /// Fill the buffer with new random bytes, and return the data for
+    /// `arbitrary`.
+    pub fn unstructured(&mut self) -> Unstructured<'_> {
+        self.rng.fill_bytes(&mut self.buf);
+        Unstructured::new(&self.buf)
+    }
+
+    /// Generate a value of type `T` from new random bytes.
+    ///
+    /// Errors are those of the `Arbitrary` implementation of `T`. Note that
+    /// most implementations do not fail if they need more data than the length
+    /// of the buffer, but fill in the missing bytes with zeros.
+    pub fn generate<T>(&mut self) -> ::arbitrary::Result<T>
+        where T: for<'a> Arbitrary<'a>
+    {
+        T::arbitrary_take_rest(self.unstructured())
+    }
+
+    /// Consume the `ArbitraryRng`, returning the wrapped RNG.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for ArbitraryRng<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArbitraryRng")
+         .field("rng", &self.rng)
+         .field("len", &self.buf.len())
+         .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rngs::mock::StepRng;
+    use super::ArbitraryRng;
+
+    #[test]
+    fn test_arbitrary() {
+        let mut source = ArbitraryRng::new(StepRng::new(0x0102_0304, 0), 16);
+        {
+            let mut u = source.unstructured();
+            assert_eq!(u.len(), 16);
+            assert_eq!(u.arbitrary::<u32>().unwrap(), 0x0102_0304);
+        }
+        let x: [u16; 8] = source.generate().unwrap();
+        assert_eq!(x, [0x0304, 0x0102, 0, 0, 0x0304, 0x0102, 0, 0]);
+        let v: Vec<u8> = source.generate().unwrap();
+        assert!(v.len() <= 16);
+        // Missing data is filled with zeros
+        let x: [u64; 3] = source.generate().unwrap();
+        assert_eq!(x, [0x0102_0304, 0x0102_0304, 0]);
+    }
+}
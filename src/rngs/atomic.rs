@@ -0,0 +1,186 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A random number generator which can be shared between threads.
+
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use rand_core::{RngCore, SeedableRng, Error, impls, le};
+
+/// The increment of the Weyl sequence: an odd number close to 2^N / φ.
+#[cfg(target_pointer_width = "64")]
+const GAMMA: usize = 0x9e3779b97f4a7c15u64 as usize;
+#[cfg(not(target_pointer_width = "64"))]
+const GAMMA: usize = 0x9e3779b9;
+
+/// A random number generator which can be shared between threads without
+/// locking.
+///
+/// `RngCore` is implemented for `&AtomicRng`, so a single generator can be
+/// used from many threads at once, for example in a `static` or an `Arc`.
+/// This is useful for randomness where quality matters little, like choosing
+/// a shard or adding jitter to retry delays, and a thread-local generator is
+/// not an option.
+///
+/// The state is a counter, which is advanced with a single atomic
+/// `fetch_add`, so generating a value never blocks or retries. The output is
+/// the counter scrambled by the finalizer of [SplitMix64]. On 64-bit
+/// platforms this is the SplitMix64 generator, with a period of
+/// 2<sup>64</sup>. On other platforms the counter has only 32 bits, and the
+/// period is 2<sup>32</sup>.
+///
+/// When used from several threads, the order in which threads receive values
+/// depends on their scheduling, so the output is not reproducible. This
+/// generator is not suitable for cryptography.
+///
+/// This type is only available with the `std` feature, since some `no_std`
+/// targets (like `thumbv6m-none-eabi`) have no atomic `fetch_add`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use rand::{Rng, FromEntropy};
+/// use rand::rngs::AtomicRng;
+///
+/// let rng = Arc::new(AtomicRng::from_entropy());
+/// let handles: Vec<_> = (0..4).map(|_| {
+///     let rng = rng.clone();
+///     thread::spawn(move || (&*rng).gen_range(0, 16))
+/// }).collect();
+/// for handle in handles {
+///     let shard: u32 = handle.join().unwrap();
+///     assert!(shard < 16);
+/// }
+/// ```
+///
+/// [SplitMix64]: http://xoshiro.di.unimi.it/splitmix64.c
+pub struct AtomicRng {
+    state: AtomicUsize,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for AtomicRng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AtomicRng {{}}")
+    }
+}
+
+impl AtomicRng {
+    /// Create a new `AtomicRng` with the given initial state.
+    ///
+    /// On platforms where `usize` has fewer than 64 bits, only the low bits
+    /// of `state` are used.
+    pub fn new(state: u64) -> AtomicRng {
+        AtomicRng { state: AtomicUsize::new(state as usize) }
+    }
+
+    #[inline]
+    fn next(&self) -> u64 {
+        let mut z = self.state.fetch_add(GAMMA, Ordering::Relaxed).wrapping_add(GAMMA) as u64;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl<'a> RngCore for &'a AtomicRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.next()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl RngCore for AtomicRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (&*self).next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        (&*self).next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (&*self).fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        (&*self).try_fill_bytes(dest)
+    }
+}
+
+impl SeedableRng for AtomicRng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut state = [0u64; 1];
+        le::read_u64_into(&seed, &mut state);
+        AtomicRng::new(state[0])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng};
+    use super::AtomicRng;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_atomic_true_values() {
+        // SplitMix64 with state 1234567, from the reference implementation
+        let mut rng = AtomicRng::new(1234567);
+        let expected = [6457827717110365317, 3203168211198807973,
+                        9817491932198370423, 4593380528125082431,
+                        16408922859458223821];
+        for &e in expected.iter() {
+            assert_eq!((&rng).next_u64(), e);
+        }
+        assert_eq!(rng.next_u64(), 7804594928223864054);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_atomic_threads() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let rng = Arc::new(AtomicRng::from_seed([1; 8]));
+        let handles: Vec<_> = (0..4).map(|_| {
+            let rng = rng.clone();
+            thread::spawn(move || {
+                (0..1000).map(|_| (&*rng).next_u64()).collect::<Vec<u64>>()
+            })
+        }).collect();
+        let mut values: Vec<u64> = handles.into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+
+        // Every thread got different values
+        values.sort();
+        values.dedup();
+        assert_eq!(values.len(), 4000);
+    }
+}
@@ -0,0 +1,456 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The CTR-DRBG random number generator.
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, CryptoRng, Error, ErrorKind, impls};
+
+use super::aes::{Aes256, BLOCK_LEN, KEY_LEN};
+
+/// Size of the seed material: key and counter block.
+const SEED_LEN: usize = KEY_LEN + BLOCK_LEN;
+
+/// Maximum number of requests between reseeds (SP 800-90A, table 3).
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Maximum number of bytes per request (2<sup>19</sup> bits).
+const MAX_REQUEST_LEN: usize = 1 << 16;
+
+/// The CTR-DRBG deterministic random bit generator with AES-256 and a
+/// derivation function, as specified by [NIST SP 800-90A](
+/// https://csrc.nist.gov/publications/detail/sp/800-90a/rev-1/final).
+///
+/// Like [`HmacDrbg`], this generator is meant for environments which require
+/// a NIST approved DRBG. It implements the instantiate, reseed and generate
+/// functions of the standard, using the block cipher derivation function, so
+/// that entropy input, nonce, personalization string and additional input
+/// can have any length. Prediction resistance is not built in, but can be
+/// achieved by calling [`reseed`] before every [`generate`].
+///
+/// Entropy has to be supplied by the user: [`new`] and [`reseed`] take the
+/// entropy input directly. SP 800-90A requires at least 32 bytes of entropy
+/// input for a security strength of 256 bits, and a nonce of at least 16
+/// bytes on instantiation.
+///
+/// The reseed counter counts the requests since the last (re)seed; after
+/// 2<sup>48</sup> requests, [`generate`] fails until the generator is
+/// reseeded.
+///
+/// The [`RngCore`] implementation calls [`generate`] without additional
+/// input, splitting requests larger than the limit of 65536 bytes.
+///
+/// The AES implementation used is not hardened against cache-timing attacks.
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::{CtrDrbg, OsRng};
+///
+/// let mut entropy = [0u8; 48];
+/// OsRng::new().unwrap().fill(&mut entropy[..]);
+/// let mut drbg = CtrDrbg::new(&entropy[..32], &entropy[32..], b"my application");
+///
+/// let mut key = [0u8; 32];
+/// drbg.generate(&mut key, b"session key").unwrap();
+/// let x: u32 = drbg.gen();
+/// assert_eq!(drbg.reseed_counter(), 2);
+/// ```
+///
+/// [`HmacDrbg`]: struct.HmacDrbg.html
+/// [`new`]: #method.new
+/// [`reseed`]: #method.reseed
+/// [`generate`]: #method.generate
+/// [`RngCore`]: ../trait.RngCore.html
+#[derive(Clone)]
+pub struct CtrDrbg {
+    cipher: Aes256,
+    value: [u8; BLOCK_LEN],
+    reseed_counter: u64,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for CtrDrbg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CtrDrbg {{}}")
+    }
+}
+
+impl CtrDrbg {
+    /// Instantiate the generator with the given entropy input, nonce and
+    /// personalization string.
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> CtrDrbg {
+        let mut drbg = CtrDrbg {
+            cipher: Aes256::new(&[0; KEY_LEN]),
+            value: [0; BLOCK_LEN],
+            reseed_counter: 1,
+        };
+        drbg.update(&derive(&[entropy, nonce, personalization]));
+        drbg
+    }
+
+    /// Reseed the generator with the given entropy input and additional
+    /// input.
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&derive(&[entropy, additional]));
+        self.reseed_counter = 1;
+    }
+
+    /// Returns the number of requests since instantiation or the last
+    /// reseed.
+    pub fn reseed_counter(&self) -> u64 {
+        self.reseed_counter - 1
+    }
+
+    /// Fill `dest` with random bytes, mixing in the additional input
+    /// `additional` (which may be empty).
+    ///
+    /// Fails with [`ErrorKind::NotReady`] if the generator has to be
+    /// reseeded. In that case `dest` is not modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dest` is longer than 65536 bytes, the maximum request size
+    /// of SP 800-90A.
+    ///
+    /// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+    pub fn generate(&mut self, dest: &mut [u8], additional: &[u8]) -> Result<(), Error> {
+        assert!(dest.len() <= MAX_REQUEST_LEN,
+                "CtrDrbg::generate: request is longer than 65536 bytes");
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err(Error::new(ErrorKind::NotReady, "CTR-DRBG: reseed required"));
+        }
+        let additional = if additional.is_empty() {
+            [0; SEED_LEN]
+        } else {
+            let additional = derive(&[additional]);
+            self.update(&additional);
+            additional
+        };
+        for chunk in dest.chunks_mut(BLOCK_LEN) {
+            let block = self.next_block();
+            let n = chunk.len();
+            chunk.copy_from_slice(&block[..n]);
+        }
+        self.update(&additional);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    /// Increment the counter block and encrypt it.
+    fn next_block(&mut self) -> [u8; BLOCK_LEN] {
+        for b in self.value.iter_mut().rev() {
+            *b = b.wrapping_add(1);
+            if *b != 0 {
+                break;
+            }
+        }
+        let mut block = self.value;
+        self.cipher.encrypt_block(&mut block);
+        block
+    }
+
+    /// The CTR_DRBG_Update function.
+    fn update(&mut self, provided: &[u8; SEED_LEN]) {
+        let mut temp = [0u8; SEED_LEN];
+        for chunk in temp.chunks_mut(BLOCK_LEN) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        for (t, p) in temp.iter_mut().zip(provided.iter()) {
+            *t ^= *p;
+        }
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(&temp[..KEY_LEN]);
+        self.cipher = Aes256::new(&key);
+        self.value.copy_from_slice(&temp[KEY_LEN..]);
+    }
+}
+
+/// The Block_Cipher_df derivation function, applied to the concatenation of
+/// `inputs`, returning `SEED_LEN` bytes.
+fn derive(inputs: &[&[u8]]) -> [u8; SEED_LEN] {
+    let len = inputs.iter().fold(0, |len, input| len + input.len()) as u32;
+    let mut header = [0u8; 8];
+    for i in 0..4 {
+        header[i] = (len >> (24 - 8 * i)) as u8;
+        header[4 + i] = ((SEED_LEN as u32) >> (24 - 8 * i)) as u8;
+    }
+
+    let mut df_key = [0u8; KEY_LEN];
+    for (i, k) in df_key.iter_mut().enumerate() {
+        *k = i as u8;
+    }
+    let df_cipher = Aes256::new(&df_key);
+    let mut temp = [0u8; SEED_LEN];
+    for (i, chunk) in temp.chunks_mut(BLOCK_LEN).enumerate() {
+        let mut mac = CbcMac::new(&df_cipher);
+        let mut iv = [0u8; BLOCK_LEN];
+        iv[3] = i as u8;
+        mac.update(&iv);
+        mac.update(&header);
+        for input in inputs.iter() {
+            mac.update(input);
+        }
+        mac.update(&[0x80]);
+        chunk.copy_from_slice(&mac.finalize());
+    }
+
+    let mut key = [0u8; KEY_LEN];
+    key.copy_from_slice(&temp[..KEY_LEN]);
+    let cipher = Aes256::new(&key);
+    let mut x = [0u8; BLOCK_LEN];
+    x.copy_from_slice(&temp[KEY_LEN..]);
+    let mut output = [0u8; SEED_LEN];
+    for chunk in output.chunks_mut(BLOCK_LEN) {
+        cipher.encrypt_block(&mut x);
+        chunk.copy_from_slice(&x);
+    }
+    output
+}
+
+/// The BCC function of SP 800-90A (CBC-MAC), with zero padding.
+struct CbcMac<'a> {
+    cipher: &'a Aes256,
+    chain: [u8; BLOCK_LEN],
+    // Number of bytes of the current block which have been added to `chain`
+    pos: usize,
+}
+
+impl<'a> CbcMac<'a> {
+    fn new(cipher: &'a Aes256) -> CbcMac<'a> {
+        CbcMac { cipher, chain: [0; BLOCK_LEN], pos: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &b in data.iter() {
+            self.chain[self.pos] ^= b;
+            self.pos += 1;
+            if self.pos == BLOCK_LEN {
+                self.cipher.encrypt_block(&mut self.chain);
+                self.pos = 0;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> [u8; BLOCK_LEN] {
+        if self.pos != 0 {
+            self.cipher.encrypt_block(&mut self.chain);
+        }
+        self.chain
+    }
+}
+
+impl RngCore for CtrDrbg {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+            panic!("CtrDrbg: {}", err))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        for chunk in dest.chunks_mut(MAX_REQUEST_LEN) {
+            self.generate(chunk, &[])?;
+        }
+        Ok(())
+    }
+}
+
+impl SeedableRng for CtrDrbg {
+    type Seed = [u8; 32];
+
+    /// Instantiate the generator with `seed` as entropy input, and an empty
+    /// nonce and personalization string.
+    fn from_seed(seed: Self::Seed) -> Self {
+        CtrDrbg::new(&seed, &[], &[])
+    }
+}
+
+impl CryptoRng for CtrDrbg {}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, ErrorKind};
+    use super::{CtrDrbg, RESEED_INTERVAL};
+    #[cfg(feature="alloc")]
+    use super::SEED_LEN;
+    #[cfg(feature="alloc")]
+    use super::super::aes::{Aes256, BLOCK_LEN, KEY_LEN};
+    #[cfg(all(feature="alloc", not(feature="std")))]
+    use alloc::Vec;
+
+    #[cfg(feature="alloc")]
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len() / 2).map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // Known-answer tests from `CTR_DRBG.rsp` of the NIST CAVP DRBG test
+    // vectors. In the sections with `PredictionResistance = False` every test
+    // instantiates the generator, generates `ReturnedBitsLen` bits with the
+    // first `AdditionalInput`, reseeds with `EntropyInputReseed` and
+    // `AdditionalInputReseed`, and then compares the output of a second
+    // request (with the second `AdditionalInput`) to `ReturnedBits`.
+    #[cfg(feature="alloc")]
+    struct NistVector {
+        entropy: &'static str,
+        nonce: &'static str,
+        personalization: &'static str,
+        additional_1: &'static str,
+        entropy_reseed: &'static str,
+        additional_reseed: &'static str,
+        additional_2: &'static str,
+        returned_bits: &'static str,
+    }
+
+    #[cfg(feature="alloc")]
+    fn check_nist_use_df(v: &NistVector) {
+        let expected = from_hex(v.returned_bits);
+        let mut output = expected.clone();
+        let mut drbg = CtrDrbg::new(&from_hex(v.entropy), &from_hex(v.nonce),
+                                    &from_hex(v.personalization));
+        drbg.generate(&mut output, &from_hex(v.additional_1)).unwrap();
+        assert_eq!(drbg.reseed_counter(), 1);
+        drbg.reseed(&from_hex(v.entropy_reseed), &from_hex(v.additional_reseed));
+        assert_eq!(drbg.reseed_counter(), 0);
+        drbg.generate(&mut output, &from_hex(v.additional_2)).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_ctr_drbg_nist() {
+        // [AES-256 use df] [PredictionResistance = False]
+        // [EntropyInputLen = 256] [NonceLen = 128]
+        // [PersonalizationStringLen = 0] [AdditionalInputLen = 0]
+        // [ReturnedBitsLen = 128]
+        // COUNT = 0
+        check_nist_use_df(&NistVector {
+            entropy: "5a194d5e2b31581454def675fb7958fec7db873e5689fc9d03217c68d8033820",
+            nonce: "1b54b8ff0642bff521f15c1c0b665f3f",
+            personalization: "",
+            additional_1: "",
+            entropy_reseed: "f9e65e04d856f3a9c44a4cbdc1d00846f5983d771c1b137e4e0f9d8ef409f92e",
+            additional_reseed: "",
+            additional_2: "",
+            returned_bits: "a054303d8a7ea9889d903e077c6f218f",
+        });
+        // COUNT = 1
+        check_nist_use_df(&NistVector {
+            entropy: "93b7055d7888ae234bfb431e379069d00ae810fbd48f2e06c204beae3b0bfaf0",
+            nonce: "90bc3b555b9d6b6aeb1774a583f98cad",
+            personalization: "",
+            additional_1: "",
+            entropy_reseed: "91d1d0e853525ead0e7f79abb0f0bf68064576339c3585cfd6d9b55d4f39278d",
+            additional_reseed: "",
+            additional_2: "",
+            returned_bits: "aaf27fc2bf64b0320dd3564bb9b03377",
+        });
+
+        // [AES-256 use df] [PredictionResistance = False]
+        // [EntropyInputLen = 256] [NonceLen = 128]
+        // [PersonalizationStringLen = 0] [AdditionalInputLen = 256]
+        // [ReturnedBitsLen = 128]
+        // COUNT = 0
+        check_nist_use_df(&NistVector {
+            entropy: "f84d395b1734eac4600dbc36f6b1e1599bc7f2608dc8ecb3a55369d7b1b122a0",
+            nonce: "176200bb44808b5400b24e1b5f56cf73",
+            personalization: "",
+            additional_1: "aef28c9169e9af74c73432d4aa6f5dff9ea4a53433de2ecb9bf380a8868c86e1",
+            entropy_reseed: "9f5ac9c16d9a2be37d2ff70a9bba732fc3785b23ff4ade3c8404da3f09f95a8f",
+            additional_reseed: "0626ae19763c5313b627a8d65cf1cfba46dfd6773242738b9b81fde8d566ade1",
+            additional_2: "63c160ed6a6c1fffd0586f52fa488a9055533930b36d4fa5ea3467cda9ffe198",
+            returned_bits: "e8f91633725d786081625fb99336a993",
+        });
+
+        // [AES-256 use df] [PredictionResistance = False]
+        // [EntropyInputLen = 256] [NonceLen = 128]
+        // [PersonalizationStringLen = 256] [AdditionalInputLen = 256]
+        // [ReturnedBitsLen = 128]
+        // COUNT = 0
+        check_nist_use_df(&NistVector {
+            entropy: "a53e371017439193591e475087aaddd5c1c386cdca0ddb68e002d80fdc401a47",
+            nonce: "a94da55afdc50ce51c9a3b8a4c448440",
+            personalization: "8b52a24a93c34ea71e1ca705eb829ba65de4d4e07fa3d86b37845ff1c7d5f6d2",
+            additional_1: "20f422edf85ca16a01cfbe5f8d6c947fae12a857db2aa9bfc7b36581808d0d46",
+            entropy_reseed: "dd40e5987b2716731568d276bf0c6715757903d3dede914642ddd467c879c81e",
+            additional_reseed: "7fd81fbd2ab51c115d834e99f65ca54020ed388ed59ee07593fe125e5d73fb75",
+            additional_2: "cd2cff14693e4c9efdfe260de986004930bab1c65057772a62392c3b74ebc90d",
+            returned_bits: "4f78beb94d978ce9d097feadfafd355e",
+        });
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_ctr_drbg_nist_no_df() {
+        // [AES-256 no df] [PredictionResistance = False]
+        // [EntropyInputLen = 384] [NonceLen = 0]
+        // [PersonalizationStringLen = 0] [AdditionalInputLen = 0]
+        // [ReturnedBitsLen = 512]
+        //
+        // `CtrDrbg` always uses the derivation function, so instantiate and
+        // reseed by hand to check `update` and `generate` on their own. This
+        // test reseeds before the first request.
+        let mut seed = [0u8; SEED_LEN];
+        let mut drbg = CtrDrbg {
+            cipher: Aes256::new(&[0; KEY_LEN]),
+            value: [0; BLOCK_LEN],
+            reseed_counter: 1,
+        };
+        seed.copy_from_slice(&from_hex(
+            "e4bc23c5089a19d86f4119cb3fa08c0a4991e0a1def17e101e4c14d9c323460a\
+             7c2fb58e0b086c6c57b55f56cae25bad"));
+        drbg.update(&seed);
+        seed.copy_from_slice(&from_hex(
+            "fd85a836bba85019881e8c6bad23c9061adc75477659acaea8e4a01dfe07a183\
+             2dad1c136f59d70f8653a5dc118663d6"));
+        drbg.update(&seed);
+
+        let mut output = [0u8; 64];
+        drbg.generate(&mut output, &[]).unwrap();
+        drbg.generate(&mut output, &[]).unwrap();
+        assert_eq!(&output[..], &from_hex(
+            "b2cb8905c05e5950ca31895096be29ea3d5a3b82b269495554eb80fe07de43e1\
+             93b9e7c3ece73b80e062b1c1f68202fbb1c52a040ea2478864295282234aaada")[..]);
+    }
+
+    #[test]
+    fn test_ctr_drbg_rng() {
+        let mut drbg = CtrDrbg::from_seed([7; 32]);
+        assert_eq!(drbg.next_u32(), 3295257566);
+        assert_eq!(drbg.next_u32(), 2223486073);
+
+        // Large requests are split
+        let mut buf = [0u8; 100_000];
+        drbg.fill_bytes(&mut buf[..]);
+        assert!(buf[70_000..].iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn test_ctr_drbg_reseed_required() {
+        let mut drbg = CtrDrbg::from_seed([7; 32]);
+        drbg.reseed_counter = RESEED_INTERVAL + 1;
+        let mut output = [0u8; 16];
+        let err = drbg.try_fill_bytes(&mut output).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotReady);
+        assert_eq!(output, [0u8; 16]);
+
+        drbg.reseed(&[1; 32], &[]);
+        drbg.try_fill_bytes(&mut output).unwrap();
+        assert!(output != [0u8; 16]);
+    }
+}
@@ -39,11 +39,23 @@ use rngs::{OsRng, JitterRng};
 /// report the error, and only the one from `OsRng`. The other [`RngCore`]
 /// methods will panic in case of an error.
 ///
+/// # Example
+///
+/// ```
+/// use rand::RngCore;
+/// use rand::rngs::EntropyRng;
+///
+/// let mut rng = EntropyRng::new();
+/// let mut seed = [0u8; 32];
+/// rng.fill_bytes(&mut seed);
+/// println!("seeded from {}", rng.source().unwrap());
+/// ```
+///
 /// [`OsRng`]: struct.OsRng.html
 /// [`JitterRng`]: jitter/struct.JitterRng.html
 /// [`thread_rng`]: ../fn.thread_rng.html
 /// [`RngCore`]: ../trait.RngCore.html
-/// [`try_fill_bytes`]: ../trait.RngCore.html#method.tymethod.try_fill_bytes
+/// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
 #[derive(Debug)]
 pub struct EntropyRng {
     rng: EntropySource,
@@ -65,6 +77,21 @@ impl EntropyRng {
     pub fn new() -> Self {
         EntropyRng { rng: EntropySource::None }
     }
+
+    /// Returns the name of the entropy source currently in use: `"OsRng"` or
+    /// `"JitterRng"`.
+    ///
+    /// Returns `None` if no source is in use yet, because no random data was
+    /// requested or the first request failed. The source may change on every
+    /// request, so this reports the one that succeeded last; it is meant for
+    /// logging and diagnostics.
+    pub fn source(&self) -> Option<&'static str> {
+        match self.rng {
+            EntropySource::Os(_) => Some("OsRng"),
+            EntropySource::Jitter(_) => Some("JitterRng"),
+            EntropySource::None => None,
+        }
+    }
 }
 
 impl Default for EntropyRng {
@@ -174,4 +201,12 @@ mod test {
         let n = (rng.next_u32() ^ rng.next_u32()).count_ones();
         assert!(n >= 2);    // p(failure) approx 1e-7
     }
+
+    #[test]
+    fn test_entropy_source() {
+        let mut rng = EntropyRng::new();
+        assert_eq!(rng.source(), None);
+        rng.next_u32();
+        assert!(rng.source() == Some("OsRng") || rng.source() == Some("JitterRng"));
+    }
 }
@@ -0,0 +1,341 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Fortuna random number generator.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+use rand_core::{RngCore, SeedableRng, CryptoRng, Error, ErrorKind, impls};
+
+use super::aes::{Aes256, BLOCK_LEN, KEY_LEN};
+use super::sha256::{Sha256, DIGEST_LEN};
+
+/// Number of entropy pools.
+const NUM_POOLS: usize = 32;
+
+/// Number of bytes in pool 0 which triggers a reseed.
+const MIN_POOL_SIZE: usize = 64;
+
+/// Maximum number of bytes generated with one key.
+const MAX_REQUEST_LEN: usize = 1 << 20;
+
+/// An entropy accumulator and generator following the Fortuna design of
+/// Ferguson and Schneier ([*Cryptography Engineering*](
+/// https://www.schneier.com/books/cryptography_engineering/), chapter 9).
+///
+/// Fortuna collects entropy from any number of sources into 32 pools, and
+/// uses it to reseed an internal generator (AES-256 in counter mode). This is
+/// meant for long-lived processes, such as servers, which want to mix
+/// application-level events into their random numbers, without having to
+/// estimate how much entropy each event contains:
+///
+/// - [`add_entropy`] adds an event from a source to the next pool of that
+///   source; every source cycles through all pools.
+/// - When pool 0 has collected at least 64 bytes and the last reseed was at
+///   least 100 ms ago, the next request reseeds the generator. Reseed number
+///   `r` uses the contents of pool `i` if 2<sup>`i`</sup> divides `r`, so
+///   pools with a higher index collect entropy over longer periods. An
+///   attacker who can inject or observe many events therefore cannot keep
+///   the generator in a known state forever.
+/// - After every request, the generator switches to a new key, so that
+///   earlier output cannot be reconstructed from its state.
+///
+/// A new `Fortuna` created with [`new`] is not seeded, and fails to generate
+/// random numbers until enough entropy has been added. It is recommended to
+/// seed the generator directly with [`FromEntropy::from_entropy`] or
+/// [`SeedableRng::from_rng`], and to add events afterwards.
+///
+/// The AES implementation used is not hardened against cache-timing attacks.
+///
+/// # Example
+///
+/// ```
+/// use rand::{Rng, FromEntropy};
+/// use rand::rngs::Fortuna;
+///
+/// let mut rng = Fortuna::from_entropy();
+///
+/// // Mix in events as they happen
+/// let request_id = 12345u64;
+/// rng.add_entropy(0, b"GET /index.html");
+/// rng.add_entropy(1, &[request_id as u8, (request_id >> 8) as u8]);
+///
+/// let session_key: [u8; 16] = rng.gen();
+/// ```
+///
+/// [`add_entropy`]: #method.add_entropy
+/// [`new`]: #method.new
+/// [`FromEntropy::from_entropy`]: ../trait.FromEntropy.html#tymethod.from_entropy
+/// [`SeedableRng::from_rng`]: ../trait.SeedableRng.html#method.from_rng
+#[derive(Clone)]
+pub struct Fortuna {
+    // Generator
+    key: [u8; KEY_LEN],
+    cipher: Aes256,
+    counter: [u8; BLOCK_LEN],
+    // Accumulator
+    pools: [Sha256; NUM_POOLS],
+    pool0_len: usize,
+    source_pools: [u8; 256],
+    reseed_count: u64,
+    last_reseed: Option<Instant>,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Fortuna {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fortuna {{}}")
+    }
+}
+
+impl Fortuna {
+    /// Create a new, unseeded generator.
+    pub fn new() -> Fortuna {
+        Fortuna {
+            key: [0; KEY_LEN],
+            cipher: Aes256::new(&[0; KEY_LEN]),
+            counter: [0; BLOCK_LEN],
+            pools: [Sha256::new(); NUM_POOLS],
+            pool0_len: 0,
+            source_pools: [0; 256],
+            reseed_count: 0,
+            last_reseed: None,
+        }
+    }
+
+    /// Add an event from the source `source_id` to the entropy pools.
+    ///
+    /// Each source should have its own identifier. The events of a source
+    /// are distributed over all pools in turn. Events longer than 32 bytes
+    /// are hashed with SHA-256 first.
+    pub fn add_entropy(&mut self, source_id: u8, data: &[u8]) {
+        let digest;
+        let data = if data.len() > DIGEST_LEN {
+            let mut hash = Sha256::new();
+            hash.update(data);
+            digest = hash.finalize();
+            &digest[..]
+        } else {
+            data
+        };
+
+        let pool = self.source_pools[source_id as usize] as usize;
+        self.source_pools[source_id as usize] = ((pool + 1) % NUM_POOLS) as u8;
+        self.pools[pool].update(&[source_id, data.len() as u8]);
+        self.pools[pool].update(data);
+        if pool == 0 {
+            self.pool0_len += 2 + data.len();
+        }
+    }
+
+    /// Returns the number of times the generator was reseeded from the
+    /// pools.
+    pub fn reseed_count(&self) -> u64 {
+        self.reseed_count
+    }
+
+    /// Returns `true` if the generator has been seeded, either directly or
+    /// from the pools.
+    fn is_seeded(&self) -> bool {
+        self.counter.iter().any(|&b| b != 0)
+    }
+
+    /// Reseed the generator from the pools if pool 0 has collected enough
+    /// entropy, and the last reseed is long enough ago.
+    fn reseed_from_pools(&mut self) {
+        if self.pool0_len < MIN_POOL_SIZE {
+            return;
+        }
+        let now = Instant::now();
+        if let Some(last) = self.last_reseed {
+            if now.duration_since(last) < Duration::from_millis(100) {
+                return;
+            }
+        }
+        self.last_reseed = Some(now);
+        self.reseed_count += 1;
+        self.pool0_len = 0;
+
+        let mut hash = Sha256::new();
+        hash.update(&self.key);
+        for (i, pool) in self.pools.iter_mut().enumerate() {
+            // Use pool `i` if 2^i divides the reseed count
+            if self.reseed_count % (1u64 << i) != 0 {
+                break;
+            }
+            hash.update(&sha256d(::std::mem::replace(pool, Sha256::new())));
+        }
+        self.rekey(hash);
+    }
+
+    /// Set the key of the generator to the SHA-256d of the old key and the
+    /// seed, which `hash` has absorbed, and increment the counter.
+    fn rekey(&mut self, hash: Sha256) {
+        self.key = sha256d(hash);
+        self.cipher = Aes256::new(&self.key);
+        self.increment_counter();
+    }
+
+    fn increment_counter(&mut self) {
+        for b in self.counter.iter_mut() {
+            *b = b.wrapping_add(1);
+            if *b != 0 {
+                break;
+            }
+        }
+    }
+
+    /// Fill `dest` with the blocks of the generator, and switch to a new key.
+    fn generate(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(BLOCK_LEN) {
+            let block = self.next_block();
+            let n = chunk.len();
+            chunk.copy_from_slice(&block[..n]);
+        }
+        let mut key = [0u8; KEY_LEN];
+        for chunk in key.chunks_mut(BLOCK_LEN) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        self.key = key;
+        self.cipher = Aes256::new(&self.key);
+    }
+
+    /// Encrypt the counter and increment it.
+    fn next_block(&mut self) -> [u8; BLOCK_LEN] {
+        let mut block = self.counter;
+        self.cipher.encrypt_block(&mut block);
+        self.increment_counter();
+        block
+    }
+}
+
+impl Default for Fortuna {
+    fn default() -> Self {
+        Fortuna::new()
+    }
+}
+
+/// Finish a SHA-256 hash and hash the result again.
+fn sha256d(hash: Sha256) -> [u8; DIGEST_LEN] {
+    let mut outer = Sha256::new();
+    outer.update(&hash.finalize());
+    outer.finalize()
+}
+
+impl RngCore for Fortuna {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+            panic!("Fortuna: {}", err))
+    }
+
+    /// Fails with [`ErrorKind::NotReady`] if the generator is not seeded.
+    ///
+    /// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.reseed_from_pools();
+        if !self.is_seeded() {
+            return Err(Error::new(ErrorKind::NotReady,
+                                  "Fortuna: not enough entropy collected"));
+        }
+        for chunk in dest.chunks_mut(MAX_REQUEST_LEN) {
+            self.generate(chunk);
+        }
+        Ok(())
+    }
+}
+
+impl SeedableRng for Fortuna {
+    type Seed = [u8; 32];
+
+    /// Create a new generator, and seed it directly with `seed`. Entropy
+    /// added to the pools is used from the next reseed on.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut fortuna = Fortuna::new();
+        let mut hash = Sha256::new();
+        hash.update(&fortuna.key);
+        hash.update(&seed);
+        fortuna.rekey(hash);
+        fortuna
+    }
+}
+
+impl CryptoRng for Fortuna {}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, ErrorKind};
+    use super::{Fortuna, NUM_POOLS};
+
+    #[test]
+    fn test_fortuna_true_values() {
+        // Key SHA-256d(zero key || seed), counter 1
+        let mut rng = Fortuna::from_seed([7; 32]);
+        let mut output = [0u8; 20];
+        rng.fill_bytes(&mut output);
+        assert_eq!(output, [0x5b, 0x74, 0xcc, 0x9c, 0xc6, 0x33, 0xa0, 0xf3, 0x9b, 0xb8,
+                            0x3b, 0xb8, 0x27, 0xc1, 0x48, 0xf6, 0x18, 0xad, 0xe6, 0x68]);
+        // After the request, the generator switched to a new key
+        assert_eq!(rng.next_u32(), 0xaab2184e);
+    }
+
+    #[test]
+    fn test_fortuna_unseeded() {
+        let mut rng = Fortuna::new();
+        let mut output = [0u8; 16];
+        assert_eq!(rng.try_fill_bytes(&mut output).unwrap_err().kind, ErrorKind::NotReady);
+
+        // Not enough entropy in pool 0
+        for i in 0..(NUM_POOLS * 2) {
+            rng.add_entropy(0, &[i as u8; 20]);
+        }
+        assert_eq!(rng.try_fill_bytes(&mut output).unwrap_err().kind, ErrorKind::NotReady);
+
+        rng.add_entropy(0, &[0; 20]);
+        rng.try_fill_bytes(&mut output).unwrap();
+        assert_eq!(rng.reseed_count(), 1);
+    }
+
+    #[test]
+    fn test_fortuna_reseed() {
+        let mut rng1 = Fortuna::from_seed([7; 32]);
+        let mut rng2 = Fortuna::from_seed([7; 32]);
+        // A long event is hashed
+        rng1.add_entropy(0, &[1; 100]);
+        rng2.add_entropy(0, &[2; 100]);
+        for source in 1..64 {
+            rng1.add_entropy(source, b"event");
+            rng2.add_entropy(source, b"event");
+        }
+        assert_eq!(rng1.reseed_count(), 0);
+        let a = rng1.next_u64();
+        let b = rng2.next_u64();
+        assert_eq!(rng1.reseed_count(), 1);
+        assert_eq!(rng2.reseed_count(), 1);
+        assert!(a != b);
+
+        // No reseed within 100 ms
+        for source in 64..128 {
+            rng1.add_entropy(source, b"event");
+        }
+        rng1.next_u64();
+        assert_eq!(rng1.reseed_count(), 1);
+        rng1.last_reseed = None;
+        rng1.next_u64();
+        assert_eq!(rng1.reseed_count(), 2);
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The HMAC-DRBG random number generator.
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, CryptoRng, Error, ErrorKind, impls};
+
+use super::sha256::{HmacSha256, DIGEST_LEN};
+
+/// Maximum number of requests between reseeds (SP 800-90A, table 2).
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Maximum number of bytes per request (2<sup>19</sup> bits).
+const MAX_REQUEST_LEN: usize = 1 << 16;
+
+/// The HMAC-DRBG deterministic random bit generator with HMAC-SHA-256, as
+/// specified by [NIST SP 800-90A](
+/// https://csrc.nist.gov/publications/detail/sp/800-90a/rev-1/final).
+///
+/// This generator is meant for environments which require a NIST approved
+/// DRBG; otherwise [`StdRng`] is faster and just as secure. It implements the
+/// instantiate, reseed and generate functions of the standard, with optional
+/// personalization strings and additional input, and without prediction
+/// resistance (which can be achieved by calling [`reseed`] before every
+/// [`generate`]).
+///
+/// Entropy has to be supplied by the user: [`new`] and [`reseed`] take the
+/// entropy input directly. SP 800-90A requires at least 32 bytes of entropy
+/// input for a security strength of 256 bits, and a nonce of at least 16
+/// bytes on instantiation.
+///
+/// After 2<sup>48</sup> requests, [`generate`] fails until the generator is
+/// reseeded.
+///
+/// The [`RngCore`] implementation calls [`generate`] without additional
+/// input, splitting requests larger than the limit of 65536 bytes. Note
+/// that every call of `next_u32` or `next_u64` is a separate request, which
+/// costs several evaluations of HMAC.
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::{HmacDrbg, OsRng};
+///
+/// let mut entropy = [0u8; 48];
+/// OsRng::new().unwrap().fill(&mut entropy[..]);
+/// let mut drbg = HmacDrbg::new(&entropy[..32], &entropy[32..], b"my application");
+///
+/// let mut key = [0u8; 32];
+/// drbg.generate(&mut key, b"session key").unwrap();
+/// let x: u32 = drbg.gen();
+/// ```
+///
+/// [`StdRng`]: struct.StdRng.html
+/// [`new`]: #method.new
+/// [`reseed`]: #method.reseed
+/// [`generate`]: #method.generate
+/// [`RngCore`]: ../trait.RngCore.html
+#[derive(Clone)]
+pub struct HmacDrbg {
+    key: [u8; DIGEST_LEN],
+    value: [u8; DIGEST_LEN],
+    reseed_counter: u64,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for HmacDrbg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HmacDrbg {{}}")
+    }
+}
+
+impl HmacDrbg {
+    /// Instantiate the generator with the given entropy input, nonce and
+    /// personalization string.
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> HmacDrbg {
+        let mut drbg = HmacDrbg {
+            key: [0; DIGEST_LEN],
+            value: [1; DIGEST_LEN],
+            reseed_counter: 1,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        drbg
+    }
+
+    /// Reseed the generator with the given entropy input and additional
+    /// input.
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&[entropy, additional]);
+        self.reseed_counter = 1;
+    }
+
+    /// Fill `dest` with random bytes, mixing in the additional input
+    /// `additional` (which may be empty).
+    ///
+    /// Fails with [`ErrorKind::NotReady`] if the generator has to be
+    /// reseeded. In that case `dest` is not modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dest` is longer than 65536 bytes, the maximum request size
+    /// of SP 800-90A.
+    ///
+    /// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+    pub fn generate(&mut self, dest: &mut [u8], additional: &[u8]) -> Result<(), Error> {
+        assert!(dest.len() <= MAX_REQUEST_LEN,
+                "HmacDrbg::generate: request is longer than 65536 bytes");
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err(Error::new(ErrorKind::NotReady, "HMAC-DRBG: reseed required"));
+        }
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        for chunk in dest.chunks_mut(DIGEST_LEN) {
+            self.value = self.hmac(&[&self.value]);
+            let n = chunk.len();
+            chunk.copy_from_slice(&self.value[..n]);
+        }
+        self.update(&[additional]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    /// HMAC with the current key of the concatenation of `data`.
+    fn hmac(&self, data: &[&[u8]]) -> [u8; DIGEST_LEN] {
+        let mut hmac = HmacSha256::new(&self.key);
+        for d in data.iter() {
+            hmac.update(d);
+        }
+        hmac.finalize()
+    }
+
+    /// The HMAC_DRBG_Update function, with the concatenation of `data` as
+    /// provided data.
+    fn update(&mut self, data: &[&[u8]]) {
+        let empty = data.iter().all(|d| d.is_empty());
+        for &separator in [0u8, 1].iter() {
+            let mut hmac = HmacSha256::new(&self.key);
+            hmac.update(&self.value);
+            hmac.update(&[separator]);
+            for d in data.iter() {
+                hmac.update(d);
+            }
+            self.key = hmac.finalize();
+            self.value = self.hmac(&[&self.value]);
+            if empty {
+                break;
+            }
+        }
+    }
+}
+
+impl RngCore for HmacDrbg {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+            panic!("HmacDrbg: {}", err))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        for chunk in dest.chunks_mut(MAX_REQUEST_LEN) {
+            self.generate(chunk, &[])?;
+        }
+        Ok(())
+    }
+}
+
+impl SeedableRng for HmacDrbg {
+    type Seed = [u8; 32];
+
+    /// Instantiate the generator with `seed` as entropy input, and an empty
+    /// nonce and personalization string.
+    fn from_seed(seed: Self::Seed) -> Self {
+        HmacDrbg::new(&seed, &[], &[])
+    }
+}
+
+impl CryptoRng for HmacDrbg {}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, ErrorKind};
+    use super::{HmacDrbg, RESEED_INTERVAL};
+    #[cfg(all(feature="alloc", not(feature="std")))]
+    use alloc::Vec;
+
+    #[cfg(feature="alloc")]
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len() / 2).map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_hmac_drbg_nist() {
+        // NIST CAVP HMAC_DRBG.rsp, [SHA-256], no prediction resistance,
+        // no personalization string and additional input, COUNT = 0
+        let entropy = from_hex("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488");
+        let nonce = from_hex("659ba96c601dc69fc902940805ec0ca8");
+        let expected = from_hex("e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89\
+                                 d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1\
+                                 07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668\
+                                 961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8");
+
+        let mut drbg = HmacDrbg::new(&entropy, &nonce, &[]);
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &[]).unwrap();
+        drbg.generate(&mut output, &[]).unwrap();
+        assert_eq!(&output[..], &expected[..]);
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_hmac_drbg_additional_input() {
+        // Personalization string, additional input and reseeding; computed
+        // with an independent implementation based on Python's `hmac`
+        let entropy: Vec<u8> = (0..32).collect();
+        let nonce: Vec<u8> = (32..48).collect();
+        let mut drbg = HmacDrbg::new(&entropy, &nonce, b"personalization");
+        let mut output = [0u8; 40];
+        drbg.generate(&mut output, b"additional 1").unwrap();
+        assert_eq!(&output[..], &from_hex("8646fd01500d97e40d157b06571b5a0be204f4add23292d6\
+                                           c5d927e72835ffe3638711cc858c88d5")[..]);
+
+        let entropy: Vec<u8> = (48..80).collect();
+        drbg.reseed(&entropy, b"reseed");
+        drbg.generate(&mut output, &[]).unwrap();
+        assert_eq!(&output[..], &from_hex("0d2ba44e6f4baf5bf1afdc1aad461fe7cff66c8b100fb14e\
+                                           7682ed257feb2c8d4d9fa2e9d9cb5c1e")[..]);
+    }
+
+    #[test]
+    fn test_hmac_drbg_rng() {
+        let mut drbg = HmacDrbg::from_seed([7; 32]);
+        assert_eq!(drbg.next_u32(), 2659001164);
+        assert_eq!(drbg.next_u32(), 2113268123);
+
+        // Large requests are split
+        let mut buf = [0u8; 100_000];
+        drbg.fill_bytes(&mut buf[..]);
+        assert!(buf[70_000..].iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn test_hmac_drbg_reseed_required() {
+        let mut drbg = HmacDrbg::from_seed([7; 32]);
+        drbg.reseed_counter = RESEED_INTERVAL + 1;
+        let mut output = [0u8; 16];
+        let err = drbg.try_fill_bytes(&mut output).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotReady);
+        assert_eq!(output, [0u8; 16]);
+
+        drbg.reseed(&[1; 32], &[]);
+        drbg.try_fill_bytes(&mut output).unwrap();
+        assert!(output != [0u8; 16]);
+    }
+}
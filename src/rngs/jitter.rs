@@ -56,7 +56,7 @@ const MEMORY_SIZE: usize = MEMORY_BLOCKS * MEMORY_BLOCKSIZE;
 ///
 /// # Quality testing
 ///
-/// [`JitterRng::new()`] has build-in, but limited, quality testing, however
+/// [`JitterRng::new()`] has built-in, but limited, quality testing, however
 /// before using `JitterRng` on untested hardware, or after changes that could
 /// effect how the code is optimized (such as a new LLVM version), it is
 /// recommend to run the much more stringent
@@ -66,6 +66,7 @@ const MEMORY_SIZE: usize = MEMORY_BLOCKS * MEMORY_BLOCKSIZE;
 /// Use the following code using [`timer_stats`] to collect the data:
 ///
 /// ```no_run
+/// # #![allow(unknown_lints, bare_trait_objects)]
 /// use rand::jitter::JitterRng;
 /// #
 /// # use std::error::Error;
@@ -112,14 +113,14 @@ const MEMORY_SIZE: usize = MEMORY_BLOCKS * MEMORY_BLOCKSIZE;
 ///    restart.py -v jitter_rng_var.bin 8 <min-entropy>
 ///    ```
 /// 2. Estimate the expected amount of entropy that is available in the last 4
-///    bits of the timer delta after running noice sources. Note that a value of
+///    bits of the timer delta after running noise sources. Note that a value of
 ///    `3.70` is the minimum estimated entropy for true randomness.
 ///    ```sh
 ///    python noniid_main.py -v -u 4 jitter_rng_var.bin 4
 ///    restart.py -v -u 4 jitter_rng_var.bin 4 <min-entropy>
 ///    ```
 /// 3. Estimate the expected amount of entropy that is available to the entropy
-///    collector if both noice sources only run their minimal number of times.
+///    collector if both noise sources only run their minimal number of times.
 ///    This measures the absolute worst-case, and gives a lower bound for the
 ///    available entropy.
 ///    ```sh
@@ -574,7 +575,7 @@ impl JitterRng {
     fn gen_entropy(&mut self) -> u64 {
         trace!("JitterRng: collecting entropy");
 
-        // Prime `ec.prev_time`, and run the noice sources to make sure the
+        // Prime `ec.prev_time`, and run the noise sources to make sure the
         // first loop round collects the expected entropy.
         let mut ec = EcState {
             prev_time: (self.timer)(),
@@ -602,7 +603,7 @@ impl JitterRng {
     /// Basic quality tests on the timer, by measuring CPU timing jitter a few
     /// hundred times.
     ///
-    /// If succesful, this will return the estimated number of rounds necessary
+    /// If successful, this will return the estimated number of rounds necessary
     /// to collect 64 bits of entropy. Otherwise a [`TimerError`] with the cause
     /// of the failure will be returned.
     ///
@@ -753,10 +754,10 @@ impl JitterRng {
     /// `JitterRng` entropy collector.
     ///
     /// Setting `var_rounds` to `true` will execute the memory access and the
-    /// CPU jitter noice sources a variable amount of times (just like a real
+    /// CPU jitter noise sources a variable amount of times (just like a real
     /// `JitterRng` round).
     ///
-    /// Setting `var_rounds` to `false` will execute the noice sources the
+    /// Setting `var_rounds` to `false` will execute the noise sources the
     /// minimal number of times. This can be used to measure the minimum amount
     /// of entropy one round of the entropy collector can collect in the worst
     /// case.
@@ -870,7 +871,7 @@ mod test_jitter_init {
     #[test]
     fn test_jitter_init() {
         use RngCore;
-        // Because this is a debug build, measurements here are not representive
+        // Because this is a debug build, measurements here are not representative
         // of the final release build.
         // Don't fail this test if initializing `JitterRng` fails because of a
         // bad timer (the timer from the standard library may not have enough
@@ -890,4 +891,29 @@ mod test_jitter_init {
         let mut rng = JitterRng::new_with_timer(bad_timer);
         assert!(rng.test_timer().is_err());
     }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_jitter_constant_step_timer() {
+        use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+        use jitter::TimerError;
+        static TIME: AtomicUsize = ATOMIC_USIZE_INIT;
+        // Increasing, but without any jitter
+        fn step_timer() -> u64 { TIME.fetch_add(1, Ordering::Relaxed) as u64 + 1 }
+        let mut rng = JitterRng::new_with_timer(step_timer);
+        assert_eq!(rng.test_timer(), Err(TimerError::TinyVariantions));
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_jitter_backwards_timer() {
+        use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+        use jitter::TimerError;
+        static TIME: AtomicUsize = ATOMIC_USIZE_INIT;
+        fn backwards_timer() -> u64 {
+            1_000_000 - TIME.fetch_add(1, Ordering::Relaxed) as u64
+        }
+        let mut rng = JitterRng::new_with_timer(backwards_timer);
+        assert_eq!(rng.test_timer(), Err(TimerError::NotMonotonic));
+    }
 }
@@ -59,3 +59,52 @@ impl RngCore for StepRng {
         Ok(self.fill_bytes(dest))
     }
 }
+
+/// A mock `RngCore` which always returns the same value.
+///
+/// Every call to `next_u64` returns the given `u64`, `next_u32` returns its
+/// lower 32 bits, and byte slices are filled with repetitions of its
+/// little-endian bytes. This is equivalent to `StepRng::new(value, 0)`, but
+/// states the intent more clearly.
+///
+/// This is useful to test the behaviour of code at the edges of the possible
+/// random values, for example with `ConstRng::new(0)` and
+/// `ConstRng::new(u64::MAX)`.
+///
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::mock::ConstRng;
+///
+/// let mut rng = ConstRng::new(::std::u64::MAX);
+/// assert_eq!(rng.gen::<u32>(), ::std::u32::MAX);
+/// assert_eq!(rng.gen::<[u8; 3]>(), [255, 255, 255]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstRng {
+    value: u64,
+}
+
+impl ConstRng {
+    /// Create a `ConstRng` which always yields `value`.
+    pub fn new(value: u64) -> Self {
+        ConstRng { value }
+    }
+}
+
+impl RngCore for ConstRng {
+    fn next_u32(&mut self) -> u32 {
+        self.value as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.value
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
@@ -11,10 +11,25 @@
 //! Random number generators and adapters for common usage:
 //!
 //! - [`ThreadRng`], a fast, secure, auto-seeded thread-local generator
+//! - [`ThreadCryptoRng`], a conservative thread-local CSPRNG for keys and
+//!   tokens, seeded by the OS
 //! - [`StdRng`] and [`SmallRng`], algorithms to cover typical usage
 //! - [`EntropyRng`], [`OsRng`] and [`JitterRng`] as entropy sources
+//! - [`RdRand`] and [`RdSeed`], the hardware generators of x86 processors
+//! - [`XofRng`], seeded by byte strings of any length
+//! - [`HmacDrbg`] and [`CtrDrbg`], the HMAC-DRBG and CTR-DRBG of NIST
+//!   SP 800-90A
+//! - [`Fortuna`], which accumulates entropy from application events
+//! - [`AtomicRng`], a simple generator which can be shared between threads
+//! - [`WorkerPool`], a generator for each worker thread of Rayon
+//! - [`registry::by_name`] to select an algorithm by name at runtime
 //! - [`mock::StepRng`] as a simple counter for tests
+//! - [`quickcheck::TestRng`] as a seeded generator for quickcheck properties
 //! - [`adapter::ReadRng`] to read from a file/stream
+//! - [`adapter::RecordingRng`] and [`adapter::ReplayRng`] to record the
+//!   output of another RNG and play it back
+//! - [`adapter::CountingRng`] to count the output of another RNG
+//! - [`adapter::ArbitraryRng`] to generate values with the `arbitrary` crate
 //!
 //! # Background — Random number generators (RNGs)
 //!
@@ -49,9 +64,7 @@
 //!
 //! - [`SmallRng`] is a PRNG chosen for low memory usage, high performance and
 //!   good statistical quality.
-//!   The current algorithm (plain Xorshift) unfortunately performs
-//!   poorly in statistical quality test suites (TestU01 and PractRand) and will
-//!   be replaced in the next major release.
+//!   The current algorithm is xoshiro256\*\*.
 //! - [`StdRng`] is a CSPRNG chosen for good performance and trust of security
 //!   (based on reviews, maturity and usage). The current algorithm is HC-128,
 //!   which is one of the recommendations by ECRYPT's eSTREAM project.
@@ -122,7 +135,8 @@
 //! # try_inner().unwrap();
 //!
 //! // SmallRng seeded by a constant, for deterministic results:
-//! let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16]; // byte array
+//! let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16,
+//!             17,18,19,20, 21,22,23,24, 25,26,27,28, 29,30,31,32]; // byte array
 //! let mut rng = SmallRng::from_seed(seed);
 //! # let v: u32 = rng.gen();
 //! ```
@@ -142,11 +156,16 @@
 //!
 // This module:
 //! [`ThreadRng`]: struct.ThreadRng.html
+//! [`ThreadCryptoRng`]: struct.ThreadCryptoRng.html
 //! [`StdRng`]: struct.StdRng.html
 //! [`SmallRng`]: struct.SmallRng.html
 //! [`EntropyRng`]: struct.EntropyRng.html
 //! [`OsRng`]: struct.OsRng.html
 //! [`JitterRng`]: struct.JitterRng.html
+//! [`RdRand`]: struct.RdRand.html
+//! [`RdSeed`]: struct.RdSeed.html
+//! [`XofRng`]: struct.XofRng.html
+//! [`AtomicRng`]: struct.AtomicRng.html
 // Other traits and functions:
 //! [`rand_core`]: https://crates.io/crates/rand_core
 //! [`prng` module]: ../prng/index.html
@@ -159,26 +178,56 @@
 //! [`SeedableRng::from_rng`]: ../trait.SeedableRng.html#tymethod.from_rng
 //! [`SeedableRng::from_seed`]: ../trait.SeedableRng.html#tymethod.from_seed
 //! [`thread_rng`]: ../fn.thread_rng.html
+//! [`WorkerPool`]: struct.WorkerPool.html
+//! [`HmacDrbg`]: struct.HmacDrbg.html
+//! [`CtrDrbg`]: struct.CtrDrbg.html
+//! [`Fortuna`]: struct.Fortuna.html
 //! [`mock::StepRng`]: mock/struct.StepRng.html
+//! [`quickcheck::TestRng`]: quickcheck/struct.TestRng.html
 //! [`adapter::ReadRng`]: adapter/struct.ReadRng.html
+//! [`registry::by_name`]: registry/fn.by_name.html
+//! [`adapter::RecordingRng`]: adapter/struct.RecordingRng.html
+//! [`adapter::ReplayRng`]: adapter/struct.ReplayRng.html
+//! [`adapter::CountingRng`]: adapter/struct.CountingRng.html
+//! [`adapter::ArbitraryRng`]: adapter/struct.ArbitraryRng.html
 //! [`ChaChaRng`]: ../prng/chacha/struct.ChaChaRng.html
 
 pub mod adapter;
 
+mod aes;
+#[cfg(feature="std")] mod atomic;
+mod ctr_drbg;
 #[cfg(feature="std")] mod entropy;
+#[cfg(feature="std")] mod fortuna;
+mod hmac_drbg;
 #[doc(hidden)] pub mod jitter;
 pub mod mock;   // Public so we don't export `StepRng` directly, making it a bit
                 // more clear it is intended for testing.
+#[cfg(all(feature="quickcheck", feature="std"))] pub mod quickcheck;
 #[cfg(feature="std")] #[doc(hidden)] pub mod os;
-mod small;
+#[cfg(all(feature="std", feature="rayon"))] mod pool;
+#[cfg(all(feature="rdrand", any(target_arch="x86", target_arch="x86_64")))]
+mod rdrand;
+#[cfg(feature="alloc")] pub mod registry;
+#[cfg(feature="small_rng")] mod small;
+mod sha256;
 mod std;
 #[cfg(feature="std")] pub(crate) mod thread;
+mod xof;
 
 
+#[cfg(feature="std")] pub use self::atomic::AtomicRng;
+pub use self::ctr_drbg::CtrDrbg;
 pub use self::jitter::{JitterRng, TimerError};
 #[cfg(feature="std")] pub use self::entropy::EntropyRng;
+#[cfg(feature="std")] pub use self::fortuna::Fortuna;
+pub use self::hmac_drbg::HmacDrbg;
 #[cfg(feature="std")] pub use self::os::OsRng;
+#[cfg(all(feature="std", feature="rayon"))] pub use self::pool::WorkerPool;
+#[cfg(all(feature="rdrand", any(target_arch="x86", target_arch="x86_64")))]
+pub use self::rdrand::{RdRand, RdSeed};
 
-pub use self::small::SmallRng;
+#[cfg(feature="small_rng")] pub use self::small::SmallRng;
 pub use self::std::StdRng;
-#[cfg(feature="std")] pub use self::thread::ThreadRng;
+#[cfg(feature="std")] pub use self::thread::{ThreadRng, ThreadCryptoRng};
+pub use self::xof::XofRng;
@@ -36,9 +36,16 @@ use rand_core::{CryptoRng, RngCore, Error, impls};
 /// - Linux, Android: reads from the `getrandom(2)` system call if available,
 ///   otherwise from `/dev/urandom`.
 /// - macOS, iOS: calls `SecRandomCopyBytes`.
-/// - Windows: calls `RtlGenRandom`.
+/// - Windows: calls `BCryptGenRandom`, or `RtlGenRandom` on versions before
+///   Vista SP2.
 /// - WASM (with `stdweb` feature): calls `window.crypto.getRandomValues` in
 ///   browsers, and in Node.js `require("crypto").randomBytes`.
+/// - WASM (with `js-sys` feature): calls `crypto.getRandomValues` via
+///   `js-sys`, in browsers, web workers and Node.js 19 or newer.
+/// - WASI: calls `random_get`.
+/// - UEFI (with `uefi` feature): uses `EFI_RNG_PROTOCOL`, while boot services
+///   are available.
+/// - SGX enclaves (with `rdrand` feature): uses the RDSEED instruction.
 /// - Emscripten: reads from emulated `/dev/urandom`, which maps to the same
 ///   interfaces as `stdweb`, but falls back to the insecure `Math.random()` if
 ///   unavailable.
@@ -63,20 +70,34 @@ use rand_core::{CryptoRng, RngCore, Error, impls};
 /// random. As a countermeasure we try to do a single read from `/dev/random` in
 /// non-blocking mode. If the OS RNG is not yet properly seeded, we will get an
 /// error. Because we keep one file descriptor to `/dev/urandom` open when
-/// succesful, this is only a small one-time cost.
+/// successful, this is only a small one-time cost.
 ///
 /// # Panics
 ///
-/// `OsRng` is extremely unlikely to fail if `OsRng::new()` was succesfull. But
+/// `OsRng` is extremely unlikely to fail if `OsRng::new()` was successful. But
 /// in case it does fail, only [`try_fill_bytes`] is able to report the cause.
 /// Depending on the error the other [`RngCore`] methods will retry several
 /// times, and panic in case the error remains.
 ///
+/// # Example
+///
+/// ```
+/// use rand::{Rng, SeedableRng};
+/// use rand::rngs::OsRng;
+/// use rand::prng::ChaChaRng;
+///
+/// let mut os_rng = OsRng::new().unwrap();
+/// let mut key = [0u8; 16];
+/// os_rng.fill(&mut key);
+///
+/// // Seed a fast user-space RNG from the OS
+/// let mut rng = ChaChaRng::from_rng(os_rng).unwrap();
+/// let x: u32 = rng.gen();
+/// ```
+///
 /// [`EntropyRng`]: struct.EntropyRng.html
 /// [`RngCore`]: ../trait.RngCore.html
-/// [`try_fill_bytes`]: ../trait.RngCore.html#method.tymethod.try_fill_bytes
-
-
+/// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
 #[allow(unused)]    // not used by all targets
 #[derive(Clone)]
 pub struct OsRng(imp::OsRng);
@@ -639,10 +660,26 @@ mod imp {
     use {Error, ErrorKind};
     
     use std::io;
+    use std::mem;
+    use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
 
     use self::winapi::shared::minwindef::ULONG;
+    use self::winapi::shared::ntdef::{NTSTATUS, PVOID};
+    use self::winapi::um::libloaderapi::{GetProcAddress, LoadLibraryA};
     use self::winapi::um::ntsecapi::RtlGenRandom;
-    use self::winapi::um::winnt::PVOID;
+    use self::winapi::um::winnt::LPCSTR;
+
+    // Not in winapi without the `bcrypt` feature, which would make bcrypt.dll
+    // a load-time dependency.
+    const BCRYPT_USE_SYSTEM_PREFERRED_RNG: ULONG = 0x00000002;
+    const STATUS_INVALID_PARAMETER: NTSTATUS = 0xC000000Du32 as NTSTATUS;
+
+    type BCryptGenRandomFn =
+        unsafe extern "system" fn(PVOID, *mut u8, ULONG, ULONG) -> NTSTATUS;
+
+    // Address of `BCryptGenRandom`; 0 if not yet loaded, 1 if unavailable.
+    static BCRYPT_GEN_RANDOM: AtomicUsize = ATOMIC_USIZE_INIT;
+    const UNAVAILABLE: usize = 1;
 
     #[derive(Clone, Debug)]
     pub struct OsRng;
@@ -651,29 +688,92 @@ mod imp {
         pub fn new() -> Result<OsRng, Error> {
             Ok(OsRng)
         }
+
         pub fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
-            // RtlGenRandom takes an ULONG (u32) for the length so we need to
+            // Both functions take an ULONG (u32) for the length so we need to
             // split up the buffer.
             for slice in dest.chunks_mut(<ULONG>::max_value() as usize) {
-                trace!("OsRng: reading {} bytes via RtlGenRandom", slice.len());
-                let ret = unsafe {
-                    RtlGenRandom(slice.as_mut_ptr() as PVOID, slice.len() as ULONG)
-                };
-                if ret == 0 {
-                    return Err(Error::with_cause(
-                        ErrorKind::Unavailable,
-                        "couldn't generate random bytes",
-                        io::Error::last_os_error()));
+                match bcrypt_gen_random() {
+                    Some(gen_random) => bcrypt_try_fill(gen_random, slice)?,
+                    None => rtl_gen_random_try_fill(slice)?,
                 }
             }
             Ok(())
         }
     }
+
+    // `BCryptGenRandom` with `BCRYPT_USE_SYSTEM_PREFERRED_RNG` is available
+    // since Windows Vista SP2. It is loaded at runtime, so that on older
+    // versions we can fall back on `RtlGenRandom`.
+    fn bcrypt_gen_random() -> Option<BCryptGenRandomFn> {
+        let mut addr = BCRYPT_GEN_RANDOM.load(Ordering::Acquire);
+        if addr == 0 {
+            addr = unsafe {
+                let lib = LoadLibraryA(b"bcrypt.dll\0".as_ptr() as LPCSTR);
+                if lib.is_null() {
+                    0
+                } else {
+                    GetProcAddress(lib, b"BCryptGenRandom\0".as_ptr() as LPCSTR) as usize
+                }
+            };
+            if addr == 0 {
+                debug!("OsRng: BCryptGenRandom not available, using RtlGenRandom");
+                addr = UNAVAILABLE;
+            }
+            BCRYPT_GEN_RANDOM.store(addr, Ordering::Release);
+        }
+        if addr == UNAVAILABLE {
+            None
+        } else {
+            Some(unsafe { mem::transmute::<usize, BCryptGenRandomFn>(addr) })
+        }
+    }
+
+    fn bcrypt_try_fill(gen_random: BCryptGenRandomFn, dest: &mut [u8])
+        -> Result<(), Error>
+    {
+        trace!("OsRng: reading {} bytes via BCryptGenRandom", dest.len());
+        let status = unsafe {
+            gen_random(0 as PVOID, dest.as_mut_ptr(), dest.len() as ULONG,
+                       BCRYPT_USE_SYSTEM_PREFERRED_RNG)
+        };
+        if status == STATUS_INVALID_PARAMETER {
+            // Windows Vista before SP2 does not support the flag
+            debug!("OsRng: BCryptGenRandom does not support the system \
+                    preferred RNG, using RtlGenRandom");
+            BCRYPT_GEN_RANDOM.store(UNAVAILABLE, Ordering::Release);
+            return rtl_gen_random_try_fill(dest);
+        }
+        if status < 0 {
+            return Err(Error::with_cause(
+                ErrorKind::Unavailable,
+                "BCryptGenRandom failed",
+                io::Error::new(io::ErrorKind::Other,
+                               format!("NTSTATUS {:#010x}", status as u32))));
+        }
+        Ok(())
+    }
+
+    fn rtl_gen_random_try_fill(dest: &mut [u8]) -> Result<(), Error> {
+        trace!("OsRng: reading {} bytes via RtlGenRandom", dest.len());
+        let ret = unsafe {
+            RtlGenRandom(dest.as_mut_ptr() as PVOID, dest.len() as ULONG)
+        };
+        if ret == 0 {
+            return Err(Error::with_cause(
+                ErrorKind::Unavailable,
+                "couldn't generate random bytes",
+                io::Error::last_os_error()));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(all(target_arch = "wasm32",
           not(target_os = "emscripten"),
-          not(feature = "stdweb")))]
+          not(target_os = "wasi"),
+          not(feature = "stdweb"),
+          not(feature = "js-sys")))]
 mod imp {
     use {Error, ErrorKind};
 
@@ -683,18 +783,108 @@ mod imp {
     impl OsRng {
         pub fn new() -> Result<OsRng, Error> {
             Err(Error::new(ErrorKind::Unavailable,
-                           "not supported on WASM without stdweb"))
+                           "not supported on WASM without stdweb or js-sys"))
         }
 
         pub fn try_fill_bytes(&mut self, _v: &mut [u8]) -> Result<(), Error> {
             Err(Error::new(ErrorKind::Unavailable,
-                           "not supported on WASM without stdweb"))
+                           "not supported on WASM without stdweb or js-sys"))
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32",
+          not(target_os = "emscripten"),
+          not(target_os = "wasi"),
+          not(feature = "stdweb"),
+          feature = "js-sys"))]
+mod imp {
+    use js_sys::{self, Function, Reflect, Uint8Array};
+    use js_sys::wasm_bindgen::{JsCast, JsValue};
+    use {Error, ErrorKind};
+
+    // `crypto.getRandomValues` fills at most 65536 bytes per call
+    const MAX_CHUNK_LEN: usize = 65536;
+
+    #[derive(Clone, Debug)]
+    pub struct OsRng;
+
+    impl OsRng {
+        pub fn new() -> Result<OsRng, Error> {
+            get_random_values()?;
+            Ok(OsRng)
+        }
+
+        pub fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            let (crypto, get_random_values) = get_random_values()?;
+            for chunk in dest.chunks_mut(MAX_CHUNK_LEN) {
+                trace!("OsRng: reading {} bytes via crypto.getRandomValues", chunk.len());
+                let array = Uint8Array::new_with_length(chunk.len() as u32);
+                let result: Result<JsValue, JsValue> =
+                    get_random_values.call1(&crypto, &array.clone().into());
+                if result.is_err() {
+                    return Err(Error::new(ErrorKind::Unexpected,
+                                          "crypto.getRandomValues failed"));
+                }
+                array.copy_to(chunk);
+            }
+            Ok(())
+        }
+    }
+
+    // Look up `globalThis.crypto.getRandomValues`, which is available in
+    // browsers, web workers and Node.js 19 or newer.
+    fn get_random_values() -> Result<(JsValue, Function), Error> {
+        let unavailable = || Error::new(ErrorKind::Unavailable,
+                                        "crypto.getRandomValues is not available");
+        let crypto = Reflect::get(&js_sys::global(), &JsValue::from_str("crypto"))
+            .map_err(|_| unavailable())?;
+        if !crypto.is_object() {
+            return Err(unavailable());
+        }
+        let function = Reflect::get(&crypto, &JsValue::from_str("getRandomValues"))
+            .map_err(|_| unavailable())?;
+        let function = function.dyn_into::<Function>().map_err(|_| unavailable())?;
+        Ok((crypto, function))
+    }
+}
+
+#[cfg(target_os = "wasi")]
+mod imp {
+    use {Error, ErrorKind};
+
+    use std::io;
+
+    #[link(wasm_import_module = "wasi_snapshot_preview1")]
+    extern "C" {
+        fn random_get(buf: *mut u8, buf_len: usize) -> u16;
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct OsRng;
+
+    impl OsRng {
+        pub fn new() -> Result<OsRng, Error> {
+            Ok(OsRng)
+        }
+
+        pub fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            trace!("OsRng: reading {} bytes via random_get", dest.len());
+            let errno = unsafe { random_get(dest.as_mut_ptr(), dest.len()) };
+            if errno != 0 {
+                return Err(Error::with_cause(
+                    ErrorKind::Unavailable,
+                    "random_get failed",
+                    io::Error::from_raw_os_error(errno as i32)));
+            }
+            Ok(())
         }
     }
 }
 
 #[cfg(all(target_arch = "wasm32",
           not(target_os = "emscripten"),
+          not(target_os = "wasi"),
           feature = "stdweb"))]
 mod imp {
     use std::mem;
@@ -785,6 +975,129 @@ mod imp {
     }
 }
 
+#[cfg(all(target_os = "uefi", feature = "uefi"))]
+mod imp {
+    extern crate r_efi;
+
+    use {Error, ErrorKind};
+
+    use std::os::uefi::env;
+    use std::ptr;
+    use self::r_efi::efi;
+    use self::r_efi::protocols::rng;
+
+    #[derive(Clone, Debug)]
+    pub struct OsRng;
+
+    impl OsRng {
+        pub fn new() -> Result<OsRng, Error> {
+            locate_rng_protocol()?;
+            Ok(OsRng)
+        }
+
+        pub fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            trace!("OsRng: reading {} bytes via EFI_RNG_PROTOCOL", dest.len());
+            let protocol = locate_rng_protocol()?;
+            // A null algorithm selects the default algorithm of the firmware
+            let status = unsafe {
+                ((*protocol).get_rng)(protocol, ptr::null_mut(), dest.len(),
+                                      dest.as_mut_ptr())
+            };
+            if status.is_error() {
+                let kind = match status {
+                    efi::Status::NOT_READY => ErrorKind::NotReady,
+                    efi::Status::DEVICE_ERROR => ErrorKind::Transient,
+                    _ => ErrorKind::Unavailable,
+                };
+                return Err(Error::new(kind, "EFI_RNG_PROTOCOL.GetRNG failed"));
+            }
+            Ok(())
+        }
+    }
+
+    // The protocol is looked up on every use, because boot services (and
+    // with them the protocol) disappear after `ExitBootServices`.
+    fn locate_rng_protocol() -> Result<*mut rng::Protocol, Error> {
+        let boot_services = match env::boot_services() {
+            Some(boot_services) => boot_services.as_ptr() as *mut efi::BootServices,
+            None => return Err(Error::new(ErrorKind::Unavailable,
+                                          "UEFI boot services are not available")),
+        };
+        let mut guid = rng::PROTOCOL_GUID;
+        let mut protocol = ptr::null_mut();
+        let status = unsafe {
+            ((*boot_services).locate_protocol)(&mut guid, ptr::null_mut(), &mut protocol)
+        };
+        if status.is_error() || protocol.is_null() {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "EFI_RNG_PROTOCOL not found"));
+        }
+        Ok(protocol as *mut rng::Protocol)
+    }
+}
+
+#[cfg(all(target_os = "uefi", not(feature = "uefi")))]
+mod imp {
+    use {Error, ErrorKind};
+
+    #[derive(Clone, Debug)]
+    pub struct OsRng;
+
+    impl OsRng {
+        pub fn new() -> Result<OsRng, Error> {
+            Err(Error::new(ErrorKind::Unavailable,
+                           "not supported on UEFI without the uefi feature"))
+        }
+
+        pub fn try_fill_bytes(&mut self, _v: &mut [u8]) -> Result<(), Error> {
+            Err(Error::new(ErrorKind::Unavailable,
+                           "not supported on UEFI without the uefi feature"))
+        }
+    }
+}
+
+#[cfg(all(target_env = "sgx", feature = "rdrand"))]
+mod imp {
+    use {Error, RngCore};
+    use rngs::RdSeed;
+
+    #[derive(Clone, Debug)]
+    pub struct OsRng(RdSeed);
+
+    impl OsRng {
+        pub fn new() -> Result<OsRng, Error> {
+            // CPUID is not allowed in enclaves, but all processors with SGX
+            // support RDSEED.
+            Ok(OsRng(RdSeed::new_unchecked()))
+        }
+
+        pub fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            trace!("OsRng: reading {} bytes via RDSEED", dest.len());
+            self.0.try_fill_bytes(dest)
+        }
+    }
+}
+
+#[cfg(all(target_env = "sgx", not(feature = "rdrand")))]
+mod imp {
+    use {Error, ErrorKind};
+
+    #[derive(Clone, Debug)]
+    pub struct OsRng;
+
+    impl OsRng {
+        pub fn new() -> Result<OsRng, Error> {
+            Err(Error::new(ErrorKind::Unavailable,
+                           "not supported in SGX enclaves without the rdrand feature"))
+        }
+
+        pub fn try_fill_bytes(&mut self, _v: &mut [u8]) -> Result<(), Error> {
+            Err(Error::new(ErrorKind::Unavailable,
+                           "not supported in SGX enclaves without the rdrand feature"))
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use RngCore;
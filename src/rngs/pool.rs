@@ -0,0 +1,182 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A pool of generators for the worker threads of Rayon.
+
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, TryLockError};
+
+use rayon;
+
+use Jumpable;
+
+/// A pool of generators, one for each worker thread of a Rayon thread pool.
+///
+/// The generators are derived from a single generator by [`Jumpable::jump`],
+/// so their streams do not overlap. [`with_rng`] gives access to the
+/// generator of the current worker thread, without contention between the
+/// workers.
+///
+/// Each worker's generator produces a deterministic stream. Which items of a
+/// parallel iterator are processed by which worker does depend on work
+/// stealing however; results which are combined without regard to the order,
+/// such as the estimates of a Monte Carlo simulation, are reproducible in
+/// distribution but not bit for bit. Use [`Distribution::par_sample_iter`]
+/// for samples which do not depend on the scheduling.
+///
+/// Calls from threads which are not workers of the current Rayon pool (or
+/// whose index is beyond the number of generators) share one extra
+/// generator.
+///
+/// # Example
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate rayon;
+/// use rand::{Rng, SeedableRng};
+/// use rand::prng::Xoshiro256StarStar;
+/// use rand::rngs::WorkerPool;
+/// use rayon::prelude::*;
+///
+/// # fn main() {
+/// let pool = WorkerPool::new(Xoshiro256StarStar::from_seed([1; 32]));
+/// let inside: usize = (0..10_000).into_par_iter().filter(|_| {
+///     pool.with_rng(|rng| {
+///         let (x, y): (f64, f64) = rng.gen();
+///         x * x + y * y <= 1.0
+///     })
+/// }).count();
+/// println!("pi is about {}", 4.0 * inside as f64 / 10_000.0);
+/// # }
+/// ```
+///
+/// [`Jumpable::jump`]: ../trait.Jumpable.html#tymethod.jump
+/// [`with_rng`]: #method.with_rng
+/// [`Distribution::par_sample_iter`]: ../distributions/trait.Distribution.html#method.par_sample_iter
+pub struct WorkerPool<R> {
+    // One generator per worker, plus the shared generator at the end
+    rngs: Vec<Mutex<R>>,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl<R> fmt::Debug for WorkerPool<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WorkerPool {{ num_threads: {} }}", self.num_threads())
+    }
+}
+
+impl<R: Jumpable> WorkerPool<R> {
+    /// Create a pool with a generator for each worker thread of the current
+    /// Rayon thread pool.
+    ///
+    /// The generator of worker `i` is `rng` jumped `i` times.
+    pub fn new(rng: R) -> WorkerPool<R> {
+        WorkerPool::with_num_threads(rng, rayon::current_num_threads())
+    }
+
+    /// Create a pool with generators for `num_threads` worker threads.
+    ///
+    /// The generator of worker `i` is `rng` jumped `i` times.
+    pub fn with_num_threads(mut rng: R, num_threads: usize) -> WorkerPool<R> {
+        let mut rngs = Vec::with_capacity(num_threads + 1);
+        for _ in 0..(num_threads + 1) {
+            rngs.push(Mutex::new(rng.clone()));
+            rng.jump();
+        }
+        WorkerPool { rngs }
+    }
+
+    /// Call `f` with the generator of the current worker thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from `f` on the same worker, directly or through a
+    /// Rayon task which the worker picked up while running `f`.
+    pub fn with_rng<F, T>(&self, f: F) -> T where F: FnOnce(&mut R) -> T {
+        let shared = self.rngs.len() - 1;
+        let mut guard = match rayon::current_thread_index() {
+            Some(i) if i < shared => match self.rngs[i].try_lock() {
+                Ok(guard) => guard,
+                Err(TryLockError::Poisoned(err)) => err.into_inner(),
+                Err(TryLockError::WouldBlock) =>
+                    panic!("WorkerPool::with_rng: called recursively"),
+            },
+            _ => lock(&self.rngs[shared]),
+        };
+        f(&mut guard)
+    }
+}
+
+impl<R> WorkerPool<R> {
+    /// Returns the number of worker threads with their own generator.
+    pub fn num_threads(&self) -> usize {
+        self.rngs.len() - 1
+    }
+}
+
+// A panic in a user function does not leave a generator in an invalid state,
+// so the poison can be ignored.
+fn lock<'a, R>(mutex: &'a Mutex<R>) -> MutexGuard<'a, R> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+    use rayon::ThreadPoolBuilder;
+    use rayon::prelude::*;
+
+    use {RngCore, SeedableRng};
+    use prng::Xoshiro256StarStar;
+    use super::WorkerPool;
+
+    #[test]
+    fn test_worker_pool() {
+        let threads = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        let rng = Xoshiro256StarStar::from_seed([1; 32]);
+        let pool = threads.install(|| WorkerPool::new(rng.clone()));
+        assert_eq!(pool.num_threads(), 2);
+
+        // Outside of the thread pool, the shared generator is used
+        let mut shared = rng.clone();
+        shared.jump();
+        shared.jump();
+        assert_eq!(pool.with_rng(|r| r.next_u64()), shared.next_u64());
+
+        let values: Vec<(usize, u64)> = threads.install(|| {
+            (0..1000).into_par_iter().map(|_| {
+                (::rayon::current_thread_index().unwrap(),
+                 pool.with_rng(|r| r.next_u64()))
+            }).collect()
+        });
+
+        // Each worker produces its own stream. The order in which a worker
+        // processed its items is not known, so compare sorted values.
+        let mut worker = rng.clone();
+        for i in 0..2 {
+            let mut produced: Vec<u64> = values.iter()
+                .filter(|v| v.0 == i).map(|v| v.1).collect();
+            let mut stream = worker.clone();
+            let mut expected: Vec<u64> = produced.iter()
+                .map(|_| stream.next_u64()).collect();
+            produced.sort();
+            expected.sort();
+            assert_eq!(produced, expected);
+            worker.jump();
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_worker_pool_recursive() {
+        let threads = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let pool = WorkerPool::with_num_threads(Xoshiro256StarStar::from_seed([1; 32]), 1);
+        threads.install(|| pool.with_rng(|_| pool.with_rng(|r| r.next_u32())));
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Integration with the `quickcheck` property testing framework.
+//!
+//! [`TestSeed`] and [`TestRng`] implement `quickcheck::Arbitrary`, so a
+//! property can take a seed or a seeded generator as an argument. When a
+//! property fails, quickcheck shrinks the seed by clearing its bytes one at a
+//! time, and reports the simplest failing seed, which can then be used to
+//! reproduce the failure:
+//!
+//! ```
+//! extern crate quickcheck;
+//! extern crate rand;
+//!
+//! use quickcheck::QuickCheck;
+//! use rand::Rng;
+//! use rand::prng::XorShiftRng;
+//! use rand::rngs::quickcheck::TestRng;
+//!
+//! fn prop(mut rng: TestRng<XorShiftRng>) -> bool {
+//!     let x: f64 = rng.gen();
+//!     0.0 <= x && x < 1.0
+//! }
+//!
+//! # fn main() {
+//! QuickCheck::new().quickcheck(prop as fn(TestRng<XorShiftRng>) -> bool);
+//! # }
+//! ```
+//!
+//! This module is only available with the `quickcheck` and `std` features.
+//!
+//! [`TestSeed`]: struct.TestSeed.html
+//! [`TestRng`]: struct.TestRng.html
+
+use core::fmt;
+use quickcheck::{Arbitrary, Gen};
+use rand_core::{RngCore, SeedableRng, Error};
+
+/// A seed which can be generated and shrunk by quickcheck.
+///
+/// The bytes of the seed are generated independently. Shrinking clears the
+/// non-zero bytes one at a time, so a reported seed contains as few non-zero
+/// bytes as possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestSeed<S>(pub S);
+
+impl<S> Arbitrary for TestSeed<S>
+    where S: AsMut<[u8]> + Default + Clone + Send + 'static
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        let mut seed = S::default();
+        for b in seed.as_mut().iter_mut() {
+            *b = u8::arbitrary(g);
+        }
+        TestSeed(seed)
+    }
+
+    #[allow(unknown_lints, bare_trait_objects)]
+    fn shrink(&self) -> Box<Iterator<Item = Self>> {
+        let seed = self.0.clone();
+        let len = self.0.clone().as_mut().len();
+        Box::new((0..len).filter_map(move |i| {
+            let mut shrunk = seed.clone();
+            if shrunk.as_mut()[i] == 0 {
+                return None;
+            }
+            shrunk.as_mut()[i] = 0;
+            Some(TestSeed(shrunk))
+        }))
+    }
+}
+
+/// A generator seeded with a [`TestSeed`], for use as an argument of quickcheck
+/// properties.
+///
+/// `TestRng` forwards `RngCore` to the wrapped generator. Its `Debug` output
+/// is the seed, so the seed of a failing case is included in the report of
+/// quickcheck, and the generator can be recreated with [`TestRng::new`].
+///
+/// [`TestSeed`]: struct.TestSeed.html
+/// [`TestRng::new`]: #method.new
+pub struct TestRng<R: SeedableRng> {
+    seed: R::Seed,
+    rng: R,
+}
+
+impl<R: SeedableRng> TestRng<R> where R::Seed: Clone {
+    /// Create a new `TestRng`, seeding the generator with `seed`.
+    pub fn new(seed: R::Seed) -> Self {
+        TestRng { seed: seed.clone(), rng: R::from_seed(seed) }
+    }
+
+    /// Returns the seed the generator was created with.
+    pub fn seed(&self) -> &R::Seed {
+        &self.seed
+    }
+
+    /// Consume the `TestRng`, returning the wrapped generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: SeedableRng + Clone> Clone for TestRng<R> where R::Seed: Clone {
+    fn clone(&self) -> Self {
+        TestRng { seed: self.seed.clone(), rng: self.rng.clone() }
+    }
+}
+
+impl<R: SeedableRng> fmt::Debug for TestRng<R> where R::Seed: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TestRng").field("seed", &self.seed).finish()
+    }
+}
+
+impl<R: SeedableRng + RngCore> RngCore for TestRng<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl<R> Arbitrary for TestRng<R>
+    where R: SeedableRng + Clone + Send + 'static,
+          R::Seed: Clone + Send
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        TestRng::new(TestSeed::<R::Seed>::arbitrary(g).0)
+    }
+
+    #[allow(unknown_lints, bare_trait_objects)]
+    fn shrink(&self) -> Box<Iterator<Item = Self>> {
+        Box::new(TestSeed(self.seed.clone()).shrink()
+                 .map(|seed| TestRng::new(seed.0)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quickcheck::{Arbitrary, Gen, QuickCheck, TestResult};
+    use {RngCore, SeedableRng};
+    use prng::XorShiftRng;
+    use super::{TestRng, TestSeed};
+
+    #[test]
+    fn test_seed_shrink() {
+        let seed = TestSeed([0u8, 3, 0, 7]);
+        let shrunk: Vec<_> = seed.shrink().collect();
+        assert_eq!(shrunk, vec![TestSeed([0, 0, 0, 7]), TestSeed([0, 3, 0, 0])]);
+        assert_eq!(TestSeed([0u8; 4]).shrink().count(), 0);
+
+        let mut g = Gen::new(10);
+        let rng = TestRng::<XorShiftRng>::arbitrary(&mut g);
+        for shrunk in rng.shrink() {
+            let zeros = |s: &[u8; 16]| s.iter().filter(|&&b| b == 0).count();
+            assert_eq!(zeros(shrunk.seed()), zeros(rng.seed()) + 1);
+        }
+    }
+
+    #[test]
+    fn test_quickcheck() {
+        fn prop(mut rng: TestRng<XorShiftRng>) -> bool {
+            let mut expected = XorShiftRng::from_seed(*rng.seed());
+            rng.next_u64() == expected.next_u64()
+        }
+        QuickCheck::new().tests(100)
+            .quickcheck(prop as fn(TestRng<XorShiftRng>) -> bool);
+
+        // A failing property is reported with a shrunk seed
+        fn fails(rng: TestRng<XorShiftRng>) -> TestResult {
+            TestResult::from_bool(rng.seed()[0] == 0)
+        }
+        let result = QuickCheck::new().tests(100)
+            .quicktest(fails as fn(TestRng<XorShiftRng>) -> TestResult);
+        assert!(result.is_err());
+    }
+}
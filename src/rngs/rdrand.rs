@@ -0,0 +1,307 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generators using the RDRAND and RDSEED instructions of x86 processors.
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64 as arch;
+
+use rand_core::{RngCore, CryptoRng, Error, ErrorKind};
+
+/// Number of attempts before RDRAND is considered broken, as recommended by
+/// Intel's *Digital Random Number Generator Software Implementation Guide*.
+const RDRAND_RETRIES: usize = 10;
+
+/// Number of attempts before RDSEED is considered exhausted. Unlike RDRAND,
+/// RDSEED regularly fails when it is called in a tight loop.
+const RDSEED_RETRIES: usize = 100;
+
+/// A generator using the RDRAND instruction of x86 processors.
+///
+/// RDRAND returns numbers from a CSPRNG (AES-CTR-DRBG on Intel processors),
+/// which is continuously reseeded from an on-chip entropy source. It is
+/// available on Intel processors since Ivy Bridge and AMD processors since
+/// Zen.
+///
+/// [`new`] uses CPUID to check that the instruction is available, and fails
+/// with [`ErrorKind::Unavailable`] if it is not. Some AMD processors have a
+/// bug which makes RDRAND return all ones after a suspend; [`new`] also fails
+/// on such processors. If RDRAND fails repeatedly at a later time, which
+/// indicates a hardware problem, [`try_fill_bytes`] fails with
+/// [`ErrorKind::Unavailable`] and the other methods panic.
+///
+/// `RdRand` is not much faster than [`OsRng`], but does not depend on the
+/// operating system. Whether to trust the hardware generator is up to the
+/// user; the Linux kernel for example only mixes it into its own entropy
+/// pool. A good way to use it is as the reseeding source of a
+/// [`ReseedingRng`], or as one of several sources of a seed.
+///
+/// This requires the `rdrand` feature, and Rustc 1.27 or greater.
+///
+/// # Example
+///
+/// ```
+/// use rand::{Rng, SeedableRng};
+/// use rand::prng::hc128::Hc128Core;
+/// use rand::rngs::adapter::ReseedingRng;
+/// use rand::rngs::RdRand;
+///
+/// if let Ok(mut rdrand) = RdRand::new() {
+///     let x: u64 = rdrand.gen();
+///
+///     let mut rng = ReseedingRng::new(Hc128Core::from_rng(&mut rdrand).unwrap(),
+///                                     1 << 20, rdrand);
+///     let y: u64 = rng.gen();
+/// }
+/// ```
+///
+/// [`new`]: #method.new
+/// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
+/// [`ErrorKind::Unavailable`]: ../enum.ErrorKind.html#variant.Unavailable
+/// [`OsRng`]: struct.OsRng.html
+/// [`ReseedingRng`]: adapter/struct.ReseedingRng.html
+#[derive(Clone, Copy, Debug)]
+pub struct RdRand(());
+
+/// A generator using the RDSEED instruction of x86 processors.
+///
+/// RDSEED returns numbers directly from the conditioned on-chip entropy
+/// source, which RDRAND uses to reseed its CSPRNG. It is intended for seeding
+/// other generators, and is considerably slower than [`RdRand`]. It is
+/// available on Intel processors since Broadwell and AMD processors since
+/// Zen.
+///
+/// [`new`] uses CPUID to check that the instruction is available, and fails
+/// with [`ErrorKind::Unavailable`] if it is not. RDSEED fails when the entropy
+/// source cannot keep up; after retrying for a while, [`try_fill_bytes`]
+/// fails with [`ErrorKind::NotReady`] and the other methods panic.
+///
+/// This requires the `rdrand` feature, and Rustc 1.27 or greater.
+///
+/// # Example
+///
+/// ```
+/// use rand::{SeedableRng, StdRng};
+/// use rand::rngs::RdSeed;
+///
+/// if let Ok(mut rdseed) = RdSeed::new() {
+///     let rng = StdRng::from_rng(&mut rdseed);
+/// }
+/// ```
+///
+/// [`RdRand`]: struct.RdRand.html
+/// [`new`]: #method.new
+/// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
+/// [`ErrorKind::Unavailable`]: ../enum.ErrorKind.html#variant.Unavailable
+/// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+#[derive(Clone, Copy, Debug)]
+pub struct RdSeed(());
+
+impl RdRand {
+    /// Create a new `RdRand`, if the processor supports RDRAND.
+    pub fn new() -> Result<RdRand, Error> {
+        if !has_rdrand() {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "RDRAND instruction not supported"));
+        }
+        // Some AMD processors return all ones after a suspend and resume
+        let mut rng = RdRand(());
+        let mut broken = true;
+        for _ in 0..8 {
+            if rng.try_next_word()? != !0 {
+                broken = false;
+                break;
+            }
+        }
+        if broken {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "RDRAND instruction returns constant values"));
+        }
+        Ok(rng)
+    }
+
+    fn try_next_word(&mut self) -> Result<u64, Error> {
+        for _ in 0..RDRAND_RETRIES {
+            if let Some(x) = unsafe { rdrand_step() } {
+                return Ok(x);
+            }
+        }
+        Err(Error::new(ErrorKind::Unavailable, "RDRAND instruction failed repeatedly"))
+    }
+}
+
+impl RdSeed {
+    /// Create a new `RdSeed`, if the processor supports RDSEED.
+    pub fn new() -> Result<RdSeed, Error> {
+        if !has_rdseed() {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "RDSEED instruction not supported"));
+        }
+        Ok(RdSeed(()))
+    }
+
+    /// Create a new `RdSeed` without checking that the processor supports
+    /// RDSEED, for environments where CPUID is not available.
+    #[cfg(target_env = "sgx")]
+    pub(crate) fn new_unchecked() -> RdSeed {
+        RdSeed(())
+    }
+
+    fn try_next_word(&mut self) -> Result<u64, Error> {
+        for _ in 0..RDSEED_RETRIES {
+            if let Some(x) = unsafe { rdseed_step() } {
+                return Ok(x);
+            }
+        }
+        Err(Error::new(ErrorKind::NotReady, "RDSEED instruction is out of entropy"))
+    }
+}
+
+macro_rules! impl_rng_core {
+    ($name:ident) => {
+        impl RngCore for $name {
+            fn next_u32(&mut self) -> u32 {
+                self.next_u64() as u32
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                self.try_next_word().unwrap_or_else(|err|
+                    panic!("{}: {}", stringify!($name), err))
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.try_fill_bytes(dest).unwrap_or_else(|err|
+                    panic!("{}: {}", stringify!($name), err))
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+                for chunk in dest.chunks_mut(8) {
+                    let x = self.try_next_word()?;
+                    for (i, b) in chunk.iter_mut().enumerate() {
+                        *b = (x >> (8 * i)) as u8;
+                    }
+                }
+                Ok(())
+            }
+
+            fn try_next_u32(&mut self) -> Result<u32, Error> {
+                self.try_next_word().map(|x| x as u32)
+            }
+
+            fn try_next_u64(&mut self) -> Result<u64, Error> {
+                self.try_next_word()
+            }
+        }
+
+        impl CryptoRng for $name {}
+    }
+}
+
+impl_rng_core!(RdRand);
+impl_rng_core!(RdSeed);
+
+#[cfg(feature="std")]
+fn has_rdrand() -> bool {
+    is_x86_feature_detected!("rdrand")
+}
+
+#[cfg(feature="std")]
+fn has_rdseed() -> bool {
+    is_x86_feature_detected!("rdseed")
+}
+
+#[cfg(not(feature="std"))]
+fn has_rdrand() -> bool {
+    // CPUID leaf 1, ECX bit 30
+    cpuid(1, 0).ecx & (1 << 30) != 0
+}
+
+#[cfg(not(feature="std"))]
+fn has_rdseed() -> bool {
+    // CPUID leaf 7, sub-leaf 0, EBX bit 18
+    cpuid(0, 0).eax >= 7 && cpuid(7, 0).ebx & (1 << 18) != 0
+}
+
+// All x86 processors supported by Rustc (i586 and newer) have the CPUID
+// instruction, so there is no need to check for it.
+// `__cpuid_count` is a safe function in newer versions of Rustc
+#[cfg(not(feature="std"))]
+#[allow(unused_unsafe)]
+fn cpuid(leaf: u32, sub_leaf: u32) -> arch::CpuidResult {
+    unsafe { arch::__cpuid_count(leaf, sub_leaf) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand_step() -> Option<u64> {
+    let mut x = 0;
+    if arch::_rdrand64_step(&mut x) == 1 { Some(x) } else { None }
+}
+
+#[cfg(target_arch = "x86")]
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand_step() -> Option<u64> {
+    let (mut lo, mut hi) = (0, 0);
+    if arch::_rdrand32_step(&mut lo) == 1 && arch::_rdrand32_step(&mut hi) == 1 {
+        Some((hi as u64) << 32 | lo as u64)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdseed")]
+unsafe fn rdseed_step() -> Option<u64> {
+    let mut x = 0;
+    if arch::_rdseed64_step(&mut x) == 1 { Some(x) } else { None }
+}
+
+#[cfg(target_arch = "x86")]
+#[target_feature(enable = "rdseed")]
+unsafe fn rdseed_step() -> Option<u64> {
+    let (mut lo, mut hi) = (0, 0);
+    if arch::_rdseed32_step(&mut lo) == 1 && arch::_rdseed32_step(&mut hi) == 1 {
+        Some((hi as u64) << 32 | lo as u64)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use RngCore;
+    use super::{RdRand, RdSeed};
+
+    #[test]
+    fn test_rdrand() {
+        // The processor running the tests may not support RDRAND
+        let mut rng = match RdRand::new() {
+            Ok(rng) => rng,
+            Err(_) => return,
+        };
+        let mut buf = [0u8; 21];
+        rng.fill_bytes(&mut buf);
+        assert!(buf.iter().any(|&b| b != 0));
+        assert!(rng.next_u64() != rng.next_u64());
+    }
+
+    #[test]
+    fn test_rdseed() {
+        let mut rng = match RdSeed::new() {
+            Ok(rng) => rng,
+            Err(_) => return,
+        };
+        let mut buf = [0u8; 21];
+        rng.try_fill_bytes(&mut buf).unwrap();
+        assert!(buf.iter().any(|&b| b != 0));
+    }
+}
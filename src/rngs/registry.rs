@@ -0,0 +1,182 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Selection of RNG algorithms by name at runtime.
+
+use core::fmt;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::boxed::Box;
+
+use {RngCore, SeedableRng};
+use prng::{ChaChaRng, Hc128Rng, IsaacRng, Isaac64Rng, SplitMix64, XorShiftRng,
+           Xoshiro256StarStar, Xoshiro256StarStarX4};
+use rngs::XofRng;
+
+/// A boxed RNG of a type chosen at runtime, as returned by [`by_name`].
+///
+/// [`by_name`]: fn.by_name.html
+#[allow(unknown_lints, bare_trait_objects)]
+pub type DynRng = Box<RngCore + Send>;
+
+/// Information about an RNG algorithm available through [`by_name`].
+///
+/// [`by_name`]: fn.by_name.html
+#[derive(Clone, Copy)]
+pub struct RngInfo {
+    /// The name of the algorithm, as accepted by [`by_name`].
+    ///
+    /// [`by_name`]: fn.by_name.html
+    pub name: &'static str,
+    /// The length of the seed in bytes.
+    pub seed_size: usize,
+    /// Whether the algorithm is a cryptographically secure PRNG.
+    pub crypto: bool,
+    new: fn(&[u8]) -> DynRng,
+}
+
+impl fmt::Debug for RngInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RngInfo")
+            .field("name", &self.name)
+            .field("seed_size", &self.seed_size)
+            .field("crypto", &self.crypto)
+            .finish()
+    }
+}
+
+fn new_boxed<R>(seed: &[u8]) -> DynRng
+    where R: SeedableRng + RngCore + Send + 'static
+{
+    let mut s = R::Seed::default();
+    s.as_mut().copy_from_slice(seed);
+    Box::new(R::from_seed(s))
+}
+
+static ALGORITHMS: [RngInfo; 9] = [
+    RngInfo { name: "chacha20", seed_size: 32, crypto: true,
+              new: new_boxed::<ChaChaRng> },
+    RngInfo { name: "hc128", seed_size: 32, crypto: true,
+              new: new_boxed::<Hc128Rng> },
+    RngInfo { name: "isaac", seed_size: 32, crypto: false,
+              new: new_boxed::<IsaacRng> },
+    RngInfo { name: "isaac64", seed_size: 32, crypto: false,
+              new: new_boxed::<Isaac64Rng> },
+    RngInfo { name: "shake128", seed_size: 32, crypto: true,
+              new: new_boxed::<XofRng> },
+    RngInfo { name: "splitmix64", seed_size: 8, crypto: false,
+              new: new_boxed::<SplitMix64> },
+    RngInfo { name: "xorshift128", seed_size: 16, crypto: false,
+              new: new_boxed::<XorShiftRng> },
+    RngInfo { name: "xoshiro256**", seed_size: 32, crypto: false,
+              new: new_boxed::<Xoshiro256StarStar> },
+    RngInfo { name: "xoshiro256**x4", seed_size: 32, crypto: false,
+              new: new_boxed::<Xoshiro256StarStarX4> },
+];
+
+/// Returns the RNG algorithms available through [`by_name`].
+///
+/// [`by_name`]: fn.by_name.html
+pub fn algorithms() -> &'static [RngInfo] {
+    &ALGORITHMS
+}
+
+/// Create an RNG of the algorithm named `name`, seeded with `seed`.
+///
+/// This makes it possible to choose the algorithm at runtime, for example
+/// from a command line flag or a configuration file. The names of the
+/// available algorithms and their seed sizes are listed by [`algorithms`].
+/// The RNG is constructed with [`SeedableRng::from_seed`], so the output for
+/// a given name and seed is reproducible.
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::registry;
+///
+/// for info in registry::algorithms() {
+///     println!("{}: {} byte seed", info.name, info.seed_size);
+/// }
+///
+/// let mut rng = registry::by_name("xoshiro256**", &[1; 32]).unwrap();
+/// let x: f64 = rng.gen();
+/// ```
+///
+/// [`algorithms`]: fn.algorithms.html
+/// [`SeedableRng::from_seed`]: ../../trait.SeedableRng.html#tymethod.from_seed
+pub fn by_name(name: &str, seed: &[u8]) -> Result<DynRng, ByNameError> {
+    match ALGORITHMS.iter().find(|info| info.name == name) {
+        Some(info) => {
+            if seed.len() != info.seed_size {
+                return Err(ByNameError::SeedSize(info.seed_size));
+            }
+            Ok((info.new)(seed))
+        }
+        None => Err(ByNameError::UnknownName),
+    }
+}
+
+/// An error returned by [`by_name`].
+///
+/// [`by_name`]: fn.by_name.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByNameError {
+    /// No algorithm has the given name.
+    UnknownName,
+    /// The seed does not have the given required length.
+    SeedSize(usize),
+}
+
+impl ByNameError {
+    fn msg(&self) -> &'static str {
+        match *self {
+            ByNameError::UnknownName => "unknown RNG algorithm",
+            ByNameError::SeedSize(_) => "seed has the wrong length",
+        }
+    }
+}
+
+impl fmt::Display for ByNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ByNameError::SeedSize(n) => write!(f, "{}, expected {} bytes", self.msg(), n),
+            _ => write!(f, "{}", self.msg()),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl ::std::error::Error for ByNameError {
+    fn description(&self) -> &str {
+        self.msg()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use RngCore;
+    use super::*;
+
+    #[test]
+    fn test_by_name() {
+        for info in algorithms() {
+            let seed = [7u8; 32];
+            let mut rng1 = by_name(info.name, &seed[..info.seed_size]).unwrap();
+            let mut rng2 = by_name(info.name, &seed[..info.seed_size]).unwrap();
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+
+        let mut rng = by_name("xoshiro256**", &[1; 32]).unwrap();
+        let mut expected = Xoshiro256StarStar::from_seed([1; 32]);
+        assert_eq!(rng.next_u64(), expected.next_u64());
+
+        assert_eq!(by_name("mt19937", &[1; 32]).err(), Some(ByNameError::UnknownName));
+        assert_eq!(by_name("hc128", &[1; 16]).err(), Some(ByNameError::SeedSize(32)));
+    }
+}
@@ -10,8 +10,8 @@
 
 //! A small fast RNG
 
-use {RngCore, SeedableRng, Error};
-use prng::XorShiftRng;
+use {RngCore, SeedableRng, Error, BatchRng};
+use prng::Xoshiro256StarStar;
 
 /// An RNG recommended when small state, cheap initialization and good
 /// performance are required. The PRNG algorithm in `SmallRng` is chosen to be
@@ -22,9 +22,12 @@ use prng::XorShiftRng;
 /// future library versions may use a different internal generator with
 /// different output. Further, this generator may not be portable and can
 /// produce different output depending on the architecture. If you require
-/// reproducible output, use a named RNG, for example [`XorShiftRng`].
+/// reproducible output, use a named RNG, for example [`Xoshiro256StarStar`].
 ///
-/// The current algorithm used on all platforms is [Xorshift].
+/// The current algorithm used on all platforms is [xoshiro256\*\*].
+///
+/// `SmallRng` is only available with the `small_rng` feature, which is enabled
+/// by default.
 ///
 /// # Examples
 ///
@@ -63,10 +66,10 @@ use prng::XorShiftRng;
 /// [`FromEntropy`]: ../trait.FromEntropy.html
 /// [`StdRng`]: struct.StdRng.html
 /// [`thread_rng`]: ../fn.thread_rng.html
-/// [Xorshift]: ../prng/struct.XorShiftRng.html
-/// [`XorShiftRng`]: ../prng/struct.XorShiftRng.html
+/// [xoshiro256\*\*]: ../prng/struct.Xoshiro256StarStar.html
+/// [`Xoshiro256StarStar`]: ../prng/struct.Xoshiro256StarStar.html
 #[derive(Clone, Debug)]
-pub struct SmallRng(XorShiftRng);
+pub struct SmallRng(Xoshiro256StarStar);
 
 impl RngCore for SmallRng {
     #[inline(always)]
@@ -88,14 +91,16 @@ impl RngCore for SmallRng {
     }
 }
 
+impl BatchRng for SmallRng {}
+
 impl SeedableRng for SmallRng {
-    type Seed = <XorShiftRng as SeedableRng>::Seed;
+    type Seed = <Xoshiro256StarStar as SeedableRng>::Seed;
 
     fn from_seed(seed: Self::Seed) -> Self {
-        SmallRng(XorShiftRng::from_seed(seed))
+        SmallRng(Xoshiro256StarStar::from_seed(seed))
     }
 
     fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
-        XorShiftRng::from_rng(rng).map(SmallRng)
+        Xoshiro256StarStar::from_rng(rng).map(SmallRng)
     }
 }
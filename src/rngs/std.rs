@@ -10,7 +10,7 @@
 
 //! The standard RNG
 
-use {RngCore, CryptoRng, Error, SeedableRng};
+use {RngCore, CryptoRng, Error, SeedableRng, BatchRng};
 use prng::Hc128Rng;
 
 /// The standard RNG. The PRNG algorithm in `StdRng` is chosen to be efficient
@@ -64,6 +64,12 @@ impl SeedableRng for StdRng {
 
 impl CryptoRng for StdRng {}
 
+impl BatchRng for StdRng {
+    fn next_u32_into(&mut self, dest: &mut [u32]) {
+        self.0.next_u32_into(dest)
+    }
+}
+
 
 #[cfg(test)]
 mod test {
@@ -15,7 +15,8 @@ use std::rc::Rc;
 
 use {RngCore, CryptoRng, SeedableRng, Error};
 use rngs::adapter::ReseedingRng;
-use rngs::EntropyRng;
+use rngs::{EntropyRng, OsRng};
+use prng::chacha::ChaChaCore;
 use prng::hc128::Hc128Core;
 
 // Rationale for using `UnsafeCell` in `ThreadRng`:
@@ -32,18 +33,24 @@ use prng::hc128::Hc128Core;
 // `ThreadRng` internally, which is nonsensical anyway. We should also never run
 // `ThreadRng` in destructors of its implementation, which is also nonsensical.
 //
-// The additional `Rc` is not strictly neccesary, and could be removed. For now
+// The additional `Rc` is not strictly necessary, and could be removed. For now
 // it ensures `ThreadRng` stays `!Send` and `!Sync`, and implements `Clone`.
 
 
-// Number of generated bytes after which to reseed `TreadRng`.
+// Number of generated bytes after which to reseed `ThreadRng`.
 //
 // The time it takes to reseed HC-128 is roughly equivalent to generating 7 KiB.
-// We pick a treshold here that is large enough to not reduce the average
+// We pick a threshold here that is large enough to not reduce the average
 // performance too much, but also small enough to not make reseeding something
 // that basically never happens.
 const THREAD_RNG_RESEED_THRESHOLD: u64 = 32*1024*1024; // 32 MiB
 
+// Number of generated bytes after which to reseed `ThreadCryptoRng`.
+//
+// Reseeding ChaCha is cheap compared to reading from the OS, which dominates
+// the cost; at 1 MiB it is well below 1% of the generation time.
+const THREAD_CRYPTO_RNG_RESEED_THRESHOLD: u64 = 1024*1024; // 1 MiB
+
 /// The type returned by [`thread_rng`], essentially just a reference to the
 /// PRNG in thread-local memory.
 ///
@@ -123,6 +130,90 @@ impl RngCore for ThreadRng {
 
 impl CryptoRng for ThreadRng {}
 
+/// The type returned by [`thread_crypto_rng`], a reference to a CSPRNG in
+/// thread-local memory, meant for generating keys, tokens and nonces.
+///
+/// [`ThreadRng`] is also a cryptographically secure PRNG, but it is optimized
+/// for speed, and its algorithm and entropy sources may change to cover
+/// typical usage. `ThreadCryptoRng` makes more conservative choices:
+///
+/// - The algorithm is [ChaCha20], a widely analysed stream cipher with a small
+///   state.
+/// - It is seeded and reseeded only from [`OsRng`], never from a fallback
+///   entropy source. If `OsRng` is not available, the first use of
+///   `ThreadCryptoRng` panics, and a failed reseed is retried later (see
+///   [`ReseedingRng`]).
+/// - It is reseeded after every 1 MiB of generated data.
+///
+/// A single instance is cached per thread; `ThreadCryptoRng` is neither `Send`
+/// nor `Sync`. Cloning this handle produces a new reference to the same
+/// thread-local generator.
+///
+/// [`thread_crypto_rng`]: ../fn.thread_crypto_rng.html
+/// [`ThreadRng`]: struct.ThreadRng.html
+/// [`OsRng`]: struct.OsRng.html
+/// [`ReseedingRng`]: adapter/struct.ReseedingRng.html
+/// [ChaCha20]: ../prng/chacha/struct.ChaChaRng.html
+#[derive(Clone, Debug)]
+pub struct ThreadCryptoRng {
+    rng: Rc<UnsafeCell<ReseedingRng<ChaChaCore, OsRng>>>,
+}
+
+thread_local!(
+    static THREAD_CRYPTO_RNG_KEY: Rc<UnsafeCell<ReseedingRng<ChaChaCore, OsRng>>> = {
+        let mut os_rng = OsRng::new().unwrap_or_else(|err|
+                panic!("could not initialize thread_crypto_rng: {}", err));
+        let r = ChaChaCore::from_rng(&mut os_rng).unwrap_or_else(|err|
+                panic!("could not initialize thread_crypto_rng: {}", err));
+        let rng = ReseedingRng::new(r,
+                                    THREAD_CRYPTO_RNG_RESEED_THRESHOLD,
+                                    os_rng);
+        Rc::new(UnsafeCell::new(rng))
+    }
+);
+
+/// Retrieve the lazily-initialized thread-local cryptographically secure
+/// random number generator, seeded by the operating system.
+///
+/// For more information see [`ThreadCryptoRng`].
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+///
+/// let mut token = [0u8; 32];
+/// rand::thread_crypto_rng().fill(&mut token);
+/// ```
+///
+/// [`ThreadCryptoRng`]: rngs/struct.ThreadCryptoRng.html
+pub fn thread_crypto_rng() -> ThreadCryptoRng {
+    ThreadCryptoRng { rng: THREAD_CRYPTO_RNG_KEY.with(|t| t.clone()) }
+}
+
+// The same rationale for `UnsafeCell` applies as for `ThreadRng`.
+impl RngCore for ThreadCryptoRng {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        unsafe { (*self.rng.get()).next_u32() }
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        unsafe { (*self.rng.get()).next_u64() }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        unsafe { (*self.rng.get()).fill_bytes(dest) }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        unsafe { (*self.rng.get()).try_fill_bytes(dest) }
+    }
+}
+
+impl CryptoRng for ThreadCryptoRng {}
+
 
 #[cfg(test)]
 mod test {
@@ -138,4 +229,30 @@ mod test {
         assert_eq!(v, b);
         assert_eq!(r.gen_range(0, 1), 0);
     }
+
+    #[test]
+    #[cfg(not(feature="stdweb"))]
+    fn test_thread_crypto_rng() {
+        use std::thread;
+        use {Rng, CryptoRng, RngCore};
+        fn is_crypto<R: RngCore + CryptoRng>(_: &R) {}
+
+        let mut r = ::thread_crypto_rng();
+        is_crypto(&r);
+        let a: [u64; 4] = r.gen();
+        assert!(a != ::thread_crypto_rng().gen::<[u64; 4]>());
+        let b: [u64; 4] = thread::spawn(|| ::thread_crypto_rng().gen()).join().unwrap();
+        assert!(a != b);
+    }
+
+    #[test]
+    #[cfg(not(feature="stdweb"))]
+    fn test_thread_rng_per_thread() {
+        use std::thread;
+        use Rng;
+        // Each thread has its own independently seeded generator
+        let a: [u64; 4] = ::thread_rng().gen();
+        let b: [u64; 4] = thread::spawn(|| ::thread_rng().gen()).join().unwrap();
+        assert!(a != b);
+    }
 }
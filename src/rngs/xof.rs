@@ -0,0 +1,253 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A random number generator based on an extendable-output function.
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, CryptoRng, Error, impls};
+
+/// Number of bytes absorbed or squeezed per permutation (SHAKE128).
+const RATE: usize = 168;
+
+/// A deterministic random number generator seeded by arbitrary byte strings,
+/// using the SHAKE128 extendable-output function.
+///
+/// The seed, and an optional label for domain separation, are hashed with
+/// [SHAKE128] (FIPS 202), and the output of the hash is used as random stream.
+/// This makes it possible to derive reproducible, independent streams from
+/// human-readable keys, like `"experiment-42"` and `"worker-7"`, instead of
+/// having to map them to fixed-size seeds.
+///
+/// The input of SHAKE128 is the length of the label as 64-bit little-endian
+/// integer, followed by the label and the seed. Because the length is
+/// included, different labels always produce different streams. The output
+/// for a given label and seed is fixed and will not change in future
+/// versions of Rand.
+///
+/// SHAKE128 is a cryptographic function, so `XofRng` is a CSPRNG if the seed
+/// is secret and has enough entropy. It is however considerably slower than
+/// [`ChaChaRng`] or [`Hc128Rng`].
+///
+/// # Example
+///
+/// ```
+/// use rand::Rng;
+/// use rand::rngs::XofRng;
+///
+/// let mut rng = XofRng::with_label(b"experiment-42", b"worker-7");
+/// let x: f64 = rng.gen();
+///
+/// // The same label and seed produce the same stream
+/// let mut rng2 = XofRng::with_label(b"experiment-42", b"worker-7");
+/// assert_eq!(rng2.gen::<f64>(), x);
+/// ```
+///
+/// [SHAKE128]: https://en.wikipedia.org/wiki/SHA-3
+/// [`ChaChaRng`]: ../prng/chacha/struct.ChaChaRng.html
+/// [`Hc128Rng`]: ../prng/hc128/struct.Hc128Rng.html
+#[derive(Clone)]
+pub struct XofRng {
+    state: [u64; 25],
+    // Position of the next byte in the current block of the state
+    pos: usize,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for XofRng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "XofRng {{}}")
+    }
+}
+
+impl XofRng {
+    /// Create a new `XofRng` from a seed of any length.
+    ///
+    /// This is the same as `XofRng::with_label(b"", seed)`.
+    pub fn new(seed: &[u8]) -> XofRng {
+        XofRng::with_label(b"", seed)
+    }
+
+    /// Create a new `XofRng` from a domain-separation label and a seed of any
+    /// length.
+    pub fn with_label(label: &[u8], seed: &[u8]) -> XofRng {
+        let mut rng = XofRng { state: [0; 25], pos: 0 };
+        let mut len = [0u8; 8];
+        for (i, byte) in len.iter_mut().enumerate() {
+            *byte = ((label.len() as u64) >> (8 * i)) as u8;
+        }
+        rng.absorb(&len);
+        rng.absorb(label);
+        rng.absorb(seed);
+        rng.finalize();
+        rng
+    }
+
+    fn absorb(&mut self, data: &[u8]) {
+        for &byte in data.iter() {
+            self.state[self.pos / 8] ^= (byte as u64) << (8 * (self.pos % 8));
+            self.pos += 1;
+            if self.pos == RATE {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    fn finalize(&mut self) {
+        // SHAKE domain separation and padding
+        self.state[self.pos / 8] ^= 0x1f << (8 * (self.pos % 8));
+        self.state[(RATE - 1) / 8] ^= 0x80 << (8 * ((RATE - 1) % 8));
+        keccak_f(&mut self.state);
+        self.pos = 0;
+    }
+}
+
+impl RngCore for XofRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest.iter_mut() {
+            if self.pos == RATE {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+            *byte = (self.state[self.pos / 8] >> (8 * (self.pos % 8))) as u8;
+            self.pos += 1;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+impl SeedableRng for XofRng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        XofRng::new(&seed)
+    }
+}
+
+impl CryptoRng for XofRng {}
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a,
+    0x8000000080008000, 0x000000000000808b, 0x0000000080000001,
+    0x8000000080008081, 0x8000000000008009, 0x000000000000008a,
+    0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089,
+    0x8000000000008003, 0x8000000000008002, 0x8000000000000080,
+    0x000000000000800a, 0x800000008000000a, 0x8000000080008081,
+    0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14,
+    27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI_LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4,
+    15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f[1600] permutation.
+fn keccak_f(state: &mut [u64; 25]) {
+    let mut c = [0u64; 5];
+    for rc in ROUND_CONSTANTS.iter() {
+        // Theta
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[5 * y + x] ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut last = state[1];
+        for (&lane, &rotation) in PI_LANES.iter().zip(ROTATIONS.iter()) {
+            let next = state[lane];
+            state[lane] = last.rotate_left(rotation);
+            last = next;
+        }
+
+        // Chi
+        for y in 0..5 {
+            c.copy_from_slice(&state[5 * y..5 * y + 5]);
+            for x in 0..5 {
+                state[5 * y + x] = c[x] ^ (!c[(x + 1) % 5] & c[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        state[0] ^= *rc;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng};
+    use super::XofRng;
+
+    #[test]
+    fn test_xof_true_values() {
+        // Test vectors computed with an independent SHAKE128 implementation
+        let mut rng = XofRng::new(b"");
+        let mut results = [0u8; 16];
+        rng.fill_bytes(&mut results);
+        assert_eq!(results, [0x7a, 0x24, 0xb6, 0x66, 0xda, 0x34, 0x5c, 0x98,
+                             0xc3, 0xa4, 0x00, 0xaa, 0xfd, 0x14, 0xa5, 0x1a]);
+
+        let mut rng = XofRng::with_label(b"experiment-42", b"worker-7");
+        rng.fill_bytes(&mut results);
+        assert_eq!(results, [0x53, 0xf1, 0xb5, 0xd5, 0xed, 0xa7, 0x0d, 0xcd,
+                             0x45, 0xee, 0x33, 0xf3, 0xc8, 0x2d, 0x5c, 0x09]);
+
+        // Absorbing and squeezing more than one block
+        let mut seed = [0u8; 200];
+        for (i, x) in seed.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        let mut rng = XofRng::new(&seed);
+        let mut skip = [0u8; 160];
+        rng.fill_bytes(&mut skip);
+        rng.fill_bytes(&mut results);
+        assert_eq!(results, [0xdc, 0xce, 0x86, 0x64, 0xaa, 0x7f, 0x73, 0x35,
+                             0x46, 0x40, 0xf3, 0x72, 0xe8, 0xb6, 0x0b, 0xe4]);
+    }
+
+    #[test]
+    fn test_xof_domain_separation() {
+        let a = XofRng::with_label(b"a", b"b").next_u64();
+        let b = XofRng::with_label(b"", b"ab").next_u64();
+        let c = XofRng::with_label(b"ab", b"").next_u64();
+        assert!(a != b && b != c && a != c);
+    }
+
+    #[test]
+    fn test_xof_from_seed() {
+        let seed = [7u8; 32];
+        let mut rng1 = XofRng::from_seed(seed);
+        let mut rng2 = XofRng::new(&seed);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+        let mut rng3 = rng1.clone();
+        assert_eq!(rng1.next_u32(), rng3.next_u32());
+    }
+}
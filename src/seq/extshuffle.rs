@@ -0,0 +1,284 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Shuffling of datasets larger than memory.
+//!
+//! [`ExtShuffle`] shuffles a stream of records using temporary files: each
+//! record is appended to a randomly chosen bucket file, and when all records
+//! have been added, the buckets are read back one at a time, shuffled in
+//! memory and written to the output. Only one bucket needs to fit in memory,
+//! so with `b` buckets a dataset of about `b` times the available memory can
+//! be shuffled. The result is an unbiased permutation of the records.
+//!
+//! Records are arbitrary byte strings. [`shuffle_lines`] shuffles the lines
+//! of a text stream.
+//!
+//! # Example
+//!
+//! ```
+//! use rand::thread_rng;
+//! use rand::seq::extshuffle::ExtShuffle;
+//!
+//! let mut rng = thread_rng();
+//! let mut shuffle = ExtShuffle::new(&mut rng, 4).unwrap();
+//! for i in 0..1000u32 {
+//!     shuffle.push(&mut rng, format!("record {}\n", i).as_bytes()).unwrap();
+//! }
+//! let mut output = Vec::new();
+//! shuffle.finish(&mut rng, &mut output).unwrap();
+//! assert_eq!(output.iter().filter(|&&b| b == b'\n').count(), 1000);
+//! ```
+//!
+//! [`ExtShuffle`]: struct.ExtShuffle.html
+//! [`shuffle_lines`]: fn.shuffle_lines.html
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use Rng;
+use super::SliceRandom;
+
+/// A shuffle of a stream of records through temporary files.
+///
+/// See the [module documentation](index.html) for details.
+///
+/// The temporary files are removed when the `ExtShuffle` is dropped or
+/// finished.
+#[derive(Debug)]
+pub struct ExtShuffle {
+    buckets: Vec<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    path: PathBuf,
+    file: Option<BufWriter<File>>,
+}
+
+impl Drop for Bucket {
+    fn drop(&mut self) {
+        // Close the file before removing it.
+        self.file.take();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl ExtShuffle {
+    /// Create a new shuffle with `num_buckets` temporary files in the
+    /// temporary directory of the system.
+    ///
+    /// The expected size of each bucket is the total size of the records
+    /// divided by `num_buckets`.
+    ///
+    /// Panics if `num_buckets` is zero.
+    pub fn new<R: Rng + ?Sized>(rng: &mut R, num_buckets: usize) -> io::Result<ExtShuffle> {
+        ExtShuffle::new_in(env::temp_dir(), rng, num_buckets)
+    }
+
+    /// Create a new shuffle with `num_buckets` temporary files in the
+    /// directory `dir`.
+    ///
+    /// Panics if `num_buckets` is zero.
+    pub fn new_in<P, R>(dir: P, rng: &mut R, num_buckets: usize) -> io::Result<ExtShuffle>
+        where P: AsRef<Path>, R: Rng + ?Sized
+    {
+        assert!(num_buckets > 0, "ExtShuffle: `num_buckets` must be positive");
+        let mut buckets = Vec::with_capacity(num_buckets);
+        for _ in 0..num_buckets {
+            buckets.push(Bucket::create(dir.as_ref(), rng)?);
+        }
+        Ok(ExtShuffle { buckets })
+    }
+
+    /// Add a record to the shuffle.
+    pub fn push<R: Rng + ?Sized>(&mut self, rng: &mut R, record: &[u8]) -> io::Result<()> {
+        let i = rng.gen_range(0, self.buckets.len());
+        let file = self.buckets[i].file.as_mut().unwrap();
+        file.write_all(&encode_len(record.len() as u64))?;
+        file.write_all(record)
+    }
+
+    /// Add each line of `reader` as a record, including its line terminator.
+    ///
+    /// Returns the number of lines added.
+    pub fn push_lines<B, R>(&mut self, rng: &mut R, mut reader: B) -> io::Result<u64>
+        where B: BufRead, R: Rng + ?Sized
+    {
+        let mut line = Vec::new();
+        let mut count = 0;
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(count);
+            }
+            self.push(rng, &line)?;
+            count += 1;
+        }
+    }
+
+    /// Write all records in random order to `writer`, and remove the
+    /// temporary files.
+    ///
+    /// The records are written back to back, without separators. Each bucket
+    /// is read into memory in turn.
+    pub fn finish<W, R>(self, rng: &mut R, mut writer: W) -> io::Result<()>
+        where W: Write, R: Rng + ?Sized
+    {
+        for mut bucket in self.buckets {
+            let mut records = bucket.read_records()?;
+            records.shuffle(rng);
+            for record in records.iter() {
+                writer.write_all(record)?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+impl Bucket {
+    fn create<R: Rng + ?Sized>(dir: &Path, rng: &mut R) -> io::Result<Bucket> {
+        loop {
+            let path = dir.join(format!("rand-extshuffle-{:016x}", rng.gen::<u64>()));
+            match OpenOptions::new().read(true).write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok(Bucket { path, file: Some(BufWriter::new(file)) }),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn read_records(&mut self) -> io::Result<Vec<Vec<u8>>> {
+        let mut file = match self.file.take() {
+            Some(writer) => writer.into_inner()?,
+            None => return Ok(Vec::new()),
+        };
+        file.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(file);
+        let mut records = Vec::new();
+        let mut len = [0u8; 8];
+        loop {
+            match reader.read_exact(&mut len) {
+                Ok(()) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(records),
+                Err(e) => return Err(e),
+            }
+            let mut record = vec![0u8; decode_len(len) as usize];
+            reader.read_exact(&mut record)?;
+            records.push(record);
+        }
+    }
+}
+
+/// Encode the length of a record as little-endian bytes.
+fn encode_len(len: u64) -> [u8; 8] {
+    let mut bytes = [0u8; 8];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (len >> (8 * i)) as u8;
+    }
+    bytes
+}
+
+/// Decode the length of a record from little-endian bytes.
+fn decode_len(bytes: [u8; 8]) -> u64 {
+    bytes.iter().rev().fold(0, |len, &byte| (len << 8) | byte as u64)
+}
+
+/// Shuffle the lines of `reader` and write them to `writer`, using
+/// `num_buckets` temporary files in the temporary directory of the system.
+///
+/// Returns the number of lines. A last line without terminator is written
+/// without terminator, which may join it with the following line; terminate
+/// the input with a newline to avoid this.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use rand::thread_rng;
+/// use rand::seq::extshuffle;
+///
+/// let input = Cursor::new("a\nb\nc\nd\n");
+/// let mut output = Vec::new();
+/// let lines = extshuffle::shuffle_lines(&mut thread_rng(), input, &mut output, 2).unwrap();
+/// assert_eq!(lines, 4);
+/// assert_eq!(output.len(), 8);
+/// ```
+pub fn shuffle_lines<R, B, W>(rng: &mut R, reader: B, writer: W, num_buckets: usize)
+    -> io::Result<u64>
+    where R: Rng + ?Sized, B: BufRead, W: Write
+{
+    let mut shuffle = ExtShuffle::new(rng, num_buckets)?;
+    let lines = shuffle.push_lines(rng, reader)?;
+    shuffle.finish(rng, writer)?;
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_extshuffle() {
+        let mut r = ::test::rng(435);
+        let dir = env::temp_dir();
+        let mut shuffle = ExtShuffle::new_in(&dir, &mut r, 8).unwrap();
+        let paths: Vec<PathBuf> = shuffle.buckets.iter().map(|b| b.path.clone()).collect();
+        assert!(paths.iter().all(|p| p.exists()));
+
+        for i in 0..10_000u64 {
+            shuffle.push(&mut r, &encode_len(i)).unwrap();
+        }
+        shuffle.push(&mut r, &[]).unwrap();
+        let mut output = Vec::new();
+        shuffle.finish(&mut r, &mut output).unwrap();
+        assert!(paths.iter().all(|p| !p.exists()));
+
+        assert_eq!(output.len(), 80_000);
+        let mut values: Vec<u64> = output.chunks(8).map(|c| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(c);
+            decode_len(bytes)
+        }).collect();
+        assert!(values.iter().enumerate().filter(|&(i, &x)| i as u64 == x).count() < 10);
+        values.sort();
+        assert!(values.iter().enumerate().all(|(i, &x)| i as u64 == x));
+
+        // Dropping removes the files
+        let shuffle = ExtShuffle::new_in(&dir, &mut r, 2).unwrap();
+        let paths: Vec<PathBuf> = shuffle.buckets.iter().map(|b| b.path.clone()).collect();
+        drop(shuffle);
+        assert!(paths.iter().all(|p| !p.exists()));
+    }
+
+    #[test]
+    fn test_shuffle_lines() {
+        let mut r = ::test::rng(436);
+        let input = "a\nb\nc\n";
+        let mut counts: Vec<(String, i32)> = Vec::new();
+        for _ in 0..1200 {
+            let mut output = Vec::new();
+            let lines = shuffle_lines(&mut r, Cursor::new(input), &mut output, 2).unwrap();
+            assert_eq!(lines, 3);
+            let output = String::from_utf8(output).unwrap();
+            match counts.iter().position(|entry| entry.0 == output) {
+                Some(pos) => counts[pos].1 += 1,
+                None => counts.push((output, 1)),
+            }
+        }
+        // All 6 permutations should be about equally likely
+        assert_eq!(counts.len(), 6);
+        for &(_, c) in counts.iter() {
+            assert!(c > 140 && c < 260, "{:?}", counts);
+        }
+    }
+}
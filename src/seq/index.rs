@@ -0,0 +1,454 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Index sampling.
+//!
+//! The [`sample`] function returns a random set of distinct indices of a
+//! sequence of known length, as an [`IndexVec`]. This is the building block
+//! of the sampling methods of [`SliceRandom`].
+//!
+//! [`sample`]: fn.sample.html
+//! [`IndexVec`]: enum.IndexVec.html
+//! [`SliceRandom`]: ../trait.SliceRandom.html
+
+use core::hash::Hash;
+use core::slice;
+
+#[cfg(feature="std")] use std::collections::HashSet;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::btree_set::BTreeSet;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::Vec;
+#[cfg(feature="std")] use std::vec;
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec;
+
+use Rng;
+use distributions::{Distribution, Uniform};
+use distributions::uniform::SampleUniform;
+
+/// A vector of indices.
+///
+/// Indices are stored as `u32` if the length of the sampled sequence fits,
+/// which halves the memory use on 64-bit platforms, and as `usize`
+/// otherwise. The accessors always return `usize`.
+#[derive(Clone, Debug)]
+pub enum IndexVec {
+    #[doc(hidden)] U32(Vec<u32>),
+    #[doc(hidden)] USize(Vec<usize>),
+}
+
+impl IndexVec {
+    /// Returns the number of indices.
+    pub fn len(&self) -> usize {
+        match *self {
+            IndexVec::U32(ref v) => v.len(),
+            IndexVec::USize(ref v) => v.len(),
+        }
+    }
+
+    /// Returns `true` if there are no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value at the given `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn index(&self, index: usize) -> usize {
+        match *self {
+            IndexVec::U32(ref v) => v[index] as usize,
+            IndexVec::USize(ref v) => v[index],
+        }
+    }
+
+    /// Return the result as a `Vec<usize>`.
+    ///
+    /// This allocates if the indices are stored as `u32`.
+    pub fn into_vec(self) -> Vec<usize> {
+        match self {
+            IndexVec::U32(v) => v.into_iter().map(|i| i as usize).collect(),
+            IndexVec::USize(v) => v,
+        }
+    }
+
+    /// Iterate over the indices as a sequence of `usize` values.
+    pub fn iter<'a>(&'a self) -> IndexVecIter<'a> {
+        match *self {
+            IndexVec::U32(ref v) => IndexVecIter::U32(v.iter()),
+            IndexVec::USize(ref v) => IndexVecIter::USize(v.iter()),
+        }
+    }
+}
+
+impl IntoIterator for IndexVec {
+    type Item = usize;
+    type IntoIter = IndexVecIntoIter;
+
+    /// Convert into an iterator over the indices as a sequence of `usize`
+    /// values.
+    fn into_iter(self) -> IndexVecIntoIter {
+        match self {
+            IndexVec::U32(v) => IndexVecIntoIter::U32(v.into_iter()),
+            IndexVec::USize(v) => IndexVecIntoIter::USize(v.into_iter()),
+        }
+    }
+}
+
+impl PartialEq for IndexVec {
+    fn eq(&self, other: &IndexVec) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl From<Vec<u32>> for IndexVec {
+    fn from(v: Vec<u32>) -> Self {
+        IndexVec::U32(v)
+    }
+}
+
+impl From<Vec<usize>> for IndexVec {
+    fn from(v: Vec<usize>) -> Self {
+        IndexVec::USize(v)
+    }
+}
+
+/// Borrowing iterator over the indices of an [`IndexVec`].
+///
+/// [`IndexVec`]: enum.IndexVec.html
+#[derive(Debug)]
+pub enum IndexVecIter<'a> {
+    #[doc(hidden)] U32(slice::Iter<'a, u32>),
+    #[doc(hidden)] USize(slice::Iter<'a, usize>),
+}
+
+impl<'a> Iterator for IndexVecIter<'a> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        match *self {
+            IndexVecIter::U32(ref mut iter) => iter.next().map(|i| *i as usize),
+            IndexVecIter::USize(ref mut iter) => iter.next().cloned(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            IndexVecIter::U32(ref iter) => iter.size_hint(),
+            IndexVecIter::USize(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for IndexVecIter<'a> {}
+
+/// Consuming iterator over the indices of an [`IndexVec`].
+///
+/// [`IndexVec`]: enum.IndexVec.html
+#[derive(Clone, Debug)]
+pub enum IndexVecIntoIter {
+    #[doc(hidden)] U32(vec::IntoIter<u32>),
+    #[doc(hidden)] USize(vec::IntoIter<usize>),
+}
+
+impl Iterator for IndexVecIntoIter {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        match *self {
+            IndexVecIntoIter::U32(ref mut v) => v.next().map(|i| i as usize),
+            IndexVecIntoIter::USize(ref mut v) => v.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            IndexVecIntoIter::U32(ref v) => v.size_hint(),
+            IndexVecIntoIter::USize(ref v) => v.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for IndexVecIntoIter {}
+
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`, and
+/// return them in random order (fully shuffled).
+///
+/// This method is used internally by the slice sampling methods, but it can
+/// sometimes be useful to have the indices themselves so this is provided as
+/// an alternative.
+///
+/// The implementation used is not specified; we automatically select the
+/// fastest available algorithm for the `length` and `amount` parameters:
+///
+/// - Floyd's algorithm, which needs `O(amount)` memory but `O(amount²)`
+///   time, is used for small `amount`.
+/// - A partial Fisher–Yates shuffle, which needs `O(length)` memory and
+///   time, is used when `amount` is not much smaller than `length`.
+/// - Rejection sampling with a set of the chosen indices, which needs
+///   `O(amount)` memory and time, is used otherwise.
+///
+/// If `length` fits in a `u32`, the indices are stored as `u32`.
+///
+/// Panics if `amount > length`.
+///
+/// # Example
+///
+/// ```
+/// use rand::thread_rng;
+/// use rand::seq::index;
+///
+/// let mut rng = thread_rng();
+/// let indices = index::sample(&mut rng, 1000, 10);
+/// assert_eq!(indices.len(), 10);
+/// assert!(indices.iter().all(|i| i < 1000));
+/// ```
+pub fn sample<R>(rng: &mut R, length: usize, amount: usize) -> IndexVec
+    where R: Rng + ?Sized,
+{
+    if amount > length {
+        panic!("`amount` of samples must be less than or equal to `length`");
+    }
+    if length > (::core::u32::MAX as usize) {
+        // We never want to allocate `length` indices here, so only rejection
+        // sampling is applicable.
+        return sample_rejection(rng, length, amount);
+    }
+    let amount = amount as u32;
+    let length = length as u32;
+
+    // Floyd's algorithm needs no extra memory, but each step searches the
+    // indices chosen so far. It is the fastest choice for small `amount`,
+    // unless `length` is so small that the shuffle is cheaper.
+    //
+    // The constants were found by benchmarking; the break-even point of the
+    // shuffle is at a lower density for large `length`, where its memory use
+    // starts to hurt.
+    if amount < 163 {
+        const C: [[f32; 2]; 2] = [[1.6, 8.0 / 45.0], [10.0, 70.0 / 9.0]];
+        let j = if length < 500_000 { 0 } else { 1 };
+        let amount_fp = amount as f32;
+        let m4 = C[0][j] * amount_fp;
+        // Short-cut: when amount < 12, Floyd's is always faster
+        if amount > 11 && (length as f32) < (C[1][j] + m4) * amount_fp {
+            sample_inplace(rng, length, amount)
+        } else {
+            sample_floyd(rng, length, amount)
+        }
+    } else {
+        const C: [f32; 2] = [270.0, 330.0 / 9.0];
+        let j = if length < 500_000 { 0 } else { 1 };
+        if (length as f32) < C[j] * (amount as f32) {
+            sample_inplace(rng, length, amount)
+        } else {
+            sample_rejection(rng, length, amount)
+        }
+    }
+}
+
+/// Randomly sample exactly `amount` indices from `0..length`, using Floyd's
+/// combination algorithm.
+///
+/// The output values are fully shuffled. (Overhead is under 50%.)
+///
+/// This implementation uses `O(amount)` memory and `O(amount^2)` time.
+fn sample_floyd<R>(rng: &mut R, length: u32, amount: u32) -> IndexVec
+    where R: Rng + ?Sized,
+{
+    // For small amount we use Floyd's fully-shuffled variant. For larger
+    // amounts this is slow due to Vec::insert performance, so we shuffle
+    // afterwards. Benchmarks show little overhead from extra logic.
+    let floyd_shuffle = amount < 50;
+
+    debug_assert!(amount <= length);
+    let mut indices = Vec::with_capacity(amount as usize);
+    for j in length - amount .. length {
+        let t = rng.gen_range(0, j + 1);
+        if floyd_shuffle {
+            if let Some(pos) = indices.iter().position(|&x| x == t) {
+                indices.insert(pos, j);
+                continue;
+            }
+        } else if indices.contains(&t) {
+            indices.push(j);
+            continue;
+        }
+        indices.push(t);
+    }
+    if !floyd_shuffle {
+        // Reimplement SliceRandom::shuffle with smaller indices
+        for i in (1..amount).rev() {
+            // invariant: elements with index > i have been locked in place.
+            indices.swap(i as usize, rng.gen_range(0, i + 1) as usize);
+        }
+    }
+    IndexVec::from(indices)
+}
+
+/// Randomly sample exactly `amount` indices from `0..length`, using an inplace
+/// partial Fisher-Yates method.
+///
+/// This allocates the entire `length` of indices and randomizes only the
+/// first `amount`. It then truncates to `amount` and returns.
+///
+/// This method is not appropriate for large `length` and potentially uses a
+/// lot of memory; because of this we only implement for `u32` index (which
+/// improves performance in all cases).
+///
+/// This implementation uses `O(length)` time and memory.
+fn sample_inplace<R>(rng: &mut R, length: u32, amount: u32) -> IndexVec
+    where R: Rng + ?Sized,
+{
+    debug_assert!(amount <= length);
+    let mut indices: Vec<u32> = Vec::with_capacity(length as usize);
+    indices.extend(0..length);
+    for i in 0..amount {
+        let j: u32 = rng.gen_range(i, length);
+        indices.swap(i as usize, j as usize);
+    }
+    indices.truncate(amount as usize);
+    debug_assert_eq!(indices.len(), amount as usize);
+    IndexVec::from(indices)
+}
+
+/// Index types which can be sampled by `sample_rejection`.
+trait UInt: Copy + Ord + Hash + SampleUniform {
+    fn zero() -> Self;
+    fn as_usize(self) -> usize;
+}
+
+impl UInt for u32 {
+    #[inline] fn zero() -> Self { 0 }
+    #[inline] fn as_usize(self) -> usize { self as usize }
+}
+
+impl UInt for usize {
+    #[inline] fn zero() -> Self { 0 }
+    #[inline] fn as_usize(self) -> usize { self }
+}
+
+/// Randomly sample exactly `amount` indices from `0..length`, using rejection
+/// sampling.
+///
+/// Since `amount <<< length` there is a low chance of a random sample in
+/// `0..length` being a duplicate. We test for duplicates and resample where
+/// necessary. The algorithm is `O(amount)` time and memory.
+fn sample_rejection<X: UInt, R>(rng: &mut R, length: X, amount: X) -> IndexVec
+    where R: Rng + ?Sized,
+          IndexVec: From<Vec<X>>,
+{
+    debug_assert!(amount <= length);
+    let distr = Uniform::new(X::zero(), length);
+    // BTreeSet is not as fast in tests, but better than nothing.
+    #[cfg(feature="std")] let mut cache = HashSet::with_capacity(amount.as_usize());
+    #[cfg(not(feature="std"))] let mut cache = BTreeSet::new();
+    let mut indices = Vec::with_capacity(amount.as_usize());
+    for _ in 0..amount.as_usize() {
+        let mut pos = distr.sample(rng);
+        while !cache.insert(pos) {
+            pos = distr.sample(rng);
+        }
+        indices.push(pos);
+    }
+
+    debug_assert_eq!(indices.len(), amount.as_usize());
+    IndexVec::from(indices)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(all(feature="alloc", not(feature="std")))]
+    use alloc::Vec;
+
+    #[test]
+    fn test_sample_boundaries() {
+        let mut r = ::test::rng(404);
+
+        assert_eq!(sample_inplace(&mut r, 0, 0).len(), 0);
+        assert_eq!(sample_inplace(&mut r, 1, 0).len(), 0);
+        assert_eq!(sample_inplace(&mut r, 1, 1).into_vec(), vec![0]);
+
+        assert_eq!(sample_rejection(&mut r, 1u32, 0).len(), 0);
+
+        assert_eq!(sample_floyd(&mut r, 0, 0).len(), 0);
+        assert_eq!(sample_floyd(&mut r, 1, 0).len(), 0);
+        assert_eq!(sample_floyd(&mut r, 1, 1).into_vec(), vec![0]);
+
+        // These algorithms should be fast with big numbers. Test average.
+        let sum: usize = sample_rejection(&mut r, 1 << 25, 10u32).into_iter().sum();
+        assert!(1 << 25 < sum && sum < (1 << 25) * 25);
+
+        let sum: usize = sample_floyd(&mut r, 1 << 25, 10).into_iter().sum();
+        assert!(1 << 25 < sum && sum < (1 << 25) * 25);
+    }
+
+    #[test]
+    fn test_sample_alg() {
+        let seed_rng = ::test::rng;
+
+        // We can't test which algorithm is used directly, but each should
+        // produce valid, distinct results, and `sample` should reproduce the
+        // output of the algorithm it selected for the same seed.
+        let (length, amount): (usize, usize) = (100, 50);
+        let v1 = sample(&mut seed_rng(420), length, amount);
+        let v2 = sample_inplace(&mut seed_rng(420), length as u32, amount as u32);
+        assert!(v1.iter().all(|e| e < length));
+        assert_eq!(v1, v2);
+
+        // Test Floyd's alg does produce different results
+        let v3 = sample_floyd(&mut seed_rng(420), length as u32, amount as u32);
+        assert!(v1 != v3);
+
+        // A large length and small amount should use Floyd
+        let (length, amount): (usize, usize) = (1 << 20, 50);
+        let v1 = sample(&mut seed_rng(421), length, amount);
+        let v2 = sample_floyd(&mut seed_rng(421), length as u32, amount as u32);
+        assert!(v1.iter().all(|e| e < length));
+        assert_eq!(v1, v2);
+
+        // A large length and larger amount should use cache
+        let (length, amount): (usize, usize) = (1 << 20, 600);
+        let v1 = sample(&mut seed_rng(422), length, amount);
+        let v2 = sample_rejection(&mut seed_rng(422), length as u32, amount as u32);
+        assert!(v1.iter().all(|e| e < length));
+        assert_eq!(v1, v2);
+    }
+
+    #[test]
+    fn test_sample_distinct() {
+        let mut r = ::test::rng(405);
+        for &(length, amount) in [(10, 10), (100, 12), (1000, 50), (1000, 200),
+                                  (100_000, 163), (100_000, 1000)].iter() {
+            let mut indices = sample(&mut r, length, amount).into_vec();
+            assert_eq!(indices.len(), amount);
+            assert!(indices.iter().all(|&i| i < length));
+            indices.sort();
+            indices.dedup();
+            assert_eq!(indices.len(), amount);
+        }
+    }
+
+    #[test]
+    fn test_index_vec() {
+        let v = IndexVec::from(vec![3u32, 1, 2]);
+        assert_eq!(v.len(), 3);
+        assert!(!v.is_empty());
+        assert_eq!(v.index(1), 1);
+        assert_eq!(v.iter().collect::<Vec<usize>>(), vec![3, 1, 2]);
+        assert_eq!(v, IndexVec::from(vec![3usize, 1, 2]));
+        assert!(v != IndexVec::from(vec![3usize, 1]));
+        assert_eq!(v.iter().len(), 3);
+        assert_eq!(v.clone().into_iter().len(), 3);
+        assert_eq!(v.into_vec(), vec![3, 1, 2]);
+    }
+}
@@ -0,0 +1,1471 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Functions for randomly accessing and sampling sequences.
+//!
+//! The [`SliceRandom`] trait provides methods operating on slices, and the
+//! [`IteratorRandom`] trait methods operating on iterators; both are
+//! available without `std` and `alloc`. The sampling functions of this module
+//! require either `std` or `alloc`, as does the [`index`] module for sampling
+//! distinct indices. [`WeightedReservoir`] samples items with weights from a
+//! stream of unknown length, and [`RandomPermutation`] visits a range in
+//! random order without storing the permutation. [`combinations`] and
+//! [`random_subset`] iterate over random subsets of a range. The
+//! [`extshuffle`] module shuffles datasets which do not fit in memory.
+//!
+//! [`SliceRandom`]: trait.SliceRandom.html
+//! [`IteratorRandom`]: trait.IteratorRandom.html
+//! [`index`]: index/index.html
+//! [`WeightedReservoir`]: struct.WeightedReservoir.html
+//! [`RandomPermutation`]: struct.RandomPermutation.html
+//! [`combinations`]: fn.combinations.html
+//! [`random_subset`]: fn.random_subset.html
+//! [`extshuffle`]: extshuffle/index.html
+
+#[cfg(feature="std")] pub mod extshuffle;
+#[cfg(feature="alloc")] pub mod index;
+#[cfg(feature="rayon")] mod parallel;
+mod permutation;
+#[cfg(feature="std")] mod reservoir;
+mod subset;
+
+#[cfg(feature="rayon")] pub use self::parallel::par_shuffle;
+pub use self::permutation::{RandomPermutation, RandomPermutationIter};
+pub use self::subset::{combinations, random_subset, Combination, Subset};
+#[cfg(feature="std")] pub use self::reservoir::WeightedReservoir;
+
+use super::Rng;
+
+#[cfg(feature="std")] use std::collections::BinaryHeap;
+#[cfg(feature="std")] use core::cmp::Ordering;
+
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::Vec;
+#[cfg(feature="alloc")] use core::ops::{AddAssign, Index};
+#[cfg(feature="alloc")] use distributions::{WeightedIndex, WeightedError};
+#[cfg(feature="alloc")] use distributions::uniform::{SampleUniform, SampleBorrow};
+#[cfg(feature="std")] use distributions::OpenClosed01;
+
+/// Extension trait on slices, providing random mutation and sampling methods.
+///
+/// This trait is implemented for all slices `[T]`, and is part of the
+/// [prelude](../prelude/index.html).
+///
+/// # Example
+///
+/// ```
+/// use rand::thread_rng;
+/// use rand::seq::SliceRandom;
+///
+/// let mut rng = thread_rng();
+/// let mut cards = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+/// cards.shuffle(&mut rng);
+/// println!("Shuffled: {:?}", cards);
+/// ```
+pub trait SliceRandom {
+    /// The element type.
+    type Item;
+
+    /// Return a random element from the slice, or `None` if it is empty.
+    ///
+    /// The index is sampled with [`Uniform`], so all elements are equally
+    /// likely to be chosen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::SliceRandom;
+    ///
+    /// let choices = [1, 2, 4, 8, 16, 32];
+    /// let mut rng = thread_rng();
+    /// println!("{:?}", choices.choose(&mut rng));
+    /// assert_eq!(choices[..0].choose(&mut rng), None);
+    /// ```
+    ///
+    /// [`Uniform`]: ../distributions/struct.Uniform.html
+    fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&Self::Item>;
+
+    /// Return a mutable reference to a random element from the slice, or
+    /// `None` if it is empty.
+    ///
+    /// All elements are equally likely to be chosen.
+    fn choose_mut<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&mut Self::Item>;
+
+    /// Return an iterator over `amount` distinct elements of the slice,
+    /// chosen at random and in random order.
+    ///
+    /// If `amount` is greater than the length of the slice, all elements are
+    /// returned, in random order.
+    ///
+    /// The indices of the elements are sampled with a partial Fisher–Yates
+    /// shuffle, using either a vector of all indices or, if `amount` is small
+    /// compared to the length, a map of the swapped indices only. This uses
+    /// `O(amount)` time, and memory of `O(len)` or `O(amount)` respectively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::SliceRandom;
+    ///
+    /// let mut rng = thread_rng();
+    /// let sample = "Hello, audience!".as_bytes();
+    ///
+    /// // collect the results into a vector:
+    /// let v: Vec<u8> = sample.choose_multiple(&mut rng, 3).cloned().collect();
+    /// assert_eq!(v.len(), 3);
+    ///
+    /// // store in a buffer:
+    /// let mut buf = [0u8; 5];
+    /// for (b, slot) in sample.choose_multiple(&mut rng, buf.len()).zip(buf.iter_mut()) {
+    ///     *slot = *b;
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn choose_multiple<'a, R: Rng + ?Sized>(&'a self, rng: &mut R, amount: usize)
+        -> SliceChooseIter<'a, Self, Self::Item>;
+
+    /// Return a random element from the slice, with the probability of each
+    /// element proportional to its weight.
+    ///
+    /// The function `weight` maps each element to its weight, or a reference
+    /// to it. The weights can be of any type `X` for which [`Uniform`] is
+    /// implemented. This uses
+    /// [`WeightedIndex`] internally, so `O(len)` time and memory; to choose
+    /// repeatedly from the same slice, use a `WeightedIndex` directly.
+    ///
+    /// Returns an error if the slice is empty, if any weight is negative or
+    /// NaN, or if all weights are zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::SliceRandom;
+    ///
+    /// let choices = [('a', 2), ('b', 1), ('c', 1)];
+    /// let mut rng = thread_rng();
+    /// // 50% chance to print 'a', 25% chance to print 'b', 25% chance to print 'c'
+    /// println!("{:?}", choices.choose_weighted(&mut rng, |item| item.1).unwrap().0);
+    /// ```
+    ///
+    /// [`Uniform`]: ../distributions/struct.Uniform.html
+    /// [`WeightedIndex`]: ../distributions/struct.WeightedIndex.html
+    #[cfg(feature = "alloc")]
+    fn choose_weighted<R, F, B, X>(&self, rng: &mut R, weight: F)
+        -> Result<&Self::Item, WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&Self::Item) -> B,
+              B: SampleBorrow<X>,
+              X: SampleUniform + PartialOrd + for<'b> AddAssign<&'b X> + Clone + Default;
+
+    /// Return a mutable reference to a random element from the slice, with
+    /// the probability of each element proportional to its weight.
+    ///
+    /// See [`choose_weighted`] for details.
+    ///
+    /// [`choose_weighted`]: #tymethod.choose_weighted
+    #[cfg(feature = "alloc")]
+    fn choose_weighted_mut<R, F, B, X>(&mut self, rng: &mut R, weight: F)
+        -> Result<&mut Self::Item, WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&Self::Item) -> B,
+              B: SampleBorrow<X>,
+              X: SampleUniform + PartialOrd + for<'b> AddAssign<&'b X> + Clone + Default;
+
+    /// Choose `amount` distinct elements from the slice at random, with the
+    /// probability of each element proportional to its weight.
+    ///
+    /// The function `weight` maps each element to its weight, which must be
+    /// convertible to `f64`. Elements with a weight of zero are never chosen,
+    /// so fewer than `amount` elements are returned if the slice does not
+    /// contain enough elements with a positive weight. The order of the
+    /// returned elements is not specified.
+    ///
+    /// This implements the [Efraimidis–Spirakis algorithm](
+    /// https://doi.org/10.1016/j.ipl.2005.11.003): every element is given the
+    /// key `u^(1/w)`, with `u` sampled uniformly from `(0, 1]`, and the
+    /// elements with the `amount` largest keys are chosen. This uses
+    /// `O(len * log(amount))` time and `O(amount)` memory.
+    ///
+    /// Returns an error if any weight is negative or NaN.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::SliceRandom;
+    ///
+    /// let choices = [('a', 2), ('b', 1), ('c', 1), ('d', 0)];
+    /// let mut rng = thread_rng();
+    /// // 'd' is never chosen; 'a' is chosen more often than 'b' or 'c'
+    /// let chosen: Vec<char> = choices
+    ///     .choose_multiple_weighted(&mut rng, 2, |item| item.1)
+    ///     .unwrap()
+    ///     .map(|item| item.0)
+    ///     .collect();
+    /// assert_eq!(chosen.len(), 2);
+    /// assert!(!chosen.contains(&'d'));
+    /// ```
+    #[cfg(feature = "std")]
+    fn choose_multiple_weighted<'a, R, F, X>(&'a self, rng: &mut R, amount: usize, weight: F)
+        -> Result<SliceChooseIter<'a, Self, Self::Item>, WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&Self::Item) -> X,
+              X: Into<f64>;
+
+    /// Shuffle a mutable slice in place.
+    ///
+    /// This applies Durstenfeld's algorithm for the [Fisher–Yates shuffle](
+    /// https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle#The_modern_algorithm)
+    /// which produces an unbiased permutation.
+    ///
+    /// This uses `O(len)` time and no extra memory.
+    fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R);
+
+    /// Shuffle only the first `amount` elements of a mutable slice in place.
+    ///
+    /// Afterwards the first `amount` elements are a random sample of all
+    /// elements of the slice, in random order, exactly as if the whole slice
+    /// was shuffled. The remaining elements are left in an unspecified order.
+    ///
+    /// Returns the slice split into the shuffled part, of length `amount`,
+    /// and the remainder. If `amount` is greater than the length of the slice,
+    /// the whole slice is shuffled and the remainder is empty.
+    ///
+    /// This uses `O(amount)` time and no extra memory, so it is cheaper than
+    /// [`shuffle`] when only a random prefix is needed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::SliceRandom;
+    ///
+    /// let mut rng = thread_rng();
+    /// let mut players: Vec<u32> = (0..100).collect();
+    /// let (winners, _) = players.partial_shuffle(&mut rng, 3);
+    /// println!("The winners are {:?}", winners);
+    /// ```
+    ///
+    /// [`shuffle`]: #tymethod.shuffle
+    fn partial_shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R, amount: usize)
+        -> (&mut [Self::Item], &mut [Self::Item]);
+
+    /// Shuffle a mutable slice in place, such that elements with a larger
+    /// weight tend to come first.
+    ///
+    /// The function `weight` maps each element to its weight, which must be
+    /// convertible to `f64`. The first element is chosen with probability
+    /// proportional to its weight, the second element is chosen from the
+    /// remaining elements in the same way, and so on. This is useful for
+    /// randomized rankings biased by priority. Elements with a weight of zero
+    /// come last, in uniformly random order.
+    ///
+    /// This sorts the elements by the keys of the [Efraimidis–Spirakis
+    /// algorithm](https://doi.org/10.1016/j.ipl.2005.11.003), as in
+    /// [`choose_multiple_weighted`], so it uses `O(len * log(len))` time and
+    /// `O(len)` memory.
+    ///
+    /// Returns an error if any weight is negative or NaN; the slice is
+    /// unchanged in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::SliceRandom;
+    ///
+    /// let mut rng = thread_rng();
+    /// let mut results = [("news", 5.0), ("blog", 1.0), ("ad", 0.0), ("wiki", 3.0)];
+    /// results.shuffle_weighted(&mut rng, |item| item.1).unwrap();
+    /// // the item with weight zero always comes last
+    /// assert_eq!(results[3].0, "ad");
+    /// ```
+    ///
+    /// [`choose_multiple_weighted`]: #tymethod.choose_multiple_weighted
+    #[cfg(feature = "std")]
+    fn shuffle_weighted<R, F, X>(&mut self, rng: &mut R, weight: F) -> Result<(), WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&Self::Item) -> X,
+              X: Into<f64>;
+}
+
+impl<T> SliceRandom for [T] {
+    type Item = T;
+
+    fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(&self[rng.gen_range(0, self.len())])
+        }
+    }
+
+    fn choose_mut<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            let len = self.len();
+            Some(&mut self[rng.gen_range(0, len)])
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn choose_multiple<'a, R: Rng + ?Sized>(&'a self, rng: &mut R, amount: usize)
+        -> SliceChooseIter<'a, Self, T>
+    {
+        let amount = if amount < self.len() { amount } else { self.len() };
+        SliceChooseIter {
+            slice: self,
+            _phantom: Default::default(),
+            indices: index::sample(rng, self.len(), amount).into_iter(),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn choose_weighted<R, F, B, X>(&self, rng: &mut R, weight: F)
+        -> Result<&T, WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&T) -> B,
+              B: SampleBorrow<X>,
+              X: SampleUniform + PartialOrd + for<'b> AddAssign<&'b X> + Clone + Default
+    {
+        let distr = WeightedIndex::new(self.iter().map(weight))?;
+        Ok(&self[rng.sample(distr)])
+    }
+
+    #[cfg(feature = "alloc")]
+    fn choose_weighted_mut<R, F, B, X>(&mut self, rng: &mut R, weight: F)
+        -> Result<&mut T, WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&T) -> B,
+              B: SampleBorrow<X>,
+              X: SampleUniform + PartialOrd + for<'b> AddAssign<&'b X> + Clone + Default
+    {
+        let distr = WeightedIndex::new(self.iter().map(weight))?;
+        Ok(&mut self[rng.sample(distr)])
+    }
+
+    #[cfg(feature = "std")]
+    fn choose_multiple_weighted<'a, R, F, X>(&'a self, rng: &mut R, amount: usize, weight: F)
+        -> Result<SliceChooseIter<'a, Self, T>, WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&T) -> X,
+              X: Into<f64>
+    {
+        // Keep the `amount` elements with the largest keys in a min-heap.
+        // We use `ln(u) / w` as key, which has the same order as `u^(1/w)`
+        // but is not rounded to zero for tiny weights.
+        let mut heap = BinaryHeap::with_capacity(amount);
+        if amount > 0 {
+            for (index, item) in self.iter().enumerate() {
+                let w: f64 = weight(item).into();
+                if w < 0.0 || w.is_nan() {
+                    return Err(WeightedError::InvalidWeight);
+                }
+                if w == 0.0 {
+                    continue;
+                }
+                let u: f64 = rng.sample(OpenClosed01);
+                let key = WeightedKey { key: u.ln() / w, index };
+                if heap.len() < amount {
+                    heap.push(key);
+                } else if key < *heap.peek().unwrap() {
+                    *heap.peek_mut().unwrap() = key;
+                }
+            }
+        }
+        let indices: Vec<usize> = heap.into_iter().map(|key| key.index).collect();
+        Ok(SliceChooseIter {
+            slice: self,
+            _phantom: Default::default(),
+            indices: index::IndexVec::from(indices).into_iter(),
+        })
+    }
+
+    fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let mut i = self.len();
+        while i >= 2 {
+            // invariant: elements with index >= i have been locked in place.
+            i -= 1;
+            // lock element i in place.
+            self.swap(i, rng.gen_range(0, i + 1));
+        }
+    }
+
+    fn partial_shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R, amount: usize)
+        -> (&mut [T], &mut [T])
+    {
+        let len = self.len();
+        let amount = if amount < len { amount } else { len };
+        for i in 0..amount {
+            // invariant: elements with index < i have been locked in place.
+            // lock element i in place.
+            self.swap(i, rng.gen_range(i, len));
+        }
+        self.split_at_mut(amount)
+    }
+
+    #[cfg(feature = "std")]
+    fn shuffle_weighted<R, F, X>(&mut self, rng: &mut R, weight: F) -> Result<(), WeightedError>
+        where R: Rng + ?Sized,
+              F: Fn(&T) -> X,
+              X: Into<f64>
+    {
+        let len = self.len();
+        let mut keys = Vec::with_capacity(len);
+        let mut zeros = 0;
+        for (index, item) in self.iter().enumerate() {
+            let w: f64 = weight(item).into();
+            if w < 0.0 || w.is_nan() {
+                return Err(WeightedError::InvalidWeight);
+            }
+            let key = if w == 0.0 {
+                zeros += 1;
+                ::core::f64::NEG_INFINITY
+            } else {
+                let u: f64 = rng.sample(OpenClosed01);
+                u.ln() / w
+            };
+            keys.push(WeightedKey { key, index });
+        }
+        // `WeightedKey` is ordered by decreasing key; the sort is stable, so
+        // the elements with weight zero are shuffled separately.
+        keys.sort();
+        keys[len - zeros..].shuffle(rng);
+
+        // Move the element with original index `keys[i].index` to `i`.
+        // `position[j]` is the current position of the element with original
+        // index `j`, and `origin[i]` the original index of the element at `i`.
+        let mut position: Vec<usize> = (0..len).collect();
+        let mut origin = position.clone();
+        for (i, key) in keys.iter().enumerate() {
+            let j = position[key.index];
+            self.swap(i, j);
+            let displaced = origin[i];
+            position[displaced] = j;
+            origin[j] = displaced;
+            position[key.index] = i;
+            origin[i] = key.index;
+        }
+        Ok(())
+    }
+}
+
+/// Extension trait on iterators, providing random sampling methods.
+///
+/// This trait is implemented for all iterators, and is part of the
+/// [prelude](../prelude/index.html). Unlike [`SliceRandom`], the length of
+/// the sequence does not need to be known in advance.
+///
+/// [`SliceRandom`]: trait.SliceRandom.html
+pub trait IteratorRandom: Iterator + Sized {
+    /// Choose one element at random from the iterator. If you have a slice,
+    /// it's significantly faster to call the [`choose`] method on the slice
+    /// instead.
+    ///
+    /// Returns `None` if and only if the iterator is empty.
+    ///
+    /// If the iterator reports an exact length through `size_hint`, a single
+    /// index is sampled and the iterator is advanced to that element with
+    /// `nth`. Otherwise this uses reservoir sampling in a single pass over
+    /// the iterator, consuming it completely; for each group of elements the
+    /// lower bound of `size_hint` reports, only one random number is
+    /// generated, and elements are skipped with `nth`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::IteratorRandom;
+    ///
+    /// let mut rng = thread_rng();
+    /// let odd = (1..100).filter(|x| x % 2 == 1).choose(&mut rng).unwrap();
+    /// assert!(odd % 2 == 1);
+    /// ```
+    ///
+    /// [`choose`]: trait.SliceRandom.html#tymethod.choose
+    fn choose<R>(mut self, rng: &mut R) -> Option<Self::Item>
+        where R: Rng + ?Sized
+    {
+        let (mut lower, mut upper) = self.size_hint();
+        let mut consumed = 0;
+        let mut result = None;
+
+        if upper == Some(lower) {
+            return if lower == 0 { None } else { self.nth(rng.gen_range(0, lower)) };
+        }
+
+        // Continue until the iterator is exhausted
+        loop {
+            if lower > 1 {
+                // Replace the result by one of the next `lower` elements with
+                // probability `lower / (consumed + lower)`.
+                let ix = rng.gen_range(0, lower + consumed);
+                let skip;
+                if ix < lower {
+                    result = self.nth(ix);
+                    skip = lower - (ix + 1);
+                } else {
+                    skip = lower;
+                }
+                if upper == Some(lower) {
+                    return result;
+                }
+                consumed += lower;
+                if skip > 0 {
+                    self.nth(skip - 1);
+                }
+            } else {
+                let elem = self.next();
+                if elem.is_none() {
+                    return result;
+                }
+                consumed += 1;
+                if rng.gen_range(0, consumed) == 0 {
+                    result = elem;
+                }
+            }
+
+            let hint = self.size_hint();
+            lower = hint.0;
+            upper = hint.1;
+        }
+    }
+
+    /// Collects values at random from the iterator into a supplied buffer
+    /// until that buffer is filled.
+    ///
+    /// Although the elements are selected randomly, the order of elements in
+    /// the buffer is neither stable nor fully random. If random ordering is
+    /// desired, shuffle the result.
+    ///
+    /// Returns the number of elements added to the buffer. This equals the
+    /// length of the buffer unless the iterator contains insufficient
+    /// elements, in which case this equals the number of elements available.
+    ///
+    /// This uses reservoir sampling (Vitter's Algorithm R) in a single pass
+    /// over the iterator, so it is suitable for streams of unknown length
+    /// such as the lines of a file. Complexity is `O(n)` where `n` is the
+    /// length of the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rand::seq::IteratorRandom;
+    ///
+    /// let mut rng = thread_rng();
+    /// let mut buf = [0; 3];
+    /// assert_eq!((0..100).choose_multiple_fill(&mut rng, &mut buf), 3);
+    /// assert_eq!((0..2).choose_multiple_fill(&mut rng, &mut buf), 2);
+    /// ```
+    fn choose_multiple_fill<R>(mut self, rng: &mut R, buf: &mut [Self::Item]) -> usize
+        where R: Rng + ?Sized
+    {
+        let amount = buf.len();
+        let mut len = 0;
+        while len < amount {
+            if let Some(elem) = self.next() {
+                buf[len] = elem;
+                len += 1;
+            } else {
+                // Iterator exhausted; stop early
+                return len;
+            }
+        }
+
+        // Continue, since the iterator was not exhausted
+        for (i, elem) in self.enumerate() {
+            let k = rng.gen_range(0, i + 1 + amount);
+            if let Some(slot) = buf.get_mut(k) {
+                *slot = elem;
+            }
+        }
+        len
+    }
+
+    /// Collects `amount` values at random from the iterator into a vector.
+    ///
+    /// This is equivalent to [`choose_multiple_fill`] except for the result
+    /// type.
+    ///
+    /// Although the elements are selected randomly, the order of elements in
+    /// the buffer is neither stable nor fully random. If random ordering is
+    /// desired, shuffle the result.
+    ///
+    /// The length of the returned vector equals `amount` unless the iterator
+    /// contains insufficient elements, in which case it equals the number of
+    /// elements available.
+    ///
+    /// Complexity is `O(n)` where `n` is the length of the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::{BufRead, Cursor};
+    /// use rand::thread_rng;
+    /// use rand::seq::IteratorRandom;
+    ///
+    /// let file = Cursor::new("first\nsecond\nthird\nfourth\n");
+    /// let mut rng = thread_rng();
+    /// let lines = file.lines().map(|line| line.unwrap()).choose_multiple(&mut rng, 2);
+    /// assert_eq!(lines.len(), 2);
+    /// ```
+    ///
+    /// [`choose_multiple_fill`]: #method.choose_multiple_fill
+    #[cfg(feature = "alloc")]
+    fn choose_multiple<R>(mut self, rng: &mut R, amount: usize) -> Vec<Self::Item>
+        where R: Rng + ?Sized
+    {
+        let mut reservoir = Vec::with_capacity(amount);
+        reservoir.extend(self.by_ref().take(amount));
+
+        // Continue unless the iterator was exhausted
+        //
+        // note: this prevents iterators that "restart" from causing problems.
+        // If the iterator stops once, then so do we.
+        if reservoir.len() == amount {
+            for (i, elem) in self.enumerate() {
+                let k = rng.gen_range(0, i + 1 + amount);
+                if let Some(slot) = reservoir.get_mut(k) {
+                    *slot = elem;
+                }
+            }
+        } else {
+            // Don't hang onto extra memory. There is a corner case where
+            // `amount` was much less than `self.len()`.
+            reservoir.shrink_to_fit();
+        }
+        reservoir
+    }
+}
+
+impl<I> IteratorRandom for I where I: Iterator + Sized {}
+
+/// An iterator over distinct elements of a slice, chosen at random.
+///
+/// This struct is created by [`SliceRandom::choose_multiple`].
+///
+/// [`SliceRandom::choose_multiple`]: trait.SliceRandom.html#tymethod.choose_multiple
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct SliceChooseIter<'a, S: ?Sized + 'a, T: 'a> {
+    slice: &'a S,
+    _phantom: ::core::marker::PhantomData<T>,
+    indices: index::IndexVecIntoIter,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, S: Index<usize, Output = T> + ?Sized + 'a, T: 'a> Iterator
+    for SliceChooseIter<'a, S, T>
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next().map(|i| &self.slice[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, S: Index<usize, Output = T> + ?Sized + 'a, T: 'a> ExactSizeIterator
+    for SliceChooseIter<'a, S, T>
+{
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+/// Key of an element in `choose_multiple_weighted` and `WeightedReservoir`.
+///
+/// The order is reversed, so that `BinaryHeap` is a min-heap and its top is
+/// the element with the smallest key. Keys are never NaN.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+struct WeightedKey {
+    key: f64,
+    index: usize,
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for WeightedKey {
+    fn eq(&self, other: &WeightedKey) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+#[cfg(feature = "std")]
+impl Eq for WeightedKey {}
+
+#[cfg(feature = "std")]
+impl PartialOrd for WeightedKey {
+    fn partial_cmp(&self, other: &WeightedKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Ord for WeightedKey {
+    fn cmp(&self, other: &WeightedKey) -> Ordering {
+        other.key.partial_cmp(&self.key).unwrap()
+    }
+}
+
+/// Randomly sample `amount` elements from a finite iterator.
+///
+/// The following can be returned:
+///
+/// - `Ok`: `Vec` of `amount` non-repeating randomly sampled elements. The order is not random.
+/// - `Err`: `Vec` of all the elements from `iterable` in sequential order. This happens when the
+///   length of `iterable` was less than `amount`. This is considered an error since exactly
+///   `amount` elements is typically expected.
+///
+/// This implementation uses `O(len(iterable))` time and `O(amount)` memory.
+/// See also [`IteratorRandom::choose_multiple`], which this function wraps.
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let sample = seq::sample_iter(&mut rng, 1..100, 5).unwrap();
+/// println!("{:?}", sample);
+/// ```
+///
+/// [`IteratorRandom::choose_multiple`]: trait.IteratorRandom.html#method.choose_multiple
+#[cfg(feature = "alloc")]
+pub fn sample_iter<T, I, R>(rng: &mut R, iterable: I, amount: usize) -> Result<Vec<T>, Vec<T>>
+    where I: IntoIterator<Item=T>,
+          R: Rng + ?Sized,
+{
+    let reservoir = iterable.into_iter().choose_multiple(rng, amount);
+    if reservoir.len() == amount {
+        Ok(reservoir)
+    } else {
+        Err(reservoir)
+    }
+}
+
+/// Randomly sample exactly `amount` values from `slice`.
+///
+/// The values are non-repeating and in random order.
+///
+/// This implementation uses `O(amount)` time and memory.
+///
+/// Panics if `amount > slice.len()`
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let values = vec![5, 6, 1, 3, 4, 6, 7];
+/// println!("{:?}", seq::sample_slice(&mut rng, &values, 3));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn sample_slice<R, T>(rng: &mut R, slice: &[T], amount: usize) -> Vec<T>
+    where R: Rng + ?Sized,
+          T: Clone
+{
+    let indices = index::sample(rng, slice.len(), amount);
+
+    let mut out = Vec::with_capacity(amount);
+    out.extend(indices.iter().map(|i| slice[i].clone()));
+    out
+}
+
+/// Randomly sample exactly `amount` references from `slice`.
+///
+/// The references are non-repeating and in random order.
+///
+/// This implementation uses `O(amount)` time and memory.
+///
+/// Panics if `amount > slice.len()`
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let values = vec![5, 6, 1, 3, 4, 6, 7];
+/// println!("{:?}", seq::sample_slice_ref(&mut rng, &values, 3));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn sample_slice_ref<'a, R, T>(rng: &mut R, slice: &'a [T], amount: usize) -> Vec<&'a T>
+    where R: Rng + ?Sized
+{
+    let indices = index::sample(rng, slice.len(), amount);
+
+    let mut out = Vec::with_capacity(amount);
+    out.extend(indices.iter().map(|i| &slice[i]));
+    out
+}
+
+/// Randomly sample exactly `amount` references from `slice`, preserving
+/// their order.
+///
+/// The references are non-repeating and in the same relative order as in
+/// `slice`, which is useful when the order of the sampled elements is
+/// meaningful, for example for log records sorted by time.
+///
+/// This samples the indices with [`index::sample`] and sorts them, so it uses
+/// `O(amount * log(amount))` time and `O(amount)` memory.
+///
+/// Panics if `amount > slice.len()`
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let values = [1, 2, 3, 4, 5, 6, 7];
+/// let sample = seq::sample_stable(&mut rng, &values, 3);
+/// assert!(sample[0] < sample[1] && sample[1] < sample[2]);
+/// ```
+///
+/// [`index::sample`]: index/fn.sample.html
+#[cfg(feature = "alloc")]
+pub fn sample_stable<'a, R, T>(rng: &mut R, slice: &'a [T], amount: usize) -> Vec<&'a T>
+    where R: Rng + ?Sized
+{
+    let mut indices = index::sample(rng, slice.len(), amount).into_vec();
+    indices.sort_unstable();
+
+    let mut out = Vec::with_capacity(amount);
+    out.extend(indices.iter().map(|&i| &slice[i]));
+    out
+}
+
+/// Randomly permute a slice such that no element stays in place.
+///
+/// The result is a uniformly random [derangement] of the slice, i.e. each
+/// permutation without fixed points is equally likely. This is useful for
+/// example to assign each participant of a "Secret Santa" to another one.
+///
+/// This uses rejection sampling of shuffles, aborting each shuffle as soon as
+/// it produces a fixed point. On average about `e ≈ 2.72` shuffles are
+/// started, so this uses `O(len)` expected time and `O(len)` memory to track
+/// the original positions.
+///
+/// Note that fixed points are defined by position, not by value; equal
+/// elements may still be swapped with each other.
+///
+/// Panics if the slice has exactly one element, since no derangement exists.
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let people = ["Alice", "Bob", "Carol", "Dave"];
+/// let mut recipients = people;
+/// seq::derange(&mut rng, &mut recipients);
+/// for (giver, recipient) in people.iter().zip(recipients.iter()) {
+///     assert!(giver != recipient);
+/// }
+/// ```
+///
+/// [derangement]: https://en.wikipedia.org/wiki/Derangement
+#[cfg(feature = "alloc")]
+pub fn derange<R, T>(rng: &mut R, slice: &mut [T])
+    where R: Rng + ?Sized
+{
+    let len = slice.len();
+    assert!(len != 1, "derange: a slice of length 1 has no derangement");
+    // `origin[i]` is the original position of the element now at `i`.
+    let mut origin: Vec<usize> = (0..len).collect();
+    let mut i = 0;
+    while i < len {
+        // invariant: elements with index < i have been locked in place, and
+        // none of them is in its original position.
+        let j = rng.gen_range(i, len);
+        slice.swap(i, j);
+        origin.swap(i, j);
+        if origin[i] == i {
+            // Reject this permutation and start a new shuffle. A shuffle is
+            // uniform regardless of the initial order, so we just continue
+            // from the current order.
+            i = 0;
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Randomly sample exactly `amount` indices from `0..length`.
+///
+/// The values are non-repeating and in random order.
+///
+/// This is a convenience wrapper around [`index::sample`], returning the
+/// indices as a `Vec<usize>`; see there for details of the implementation.
+///
+/// Panics if `amount > length`
+///
+/// [`index::sample`]: index/fn.sample.html
+#[cfg(feature = "alloc")]
+pub fn sample_indices<R>(rng: &mut R, length: usize, amount: usize) -> Vec<usize>
+    where R: Rng + ?Sized,
+{
+    index::sample(rng, length, amount).into_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Rng;
+    #[cfg(feature="alloc")]
+    use {XorShiftRng, SeedableRng};
+    #[cfg(all(feature="alloc", not(feature="std")))]
+    use alloc::Vec;
+
+    #[test]
+    fn test_choose() {
+        let mut r = ::test::rng(418);
+        assert_eq!([1, 1, 1].choose(&mut r).cloned(), Some(1));
+
+        let v: &[isize] = &[];
+        assert_eq!(v.choose(&mut r), None);
+
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            counts[*[0, 1, 2, 3].choose(&mut r).unwrap()] += 1;
+        }
+        for &c in counts.iter() {
+            assert!(c > 850 && c < 1150, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_choose_mut() {
+        let mut r = ::test::rng(419);
+        let mut x = [0; 8];
+        for _ in 0..100 {
+            *x.choose_mut(&mut r).unwrap() += 1;
+        }
+        assert_eq!(x.iter().sum::<i32>(), 100);
+
+        let v: &mut [isize] = &mut [];
+        assert_eq!(v.choose_mut(&mut r), None);
+    }
+
+    #[derive(Clone)]
+    struct UnhintedIterator<I: Iterator + Clone> {
+        iter: I,
+    }
+    impl<I: Iterator + Clone> Iterator for UnhintedIterator<I> {
+        type Item = I::Item;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next()
+        }
+    }
+
+    #[derive(Clone)]
+    struct ChunkHintedIterator<I: ExactSizeIterator + Iterator + Clone> {
+        iter: I,
+        chunk_remaining: usize,
+        chunk_size: usize,
+        hint_total_size: bool,
+    }
+    impl<I: ExactSizeIterator + Iterator + Clone> Iterator for ChunkHintedIterator<I> {
+        type Item = I::Item;
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.chunk_remaining == 0 {
+                self.chunk_remaining = ::core::cmp::min(self.chunk_size, self.iter.len());
+            }
+            self.chunk_remaining = self.chunk_remaining.saturating_sub(1);
+
+            self.iter.next()
+        }
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.chunk_remaining,
+             if self.hint_total_size { Some(self.iter.len()) } else { None })
+        }
+    }
+
+    #[test]
+    fn test_iterator_choose() {
+        let r = &mut ::test::rng(109);
+        fn test_iter<R: Rng + ?Sized, Iter: Iterator<Item=usize> + Clone>(r: &mut R, iter: Iter) {
+            let mut chosen = [0i32; 9];
+            for _ in 0..1000 {
+                let picked = iter.clone().choose(r).unwrap();
+                chosen[picked] += 1;
+            }
+            for count in chosen.iter() {
+                // Samples should follow Binomial(1000, 1/9)
+                // Octave: binopdf(x, 1000, 1/9) gives the prob of *count == x
+                // Note: have seen 153, which is unlikely but not impossible.
+                assert!(72 < *count && *count < 154, "count not close to 1000/9: {}", count);
+            }
+        }
+
+        test_iter(r, 0..9);
+        test_iter(r, [0, 1, 2, 3, 4, 5, 6, 7, 8].iter().cloned());
+        #[cfg(feature = "alloc")]
+        test_iter(r, (0..9).collect::<Vec<_>>().into_iter());
+        test_iter(r, UnhintedIterator { iter: 0..9 });
+        test_iter(r, ChunkHintedIterator { iter: 0..9, chunk_size: 4, chunk_remaining: 4,
+                                           hint_total_size: false });
+        test_iter(r, ChunkHintedIterator { iter: 0..9, chunk_size: 4, chunk_remaining: 4,
+                                           hint_total_size: true });
+        test_iter(r, (0..9).filter(|_| true));
+
+        assert_eq!((0..0).choose(r), None);
+        assert_eq!(UnhintedIterator { iter: 0..0 }.choose(r), None);
+        assert_eq!((0..9).filter(|_| false).choose(r), None);
+    }
+
+    #[test]
+    fn test_iterator_choose_multiple_fill() {
+        let mut r = ::test::rng(110);
+        let mut buf = [0usize; 5];
+
+        assert_eq!((0..3).choose_multiple_fill(&mut r, &mut buf), 3);
+        assert_eq!(&buf[..3], [0, 1, 2]);
+        assert_eq!(UnhintedIterator { iter: 0..0 }.choose_multiple_fill(&mut r, &mut buf), 0);
+
+        let mut counts = [0i32; 10];
+        for _ in 0..1000 {
+            assert_eq!(UnhintedIterator { iter: 0..10 }.choose_multiple_fill(&mut r, &mut buf), 5);
+            let mut chosen = buf;
+            chosen.sort();
+            for w in chosen.windows(2) {
+                assert!(w[0] < w[1]);
+            }
+            for &i in buf.iter() {
+                counts[i] += 1;
+            }
+        }
+        for &c in counts.iter() {
+            // Each element is chosen with probability 1/2
+            assert!(c > 430 && c < 570, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_iterator_choose_multiple() {
+        let mut r = ::test::rng(111);
+
+        let v = (0..3).choose_multiple(&mut r, 5);
+        assert_eq!(v, [0, 1, 2]);
+        let v = (0..20).filter(|x| x % 2 == 0).choose_multiple(&mut r, 4);
+        assert_eq!(v.len(), 4);
+        assert!(v.iter().all(|x| x % 2 == 0 && *x < 20));
+        assert_eq!((0..10).choose_multiple(&mut r, 0).len(), 0);
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_choose_multiple() {
+        let mut r = ::test::rng(420);
+        let v: Vec<usize> = (0..100).collect();
+
+        for &amount in [0, 1, 4, 50, 99, 100].iter() {
+            let chosen = v.choose_multiple(&mut r, amount);
+            assert_eq!(chosen.len(), amount);
+            let mut chosen: Vec<usize> = chosen.cloned().collect();
+            assert_eq!(chosen.len(), amount);
+            chosen.sort();
+            chosen.dedup();
+            assert_eq!(chosen.len(), amount);
+        }
+
+        // `amount` greater than the length returns all elements
+        let mut all: Vec<usize> = v[..10].choose_multiple(&mut r, 20).cloned().collect();
+        all.sort();
+        assert_eq!(all, &v[..10]);
+
+        let mut counts = [0; 5];
+        for _ in 0..2500 {
+            for &i in [0, 1, 2, 3, 4].choose_multiple(&mut r, 2) {
+                counts[i] += 1;
+            }
+        }
+        for &c in counts.iter() {
+            assert!(c > 850 && c < 1150, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_choose_weighted() {
+        use distributions::WeightedError;
+        let mut r = ::test::rng(421);
+        let choices = [('a', 2), ('b', 1), ('c', 0), ('d', 1)];
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            let c = choices.choose_weighted(&mut r, |item| item.1).unwrap().0;
+            counts[(c as u8 - b'a') as usize] += 1;
+        }
+        assert!(counts[0] > 1800 && counts[0] < 2200, "{:?}", counts);
+        assert!(counts[1] > 850 && counts[1] < 1150, "{:?}", counts);
+        assert_eq!(counts[2], 0);
+        assert!(counts[3] > 850 && counts[3] < 1150, "{:?}", counts);
+
+        let mut values = [(0, 1.0), (0, 0.0), (0, 3.0)];
+        for _ in 0..100 {
+            values.choose_weighted_mut(&mut r, |item| item.1).unwrap().0 += 1;
+        }
+        assert_eq!(values[1].0, 0);
+        assert_eq!(values[0].0 + values[2].0, 100);
+
+        let empty: [(char, u32); 0] = [];
+        assert_eq!(empty.choose_weighted(&mut r, |item| item.1).unwrap_err(),
+                   WeightedError::NoItem);
+        assert_eq!(choices.choose_weighted(&mut r, |_| 0).unwrap_err(),
+                   WeightedError::AllWeightsZero);
+        assert_eq!(choices.choose_weighted(&mut r, |item| item.1 - 1).unwrap_err(),
+                   WeightedError::InvalidWeight);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_choose_multiple_weighted() {
+        use distributions::WeightedError;
+        let mut r = ::test::rng(422);
+        let choices = [('a', 4.0), ('b', 2.0), ('c', 1.0), ('d', 1.0), ('e', 0.0)];
+
+        for &amount in [0, 1, 2, 4].iter() {
+            let mut chosen: Vec<char> = choices
+                .choose_multiple_weighted(&mut r, amount, |item| item.1)
+                .unwrap().map(|item| item.0).collect();
+            assert_eq!(chosen.len(), amount);
+            chosen.sort();
+            chosen.dedup();
+            assert_eq!(chosen.len(), amount);
+            assert!(!chosen.contains(&'e'));
+        }
+
+        // Elements with zero weight are never chosen
+        let chosen = choices.choose_multiple_weighted(&mut r, 10, |item| item.1).unwrap();
+        assert_eq!(chosen.len(), 4);
+        let empty: [(char, f64); 0] = [];
+        assert_eq!(empty.choose_multiple_weighted(&mut r, 2, |item| item.1).unwrap().len(), 0);
+
+        // With one choice, the probabilities are proportional to the weights
+        let mut counts = [0; 5];
+        for _ in 0..4000 {
+            for item in choices.choose_multiple_weighted(&mut r, 1, |item| item.1).unwrap() {
+                counts[(item.0 as u8 - b'a') as usize] += 1;
+            }
+        }
+        assert!(counts[0] > 1800 && counts[0] < 2200, "{:?}", counts);
+        assert!(counts[1] > 850 && counts[1] < 1150, "{:?}", counts);
+        assert!(counts[2] > 400 && counts[2] < 600, "{:?}", counts);
+        assert!(counts[3] > 400 && counts[3] < 600, "{:?}", counts);
+        assert_eq!(counts[4], 0);
+
+        // Integer weights
+        let int_choices = [(0, 1u32), (1, 0), (2, 3)];
+        let mut chosen: Vec<i32> = int_choices
+            .choose_multiple_weighted(&mut r, 2, |item| item.1)
+            .unwrap().map(|item| item.0).collect();
+        chosen.sort();
+        assert_eq!(chosen, [0, 2]);
+
+        assert_eq!(choices.choose_multiple_weighted(&mut r, 2, |item| item.1 - 1.0).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert_eq!(choices.choose_multiple_weighted(&mut r, 2, |_| ::core::f64::NAN).unwrap_err(),
+                   WeightedError::InvalidWeight);
+    }
+
+    #[test]
+    #[cfg(feature="std")]
+    fn test_shuffle_weighted() {
+        use distributions::WeightedError;
+        let mut r = ::test::rng(423);
+        let empty: &mut [f64] = &mut [];
+        empty.shuffle_weighted(&mut r, |&w| w).unwrap();
+
+        let mut v: Vec<(usize, u32)> = (0..100).map(|i| (i, (i % 3) as u32)).collect();
+        v.shuffle_weighted(&mut r, |item| item.1).unwrap();
+        // Elements with weight zero come last
+        assert!(v[..66].iter().all(|item| item.1 > 0));
+        assert!(v[66..].iter().all(|item| item.1 == 0));
+        v.sort();
+        assert!(v.iter().enumerate().all(|(i, item)| item.0 == i));
+
+        // The first element is chosen proportional to the weights, the
+        // second proportional to the remaining weights.
+        let mut first = [0i32; 3];
+        let mut second = [0i32; 3];
+        for _ in 0..4000 {
+            let mut v = [(0, 2.0), (1, 1.0), (2, 1.0)];
+            v.shuffle_weighted(&mut r, |item| item.1).unwrap();
+            first[v[0].0] += 1;
+            if v[0].0 == 0 {
+                second[v[1].0] += 1;
+            }
+        }
+        assert!(first[0] > 1800 && first[0] < 2200, "{:?}", first);
+        assert!(first[1] > 850 && first[1] < 1150, "{:?}", first);
+        assert!(second[1] > 850 && second[1] < 1150, "{:?}", second);
+        assert_eq!(second[1] + second[2], first[0]);
+
+        let mut v = [1.0, -1.0, 2.0];
+        assert_eq!(v.shuffle_weighted(&mut r, |&w| w).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert_eq!(v, [1.0, -1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut r = ::test::rng(414);
+        let empty: &mut [isize] = &mut [];
+        empty.shuffle(&mut r);
+        let mut one = [1];
+        one.shuffle(&mut r);
+        let b: &[_] = &[1];
+        assert_eq!(one, b);
+
+        let mut two = [1, 2];
+        two.shuffle(&mut r);
+        assert!(two == [1, 2] || two == [2, 1]);
+
+        let mut x = [1, 1, 1];
+        x.shuffle(&mut r);
+        let b: &[_] = &[1, 1, 1];
+        assert_eq!(x, b);
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut r = ::test::rng(416);
+        let mut x = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        {
+            let (shuffled, rest) = x.partial_shuffle(&mut r, 3);
+            assert_eq!(shuffled.len(), 3);
+            assert_eq!(rest.len(), 7);
+        }
+        // still a permutation
+        let mut sorted = x;
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        // `amount` larger than the length
+        {
+            let (shuffled, rest) = x.partial_shuffle(&mut r, 20);
+            assert_eq!(shuffled.len(), 10);
+            assert_eq!(rest.len(), 0);
+        }
+
+        let empty: &mut [isize] = &mut [];
+        let (shuffled, rest) = empty.partial_shuffle(&mut r, 1);
+        assert!(shuffled.is_empty() && rest.is_empty());
+    }
+
+    #[test]
+    fn test_partial_shuffle_unbiased() {
+        // Each element is equally likely to end up first
+        let mut r = ::test::rng(417);
+        let mut counts = [0; 5];
+        for _ in 0..5000 {
+            let mut x = [0, 1, 2, 3, 4];
+            let first = x.partial_shuffle(&mut r, 1).0[0];
+            counts[first] += 1;
+        }
+        for &c in counts.iter() {
+            assert!(c > 850 && c < 1150, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_unbiased() {
+        // Count how often each of the 6 permutations of 3 elements occurs
+        let mut r = ::test::rng(415);
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            let mut x = [0, 1, 2];
+            x.shuffle(&mut r);
+            let i = match x {
+                [0, 1, 2] => 0,
+                [0, 2, 1] => 1,
+                [1, 0, 2] => 2,
+                [1, 2, 0] => 3,
+                [2, 0, 1] => 4,
+                _ => 5,
+            };
+            counts[i] += 1;
+        }
+        for &c in counts.iter() {
+            assert!(c > 850 && c < 1150, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_sample_iter() {
+        let min_val = 1;
+        let max_val = 100;
+
+        let mut r = ::test::rng(401);
+        let vals = (min_val..max_val).collect::<Vec<i32>>();
+        let small_sample = sample_iter(&mut r, vals.iter(), 5).unwrap();
+        let large_sample = sample_iter(&mut r, vals.iter(), vals.len() + 5).unwrap_err();
+
+        assert_eq!(small_sample.len(), 5);
+        assert_eq!(large_sample.len(), vals.len());
+        // no randomization happens when amount >= len
+        assert_eq!(large_sample, vals.iter().collect::<Vec<_>>());
+
+        assert!(small_sample.iter().all(|e| {
+            **e >= min_val && **e <= max_val
+        }));
+    }
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_sample_slice_boundaries() {
+        let empty: &[u8] = &[];
+
+        let mut r = ::test::rng(402);
+
+        // sample 0 items
+        assert_eq!(&sample_slice(&mut r, empty, 0)[..], [0u8; 0]);
+        assert_eq!(&sample_slice(&mut r, &[42, 2, 42], 0)[..], [0u8; 0]);
+
+        // sample 1 item
+        assert_eq!(&sample_slice(&mut r, &[42], 1)[..], [42]);
+        let v = sample_slice(&mut r, &[1, 42], 1)[0];
+        assert!(v == 1 || v == 42);
+
+        // sample "all" the items
+        let v = sample_slice(&mut r, &[42, 133], 2);
+        assert!(&v[..] == [42, 133] || v[..] == [133, 42]);
+
+        assert_eq!(&sample_indices(&mut r, 0, 0)[..], [0usize; 0]);
+        assert_eq!(&sample_indices(&mut r, 1, 0)[..], [0usize; 0]);
+        assert_eq!(&sample_indices(&mut r, 1, 1)[..], [0]);
+
+        // Make sure lucky 777's aren't lucky
+        let slice = &[42, 777];
+        let mut num_42 = 0;
+        let total = 1000;
+        for _ in 0..total {
+            let v = sample_slice(&mut r, slice, 1);
+            assert_eq!(v.len(), 1);
+            let v = v[0];
+            assert!(v == 42 || v == 777);
+            if v == 42 {
+                num_42 += 1;
+            }
+        }
+        let ratio_42 = num_42 as f64 / 1000 as f64;
+        assert!(0.4 <= ratio_42 || ratio_42 <= 0.6, "{}", ratio_42);
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_sample_stable() {
+        let mut r = ::test::rng(406);
+        let empty: &[u8] = &[];
+        assert_eq!(sample_stable(&mut r, empty, 0).len(), 0);
+
+        let v: Vec<usize> = (0..100).collect();
+        for &amount in [0, 1, 10, 99, 100].iter() {
+            let sample = sample_stable(&mut r, &v, amount);
+            assert_eq!(sample.len(), amount);
+            for w in sample.windows(2) {
+                assert!(w[0] < w[1]);
+            }
+        }
+
+        let mut counts = [0; 5];
+        for _ in 0..2500 {
+            for &&i in sample_stable(&mut r, &[0, 1, 2, 3, 4], 2).iter() {
+                counts[i] += 1;
+            }
+        }
+        for &c in counts.iter() {
+            assert!(c > 850 && c < 1150, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_derange() {
+        let mut r = ::test::rng(407);
+        let mut empty: [u8; 0] = [];
+        derange(&mut r, &mut empty);
+
+        let mut v = [0, 1];
+        derange(&mut r, &mut v);
+        assert_eq!(v, [1, 0]);
+
+        // All 9 derangements of 4 elements should be about equally likely
+        let mut counts = Vec::new();
+        for _ in 0..4500 {
+            let mut v = [0, 1, 2, 3];
+            derange(&mut r, &mut v);
+            for (i, &x) in v.iter().enumerate() {
+                assert!(i != x);
+            }
+            match counts.iter().position(|&(p, _)| p == v) {
+                Some(pos) => counts[pos].1 += 1,
+                None => counts.push((v, 1)),
+            }
+        }
+        assert_eq!(counts.len(), 9);
+        for &(_, c) in counts.iter() {
+            assert!(c > 400 && c < 600, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    #[should_panic]
+    fn test_derange_single() {
+        derange(&mut ::test::rng(408), &mut [1]);
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_sample_slice() {
+        let xor_rng = XorShiftRng::from_seed;
+
+        let max_range = 100;
+        let mut r = ::test::rng(403);
+
+        for length in 1usize..max_range {
+            let amount = r.gen_range(0, length);
+            let mut seed = [0u8; 16];
+            r.fill(&mut seed);
+
+            // assert the basics work
+            let regular = sample_indices(
+                &mut xor_rng(seed), length, amount);
+            assert_eq!(regular.len(), amount);
+            assert!(regular.iter().all(|e| *e < length));
+
+            // also test that sampling the slice works
+            let vec: Vec<usize> = (0..length).collect();
+            {
+                let result = sample_slice(&mut xor_rng(seed), &vec, amount);
+                assert_eq!(result, regular);
+            }
+
+            {
+                let result = sample_slice_ref(&mut xor_rng(seed), &vec, amount);
+                let expected = regular.iter().map(|v| v).collect::<Vec<_>>();
+                assert_eq!(result, expected);
+            }
+        }
+    }
+}
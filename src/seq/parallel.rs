@@ -0,0 +1,165 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parallel shuffling with Rayon.
+
+use rayon;
+
+use {Rng, SeedableRng};
+use prng::ChaChaRng;
+use super::SliceRandom;
+
+/// Slices of at most this length are shuffled sequentially.
+const LEAF_SIZE: usize = 1 << 14;
+
+/// Shuffle a mutable slice in place, using all cores.
+///
+/// This implements [MergeShuffle](https://arxiv.org/abs/1508.03167): the
+/// slice is split in halves recursively, the parts are shuffled in parallel
+/// with Rayon, and adjacent parts are merged by random riffling. Like
+/// [`SliceRandom::shuffle`], this produces an unbiased permutation.
+///
+/// The randomness for each part and each merge comes from an independent
+/// stream of a [`ChaChaRng`] seeded from `rng`: every part of the slice uses
+/// its own stream number (see [`ChaChaRng::set_stream`]). As a result, the
+/// permutation depends only on `rng` and the length of the slice, not on the
+/// number of threads or their scheduling.
+///
+/// This uses `O(len)` time in total and `O(log(len))` extra memory. For
+/// small slices, [`SliceRandom::shuffle`] is faster.
+///
+/// # Example
+///
+/// ```
+/// use rand::thread_rng;
+/// use rand::seq;
+///
+/// let mut data: Vec<u32> = (0..1_000_000).collect();
+/// seq::par_shuffle(&mut data, &mut thread_rng());
+/// ```
+///
+/// [`SliceRandom::shuffle`]: trait.SliceRandom.html#tymethod.shuffle
+/// [`ChaChaRng`]: ../prng/chacha/struct.ChaChaRng.html
+/// [`ChaChaRng::set_stream`]: ../prng/chacha/struct.ChaChaRng.html#method.set_stream
+pub fn par_shuffle<T: Send, R: Rng + ?Sized>(slice: &mut [T], rng: &mut R) {
+    par_shuffle_with_leaf_size(slice, rng, LEAF_SIZE);
+}
+
+fn par_shuffle_with_leaf_size<T: Send, R: Rng + ?Sized>(slice: &mut [T], rng: &mut R,
+                                                        leaf_size: usize) {
+    let mut seed = <ChaChaRng as SeedableRng>::Seed::default();
+    rng.fill(&mut seed);
+    let base = ChaChaRng::from_seed(seed);
+    shuffle_node(slice, &base, 1, leaf_size);
+}
+
+/// Shuffle the part of the slice belonging to node `node` of the binary tree
+/// of splits, where the children of node `n` are `2n` and `2n + 1`.
+fn shuffle_node<T: Send>(slice: &mut [T], base: &ChaChaRng, node: u64, leaf_size: usize) {
+    let mut rng = base.clone();
+    rng.set_stream(node);
+    if slice.len() <= leaf_size {
+        slice.shuffle(&mut rng);
+        return;
+    }
+
+    let mid = slice.len() / 2;
+    {
+        let (left, right) = slice.split_at_mut(mid);
+        rayon::join(|| shuffle_node(left, base, 2 * node, leaf_size),
+                    || shuffle_node(right, base, 2 * node + 1, leaf_size));
+    }
+    merge(slice, mid, &mut rng);
+}
+
+/// Merge the shuffled parts `slice[..mid]` and `slice[mid..]` into a
+/// shuffled slice.
+fn merge<T, R: Rng + ?Sized>(slice: &mut [T], mid: usize, rng: &mut R) {
+    let end = slice.len();
+    let mut i = 0;
+    let mut j = mid;
+    let mut bits = 0u32;
+    let mut bits_left = 0;
+    // Riffle: take the next element from either part with probability 1/2,
+    // until one part is exhausted.
+    loop {
+        if bits_left == 0 {
+            bits = rng.next_u32();
+            bits_left = 32;
+        }
+        let from_right = bits & 1 == 1;
+        bits >>= 1;
+        bits_left -= 1;
+        if from_right {
+            if j == end {
+                break;
+            }
+            slice.swap(i, j);
+            j += 1;
+        } else if i == j {
+            break;
+        }
+        i += 1;
+    }
+
+    // Insert the remaining elements at random positions, as in the
+    // Fisher-Yates shuffle.
+    while i < end {
+        let k = rng.gen_range(0, i + 1);
+        slice.swap(i, k);
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_par_shuffle() {
+        let mut r = ::test::rng(432);
+        let empty: &mut [isize] = &mut [];
+        par_shuffle(empty, &mut r);
+
+        let mut v: Vec<u32> = (0..100_000).collect();
+        par_shuffle(&mut v, &mut r);
+        assert!(v.iter().enumerate().filter(|&(i, &x)| i as u32 == x).count() < 10);
+        v.sort();
+        assert!(v.iter().enumerate().all(|(i, &x)| i as u32 == x));
+
+        // The result does not depend on the scheduling
+        let mut v1: Vec<u32> = (0..100_000).collect();
+        let mut v2 = v1.clone();
+        par_shuffle(&mut v1, &mut ::test::rng(433));
+        par_shuffle(&mut v2, &mut ::test::rng(433));
+        assert_eq!(v1, v2);
+    }
+
+    #[test]
+    fn test_par_shuffle_unbiased() {
+        // With a leaf size of one, all of the randomness comes from merging.
+        // Each of the 24 permutations of 4 elements should be about equally
+        // likely.
+        let mut r = ::test::rng(434);
+        let mut counts: Vec<([u8; 4], i32)> = Vec::new();
+        for _ in 0..12000 {
+            let mut v = [0, 1, 2, 3];
+            par_shuffle_with_leaf_size(&mut v, &mut r, 1);
+            match counts.iter().position(|&(p, _)| p == v) {
+                Some(pos) => counts[pos].1 += 1,
+                None => counts.push((v, 1)),
+            }
+        }
+        assert_eq!(counts.len(), 24);
+        for &(_, c) in counts.iter() {
+            assert!(c > 400 && c < 600, "{:?}", counts);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A random permutation which is not stored in memory.
+
+use Rng;
+
+/// Number of rounds of the Feistel network.
+const ROUNDS: usize = 8;
+
+/// A random permutation of `0..length`, computed on demand.
+///
+/// Unlike shuffling a vector of indices, this needs `O(1)` memory and `O(1)`
+/// setup time regardless of `length`, and [`index`] looks up any position of
+/// the permutation in `O(1)` expected time. This makes it possible to visit
+/// very large ranges, for example the `10^10` rows of a table, in a random
+/// order.
+///
+/// The permutation is computed by a keyed [Feistel network] on the smallest
+/// power of four not less than `length`, with cycle-walking to map into
+/// `0..length`: values outside the range are permuted again until they fall
+/// inside it. The keys are sampled from the RNG passed to [`new`].
+///
+/// The permutations are not uniformly distributed over all `length!`
+/// permutations, and they are not suitable for cryptography; for small
+/// `length`, [`SliceRandom::shuffle`] produces an unbiased permutation.
+/// Within these limits the result looks random for all statistical purposes.
+///
+/// # Example
+///
+/// ```
+/// use rand::thread_rng;
+/// use rand::seq::RandomPermutation;
+///
+/// let perm = RandomPermutation::new(10_000_000_000, &mut thread_rng());
+/// let first = perm.index(0);
+/// assert!(first < 10_000_000_000);
+///
+/// // visit all elements of a smaller range in random order
+/// let perm = RandomPermutation::new(10, &mut thread_rng());
+/// let mut order: Vec<u64> = perm.iter().collect();
+/// order.sort();
+/// assert_eq!(order, (0..10).collect::<Vec<u64>>());
+/// ```
+///
+/// [`index`]: #method.index
+/// [`new`]: #method.new
+/// [Feistel network]: https://en.wikipedia.org/wiki/Feistel_cipher
+/// [`SliceRandom::shuffle`]: trait.SliceRandom.html#tymethod.shuffle
+#[derive(Debug, Clone)]
+pub struct RandomPermutation {
+    length: u64,
+    half_bits: u32,
+    half_mask: u64,
+    keys: [u64; ROUNDS],
+}
+
+impl RandomPermutation {
+    /// Create a new random permutation of `0..length`.
+    pub fn new<R: Rng + ?Sized>(length: u64, rng: &mut R) -> RandomPermutation {
+        // Number of bits of the largest value `length - 1`, rounded up to
+        // an even number, but at least two.
+        let bits = 64 - length.saturating_sub(1).leading_zeros();
+        let half_bits = ::core::cmp::max(bits / 2 + bits % 2, 1);
+        let mut keys = [0u64; ROUNDS];
+        rng.fill(&mut keys[..]);
+        RandomPermutation {
+            length,
+            half_bits,
+            half_mask: (1u64 << half_bits) - 1,
+            keys,
+        }
+    }
+
+    /// Returns the length of the permutation.
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns `true` if the permutation is of the empty range.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the element at position `i` of the permutation.
+    ///
+    /// Panics if `i >= self.len()`.
+    pub fn index(&self, i: u64) -> u64 {
+        assert!(i < self.length, "RandomPermutation::index: index out of range");
+        // Walk the cycle of `i` until we are back in `0..length`. Since the
+        // domain of the Feistel network is less than four times `length`,
+        // this takes fewer than four steps on average.
+        let mut x = self.encrypt(i);
+        while x >= self.length {
+            x = self.encrypt(x);
+        }
+        x
+    }
+
+    /// Returns an iterator over the elements of the permutation, in order of
+    /// their position.
+    pub fn iter(&self) -> RandomPermutationIter {
+        RandomPermutationIter { perm: self.clone(), next: 0 }
+    }
+
+    /// Permute `x` in the domain `0..2^(2 * half_bits)`.
+    fn encrypt(&self, x: u64) -> u64 {
+        let mut left = x >> self.half_bits;
+        let mut right = x & self.half_mask;
+        for key in self.keys.iter() {
+            let next = left ^ (round(right, *key) & self.half_mask);
+            left = right;
+            right = next;
+        }
+        (left << self.half_bits) | right
+    }
+}
+
+/// The round function of the Feistel network. This is the finalizer of
+/// SplitMix64, applied to the input mixed with the round key.
+#[inline]
+fn round(x: u64, key: u64) -> u64 {
+    let mut z = x ^ key;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// An iterator over the elements of a [`RandomPermutation`].
+///
+/// This struct is created by [`RandomPermutation::iter`].
+///
+/// [`RandomPermutation`]: struct.RandomPermutation.html
+/// [`RandomPermutation::iter`]: struct.RandomPermutation.html#method.iter
+#[derive(Debug, Clone)]
+pub struct RandomPermutationIter {
+    perm: RandomPermutation,
+    next: u64,
+}
+
+impl Iterator for RandomPermutationIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.next < self.perm.length {
+            let x = self.perm.index(self.next);
+            self.next += 1;
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.perm.length - self.next;
+        if remaining > ::core::usize::MAX as u64 {
+            (::core::usize::MAX, None)
+        } else {
+            (remaining as usize, Some(remaining as usize))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(all(feature="alloc", not(feature="std")))]
+    use alloc::Vec;
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_permutation() {
+        let mut r = ::test::rng(425);
+        for &length in [0u64, 1, 2, 3, 4, 5, 15, 16, 17, 1000, (1 << 16) + 1].iter() {
+            let perm = RandomPermutation::new(length, &mut r);
+            assert_eq!(perm.len(), length);
+            assert_eq!(perm.is_empty(), length == 0);
+            assert_eq!(perm.iter().size_hint(), (length as usize, Some(length as usize)));
+            let mut values: Vec<u64> = perm.iter().collect();
+            assert_eq!(values.len() as u64, length);
+            values.sort();
+            assert!(values.iter().enumerate().all(|(i, &x)| i as u64 == x));
+        }
+    }
+
+    #[test]
+    fn test_permutation_large() {
+        let mut r = ::test::rng(426);
+        for &length in [10_000_000_000u64, ::core::u64::MAX].iter() {
+            let perm = RandomPermutation::new(length, &mut r);
+            let mut prev = length;
+            for i in (0..100).chain(length - 100..length) {
+                let x = perm.index(i);
+                assert!(x < length);
+                assert!(x != prev);
+                prev = x;
+            }
+        }
+    }
+
+    #[test]
+    fn test_permutation_unbiased() {
+        // The position of each element should be uniformly distributed
+        let mut r = ::test::rng(427);
+        let mut counts = [[0i32; 5]; 5];
+        for _ in 0..5000 {
+            let perm = RandomPermutation::new(5, &mut r);
+            for (i, x) in perm.iter().enumerate() {
+                counts[i][x as usize] += 1;
+            }
+        }
+        for row in counts.iter() {
+            for &c in row.iter() {
+                assert!(c > 850 && c < 1150, "{:?}", counts);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permutation_index_out_of_range() {
+        let perm = RandomPermutation::new(10, &mut ::test::rng(428));
+        perm.index(10);
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Weighted reservoir sampling of streams.
+
+use std::collections::BinaryHeap;
+
+use Rng;
+use distributions::{OpenClosed01, Open01, WeightedError};
+use super::WeightedKey;
+
+/// A weighted random sample of fixed size from a stream of unknown length.
+///
+/// Items are added one at a time with [`push`], each with a weight. At any
+/// time the reservoir holds a random sample of up to `amount` distinct items
+/// of those pushed so far, where the items are chosen with probability
+/// proportional to their weight, as with
+/// [`SliceRandom::choose_multiple_weighted`]. Items with a weight of zero are
+/// never chosen.
+///
+/// This implements the A-ExpJ algorithm of [Efraimidis and Spirakis](
+/// https://doi.org/10.1016/j.ipl.2005.11.003): once the reservoir is full,
+/// random numbers are only generated for the items that enter the
+/// reservoir, and the weight of the items in between is skipped. This uses
+/// `O(amount)` memory, and `O(log(amount))` time for each item entering the
+/// reservoir.
+///
+/// # Example
+///
+/// ```
+/// use rand::thread_rng;
+/// use rand::seq::WeightedReservoir;
+///
+/// let mut rng = thread_rng();
+/// let mut reservoir = WeightedReservoir::new(2);
+/// for &(event, size) in [("a", 10), ("b", 200), ("c", 1), ("d", 50)].iter() {
+///     // sample events proportional to their size
+///     reservoir.push(&mut rng, event, size).unwrap();
+/// }
+/// assert_eq!(reservoir.len(), 2);
+/// println!("{:?}", reservoir.into_vec());
+/// ```
+///
+/// [`push`]: #method.push
+/// [`SliceRandom::choose_multiple_weighted`]: trait.SliceRandom.html#tymethod.choose_multiple_weighted
+#[derive(Debug, Clone)]
+pub struct WeightedReservoir<T> {
+    amount: usize,
+    items: Vec<T>,
+    // Keys of the items, where `index` is the position in `items`. The top of
+    // the heap is the smallest key.
+    keys: BinaryHeap<WeightedKey>,
+    // Weight still to be skipped before the next item enters the reservoir.
+    skip: f64,
+}
+
+impl<T> WeightedReservoir<T> {
+    /// Create a new, empty reservoir holding a sample of at most `amount`
+    /// items.
+    pub fn new(amount: usize) -> WeightedReservoir<T> {
+        WeightedReservoir {
+            amount,
+            items: Vec::with_capacity(amount),
+            keys: BinaryHeap::with_capacity(amount),
+            skip: 0.0,
+        }
+    }
+
+    /// Offer an item with the given weight to the reservoir.
+    ///
+    /// The item may be added to the sample, in which case another item may
+    /// be evicted, or it may be dropped. Returns an error if the weight is
+    /// negative or NaN; the reservoir is unchanged in that case.
+    pub fn push<R, X>(&mut self, rng: &mut R, item: T, weight: X) -> Result<(), WeightedError>
+        where R: Rng + ?Sized,
+              X: Into<f64>
+    {
+        let w: f64 = weight.into();
+        if w < 0.0 || w.is_nan() {
+            return Err(WeightedError::InvalidWeight);
+        }
+        if w == 0.0 || self.amount == 0 {
+            return Ok(());
+        }
+
+        if self.items.len() < self.amount {
+            // Fill the reservoir, using the key `ln(u) / w`, which has the
+            // same order as the key `u^(1/w)` of Efraimidis and Spirakis.
+            let u: f64 = rng.sample(OpenClosed01);
+            self.keys.push(WeightedKey { key: u.ln() / w, index: self.items.len() });
+            self.items.push(item);
+            if self.items.len() == self.amount {
+                self.skip = self.sample_skip(rng);
+            }
+            return Ok(());
+        }
+
+        self.skip -= w;
+        if self.skip > 0.0 {
+            return Ok(());
+        }
+
+        // The item replaces the one with the smallest key. Its key is sampled
+        // conditional on being larger than that key `t`, i.e. `u` is sampled
+        // from `(exp(t * w), 1]`.
+        let threshold = self.keys.peek().unwrap().key;
+        let low = (threshold * w).exp();
+        let u = low + (1.0 - low) * rng.sample::<f64, _>(OpenClosed01);
+        let key = (u.ln() / w).max(threshold);
+        {
+            let mut top = self.keys.peek_mut().unwrap();
+            top.key = key;
+            self.items[top.index] = item;
+        }
+        self.skip = self.sample_skip(rng);
+        Ok(())
+    }
+
+    /// Sample the total weight to skip before the next item enters the full
+    /// reservoir.
+    fn sample_skip<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let threshold = self.keys.peek().unwrap().key;
+        // Keys are at most zero, so with a threshold of zero (e.g. when the
+        // keys underflow for huge weights) no item can enter the reservoir
+        // anymore. Dividing by it would give `-inf` for `+0.0`, accepting the
+        // next item.
+        if threshold == 0.0 {
+            return ::std::f64::INFINITY;
+        }
+        let r: f64 = rng.sample(Open01);
+        // Both logarithms are negative, so the result is positive.
+        r.ln() / threshold
+    }
+
+    /// Returns the number of items in the reservoir.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the reservoir holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the items in the reservoir.
+    ///
+    /// The order of the items is not specified.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Consumes the reservoir, returning the sampled items.
+    ///
+    /// The order of the items is not specified.
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use distributions::WeightedError;
+    use rngs::mock::StepRng;
+
+    #[test]
+    fn test_weighted_reservoir() {
+        let mut r = ::test::rng(423);
+        let weights = [4.0, 2.0, 1.0, 1.0, 0.0];
+
+        // With room for all items, all with a positive weight are kept
+        let mut reservoir = WeightedReservoir::new(10);
+        for (i, &w) in weights.iter().enumerate() {
+            reservoir.push(&mut r, i, w).unwrap();
+        }
+        let mut items = reservoir.into_vec();
+        items.sort();
+        assert_eq!(items, [0, 1, 2, 3]);
+
+        // With room for one item, the probabilities are proportional to the
+        // weights
+        let mut counts = [0; 5];
+        for _ in 0..4000 {
+            let mut reservoir = WeightedReservoir::new(1);
+            for (i, &w) in weights.iter().enumerate() {
+                reservoir.push(&mut r, i, w).unwrap();
+            }
+            assert_eq!(reservoir.len(), 1);
+            counts[reservoir.as_slice()[0]] += 1;
+        }
+        assert!(counts[0] > 1800 && counts[0] < 2200, "{:?}", counts);
+        assert!(counts[1] > 850 && counts[1] < 1150, "{:?}", counts);
+        assert!(counts[2] > 400 && counts[2] < 600, "{:?}", counts);
+        assert!(counts[3] > 400 && counts[3] < 600, "{:?}", counts);
+        assert_eq!(counts[4], 0);
+
+        let mut reservoir = WeightedReservoir::new(0);
+        reservoir.push(&mut r, 'a', 1.0).unwrap();
+        assert!(reservoir.is_empty());
+
+        let mut reservoir = WeightedReservoir::new(2);
+        assert_eq!(reservoir.push(&mut r, 'a', -1.0).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert_eq!(reservoir.push(&mut r, 'a', ::std::f64::NAN).unwrap_err(),
+                   WeightedError::InvalidWeight);
+        assert!(reservoir.is_empty());
+    }
+
+    #[test]
+    fn test_weighted_reservoir_zero_keys() {
+        // With `u = 1` all keys are exactly zero, the largest possible key,
+        // so the first items stay in the reservoir.
+        let mut r = StepRng::new(!0, 0);
+        let mut reservoir = WeightedReservoir::new(2);
+        for i in 0..10 {
+            reservoir.push(&mut r, i, 1.0).unwrap();
+        }
+        assert_eq!(reservoir.skip, ::std::f64::INFINITY);
+        let mut items = reservoir.into_vec();
+        items.sort();
+        assert_eq!(items, [0, 1]);
+
+        // Infinite weights make the keys underflow to zero, and no item with
+        // a finite weight can replace them.
+        let mut r = ::test::rng(425);
+        let mut reservoir = WeightedReservoir::new(2);
+        reservoir.push(&mut r, 0, ::std::f64::INFINITY).unwrap();
+        reservoir.push(&mut r, 1, ::std::f64::INFINITY).unwrap();
+        for i in 2..100 {
+            reservoir.push(&mut r, i, 1e300).unwrap();
+        }
+        let mut items = reservoir.into_vec();
+        items.sort();
+        assert_eq!(items, [0, 1]);
+    }
+
+    #[test]
+    fn test_weighted_reservoir_stream() {
+        // Sample 2 of 100 items, where the items with even index have three
+        // times the weight of the others. Since the weights are equal within
+        // both groups, each even item is chosen equally often, and more often
+        // than each odd item.
+        let mut r = ::test::rng(424);
+        let mut counts = [0; 100];
+        for _ in 0..2000 {
+            let mut reservoir = WeightedReservoir::new(2);
+            for i in 0..100usize {
+                let w = if i % 2 == 0 { 3u32 } else { 1 };
+                reservoir.push(&mut r, i, w).unwrap();
+            }
+            let items = reservoir.into_vec();
+            assert_eq!(items.len(), 2);
+            assert!(items[0] != items[1]);
+            for &i in items.iter() {
+                counts[i] += 1;
+            }
+        }
+        let even: i32 = counts.iter().enumerate().filter(|&(i, _)| i % 2 == 0)
+            .map(|(_, c)| c).sum();
+        let odd: i32 = counts.iter().enumerate().filter(|&(i, _)| i % 2 == 1)
+            .map(|(_, c)| c).sum();
+        assert_eq!(even + odd, 4000);
+        // For a sample of size one, the ratio would be exactly 3.
+        assert!(even > 2 * odd && even < 4 * odd, "{} {}", even, odd);
+        for (i, &c) in counts.iter().enumerate() {
+            if i % 2 == 0 {
+                assert!(c > 25 && c < 95, "{:?}", &counts[..]);
+            }
+        }
+    }
+}
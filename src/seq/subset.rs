@@ -0,0 +1,210 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Random subsets of `0..n`.
+
+use Rng;
+
+/// Return an iterator over a uniformly random `k`-subset of `0..n`.
+///
+/// The iterator yields `k` distinct values in increasing order, and each of
+/// the `n choose k` subsets is equally likely. Nothing is allocated; the
+/// values are selected one at a time (Knuth's selection sampling, Algorithm
+/// S), using `O(n)` time in total.
+///
+/// Panics if `k > n`.
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let lottery: Vec<usize> = seq::combinations(&mut rng, 49, 6).map(|x| x + 1).collect();
+/// assert_eq!(lottery.len(), 6);
+/// assert!(lottery.windows(2).all(|w| w[0] < w[1]));
+/// ```
+pub fn combinations<'a, R>(rng: &'a mut R, n: usize, k: usize) -> Combination<'a, R>
+    where R: Rng + ?Sized
+{
+    assert!(k <= n, "combinations: `k` must be less than or equal to `n`");
+    Combination { rng, n, k, i: 0 }
+}
+
+/// Return an iterator over a uniformly random subset of `0..n`.
+///
+/// Each value of `0..n` is included independently with probability 1/2, so
+/// that each of the `2^n` subsets is equally likely. The values are yielded
+/// in increasing order. Nothing is allocated, and one random bit is used per
+/// value.
+///
+/// # Example
+///
+/// ```
+/// use rand::{thread_rng, seq};
+///
+/// let mut rng = thread_rng();
+/// let toppings = ["cheese", "ham", "mushrooms", "olives", "pineapple"];
+/// for i in seq::random_subset(&mut rng, toppings.len()) {
+///     println!("{}", toppings[i]);
+/// }
+/// ```
+pub fn random_subset<'a, R>(rng: &'a mut R, n: usize) -> Subset<'a, R>
+    where R: Rng + ?Sized
+{
+    Subset { rng, n, i: 0, bits: 0, bits_left: 0 }
+}
+
+/// An iterator over a random `k`-subset of `0..n`.
+///
+/// This struct is created by [`combinations`].
+///
+/// [`combinations`]: fn.combinations.html
+#[derive(Debug)]
+pub struct Combination<'a, R: ?Sized + 'a> {
+    rng: &'a mut R,
+    // Number of values not yet considered
+    n: usize,
+    // Number of values still to be selected
+    k: usize,
+    // Next value to consider
+    i: usize,
+}
+
+impl<'a, R: Rng + ?Sized + 'a> Iterator for Combination<'a, R> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.k > 0 {
+            // Select the next value with probability k / n.
+            let selected = self.rng.gen_range(0, self.n) < self.k;
+            let value = self.i;
+            self.i += 1;
+            self.n -= 1;
+            if selected {
+                self.k -= 1;
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.k, Some(self.k))
+    }
+}
+
+impl<'a, R: Rng + ?Sized + 'a> ExactSizeIterator for Combination<'a, R> {}
+
+/// An iterator over a random subset of `0..n`.
+///
+/// This struct is created by [`random_subset`].
+///
+/// [`random_subset`]: fn.random_subset.html
+#[derive(Debug)]
+pub struct Subset<'a, R: ?Sized + 'a> {
+    rng: &'a mut R,
+    n: usize,
+    // Next value to consider
+    i: usize,
+    // Unused random bits
+    bits: u64,
+    bits_left: u32,
+}
+
+impl<'a, R: Rng + ?Sized + 'a> Iterator for Subset<'a, R> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.i < self.n {
+            if self.bits_left == 0 {
+                self.bits = self.rng.next_u64();
+                self.bits_left = 64;
+            }
+            let selected = self.bits & 1 == 1;
+            self.bits >>= 1;
+            self.bits_left -= 1;
+            let value = self.i;
+            self.i += 1;
+            if selected {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.n - self.i))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_combinations() {
+        let mut r = ::test::rng(429);
+        assert_eq!(combinations(&mut r, 0, 0).next(), None);
+        assert_eq!(combinations(&mut r, 10, 0).next(), None);
+
+        let mut all = combinations(&mut r, 5, 5);
+        assert_eq!(all.len(), 5);
+        for i in 0..5 {
+            assert_eq!(all.next(), Some(i));
+        }
+        assert_eq!(all.next(), None);
+
+        // Each of the 10 2-subsets of 0..5 should be about equally likely
+        let mut counts = [[0i32; 5]; 5];
+        for _ in 0..5000 {
+            let mut iter = combinations(&mut r, 5, 2);
+            assert_eq!(iter.len(), 2);
+            let a = iter.next().unwrap();
+            let b = iter.next().unwrap();
+            assert!(a < b);
+            assert_eq!(iter.next(), None);
+            counts[a][b] += 1;
+        }
+        for a in 0..5 {
+            for b in (a + 1)..5 {
+                assert!(counts[a][b] > 400 && counts[a][b] < 600, "{:?}", counts);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_combinations_too_many() {
+        combinations(&mut ::test::rng(430), 3, 4);
+    }
+
+    #[test]
+    fn test_random_subset() {
+        let mut r = ::test::rng(431);
+        assert_eq!(random_subset(&mut r, 0).next(), None);
+
+        let mut counts = [0i32; 100];
+        for _ in 0..1000 {
+            let mut prev = None;
+            for i in random_subset(&mut r, 100) {
+                assert!(i < 100);
+                if let Some(p) = prev {
+                    assert!(p < i);
+                }
+                prev = Some(i);
+                counts[i] += 1;
+            }
+        }
+        for &c in counts.iter() {
+            assert!(c > 400 && c < 600, "{:?}", &counts[..]);
+        }
+    }
+}
@@ -0,0 +1,432 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Statistical tests for smoke-testing generators and distributions.
+//!
+//! This module provides Pearson's [`chi_square`] test for sampled counts and
+//! the [`kolmogorov_smirnov`] test for sampled values of a continuous
+//! distribution. Both return a [`TestResult`] with the test statistic and its
+//! p-value: the probability of a result at least this extreme if the samples
+//! really come from the expected distribution.
+//!
+//! These tests can only catch gross errors, like a biased range or a wrong
+//! scale parameter, and are meant for unit tests. They are no substitute for
+//! test suites like PractRand or TestU01 when evaluating a generator; see the
+//! `practrand-harness` example for that.
+//!
+//! Because the p-value of a correct implementation is uniformly distributed,
+//! a test with significance level `alpha` fails with probability `alpha` even
+//! when there is nothing wrong. Use a seeded generator to keep tests
+//! reproducible, and a small `alpha` like `1e-4`.
+//!
+//! This module is available with the `std` or `libm` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use rand::{Rng, SeedableRng};
+//! use rand::prng::Xoshiro256StarStar;
+//! use rand::stat_test::{chi_square_uniform, kolmogorov_smirnov};
+//!
+//! let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+//!
+//! // A fair die
+//! let mut counts = [0u64; 6];
+//! for _ in 0..6000 {
+//!     counts[rng.gen_range(0, 6)] += 1;
+//! }
+//! assert!(chi_square_uniform(&counts).passed(1e-4));
+//!
+//! // The square of a uniform value has the CDF `sqrt(x)` on `[0, 1]`
+//! let mut samples = [0.0f64; 1000];
+//! for x in samples.iter_mut() {
+//!     let u: f64 = rng.gen();
+//!     *x = u * u;
+//! }
+//! assert!(kolmogorov_smirnov(&mut samples, |x| x.sqrt()).passed(1e-4));
+//! ```
+//!
+//! [`chi_square`]: fn.chi_square.html
+//! [`kolmogorov_smirnov`]: fn.kolmogorov_smirnov.html
+//! [`TestResult`]: struct.TestResult.html
+
+#[cfg(not(feature="std"))] use distributions::math::Float;
+use distributions::log_gamma::log_gamma;
+use RngCore;
+
+/// The result of a statistical test.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestResult {
+    /// The value of the test statistic.
+    pub statistic: f64,
+    /// The probability of a statistic at least as extreme as this one, if
+    /// the samples come from the expected distribution.
+    pub p_value: f64,
+}
+
+impl TestResult {
+    /// Whether the test passed at significance level `alpha`, i.e. whether
+    /// the p-value is at least `alpha`.
+    pub fn passed(&self, alpha: f64) -> bool {
+        self.p_value >= alpha
+    }
+}
+
+/// Pearson's chi-square goodness-of-fit test.
+///
+/// `observed[i]` is the number of samples which fell into category `i`, and
+/// `expected[i]` the probability (or any multiple of it) of that category.
+/// The expected probabilities are scaled to the total number of samples.
+///
+/// The test is only accurate if the expected number of samples in each
+/// category is at least about 5.
+///
+/// # Panics
+///
+/// If there are less than 2 categories, if the lengths of `observed` and
+/// `expected` differ, or if an expected value is not positive and finite.
+pub fn chi_square(observed: &[u64], expected: &[f64]) -> TestResult {
+    assert!(observed.len() >= 2, "chi_square called with less than 2 categories");
+    assert!(observed.len() == expected.len(),
+            "chi_square called with `observed.len() != expected.len()`");
+    let mut total_expected = 0.0;
+    for &e in expected {
+        assert!(e > 0.0 && e.is_finite(),
+                "chi_square called with an expected value which is not \
+                 positive and finite");
+        total_expected += e;
+    }
+    let n = observed.iter().fold(0.0, |n, &o| n + o as f64);
+    let scale = n / total_expected;
+
+    let mut statistic = 0.0;
+    for (&o, &e) in observed.iter().zip(expected.iter()) {
+        let e = e * scale;
+        let d = o as f64 - e;
+        statistic += d * d / e;
+    }
+    let df = (observed.len() - 1) as f64;
+    TestResult { statistic, p_value: gamma_q(0.5 * df, 0.5 * statistic) }
+}
+
+/// Pearson's chi-square test of whether all categories are equally likely.
+///
+/// This is [`chi_square`] with the same expected value for each category.
+///
+/// # Panics
+///
+/// If there are less than 2 categories.
+///
+/// [`chi_square`]: fn.chi_square.html
+pub fn chi_square_uniform(observed: &[u64]) -> TestResult {
+    assert!(observed.len() >= 2, "chi_square called with less than 2 categories");
+    let n = observed.iter().fold(0.0, |n, &o| n + o as f64);
+    let e = n / observed.len() as f64;
+
+    let mut statistic = 0.0;
+    if e > 0.0 {
+        for &o in observed {
+            let d = o as f64 - e;
+            statistic += d * d / e;
+        }
+    }
+    let df = (observed.len() - 1) as f64;
+    TestResult { statistic, p_value: gamma_q(0.5 * df, 0.5 * statistic) }
+}
+
+/// The one-sample Kolmogorov-Smirnov test.
+///
+/// Tests whether `samples` come from the continuous distribution with the
+/// cumulative distribution function `cdf`. The statistic is the largest
+/// distance between `cdf` and the empirical distribution function of the
+/// samples. The p-value uses the asymptotic distribution, which is accurate
+/// for more than about 35 samples.
+///
+/// The samples are sorted in place.
+///
+/// # Panics
+///
+/// If `samples` is empty or contains NaN.
+pub fn kolmogorov_smirnov<F>(samples: &mut [f64], cdf: F) -> TestResult
+    where F: Fn(f64) -> f64
+{
+    assert!(!samples.is_empty(), "kolmogorov_smirnov called with no samples");
+    samples.sort_unstable_by(|a, b| {
+        a.partial_cmp(b).expect("kolmogorov_smirnov called with NaN")
+    });
+
+    let n = samples.len() as f64;
+    let mut statistic: f64 = 0.0;
+    for (i, &x) in samples.iter().enumerate() {
+        let f = cdf(x);
+        let below = f - i as f64 / n;
+        let above = (i + 1) as f64 / n - f;
+        statistic = statistic.max(below).max(above);
+    }
+    let sqrt_n = n.sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * statistic;
+    TestResult { statistic, p_value: kolmogorov_q(lambda) }
+}
+
+/// The Kolmogorov-Smirnov test for samples from the uniform distribution on
+/// `[0, 1)`, like the `f64` values of the `Standard` distribution.
+///
+/// # Panics
+///
+/// If `samples` is empty or contains NaN.
+pub fn kolmogorov_smirnov_uniform(samples: &mut [f64]) -> TestResult {
+    kolmogorov_smirnov(samples, |x| {
+        if x < 0.0 { 0.0 } else { x.min(1.0) }
+    })
+}
+
+/// Test whether the bytes produced by `rng` are uniformly distributed.
+///
+/// Fills `n` bytes with [`RngCore::fill_bytes`], and applies
+/// [`chi_square_uniform`] to the counts of the 256 byte values. `n` should be
+/// at least a few thousand.
+///
+/// [`RngCore::fill_bytes`]: ../trait.RngCore.html#tymethod.fill_bytes
+/// [`chi_square_uniform`]: fn.chi_square_uniform.html
+pub fn byte_frequency<R: RngCore + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    let mut counts = [0u64; 256];
+    let mut buf = [0u8; 256];
+    let mut remaining = n;
+    while remaining > 0 {
+        let len = if remaining < buf.len() { remaining } else { buf.len() };
+        rng.fill_bytes(&mut buf[..len]);
+        for &b in &buf[..len] {
+            counts[b as usize] += 1;
+        }
+        remaining -= len;
+    }
+    chi_square_uniform(&counts)
+}
+
+const EPSILON: f64 = 1e-15;
+const TINY: f64 = 1e-300;
+
+/// The regularized upper incomplete gamma function `Q(a, x)`, which is the
+/// survival function of the chi-square distribution with `2a` degrees of
+/// freedom at `2x`.
+///
+/// Uses the series of `P(a, x) = 1 - Q(a, x)` for `x < a + 1` and the
+/// continued fraction of `Q(a, x)` otherwise, see Numerical Recipes, 6.2.
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let factor = (a * x.ln() - x - log_gamma(a)).exp();
+    if x < a + 1.0 {
+        let mut ap = a;
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for _ in 0..1000 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1.0 - sum * factor).max(0.0)
+    } else {
+        // Modified Lentz's method
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        (h * factor).min(1.0)
+    }
+}
+
+/// The survival function of the Kolmogorov distribution,
+/// `Q(λ) = 2 Σ (-1)^(j-1) exp(-2 j² λ²)`.
+fn kolmogorov_q(lambda: f64) -> f64 {
+    let a = -2.0 * lambda * lambda;
+    let mut sign = 2.0;
+    let mut sum = 0.0;
+    let mut previous: f64 = 0.0;
+    for j in 1..101 {
+        let j = j as f64;
+        let term = sign * (a * j * j).exp();
+        sum += term;
+        if term.abs() <= 0.001 * previous || term.abs() <= 1e-8 * sum {
+            return sum;
+        }
+        sign = -sign;
+        previous = term.abs();
+    }
+    // The series does not converge for small `lambda`, where `Q` is 1.
+    1.0
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature="std"))] use distributions::math::Float;
+    use {Rng, SeedableRng};
+    use distributions::{Bernoulli, Exp1, StandardNormal, Uniform};
+    use prng::Xoshiro256StarStar;
+    use rngs::mock::StepRng;
+    use super::*;
+
+    const ALPHA: f64 = 1e-4;
+
+    fn rng(seed: u8) -> Xoshiro256StarStar {
+        Xoshiro256StarStar::from_seed([seed; 32])
+    }
+
+    // The standard normal CDF, via erfc with a relative error below 1.2e-7
+    // (Numerical Recipes, 6.2).
+    fn normal_cdf(x: f64) -> f64 {
+        let z = x.abs() / ::core::f64::consts::SQRT_2;
+        let t = 1.0 / (1.0 + 0.5 * z);
+        let erfc = t * (-z * z - 1.26551223 + t * (1.00002368 + t * (0.37409196
+            + t * (0.09678418 + t * (-0.18628806 + t * (0.27886807
+            + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223
+            + t * 0.17087277))))))))).exp();
+        if x >= 0.0 { 1.0 - 0.5 * erfc } else { 0.5 * erfc }
+    }
+
+    #[test]
+    fn test_gamma_q() {
+        // Q(1, x) = exp(-x)
+        for &x in &[0.1, 0.5, 1.0, 2.0, 5.0, 20.0] {
+            assert!((gamma_q(1.0, x) - (-x).exp()).abs() < 1e-9);
+        }
+        // Q(2, x) = (1 + x) exp(-x)
+        for &x in &[0.1, 1.0, 3.0, 10.0] {
+            assert!((gamma_q(2.0, x) - (1.0 + x) * (-x).exp()).abs() < 1e-9);
+        }
+        assert_eq!(gamma_q(3.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_kolmogorov_q() {
+        assert_eq!(kolmogorov_q(0.0), 1.0);
+        // Critical values of the Kolmogorov distribution
+        assert!((kolmogorov_q(1.3581) - 0.05).abs() < 1e-4);
+        assert!((kolmogorov_q(1.6276) - 0.01).abs() < 1e-4);
+        assert!(kolmogorov_q(3.0) < 1e-6);
+    }
+
+    #[test]
+    fn test_chi_square() {
+        let r = chi_square_uniform(&[100, 100, 100, 100]);
+        assert_eq!(r.statistic, 0.0);
+        assert_eq!(r.p_value, 1.0);
+        let r = chi_square_uniform(&[150, 50, 100, 100]);
+        assert_eq!(r.statistic, 50.0);
+        assert!(!r.passed(ALPHA));
+
+        assert_eq!(chi_square(&[10, 30], &[1.0, 3.0]).statistic, 0.0);
+        assert_eq!(chi_square(&[30, 10], &[1.0, 3.0]).statistic,
+                   chi_square(&[30, 10], &[0.25, 0.75]).statistic);
+        // chi-square with 2 degrees of freedom: p = exp(-statistic / 2)
+        let r = chi_square(&[12, 28, 60], &[1.0, 3.0, 6.0]);
+        assert!((r.p_value - (-r.statistic / 2.0).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_chi_square_invalid() {
+        chi_square(&[1, 2], &[1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_kolmogorov_smirnov() {
+        let mut samples = [0.0; 100];
+        for (i, x) in samples.iter_mut().enumerate().rev() {
+            *x = (i as f64 + 0.5) / 100.0;
+        }
+        let r = kolmogorov_smirnov_uniform(&mut samples);
+        assert!((r.statistic - 0.005).abs() < 1e-12);
+        assert!(r.p_value > 0.999);
+        assert!(samples.windows(2).all(|w| w[0] < w[1]));
+
+        for x in samples.iter_mut() {
+            *x *= 0.5;
+        }
+        assert!(!kolmogorov_smirnov_uniform(&mut samples).passed(ALPHA));
+    }
+
+    #[test]
+    fn test_generators() {
+        assert!(byte_frequency(&mut rng(1), 1 << 16).passed(ALPHA));
+        assert!(!byte_frequency(&mut StepRng::new(0, 1), 1 << 16).passed(ALPHA));
+
+        #[cfg(feature="alloc")] {
+            use rngs::registry::{algorithms, by_name};
+            let seed = [7u8; 32];
+            for info in algorithms() {
+                let mut rng = by_name(info.name, &seed[..info.seed_size]).unwrap();
+                assert!(byte_frequency(&mut *rng, 1 << 16).passed(ALPHA),
+                        "{}", info.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_distributions() {
+        let mut rng = rng(2);
+        let mut samples = [0.0f64; 2000];
+
+        for x in samples.iter_mut() {
+            *x = rng.gen();
+        }
+        assert!(kolmogorov_smirnov_uniform(&mut samples).passed(ALPHA));
+
+        for x in samples.iter_mut() {
+            *x = rng.sample(Exp1);
+        }
+        assert!(kolmogorov_smirnov(&mut samples, |x| 1.0 - (-x).exp())
+                .passed(ALPHA));
+
+        for x in samples.iter_mut() {
+            *x = rng.sample(StandardNormal);
+        }
+        assert!(kolmogorov_smirnov(&mut samples, normal_cdf).passed(ALPHA));
+        // A normal distribution with the wrong scale is caught
+        assert!(!kolmogorov_smirnov(&mut samples, |x| normal_cdf(x / 1.5))
+                .passed(ALPHA));
+
+        let mut counts = [0u64; 10];
+        let range = Uniform::new(0, 10);
+        for _ in 0..10000 {
+            counts[rng.sample(range)] += 1;
+        }
+        assert!(chi_square_uniform(&counts).passed(ALPHA));
+
+        let mut counts = [0u64; 2];
+        let bernoulli = Bernoulli::new(0.3);
+        for _ in 0..10000 {
+            counts[rng.sample(bernoulli) as usize] += 1;
+        }
+        assert!(chi_square(&counts, &[0.7, 0.3]).passed(ALPHA));
+    }
+}
@@ -16,7 +16,8 @@ fn large_probability() {
     assert!(p < 1.);
     let d = Bernoulli::new(p);
     let mut rng = SmallRng::from_seed(
-        [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
+        [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+         17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32]);
     for _ in 0..10 {
         assert!(d.sample(&mut rng), "extremely unlikely to fail by accident");
     }
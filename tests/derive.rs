@@ -0,0 +1,52 @@
+#![cfg(feature = "derive")]
+
+#[macro_use]
+extern crate rand;
+
+use rand::Rng;
+use rand::rngs::mock::StepRng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, UniformEnum)]
+enum Suit {
+    Clubs,
+    Diamonds,
+    /// Variants may have attributes
+    #[allow(dead_code)]
+    Hearts,
+    Spades = 10,
+}
+
+#[derive(Debug, PartialEq, UniformEnum)]
+pub enum Single {
+    Only,
+}
+
+#[test]
+fn uniform_enum() {
+    let mut rng = rand::thread_rng();
+    let mut seen = [false; 4];
+    for _ in 0..1000 {
+        match rng.gen::<Suit>() {
+            Suit::Clubs => seen[0] = true,
+            Suit::Diamonds => seen[1] = true,
+            Suit::Hearts => seen[2] = true,
+            Suit::Spades => seen[3] = true,
+        }
+    }
+    assert_eq!(seen, [true; 4]);
+}
+
+#[test]
+fn uniform_enum_order() {
+    // Variants are numbered in declaration order
+    let mut rng = StepRng::new(0, 0);
+    assert_eq!(rng.gen::<Suit>(), Suit::Clubs);
+    let mut rng = StepRng::new(!0, 0);
+    assert_eq!(rng.gen::<Suit>(), Suit::Spades);
+}
+
+#[test]
+fn uniform_enum_single() {
+    let mut rng = rand::thread_rng();
+    assert_eq!(rng.gen::<Single>(), Single::Only);
+}
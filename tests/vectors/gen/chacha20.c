@@ -0,0 +1,194 @@
+/* Generates tests/vectors/chacha20.txt.
+ *
+ * The block function and state layout below are those of the reference
+ * implementation chacha-ref.c, version 20080118, by D. J. Bernstein, which is
+ * in the public domain: a 256-bit key in words 4 to 11, a 64-bit block
+ * counter in words 12 and 13, and a 64-bit nonce (the stream of `ChaChaRng`)
+ * in words 14 and 15.
+ *
+ * Build and run with: cc -O2 -o chacha20 chacha20.c && ./chacha20
+ */
+
+#include <stdint.h>
+#include <stdio.h>
+
+typedef uint8_t u8;
+typedef uint32_t u32;
+
+#define ROTATE(v,c) ((u32)((v) << (c)) | ((v) >> (32 - (c))))
+#define XOR(v,w) ((v) ^ (w))
+#define PLUS(v,w) ((u32)((v) + (w)))
+#define PLUSONE(v) (PLUS((v),1))
+
+#define QUARTERROUND(a,b,c,d) \
+  x[a] = PLUS(x[a],x[b]); x[d] = ROTATE(XOR(x[d],x[a]),16); \
+  x[c] = PLUS(x[c],x[d]); x[b] = ROTATE(XOR(x[b],x[c]),12); \
+  x[a] = PLUS(x[a],x[b]); x[d] = ROTATE(XOR(x[d],x[a]), 8); \
+  x[c] = PLUS(x[c],x[d]); x[b] = ROTATE(XOR(x[b],x[c]), 7);
+
+static void salsa20_wordtobyte(u32 output[16],const u32 input[16])
+{
+  u32 x[16];
+  int i;
+
+  for (i = 0;i < 16;++i) x[i] = input[i];
+  for (i = 20;i > 0;i -= 2) {
+    QUARTERROUND( 0, 4, 8,12)
+    QUARTERROUND( 1, 5, 9,13)
+    QUARTERROUND( 2, 6,10,14)
+    QUARTERROUND( 3, 7,11,15)
+    QUARTERROUND( 0, 5,10,15)
+    QUARTERROUND( 1, 6,11,12)
+    QUARTERROUND( 2, 7, 8,13)
+    QUARTERROUND( 3, 4, 9,14)
+  }
+  for (i = 0;i < 16;++i) output[i] = PLUS(x[i],input[i]);
+}
+
+#define U8TO32_LITTLE(p) \
+  (((u32)((p)[0])) | ((u32)((p)[1]) << 8) | ((u32)((p)[2]) << 16) | ((u32)((p)[3]) << 24))
+
+/* End of the reference implementation. */
+
+static u32 input[16];
+static u32 block[16];
+static int index;
+
+static void set_counter(uint64_t counter) {
+  input[12] = (u32)counter;
+  input[13] = (u32)(counter >> 32);
+  index = 16;
+}
+
+static u32 next_word(void) {
+  if (index == 16) {
+    salsa20_wordtobyte(block, input);
+    input[12] = PLUSONE(input[12]);
+    if (!input[12]) input[13] = PLUSONE(input[13]);
+    index = 0;
+  }
+  return block[index++];
+}
+
+static void seed(const u8 key[32]) {
+  static const char sigma[16] = "expand 32-byte k";
+  int i;
+  printf("\nrng chacha20 ");
+  for (i = 0; i < 32; i++) printf("%02x", key[i]);
+  printf("\n");
+  for (i = 0; i < 4; i++) input[i] = U8TO32_LITTLE((const u8 *)sigma + 4 * i);
+  for (i = 0; i < 8; i++) input[4 + i] = U8TO32_LITTLE(key + 4 * i);
+  input[14] = 0;
+  input[15] = 0;
+  set_counter(0);
+}
+
+static void stream(uint64_t nonce) {
+  printf("stream %llu\n", (unsigned long long)nonce);
+  input[14] = (u32)nonce;
+  input[15] = (u32)(nonce >> 32);
+  set_counter(0);
+}
+
+/* The word position counts 32-bit words from the start of the stream. */
+static void word_pos(uint64_t pos) {
+  printf("word_pos %llu\n", (unsigned long long)pos);
+  set_counter(pos / 16);
+  if (pos % 16) {
+    next_word();
+    index = pos % 16;
+  }
+}
+
+static void u32s(int n) {
+  int i;
+  for (i = 0; i < n; i++) {
+    printf(i % 8 == 0 ? "u32" : "");
+    printf(" %08x", next_word());
+    if (i % 8 == 7 || i == n - 1) printf("\n");
+  }
+}
+
+/* `next_u64` combines two words, the first one in the lower half. */
+static void u64s(int n) {
+  int i;
+  for (i = 0; i < n; i++) {
+    uint64_t lo = next_word();
+    uint64_t hi = next_word();
+    printf(i % 4 == 0 ? "u64" : "");
+    printf(" %016llx", (unsigned long long)(hi << 32 | lo));
+    if (i % 4 == 3 || i == n - 1) printf("\n");
+  }
+}
+
+/* `fill_bytes` uses the little-endian bytes of whole words; the unused bytes
+ * of the last word are discarded. */
+static void bytes(int len) {
+  int i, j;
+  printf("bytes ");
+  for (i = 0; i < len; i += 4) {
+    u32 x = next_word();
+    for (j = 0; j < 4 && i + j < len; j++) printf("%02x", (x >> (8 * j)) & 0xff);
+  }
+  printf("\n");
+}
+
+int main(void) {
+  u8 zero[32] = { 0 };
+  u8 one[32] = { 0 };
+  u8 key_ff[32] = { 0 };
+  u8 key_seq[32];
+  int i, len;
+
+  one[31] = 1;
+  key_ff[1] = 0xff;
+  for (i = 0; i < 32; i++) key_seq[i] = i;
+
+  printf("# ChaCha20 test vectors, generated by gen/chacha20.c\n");
+  printf("# The first blocks of the runs with the keys 0, 0...01 and 00ff0...0 are\n");
+  printf("# test vectors 1 to 4 of draft-nir-cfrg-chacha20-poly1305-04.\n");
+  printf("\n# Long runs\n");
+  seed(zero);
+  u32s(1000);
+  seed(one);
+  u32s(64);
+  seed(key_ff);
+  u32s(64);
+  seed(key_seq);
+  u32s(1000);
+
+  printf("\n# Streams (test vector 5 of the draft uses stream 2 << 56)\n");
+  seed(zero);
+  stream(2ULL << 56);
+  u32s(32);
+  seed(key_seq);
+  stream(0x0123456789abcdefULL);
+  u32s(32);
+
+  printf("\n# Word positions\n");
+  seed(key_seq);
+  word_pos(32);
+  u32s(16);
+  word_pos(5);
+  u32s(16);
+  word_pos(0xfffffffffffffff0ULL);
+  u32s(32);
+  word_pos(1ULL << 40 | 7);
+  u32s(16);
+
+  printf("\n# next_u64, across block boundaries\n");
+  seed(key_seq);
+  u32s(15);
+  u64s(16);
+  u32s(1);
+  u64s(16);
+
+  printf("\n# fill_bytes with lengths around the word and block boundaries\n");
+  seed(key_seq);
+  for (len = 0; len <= 17; len++) bytes(len);
+  bytes(63);
+  bytes(64);
+  bytes(65);
+  bytes(300);
+  return 0;
+}
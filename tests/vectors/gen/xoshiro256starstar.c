@@ -0,0 +1,182 @@
+/* Generates tests/vectors/xoshiro256starstar.txt.
+ *
+ * The generator below is the reference implementation from
+ * http://xoshiro.di.unimi.it/xoshiro256starstar.c, unchanged except for
+ * formatting:
+ *
+ *   Written in 2018 by David Blackman and Sebastiano Vigna (vigna@acm.org)
+ *
+ *   To the extent possible under law, the author has dedicated all copyright
+ *   and related and neighboring rights to this software to the public domain
+ *   worldwide. This software is distributed without any warranty.
+ *
+ *   See <http://creativecommons.org/publicdomain/zero/1.0/>.
+ *
+ * Build and run with: cc -O2 -o xoshiro xoshiro256starstar.c && ./xoshiro
+ */
+
+#include <stdint.h>
+#include <stdio.h>
+
+static inline uint64_t rotl(const uint64_t x, int k) {
+	return (x << k) | (x >> (64 - k));
+}
+
+static uint64_t s[4];
+
+uint64_t next(void) {
+	const uint64_t result = rotl(s[1] * 5, 7) * 9;
+
+	const uint64_t t = s[1] << 17;
+
+	s[2] ^= s[0];
+	s[3] ^= s[1];
+	s[1] ^= s[2];
+	s[0] ^= s[3];
+
+	s[2] ^= t;
+
+	s[3] = rotl(s[3], 45);
+
+	return result;
+}
+
+void jump(void) {
+	static const uint64_t JUMP[] = { 0x180ec6d33cfd0aba, 0xd5a61266f0c9392c, 0xa9582618e03fc9aa, 0x39abdc4529b1661c };
+
+	uint64_t s0 = 0;
+	uint64_t s1 = 0;
+	uint64_t s2 = 0;
+	uint64_t s3 = 0;
+	for(int i = 0; i < sizeof JUMP / sizeof *JUMP; i++)
+		for(int b = 0; b < 64; b++) {
+			if (JUMP[i] & UINT64_C(1) << b) {
+				s0 ^= s[0];
+				s1 ^= s[1];
+				s2 ^= s[2];
+				s3 ^= s[3];
+			}
+			next();
+		}
+
+	s[0] = s0;
+	s[1] = s1;
+	s[2] = s2;
+	s[3] = s3;
+}
+
+void long_jump(void) {
+	static const uint64_t LONG_JUMP[] = { 0x76e15d3efefdcbbf, 0xc5004e441c522fb3, 0x77710069854ee241, 0x39109bb02acbe635 };
+
+	uint64_t s0 = 0;
+	uint64_t s1 = 0;
+	uint64_t s2 = 0;
+	uint64_t s3 = 0;
+	for(int i = 0; i < sizeof LONG_JUMP / sizeof *LONG_JUMP; i++)
+		for(int b = 0; b < 64; b++) {
+			if (LONG_JUMP[i] & UINT64_C(1) << b) {
+				s0 ^= s[0];
+				s1 ^= s[1];
+				s2 ^= s[2];
+				s3 ^= s[3];
+			}
+			next();
+		}
+
+	s[0] = s0;
+	s[1] = s1;
+	s[2] = s2;
+	s[3] = s3;
+}
+
+/* End of the reference implementation. */
+
+static void seed(const uint8_t bytes[32]) {
+	printf("\nrng xoshiro256** ");
+	for (int i = 0; i < 32; i++) printf("%02x", bytes[i]);
+	printf("\n");
+	for (int i = 0; i < 4; i++) {
+		s[i] = 0;
+		for (int j = 0; j < 8; j++) s[i] |= (uint64_t)bytes[8 * i + j] << (8 * j);
+	}
+}
+
+static void u64s(int n) {
+	for (int i = 0; i < n; i++) {
+		printf(i % 4 == 0 ? "u64" : "");
+		printf(" %016llx", (unsigned long long)next());
+		if (i % 4 == 3 || i == n - 1) printf("\n");
+	}
+}
+
+static void u32s(int n) {
+	for (int i = 0; i < n; i++) {
+		printf(i % 8 == 0 ? "u32" : "");
+		/* `next_u32` uses the upper half of the output */
+		printf(" %08x", (unsigned)(next() >> 32));
+		if (i % 8 == 7 || i == n - 1) printf("\n");
+	}
+}
+
+/* `fill_bytes` uses the little-endian bytes of the outputs. A remainder of
+ * more than four bytes is taken from an output, otherwise from the upper
+ * half of an output, like `next_u32`. */
+static void bytes(int len) {
+	printf("bytes ");
+	while (len >= 8) {
+		uint64_t x = next();
+		for (int j = 0; j < 8; j++) printf("%02x", (unsigned)(x >> (8 * j)) & 0xff);
+		len -= 8;
+	}
+	if (len > 4) {
+		uint64_t x = next();
+		for (int j = 0; j < len; j++) printf("%02x", (unsigned)(x >> (8 * j)) & 0xff);
+	} else if (len > 0) {
+		uint64_t x = next() >> 32;
+		for (int j = 0; j < len; j++) printf("%02x", (unsigned)(x >> (8 * j)) & 0xff);
+	}
+	printf("\n");
+}
+
+int main(void) {
+	uint8_t seed_1234[32] = { 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0,
+	                          3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0 };
+	uint8_t seed_seq[32];
+	for (int i = 0; i < 32; i++) seed_seq[i] = i;
+
+	printf("# xoshiro256** test vectors, generated by gen/xoshiro256starstar.c\n");
+	printf("\n# A long run\n");
+	seed(seed_1234);
+	u64s(1000);
+	seed(seed_seq);
+	u64s(1000);
+
+	printf("\n# Jump points\n");
+	seed(seed_1234);
+	printf("jump\n");
+	jump();
+	u64s(16);
+	printf("jump\n");
+	jump();
+	u64s(16);
+	printf("long_jump\n");
+	long_jump();
+	u64s(16);
+	seed(seed_seq);
+	printf("long_jump\n");
+	long_jump();
+	u64s(16);
+
+	printf("\n# next_u32\n");
+	seed(seed_seq);
+	u32s(32);
+
+	printf("\n# fill_bytes with lengths around the word and buffer boundaries\n");
+	seed(seed_seq);
+	for (int len = 0; len <= 17; len++) bytes(len);
+	bytes(127);
+	bytes(128);
+	bytes(129);
+	bytes(300);
+	return 0;
+}
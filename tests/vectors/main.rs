@@ -0,0 +1,157 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks the PRNGs against test vectors produced by reference
+//! implementations.
+//!
+//! Each `.txt` file in this directory is generated by the program of the same
+//! name in `gen/`, which contains the upstream reference implementation of
+//! the generator. The files are a sequence of commands, one per line; empty
+//! lines and lines starting with `#` are ignored:
+//!
+//! - `rng <name> <seed>` creates the generator `name` from a hex seed
+//! - `u32 <x>...` and `u64 <x>...` check the next outputs, in hex
+//! - `bytes <hex>` checks the output of `fill_bytes`, with the length of the
+//!   expected bytes (which may be empty)
+//! - `jump` and `long_jump` jump ahead, for xoshiro
+//! - `stream <n>` and `word_pos <n>` set the stream and the position in the
+//!   stream, for ChaCha; the latter requires the `i128_support` feature, and
+//!   the following commands are skipped without it
+
+extern crate rand;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use rand::{RngCore, SeedableRng};
+use rand::prng::{ChaChaRng, Xoshiro256StarStar};
+
+enum Generator {
+    Xoshiro256StarStar(Xoshiro256StarStar),
+    ChaCha20(Box<ChaChaRng>),
+}
+
+impl Generator {
+    fn new(name: &str, seed: &[u8]) -> Generator {
+        let mut s = [0u8; 32];
+        s.copy_from_slice(seed);
+        match name {
+            "xoshiro256**" => Generator::Xoshiro256StarStar(Xoshiro256StarStar::from_seed(s)),
+            "chacha20" => Generator::ChaCha20(Box::new(ChaChaRng::from_seed(s))),
+            _ => panic!("unknown generator {}", name),
+        }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        match *self {
+            Generator::Xoshiro256StarStar(ref mut rng) => rng.next_u32(),
+            Generator::ChaCha20(ref mut rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match *self {
+            Generator::Xoshiro256StarStar(ref mut rng) => rng.next_u64(),
+            Generator::ChaCha20(ref mut rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match *self {
+            Generator::Xoshiro256StarStar(ref mut rng) => rng.fill_bytes(dest),
+            Generator::ChaCha20(ref mut rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    // Returns false if the command is not supported with the enabled
+    // features.
+    fn command(&mut self, cmd: &str, arg: Option<&str>) -> bool {
+        match (cmd, self) {
+            ("jump", &mut Generator::Xoshiro256StarStar(ref mut rng)) => rng.jump(),
+            ("long_jump", &mut Generator::Xoshiro256StarStar(ref mut rng)) => rng.long_jump(),
+            ("stream", &mut Generator::ChaCha20(ref mut rng)) => {
+                rng.set_stream(arg.unwrap().parse().unwrap())
+            }
+            #[cfg(feature = "i128_support")]
+            ("word_pos", &mut Generator::ChaCha20(ref mut rng)) => {
+                rng.set_word_pos(arg.unwrap().parse().unwrap())
+            }
+            #[cfg(not(feature = "i128_support"))]
+            ("word_pos", &mut Generator::ChaCha20(_)) => return false,
+            (cmd, _) => panic!("command {} not supported by the generator", cmd),
+        }
+        true
+    }
+}
+
+fn parse_hex(s: &str) -> Vec<u8> {
+    assert!(s.len() % 2 == 0, "odd number of hex digits: {}", s);
+    (0..s.len() / 2).map(|i| u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap())
+                    .collect()
+}
+
+fn check(file: &str) {
+    let path = format!("{}/tests/vectors/{}", env!("CARGO_MANIFEST_DIR"), file);
+    let reader = BufReader::new(File::open(&path).unwrap());
+
+    let mut rng = None;
+    let mut skip = false;
+    let mut checked = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.unwrap();
+        let mut words = line.split_whitespace();
+        let cmd = match words.next() {
+            None => continue,
+            Some(cmd) if cmd.starts_with('#') => continue,
+            Some(cmd) => cmd,
+        };
+        let pos = format!("{}:{}", file, i + 1);
+
+        if cmd == "rng" {
+            let name = words.next().unwrap();
+            rng = Some(Generator::new(name, &parse_hex(words.next().unwrap())));
+            skip = false;
+            continue;
+        }
+        if skip {
+            continue;
+        }
+        let rng = rng.as_mut().expect("no generator");
+        match cmd {
+            "u32" => for x in words {
+                assert_eq!(rng.next_u32(), u32::from_str_radix(x, 16).unwrap(), "{}", pos);
+                checked += 1;
+            },
+            "u64" => for x in words {
+                assert_eq!(rng.next_u64(), u64::from_str_radix(x, 16).unwrap(), "{}", pos);
+                checked += 1;
+            },
+            "bytes" => {
+                let expected = words.next().map(parse_hex).unwrap_or_default();
+                let mut buf = vec![0u8; expected.len()];
+                rng.fill_bytes(&mut buf);
+                assert_eq!(buf, expected, "{}", pos);
+                checked += 1;
+            }
+            cmd => skip = !rng.command(cmd, words.next()),
+        }
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn xoshiro256starstar() {
+    check("xoshiro256starstar.txt");
+}
+
+#[test]
+fn chacha20() {
+    check("chacha20.txt");
+}
@@ -0,0 +1,6 @@
+fn main() {
+    // Fail to link if any symbol is left undefined, in particular the one
+    // referenced by the panic handler. libc is only needed for `memcpy`.
+    println!("cargo:rustc-cdylib-link-arg=-Wl,-z,defs");
+    println!("cargo:rustc-link-lib=c");
+}
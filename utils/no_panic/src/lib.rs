@@ -0,0 +1,68 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Check that `fill_bytes` and `try_fill_bytes` of the PRNGs can not panic.
+//!
+//! This library exports the methods of every PRNG, and its panic handler calls
+//! a function that does not exist. If the optimizer can not remove every
+//! panicking branch, the panic handler is linked in and linking fails with an
+//! undefined reference to `rand_fill_bytes_may_panic`. The methods do not
+//! allocate either: the crate is `no_std` and does not have an allocator.
+//!
+//! Build with (Linux only):
+//!
+//! ```sh
+//! cargo build --release --manifest-path utils/no_panic/Cargo.toml
+//! ```
+//!
+//! Add `--features rand/simd_dispatch` to also check the SIMD code paths.
+
+#![no_std]
+
+extern crate rand;
+
+use core::panic::PanicInfo;
+use core::slice;
+use rand::RngCore;
+use rand::prng::{ChaChaRng, Hc128Rng, IsaacRng, Isaac64Rng, XorShiftRng,
+                 Xoshiro256StarStar, Xoshiro256StarStarX4, SplitMix64};
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    extern "C" {
+        fn rand_fill_bytes_may_panic() -> !;
+    }
+    unsafe { rand_fill_bytes_may_panic() }
+}
+
+macro_rules! check_fill {
+    ($fill:ident, $try_fill:ident, $rng:ty) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $fill(rng: &mut $rng, dest: *mut u8, len: usize) {
+            rng.fill_bytes(slice::from_raw_parts_mut(dest, len))
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $try_fill(rng: &mut $rng, dest: *mut u8, len: usize)
+            -> bool
+        {
+            rng.try_fill_bytes(slice::from_raw_parts_mut(dest, len)).is_ok()
+        }
+    }
+}
+
+check_fill!(fill_chacha, try_fill_chacha, ChaChaRng);
+check_fill!(fill_hc128, try_fill_hc128, Hc128Rng);
+check_fill!(fill_isaac, try_fill_isaac, IsaacRng);
+check_fill!(fill_isaac64, try_fill_isaac64, Isaac64Rng);
+check_fill!(fill_xorshift, try_fill_xorshift, XorShiftRng);
+check_fill!(fill_xoshiro256starstar, try_fill_xoshiro256starstar, Xoshiro256StarStar);
+check_fill!(fill_xoshiro256starstar_x4, try_fill_xoshiro256starstar_x4, Xoshiro256StarStarX4);
+check_fill!(fill_splitmix64, try_fill_splitmix64, SplitMix64);
@@ -118,8 +118,7 @@ with open('ziggurat_tables.rs', 'w') as f:
 // Tables for distributions which are sampled using the ziggurat
 // algorithm. Autogenerated by `ziggurat_tables.py`.
 
-pub type ZigTable = &\'static [f64, .. %d];
-'''  % (TABLE_LEN + 1))
+''')
     for name, tables, r in [('NORM', NORM, NORM_R),
                             ('EXP', EXP, EXP_R)]:
         f.write(render_static('ZIG_%s_R' % name, 'f64', ' %.18f' % r))